@@ -0,0 +1,1029 @@
+mod persistence;
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::{scroll::Autoscroll, Editor};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, div, rems, AppContext, DismissEvent, EntityId, EventEmitter, FocusHandle,
+    FocusableView, Model, ModelContext, ParentElement, Render, Styled, Subscription, Task, View,
+    ViewContext, VisualContext, WeakModel, WeakView,
+};
+use language::{Anchor, Bias, Buffer, Point};
+use persistence::DB;
+use picker::{Picker, PickerDelegate};
+use std::{path::Path, sync::Arc};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace, WorkspaceId};
+
+actions!(
+    bookmarks,
+    [
+        ToggleBookmark,
+        NextBookmark,
+        PreviousBookmark,
+        RenameBookmark,
+        ShowBookmarks
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(register).detach();
+}
+
+fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let store = cx.new_model(|cx| BookmarkStore::new(workspace.database_id(), cx));
+
+    workspace.register_action({
+        let store = store.clone();
+        move |workspace, _: &ToggleBookmark, cx| {
+            let Some(position) = ActivePosition::new(workspace, cx) else {
+                return;
+            };
+            store.update(cx, |store, cx| {
+                store.toggle(position.buffer, position.abs_path, position.row, cx)
+            });
+        }
+    });
+    workspace.register_action({
+        let store = store.clone();
+        move |workspace, _: &NextBookmark, cx| {
+            go_to_adjacent_bookmark(&store, Direction::Next, workspace, cx)
+        }
+    });
+    workspace.register_action({
+        let store = store.clone();
+        move |workspace, _: &PreviousBookmark, cx| {
+            go_to_adjacent_bookmark(&store, Direction::Prev, workspace, cx)
+        }
+    });
+    workspace.register_action({
+        let store = store.clone();
+        move |workspace, _: &RenameBookmark, cx| {
+            let Some(position) = ActivePosition::new(workspace, cx) else {
+                return;
+            };
+            let store = store.clone();
+            workspace.toggle_modal(cx, |cx| BookmarkNameEditor::new(store, position, cx));
+        }
+    });
+    workspace.register_action(move |workspace, _: &ShowBookmarks, cx| {
+        let store = store.clone();
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| BookmarkList::new(store, weak_workspace, cx));
+    });
+}
+
+/// A marked line in a file, which can be jumped back to later.
+pub struct Bookmark {
+    pub abs_path: Arc<Path>,
+    /// What the user called the bookmark, shown in the bookmark list in front of its location.
+    pub name: Option<SharedString>,
+    position: BookmarkPosition,
+}
+
+enum BookmarkPosition {
+    /// The file is open, so the bookmark follows the edits made to it.
+    Anchor(WeakModel<Buffer>, Anchor),
+    /// The bookmark was restored from the database, or its buffer was released, and its file
+    /// hasn't been opened since.
+    Row(u32),
+}
+
+impl Bookmark {
+    /// The zero-based row the bookmark currently points at, unless its buffer is being released.
+    pub fn row(&self, cx: &AppContext) -> Option<u32> {
+        match &self.position {
+            BookmarkPosition::Anchor(buffer, anchor) => Some(
+                buffer
+                    .upgrade()?
+                    .read(cx)
+                    .summary_for_anchor::<Point>(anchor)
+                    .row,
+            ),
+            BookmarkPosition::Row(row) => Some(*row),
+        }
+    }
+
+    fn line_text(&self, cx: &AppContext) -> Option<String> {
+        let BookmarkPosition::Anchor(buffer, _) = &self.position else {
+            return None;
+        };
+        let buffer = buffer.upgrade()?;
+        let buffer = buffer.read(cx);
+        let row = self.row(cx)?;
+        let line = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        Some(
+            buffer
+                .text_for_range(line)
+                .collect::<String>()
+                .trim()
+                .to_string(),
+        )
+    }
+}
+
+/// The bookmarks of a single workspace, persisted across restarts.
+pub struct BookmarkStore {
+    workspace_id: WorkspaceId,
+    bookmarks: Vec<Bookmark>,
+    buffer_subscriptions: HashMap<EntityId, [Subscription; 2]>,
+}
+
+impl BookmarkStore {
+    fn new(workspace_id: WorkspaceId, _: &mut ModelContext<Self>) -> Self {
+        let bookmarks = DB
+            .get_bookmarks(workspace_id)
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(abs_path, row, name)| Bookmark {
+                abs_path: abs_path.into(),
+                name: name.map(SharedString::from),
+                position: BookmarkPosition::Row(row),
+            })
+            .collect();
+        Self {
+            workspace_id,
+            bookmarks,
+            buffer_subscriptions: HashMap::default(),
+        }
+    }
+
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// Returns the path and row of every bookmark, ordered by file and then by row.
+    pub fn sorted_positions(&self, cx: &AppContext) -> Vec<(Arc<Path>, u32)> {
+        let mut positions = self
+            .bookmarks
+            .iter()
+            .filter_map(|bookmark| Some((bookmark.abs_path.clone(), bookmark.row(cx)?)))
+            .collect::<Vec<_>>();
+        positions.sort();
+        positions.dedup();
+        positions
+    }
+
+    /// Removes the bookmark on the given row of the buffer, or adds one if there isn't any.
+    pub fn toggle(
+        &mut self,
+        buffer: Model<Buffer>,
+        abs_path: Arc<Path>,
+        row: u32,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.resolve(&buffer, &abs_path, cx);
+        let existing = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.abs_path == abs_path && bookmark.row(cx) == Some(row));
+        if let Some(ix) = existing {
+            self.bookmarks.remove(ix);
+        } else {
+            self.push(&buffer, abs_path, row, None, cx);
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    /// Returns the name of the bookmark on the given row of the file, if it has one.
+    pub fn name(&self, abs_path: &Path, row: u32, cx: &AppContext) -> Option<SharedString> {
+        self.bookmark_at(abs_path, row, cx)?.name.clone()
+    }
+
+    /// Names the bookmark on the given row of the buffer, adding one if there isn't any.
+    ///
+    /// An empty name removes the bookmark's name.
+    pub fn set_name(
+        &mut self,
+        buffer: Model<Buffer>,
+        abs_path: Arc<Path>,
+        row: u32,
+        name: &str,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.resolve(&buffer, &abs_path, cx);
+        let name = Some(name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| SharedString::from(name.to_string()));
+        let existing = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.abs_path == abs_path && bookmark.row(cx) == Some(row));
+        if let Some(ix) = existing {
+            self.bookmarks[ix].name = name;
+        } else {
+            self.push(&buffer, abs_path, row, name, cx);
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn push(
+        &mut self,
+        buffer: &Model<Buffer>,
+        abs_path: Arc<Path>,
+        row: u32,
+        name: Option<SharedString>,
+        cx: &AppContext,
+    ) {
+        let anchor = buffer.read(cx).anchor_after(Point::new(row, 0));
+        self.bookmarks.push(Bookmark {
+            abs_path,
+            name,
+            position: BookmarkPosition::Anchor(buffer.downgrade(), anchor),
+        });
+    }
+
+    fn bookmark_at(&self, abs_path: &Path, row: u32, cx: &AppContext) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| {
+            bookmark.abs_path.as_ref() == abs_path && bookmark.row(cx) == Some(row)
+        })
+    }
+
+    /// Attaches the bookmarks restored from the database to the buffer of their file, so that
+    /// they start following its edits.
+    fn resolve(&mut self, buffer: &Model<Buffer>, abs_path: &Path, cx: &mut ModelContext<Self>) {
+        for bookmark in &mut self.bookmarks {
+            if let BookmarkPosition::Row(row) = bookmark.position {
+                if bookmark.abs_path.as_ref() == abs_path {
+                    let buffer_snapshot = buffer.read(cx);
+                    let point = buffer_snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                    let anchor = buffer_snapshot.anchor_after(point);
+                    bookmark.position = BookmarkPosition::Anchor(buffer.downgrade(), anchor);
+                }
+            }
+        }
+
+        // Bookmarks are stored by row, so they need to be rewritten whenever the file on disk
+        // changes underneath them.
+        let buffer_id = buffer.entity_id();
+        self.buffer_subscriptions
+            .entry(buffer_id)
+            .or_insert_with(|| {
+                [
+                    cx.subscribe(buffer, |this, _, event, cx| {
+                        if matches!(event, language::Event::Saved | language::Event::Reloaded) {
+                            this.serialize(cx);
+                        }
+                    }),
+                    cx.observe_release(buffer, move |this, buffer, _| {
+                        this.release_buffer(buffer_id, buffer)
+                    }),
+                ]
+            });
+    }
+
+    /// Falls back to the current rows of the bookmarks in a buffer that's going away, so that
+    /// the store doesn't keep closed files open.
+    fn release_buffer(&mut self, buffer_id: EntityId, buffer: &Buffer) {
+        self.buffer_subscriptions.remove(&buffer_id);
+        for bookmark in &mut self.bookmarks {
+            if let BookmarkPosition::Anchor(bookmark_buffer, anchor) = &bookmark.position {
+                if bookmark_buffer.entity_id() == buffer_id {
+                    let row = buffer.summary_for_anchor::<Point>(anchor).row;
+                    bookmark.position = BookmarkPosition::Row(row);
+                }
+            }
+        }
+    }
+
+    fn serialize(&self, cx: &mut ModelContext<Self>) {
+        let workspace_id = self.workspace_id;
+        let bookmarks = self
+            .sorted_positions(cx)
+            .into_iter()
+            .map(|(abs_path, row)| {
+                let name = self.name(&abs_path, row, cx).map(|name| name.to_string());
+                (abs_path.to_path_buf(), row, name)
+            })
+            .collect();
+        cx.background_executor()
+            .spawn(async move {
+                DB.save_bookmarks(workspace_id, bookmarks).await.log_err();
+            })
+            .detach();
+    }
+}
+
+/// The cursor position in the workspace's active editor.
+struct ActivePosition {
+    editor: View<Editor>,
+    buffer: Model<Buffer>,
+    abs_path: Arc<Path>,
+    row: u32,
+}
+
+impl ActivePosition {
+    fn new(workspace: &Workspace, cx: &AppContext) -> Option<Self> {
+        let editor = workspace.active_item_as::<Editor>(cx)?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        let abs_path = buffer.read(cx).file()?.as_local()?.abs_path(cx).into();
+        let row = editor.read(cx).selections.newest::<Point>(cx).head().row;
+        Some(Self {
+            editor,
+            buffer,
+            abs_path,
+            row,
+        })
+    }
+}
+
+enum Direction {
+    Next,
+    Prev,
+}
+
+fn go_to_adjacent_bookmark(
+    store: &Model<BookmarkStore>,
+    direction: Direction,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let active_position = ActivePosition::new(workspace, cx);
+    if let Some(position) = &active_position {
+        store.update(cx, |store, cx| {
+            store.resolve(&position.buffer, &position.abs_path, cx)
+        });
+    }
+
+    let positions = store.read(cx).sorted_positions(cx);
+    let current = active_position.map(|position| (position.abs_path, position.row));
+    let target = match (direction, current) {
+        (Direction::Next, Some(current)) => positions
+            .iter()
+            .find(|position| **position > current)
+            .or_else(|| positions.first()),
+        (Direction::Prev, Some(current)) => positions
+            .iter()
+            .rev()
+            .find(|position| **position < current)
+            .or_else(|| positions.last()),
+        (Direction::Next, None) => positions.first(),
+        (Direction::Prev, None) => positions.last(),
+    };
+
+    if let Some((abs_path, row)) = target.cloned() {
+        open_bookmark(store.clone(), abs_path, row, workspace, cx).detach_and_log_err(cx);
+    }
+}
+
+/// Opens the bookmarked file and moves the cursor to the bookmarked row.
+pub fn open_bookmark(
+    store: Model<BookmarkStore>,
+    abs_path: Arc<Path>,
+    row: u32,
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    if let Some(position) = ActivePosition::new(workspace, cx) {
+        if position.abs_path == abs_path {
+            select_row(
+                &position.editor,
+                &position.buffer,
+                &store,
+                &abs_path,
+                row,
+                cx,
+            );
+            return Task::ready(Ok(()));
+        }
+    }
+
+    let open_task = workspace.open_abs_path(abs_path.to_path_buf(), true, cx);
+    cx.spawn(|workspace, mut cx| async move {
+        let editor = open_task
+            .await?
+            .downcast::<Editor>()
+            .context("bookmarked file did not open in an editor")?;
+        workspace.update(&mut cx, |_, cx| {
+            if let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
+                select_row(&editor, &buffer, &store, &abs_path, row, cx);
+            }
+        })
+    })
+}
+
+fn select_row(
+    editor: &View<Editor>,
+    buffer: &Model<Buffer>,
+    store: &Model<BookmarkStore>,
+    abs_path: &Path,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let point = store.update(cx, |store, cx| {
+        store.resolve(buffer, abs_path, cx);
+        buffer.read(cx).clip_point(Point::new(row, 0), Bias::Left)
+    });
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+            s.select_ranges([point..point])
+        });
+    });
+}
+
+/// A modal for naming the bookmark on the cursor's line.
+pub struct BookmarkNameEditor {
+    name_editor: View<Editor>,
+    store: Model<BookmarkStore>,
+    position: ActivePosition,
+    _subscription: Subscription,
+}
+
+impl ModalView for BookmarkNameEditor {}
+
+impl EventEmitter<DismissEvent> for BookmarkNameEditor {}
+
+impl FocusableView for BookmarkNameEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl BookmarkNameEditor {
+    fn new(
+        store: Model<BookmarkStore>,
+        position: ActivePosition,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let name = store.update(cx, |store, cx| {
+            store.resolve(&position.buffer, &position.abs_path, cx);
+            store.name(&position.abs_path, position.row, cx)
+        });
+        let name_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark name", cx);
+            if let Some(name) = name {
+                editor.set_text(name.to_string(), cx);
+                editor.select_all(&Default::default(), cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&name_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            name_editor,
+            store,
+            position,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let name = self.name_editor.read(cx).text(cx);
+        let position = &self.position;
+        self.store.update(cx, |store, cx| {
+            store.set_name(
+                position.buffer.clone(),
+                position.abs_path.clone(),
+                position.row,
+                &name,
+                cx,
+            )
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkNameEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_2(cx)
+            .key_context("BookmarkNameEditor")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .px_1()
+            .py_0p5()
+            .child(div().px_1().py_0p5().child(self.name_editor.clone()))
+    }
+}
+
+/// A modal listing the workspace's bookmarks.
+pub struct BookmarkList {
+    picker: View<Picker<BookmarkListDelegate>>,
+}
+
+impl ModalView for BookmarkList {}
+
+impl EventEmitter<DismissEvent> for BookmarkList {}
+
+impl FocusableView for BookmarkList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for BookmarkList {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl BookmarkList {
+    fn new(
+        store: Model<BookmarkStore>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = BookmarkListDelegate::new(cx.view().downgrade(), store, workspace, cx);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+struct BookmarkCandidate {
+    abs_path: Arc<Path>,
+    row: u32,
+    name: Option<SharedString>,
+    line_text: Option<String>,
+}
+
+pub struct BookmarkListDelegate {
+    bookmark_list: WeakView<BookmarkList>,
+    store: Model<BookmarkStore>,
+    workspace: WeakView<Workspace>,
+    candidates: Vec<BookmarkCandidate>,
+    match_candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarkListDelegate {
+    fn new(
+        bookmark_list: WeakView<BookmarkList>,
+        store: Model<BookmarkStore>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<BookmarkList>,
+    ) -> Self {
+        let worktree_roots = workspace
+            .upgrade()
+            .map(|workspace| {
+                workspace
+                    .read(cx)
+                    .project()
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let bookmarks = store.read(cx);
+        let candidates = bookmarks
+            .sorted_positions(cx)
+            .into_iter()
+            .map(|(abs_path, row)| {
+                let bookmark = bookmarks.bookmark_at(&abs_path, row, cx);
+                BookmarkCandidate {
+                    name: bookmark.and_then(|bookmark| bookmark.name.clone()),
+                    line_text: bookmark.and_then(|bookmark| bookmark.line_text(cx)),
+                    abs_path,
+                    row,
+                }
+            })
+            .collect::<Vec<_>>();
+        let match_candidates = candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| {
+                let path = worktree_roots
+                    .iter()
+                    .find_map(|root| candidate.abs_path.strip_prefix(root.parent()?).ok())
+                    .unwrap_or(&candidate.abs_path);
+                let location = format!("{}:{}", path.to_string_lossy(), candidate.row + 1);
+                let label = match &candidate.name {
+                    Some(name) => format!("{name} ({location})"),
+                    None => location,
+                };
+                StringMatchCandidate::new(id, label)
+            })
+            .collect();
+
+        Self {
+            bookmark_list,
+            store,
+            workspace,
+            candidates,
+            match_candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarkListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search bookmarks...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No bookmarks".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.match_candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let candidate = &self.candidates[mat.candidate_id];
+            let abs_path = candidate.abs_path.clone();
+            let row = candidate.row;
+            let store = self.store.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(store, abs_path, row, workspace, cx).detach_and_log_err(cx);
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.bookmark_list
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let candidate = &self.candidates[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .children(candidate.line_text.clone().map(|text| {
+                            Label::new(text).color(Color::Muted).size(LabelSize::Small)
+                        })),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::DisplayPoint;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::{AppState, CloseAllItems, SaveIntent};
+
+    #[gpui::test]
+    async fn test_bookmarks_follow_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "one\ntwo\nthree\nfour\nfive\n",
+                "b.rs": "six\nseven\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let editor_a = open_editor("/dir/a.rs", &workspace, cx).await;
+        select_row_in(&editor_a, 2, cx);
+        cx.dispatch_action(ToggleBookmark);
+
+        let editor_b = open_editor("/dir/b.rs", &workspace, cx).await;
+        select_row_in(&editor_b, 1, cx);
+        cx.dispatch_action(ToggleBookmark);
+
+        // Inserting lines above the bookmark moves it down along with its line.
+        editor_a.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| s.select_ranges([0..0]));
+            editor.insert("zero\nhalf\n", cx);
+        });
+        let bookmark_list = open_bookmark_list(&workspace, cx);
+        assert_eq!(
+            bookmark_labels(&bookmark_list, cx),
+            vec!["dir/a.rs:5".to_string(), "dir/b.rs:2".to_string()]
+        );
+
+        // Confirming a bookmark opens its file at the bookmarked line.
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, cx| workspace
+            .active_modal::<BookmarkList>(cx)
+            .is_none()));
+        assert_active_editor_row(&workspace, &editor_a, 4, cx);
+    }
+
+    #[gpui::test]
+    async fn test_navigating_between_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "one\ntwo\nthree\nfour\nfive\n",
+                "b.rs": "six\nseven\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let editor_a = open_editor("/dir/a.rs", &workspace, cx).await;
+        select_row_in(&editor_a, 1, cx);
+        cx.dispatch_action(ToggleBookmark);
+        select_row_in(&editor_a, 3, cx);
+        cx.dispatch_action(ToggleBookmark);
+        let editor_b = open_editor("/dir/b.rs", &workspace, cx).await;
+        select_row_in(&editor_b, 0, cx);
+        cx.dispatch_action(ToggleBookmark);
+
+        // Navigation wraps around at the last bookmark.
+        cx.dispatch_action(NextBookmark);
+        cx.run_until_parked();
+        assert_active_editor_row(&workspace, &editor_a, 1, cx);
+        cx.dispatch_action(NextBookmark);
+        cx.run_until_parked();
+        assert_active_editor_row(&workspace, &editor_a, 3, cx);
+        cx.dispatch_action(NextBookmark);
+        cx.run_until_parked();
+        assert_active_editor_row(&workspace, &editor_b, 0, cx);
+        cx.dispatch_action(PreviousBookmark);
+        cx.run_until_parked();
+        assert_active_editor_row(&workspace, &editor_a, 3, cx);
+
+        // Toggling an existing bookmark removes it.
+        cx.dispatch_action(ToggleBookmark);
+        let bookmark_list = open_bookmark_list(&workspace, cx);
+        assert_eq!(
+            bookmark_labels(&bookmark_list, cx),
+            vec!["dir/a.rs:2".to_string(), "dir/b.rs:1".to_string()]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_bookmarks_outlive_their_buffers(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "one\ntwo\nthree\nfour\nfive\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let editor = open_editor("/dir/a.rs", &workspace, cx).await;
+        select_row_in(&editor, 2, cx);
+        cx.dispatch_action(ToggleBookmark);
+
+        // Lines inserted at the start of the bookmarked line push it down.
+        editor.update(cx, |editor, cx| editor.insert("new\n", cx));
+        let buffer = editor
+            .read_with(cx, |editor, cx| editor.buffer().read(cx).as_singleton())
+            .unwrap()
+            .downgrade();
+        drop(editor);
+
+        // Closing the file doesn't keep its buffer around, and the bookmark stays on its line.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.close_all_items(
+                        &CloseAllItems {
+                            save_intent: Some(SaveIntent::Save),
+                        },
+                        cx,
+                    )
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(buffer.upgrade().is_none());
+        let bookmark_list = open_bookmark_list(&workspace, cx);
+        assert_eq!(
+            bookmark_labels(&bookmark_list, cx),
+            vec!["dir/a.rs:4".to_string()]
+        );
+
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        let editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        assert_active_editor_row(&workspace, &editor, 3, cx);
+    }
+
+    #[gpui::test]
+    async fn test_naming_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "a.rs": "one\ntwo\nthree\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Naming a line without a bookmark adds one.
+        let editor = open_editor("/dir/a.rs", &workspace, cx).await;
+        select_row_in(&editor, 1, cx);
+        cx.dispatch_action(RenameBookmark);
+        cx.run_until_parked();
+        cx.simulate_input("Parser entry");
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert!(workspace.update(cx, |workspace, cx| workspace
+            .active_modal::<BookmarkNameEditor>(cx)
+            .is_none()));
+        let bookmark_list = open_bookmark_list(&workspace, cx);
+        assert_eq!(
+            bookmark_labels(&bookmark_list, cx),
+            vec!["Parser entry (dir/a.rs:2)".to_string()]
+        );
+        cx.dispatch_action(menu::Cancel);
+
+        // Renaming starts from the current name, and an empty name removes it.
+        cx.dispatch_action(RenameBookmark);
+        cx.run_until_parked();
+        let name_editor = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<BookmarkNameEditor>(cx)
+                .unwrap()
+                .read(cx)
+                .name_editor
+                .clone()
+        });
+        assert_eq!(
+            name_editor.update(cx, |editor, cx| editor.text(cx)),
+            "Parser entry"
+        );
+        name_editor.update(cx, |editor, cx| editor.set_text("", cx));
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        let bookmark_list = open_bookmark_list(&workspace, cx);
+        assert_eq!(
+            bookmark_labels(&bookmark_list, cx),
+            vec!["dir/a.rs:2".to_string()]
+        );
+    }
+
+    async fn open_editor(
+        abs_path: &str,
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Editor> {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(abs_path.into(), true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap()
+    }
+
+    fn select_row_in(editor: &View<Editor>, row: u32, cx: &mut VisualTestContext) {
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(row, 0)..DisplayPoint::new(row, 0)])
+            })
+        });
+    }
+
+    fn open_bookmark_list(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<BookmarkListDelegate>> {
+        cx.dispatch_action(ShowBookmarks);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<BookmarkList>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn bookmark_labels(
+        bookmark_list: &View<Picker<BookmarkListDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        bookmark_list.update(cx, |picker, _| {
+            picker
+                .delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.clone())
+                .collect()
+        })
+    }
+
+    #[track_caller]
+    fn assert_active_editor_row(
+        workspace: &View<Workspace>,
+        expected_editor: &View<Editor>,
+        expected_row: u32,
+        cx: &mut VisualTestContext,
+    ) {
+        workspace.update(cx, |workspace, cx| {
+            let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+            assert_eq!(editor.entity_id(), expected_editor.entity_id());
+            let head = editor.read(cx).selections.newest::<Point>(cx).head();
+            assert_eq!(head, Point::new(expected_row, 0));
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
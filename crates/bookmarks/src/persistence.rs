@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // bookmarks(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   buffer_row: u32,
+    //   name: Option<String>,
+    // )
+    pub static ref DB: BookmarksDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE bookmarks (
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                buffer_row INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, path, buffer_row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            ALTER TABLE bookmarks ADD COLUMN name TEXT;
+        )];
+}
+
+impl BookmarksDb {
+    query! {
+        pub fn get_bookmarks(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, buffer_row, name FROM bookmarks
+            WHERE workspace_id = ?
+        }
+    }
+
+    /// Replaces all of the workspace's bookmarks with the given ones.
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)?;
+                for (path, buffer_row, name) in bookmarks {
+                    conn.exec_bound(sql!(
+                        INSERT OR IGNORE INTO bookmarks(workspace_id, path, buffer_row, name)
+                        VALUES (?, ?, ?, ?)
+                    ))?((workspace_id, path, buffer_row, name))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -37,9 +37,20 @@ impl Render for Breadcrumbs {
         let Some(active_item) = self.active_item.as_ref() else {
             return element;
         };
-        let Some(mut segments) = active_item.breadcrumbs(cx.theme(), cx) else {
+        let Some(segments) = active_item.breadcrumbs(cx.theme(), cx) else {
             return element;
         };
+        let editor = active_item
+            .downcast::<Editor>()
+            .map(|editor| editor.downgrade());
+
+        // Keep track of each segment's index, so that the symbols can still be found after
+        // eliding the middle segments.
+        let mut segments = segments
+            .into_iter()
+            .enumerate()
+            .map(|(ix, segment)| (Some(ix), segment))
+            .collect::<Vec<_>>();
 
         let prefix_end_ix = cmp::min(segments.len(), MAX_SEGMENTS / 2);
         let suffix_start_ix = cmp::max(
@@ -49,15 +60,18 @@ impl Render for Breadcrumbs {
         if suffix_start_ix > prefix_end_ix {
             segments.splice(
                 prefix_end_ix..suffix_start_ix,
-                Some(BreadcrumbText {
-                    text: "⋯".into(),
-                    highlights: None,
-                    font: None,
-                }),
+                Some((
+                    None,
+                    BreadcrumbText {
+                        text: "⋯".into(),
+                        highlights: None,
+                        font: None,
+                    },
+                )),
             );
         }
 
-        let highlighted_segments = segments.into_iter().map(|segment| {
+        let highlighted_segments = segments.into_iter().map(|(ix, segment)| {
             let mut text_style = cx.text_style();
             if let Some(font) = segment.font {
                 text_style.font_family = font.family;
@@ -67,19 +81,35 @@ impl Render for Breadcrumbs {
             }
             text_style.color = Color::Muted.color(cx);
 
-            StyledText::new(segment.text.replace('\n', "␤"))
-                .with_highlights(&text_style, segment.highlights.unwrap_or_default())
-                .into_any()
+            let text = StyledText::new(segment.text.replace('\n', "␤"))
+                .with_highlights(&text_style, segment.highlights.unwrap_or_default());
+
+            // The segments after the file name are the symbols containing the cursor.
+            match (ix, editor.clone()) {
+                (Some(ix), Some(editor)) if ix > 0 => div()
+                    .id(("breadcrumb-symbol", ix))
+                    .rounded_sm()
+                    .hover(|style| style.bg(cx.theme().colors().element_hover))
+                    .child(text)
+                    .on_click(move |_, cx| {
+                        cx.stop_propagation();
+                        if let Some(editor) = editor.upgrade() {
+                            editor.update(cx, |editor, cx| {
+                                editor.go_to_enclosing_symbol(ix - 1, cx);
+                                editor.focus(cx);
+                            });
+                        }
+                    })
+                    .into_any(),
+                _ => text.into_any(),
+            }
         });
         let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
             Label::new("›").color(Color::Muted).into_any_element()
         });
 
         let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
-        match active_item
-            .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
+        match editor {
             Some(editor) => element.child(
                 ButtonLike::new("toggle outline view")
                     .child(breadcrumbs_stack)
@@ -6,7 +6,7 @@ use anyhow::{anyhow, Result};
 use audio::{Audio, Sound};
 use client::{
     proto::{self, PeerId},
-    ChannelId, Client, ParticipantIndex, TypedEnvelope, User, UserStore,
+    ChannelId, Client, ErrorCode, ErrorCodeExt, ParticipantIndex, TypedEnvelope, User, UserStore,
 };
 use collections::{BTreeMap, HashMap, HashSet};
 use fs::Fs;
@@ -495,9 +495,9 @@ impl Room {
             log::info!("reconnection failed, leaving room");
             let _ = this.update(&mut cx, |this, cx| this.leave(cx))?;
         }
-        Err(anyhow!(
-            "can't reconnect to room: client failed to re-establish connection"
-        ))
+        Err(ErrorCode::Disconnected
+            .message("can't reconnect to room: client failed to re-establish connection".into())
+            .anyhow())
     }
 
     fn rejoin(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
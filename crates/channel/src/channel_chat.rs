@@ -278,32 +278,49 @@ impl ChannelChat {
             return None;
         }
 
+        let before_message_id = self.first_loaded_message_id()?;
+        let load = self.load_messages(before_message_id, None, cx);
+        Some(cx.spawn(move |_, _| async move { load.await.log_err().map(drop) }))
+    }
+
+    /// Loads up to `count` of the messages sent before `before_message_id`, merging them into the
+    /// loaded ones, and resolves with how many of them weren't loaded already.
+    pub fn load_messages_before(
+        &mut self,
+        before_message_id: u64,
+        count: usize,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<usize>> {
+        self.load_messages(before_message_id, Some(count as u32), cx)
+    }
+
+    fn load_messages(
+        &mut self,
+        before_message_id: u64,
+        count: Option<u32>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<usize>> {
         let rpc = self.rpc.clone();
         let user_store = self.user_store.clone();
         let channel_id = self.channel_id;
-        let before_message_id = self.first_loaded_message_id()?;
-        Some(cx.spawn(move |this, mut cx| {
-            async move {
-                let response = rpc
-                    .request(proto::GetChannelMessages {
-                        channel_id: channel_id.0,
-                        before_message_id,
-                    })
-                    .await?;
-                Self::handle_loaded_messages(
-                    this,
-                    user_store,
-                    rpc,
-                    response.messages,
-                    response.done,
-                    &mut cx,
-                )
+        cx.spawn(move |this, mut cx| async move {
+            let response = rpc
+                .request(proto::GetChannelMessages {
+                    channel_id: channel_id.0,
+                    before_message_id,
+                    count,
+                })
                 .await?;
-
-                anyhow::Ok(())
-            }
-            .log_err()
-        }))
+            Self::handle_loaded_messages(
+                this,
+                user_store,
+                rpc,
+                response.messages,
+                response.done,
+                &mut cx,
+            )
+            .await
+        })
     }
 
     pub fn first_loaded_message_id(&mut self) -> Option<u64> {
@@ -383,23 +400,27 @@ impl ChannelChat {
         proto_messages: Vec<proto::ChannelMessage>,
         loaded_all_messages: bool,
         cx: &mut AsyncAppContext,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let loaded_messages = messages_from_proto(proto_messages, &user_store, cx).await?;
 
-        let first_loaded_message_id = loaded_messages.first().map(|m| m.id);
-        let loaded_message_ids = this.update(cx, |this, _| {
+        let first_loaded_message_id: Option<u64> =
+            loaded_messages.first().and_then(|m| m.id.into());
+        let (loaded_message_ids, added_count) = this.update(cx, |this, _| {
             let mut loaded_message_ids: HashSet<u64> = HashSet::default();
-            for message in loaded_messages.iter() {
+            for message in this.messages.iter() {
                 if let Some(saved_message_id) = message.id.into() {
                     loaded_message_ids.insert(saved_message_id);
                 }
             }
-            for message in this.messages.iter() {
+            let mut added_count = 0;
+            for message in loaded_messages.iter() {
                 if let Some(saved_message_id) = message.id.into() {
-                    loaded_message_ids.insert(saved_message_id);
+                    if loaded_message_ids.insert(saved_message_id) {
+                        added_count += 1;
+                    }
                 }
             }
-            loaded_message_ids
+            (loaded_message_ids, added_count)
         })?;
 
         let missing_ancestors = loaded_messages
@@ -425,15 +446,23 @@ impl ChannelChat {
             Some(messages_from_proto(response.messages, &user_store, cx).await?)
         };
         this.update(cx, |this, cx| {
-            this.first_loaded_message_id = first_loaded_message_id.and_then(|msg_id| msg_id.into());
-            this.loaded_all_messages = loaded_all_messages;
+            // Messages from later in the history don't change where the loaded ones start.
+            let is_earliest = first_loaded_message_id
+                .zip(this.first_loaded_message_id)
+                .map_or(true, |(loaded_id, first_id)| loaded_id <= first_id);
+            if is_earliest {
+                if first_loaded_message_id.is_some() {
+                    this.first_loaded_message_id = first_loaded_message_id;
+                }
+                this.loaded_all_messages = loaded_all_messages;
+            }
             this.insert_messages(loaded_messages, cx);
             if let Some(loaded_ancestors) = loaded_ancestors {
                 this.insert_messages(loaded_ancestors, cx);
             }
         })?;
 
-        Ok(())
+        Ok(added_count)
     }
 
     pub fn rejoin(&mut self, cx: &mut ModelContext<Self>) {
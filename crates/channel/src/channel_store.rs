@@ -16,7 +16,7 @@ use rpc::{
     TypedEnvelope,
 };
 use settings::Settings;
-use std::{mem, sync::Arc, time::Duration};
+use std::{collections::BTreeSet, mem, sync::Arc, time::Duration};
 use util::{maybe, ResultExt};
 
 pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
@@ -83,6 +83,8 @@ pub struct ChannelState {
     latest_notes_version: NotesVersion,
     observed_notes_version: NotesVersion,
     observed_chat_message: Option<u64>,
+    /// The messages received since the last one that was acknowledged.
+    unread_messages: BTreeSet<u64>,
     role: Option<ChannelRole>,
     projects: HashSet<ProjectId>,
 }
@@ -378,6 +380,33 @@ impl ChannelStore {
             .is_some_and(|state| state.has_new_messages())
     }
 
+    /// The number of messages the user hasn't seen in the channel's chat. Only the latest one is
+    /// known about for the messages sent while disconnected.
+    pub fn unread_count(&self, channel_id: ChannelId) -> usize {
+        self.channel_states
+            .get(&channel_id)
+            .map_or(0, |state| state.unread_messages.len())
+    }
+
+    /// Acknowledges the channel's latest message, telling the server so it's remembered across
+    /// sessions.
+    pub fn mark_channel_read(&mut self, channel_id: ChannelId, cx: &mut ModelContext<Self>) {
+        let Some(message_id) = self
+            .channel_states
+            .get(&channel_id)
+            .and_then(|state| state.latest_chat_message)
+        else {
+            return;
+        };
+        self.client
+            .send(proto::AckChannelMessage {
+                channel_id: channel_id.0,
+                message_id,
+            })
+            .log_err();
+        self.acknowledge_message_id(channel_id, message_id, cx);
+    }
+
     pub fn set_acknowledged_message_id(&mut self, channel_id: ChannelId, message_id: Option<u64>) {
         if let Some(state) = self.channel_states.get_mut(&channel_id) {
             state.latest_chat_message = message_id;
@@ -1261,9 +1290,16 @@ impl ChannelState {
     fn acknowledge_message_id(&mut self, message_id: u64) {
         let observed = self.observed_chat_message.get_or_insert(message_id);
         *observed = (*observed).max(message_id);
+        self.unread_messages = self.unread_messages.split_off(&(message_id + 1));
     }
 
     fn update_latest_message_id(&mut self, message_id: u64) {
+        if self
+            .observed_chat_message
+            .map_or(true, |observed| message_id > observed)
+        {
+            self.unread_messages.insert(message_id);
+        }
         self.latest_chat_message =
             Some(message_id.max(self.latest_chat_message.unwrap_or_default()));
     }
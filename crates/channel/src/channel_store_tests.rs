@@ -337,6 +337,269 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_editing_and_removing_channel_messages(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_id = 5;
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+        }],
+        ..Default::default()
+    });
+    let get_users = server.receive::<proto::GetUsers>().await.unwrap();
+    server.respond(
+        get_users.receipt(),
+        proto::UsersResponse {
+            users: vec![proto::User {
+                id: 5,
+                github_login: "nathansobo".into(),
+                avatar_url: "http://avatar.com/nathansobo".into(),
+            }],
+        },
+    );
+    cx.executor().run_until_parked();
+
+    let channel = channel_store.update(cx, |store, cx| {
+        let channel_id = store.ordered_channels().next().unwrap().1.id;
+        store.open_channel_chat(channel_id, cx)
+    });
+    let join_channel = server.receive::<proto::JoinChannelChat>().await.unwrap();
+    server.respond(
+        join_channel.receipt(),
+        proto::JoinChannelChatResponse {
+            messages: vec![proto::ChannelMessage {
+                id: 10,
+                body: "a".into(),
+                timestamp: 1000,
+                sender_id: 5,
+                mentions: vec![],
+                nonce: Some(1.into()),
+                reply_to_message_id: None,
+                edited_at: None,
+            }],
+            done: true,
+        },
+    );
+    let channel = channel.await.unwrap();
+
+    // Editing a message sends its new body, and marks it as edited right away.
+    let update = channel.update(cx, |channel, cx| {
+        channel
+            .update_message(
+                10,
+                MessageParams {
+                    text: "b".into(),
+                    mentions: vec![],
+                    reply_to_message_id: None,
+                },
+                cx,
+            )
+            .unwrap()
+    });
+    let update_message = server
+        .receive::<proto::UpdateChannelMessage>()
+        .await
+        .unwrap();
+    assert_eq!(update_message.payload.channel_id, channel_id);
+    assert_eq!(update_message.payload.message_id, 10);
+    assert_eq!(update_message.payload.body, "b");
+    channel.update(cx, |channel, _| {
+        let message = channel.message(0);
+        assert_eq!(message.body, "b");
+        assert!(message.edited_at.is_some());
+    });
+    server.respond(update_message.receipt(), proto::Ack {});
+    update.await.unwrap();
+
+    // Removing a message only drops it once the server acknowledges it.
+    let remove = channel.update(cx, |channel, cx| channel.remove_message(10, cx));
+    let remove_message = server
+        .receive::<proto::RemoveChannelMessage>()
+        .await
+        .unwrap();
+    assert_eq!(remove_message.payload.channel_id, channel_id);
+    assert_eq!(remove_message.payload.message_id, 10);
+    channel.update(cx, |channel, _| assert_eq!(channel.message_count(), 1));
+    server.respond(remove_message.receipt(), proto::Ack {});
+    remove.await.unwrap();
+    channel.update(cx, |channel, _| assert_eq!(channel.message_count(), 0));
+}
+
+#[gpui::test]
+async fn test_loading_messages_before(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_id = 5;
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+        }],
+        ..Default::default()
+    });
+    let get_users = server.receive::<proto::GetUsers>().await.unwrap();
+    server.respond(
+        get_users.receipt(),
+        proto::UsersResponse {
+            users: vec![proto::User {
+                id: 5,
+                github_login: "nathansobo".into(),
+                avatar_url: "http://avatar.com/nathansobo".into(),
+            }],
+        },
+    );
+    cx.executor().run_until_parked();
+
+    let channel = channel_store.update(cx, |store, cx| {
+        let channel_id = store.ordered_channels().next().unwrap().1.id;
+        store.open_channel_chat(channel_id, cx)
+    });
+    let join_channel = server.receive::<proto::JoinChannelChat>().await.unwrap();
+    server.respond(
+        join_channel.receipt(),
+        proto::JoinChannelChatResponse {
+            messages: vec![
+                proto::ChannelMessage {
+                    id: 10,
+                    body: "a".into(),
+                    timestamp: 1000,
+                    sender_id: 5,
+                    mentions: vec![],
+                    nonce: Some(1.into()),
+                    reply_to_message_id: None,
+                    edited_at: None,
+                },
+                proto::ChannelMessage {
+                    id: 11,
+                    body: "b".into(),
+                    timestamp: 1001,
+                    sender_id: 5,
+                    mentions: vec![],
+                    nonce: Some(2.into()),
+                    reply_to_message_id: None,
+                    edited_at: None,
+                },
+            ],
+            done: false,
+        },
+    );
+    let channel = channel.await.unwrap();
+
+    // Messages that are already loaded aren't counted or duplicated.
+    let load = channel.update(cx, |channel, cx| channel.load_messages_before(11, 2, cx));
+    let get_messages = server.receive::<proto::GetChannelMessages>().await.unwrap();
+    assert_eq!(get_messages.payload.before_message_id, 11);
+    assert_eq!(get_messages.payload.count, Some(2));
+    server.respond(
+        get_messages.receipt(),
+        proto::GetChannelMessagesResponse {
+            messages: vec![
+                proto::ChannelMessage {
+                    id: 9,
+                    body: "x".into(),
+                    timestamp: 999,
+                    sender_id: 5,
+                    mentions: vec![],
+                    nonce: Some(3.into()),
+                    reply_to_message_id: None,
+                    edited_at: None,
+                },
+                proto::ChannelMessage {
+                    id: 10,
+                    body: "a".into(),
+                    timestamp: 1000,
+                    sender_id: 5,
+                    mentions: vec![],
+                    nonce: Some(1.into()),
+                    reply_to_message_id: None,
+                    edited_at: None,
+                },
+            ],
+            done: false,
+        },
+    );
+    assert_eq!(load.await.unwrap(), 1);
+    channel.update(cx, |channel, _| {
+        assert_eq!(
+            channel
+                .messages_in_range(0..channel.message_count())
+                .map(|message| message.body.clone())
+                .collect::<Vec<_>>(),
+            &["x", "a", "b"]
+        );
+        assert_eq!(channel.first_loaded_message_id(), Some(9));
+    });
+}
+
+#[gpui::test]
+async fn test_channel_unread_count(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    // Only the latest message is known about from while the client was away.
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: 1,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+        }],
+        latest_channel_message_ids: vec![proto::ChannelMessageId {
+            channel_id: 1,
+            message_id: 5,
+        }],
+        ..Default::default()
+    });
+    let get_users = server.receive::<proto::GetUsers>().await.unwrap();
+    server.respond(get_users.receipt(), proto::UsersResponse { users: vec![] });
+    cx.executor().run_until_parked();
+    let channel_id = channel_store.update(cx, |store, cx| {
+        let channel_id = store.ordered_channels().next().unwrap().1.id;
+        assert_eq!(store.unread_count(channel_id), 1);
+
+        store.update_latest_message_id(channel_id, 7, cx);
+        store.update_latest_message_id(channel_id, 9, cx);
+        assert_eq!(store.unread_count(channel_id), 3);
+
+        store.acknowledge_message_id(channel_id, 7, cx);
+        assert_eq!(store.unread_count(channel_id), 1);
+
+        // Messages that were seen as they arrived aren't unread.
+        store.acknowledge_message_id(channel_id, 11, cx);
+        store.update_latest_message_id(channel_id, 11, cx);
+        assert_eq!(store.unread_count(channel_id), 0);
+
+        store.update_latest_message_id(channel_id, 12, cx);
+        assert_eq!(store.unread_count(channel_id), 1);
+        channel_id
+    });
+
+    // Marking the channel as read tells the server.
+    channel_store.update(cx, |store, cx| store.mark_channel_read(channel_id, cx));
+    let ack = server.receive::<proto::AckChannelMessage>().await.unwrap();
+    assert_eq!(ack.payload.channel_id, channel_id.0);
+    assert_eq!(ack.payload.message_id, 12);
+    channel_store.update(cx, |store, _| {
+        assert_eq!(store.unread_count(channel_id), 0);
+        assert!(!store.has_new_messages(channel_id));
+    });
+}
+
 fn init_test(cx: &mut AppContext) -> Model<ChannelStore> {
     let settings_store = SettingsStore::test(cx);
     cx.set_global(settings_store);
@@ -78,6 +78,8 @@ lazy_static! {
 }
 
 pub const INITIAL_RECONNECTION_DELAY: Duration = Duration::from_millis(100);
+/// The longest the client waits between attempts to reconnect.
+pub const MAX_RECONNECTION_DELAY: Duration = Duration::from_secs(30);
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
 actions!(client, [SignIn, SignOut, Reconnect]);
@@ -190,6 +192,13 @@ pub struct Client {
 pub enum EstablishConnectionError {
     #[error("upgrade required")]
     UpgradeRequired,
+    #[error(
+        "incompatible protocol version: the server speaks {server_version}, this client {client_version}"
+    )]
+    IncompatibleProtocolVersion {
+        server_version: u32,
+        client_version: u32,
+    },
     #[error("unauthorized")]
     Unauthorized,
     #[error("{0}")]
@@ -237,10 +246,20 @@ pub enum Status {
     Reconnecting,
     ReconnectionError {
         next_reconnection: Instant,
+        /// How long the client waits before that attempt.
+        delay: Duration,
     },
 }
 
 impl Status {
+    /// How long the client is waiting before its next attempt to reconnect, if it is.
+    pub fn reconnection_delay(&self) -> Option<Duration> {
+        match self {
+            Self::ReconnectionError { delay, .. } => Some(*delay),
+            _ => None,
+        }
+    }
+
     pub fn is_connected(&self) -> bool {
         matches!(self, Self::Connected { .. })
     }
@@ -305,7 +324,7 @@ impl Default for ClientState {
             status: watch::channel_with(Status::SignedOut),
             entity_id_extractors: Default::default(),
             _reconnect_task: None,
-            reconnect_interval: Duration::from_secs(5),
+            reconnect_interval: MAX_RECONNECTION_DELAY,
             models_by_message_type: Default::default(),
             entities_by_type_and_remote_id: Default::default(),
             entity_types_by_message_type: Default::default(),
@@ -533,6 +552,22 @@ impl Client {
         }
     }
 
+    /// Changes how often the connection to the server is pinged, and how long the client waits
+    /// to hear from the server before reconnecting. Takes effect on the next connection.
+    pub fn set_keepalive(&self, settings: KeepaliveSettings) {
+        self.peer.set_keepalive(settings);
+    }
+
+    pub fn keepalive(&self) -> KeepaliveSettings {
+        self.peer.keepalive()
+    }
+
+    /// Returns a stream of the messages from the server that were skipped because they
+    /// couldn't be read, so they can be reported.
+    pub fn protocol_warnings(&self) -> futures::channel::mpsc::UnboundedReceiver<ProtocolWarning> {
+        self.peer.protocol_warnings()
+    }
+
     pub fn status(&self) -> watch::Receiver<Status> {
         self.state.read().status.1.clone()
     }
@@ -555,20 +590,22 @@ impl Client {
                     #[cfg(not(any(test, feature = "test-support")))]
                     let mut rng = StdRng::from_entropy();
 
-                    let mut delay = INITIAL_RECONNECTION_DELAY;
+                    // Back off exponentially, waiting a random part of the next delay so
+                    // that clients that lost their connection together don't all retry at once.
+                    let mut backoff = INITIAL_RECONNECTION_DELAY;
                     while let Err(error) = this.authenticate_and_connect(true, &cx).await {
                         log::error!("failed to connect {}", error);
                         if matches!(*this.status().borrow(), Status::ConnectionError) {
+                            let delay = backoff.mul_f32(rng.gen_range(0.5..=1.0));
                             this.set_status(
                                 Status::ReconnectionError {
                                     next_reconnection: Instant::now() + delay,
+                                    delay,
                                 },
                                 &cx,
                             );
                             cx.background_executor().timer(delay).await;
-                            delay = delay
-                                .mul_f32(rng.gen_range(1.0..=2.0))
-                                .min(reconnect_interval);
+                            backoff = (backoff * 2).min(reconnect_interval);
                         } else {
                             break;
                         }
@@ -848,7 +885,9 @@ impl Client {
                             result = self.set_connection(conn, cx).fuse() => result,
                             _ = timeout => {
                                 self.set_status(Status::ConnectionError, cx);
-                                Err(anyhow!("timed out waiting on hello message from server"))
+                                Err(ErrorCode::Timeout
+                                    .message("timed out waiting on hello message from server".into())
+                                    .anyhow())
                             }
                         }
                     }
@@ -863,9 +902,12 @@ impl Client {
                             Err(EstablishConnectionError::Unauthorized)?
                         }
                     }
-                    Err(EstablishConnectionError::UpgradeRequired) => {
+                    Err(
+                        error @ (EstablishConnectionError::UpgradeRequired
+                        | EstablishConnectionError::IncompatibleProtocolVersion { .. }),
+                    ) => {
                         self.set_status(Status::UpgradeRequired, cx);
-                        Err(EstablishConnectionError::UpgradeRequired)?
+                        Err(error)?
                     }
                     Err(error) => {
                         self.set_status(Status::ConnectionError, cx);
@@ -875,7 +917,9 @@ impl Client {
             }
             _ = &mut timeout => {
                 self.set_status(Status::ConnectionError, cx);
-                Err(anyhow!("timed out trying to establish connection"))
+                Err(ErrorCode::Timeout
+                    .message("timed out trying to establish connection".into())
+                    .anyhow())
             }
         }
     }
@@ -1283,7 +1327,9 @@ impl Client {
         if let Status::Connected { connection_id, .. } = *self.status().borrow() {
             Ok(connection_id)
         } else {
-            Err(anyhow!("not connected"))
+            Err(ErrorCode::Disconnected
+                .message("not connected".into())
+                .anyhow())
         }
     }
 
@@ -1292,6 +1338,17 @@ impl Client {
         self.peer.send(self.connection_id()?, message)
     }
 
+    /// Sends a message ahead of lower priority ones that haven't been written yet.
+    pub fn send_with_priority<T: EnvelopedMessage>(
+        &self,
+        message: T,
+        priority: SendPriority,
+    ) -> Result<()> {
+        log::debug!("rpc send. client_id:{}, name:{}", self.id(), T::NAME);
+        self.peer
+            .send_with_priority(self.connection_id()?, message, priority)
+    }
+
     pub fn request<T: RequestMessage>(
         &self,
         request: T,
@@ -1348,6 +1405,35 @@ impl Client {
         }
     }
 
+    /// Sends a request, failing with [`rpc::ErrorCode::Timeout`] if the server doesn't respond
+    /// within `timeout`.
+    pub fn request_with_timeout<T: RequestMessage>(
+        &self,
+        request: T,
+        timeout: Duration,
+        cx: &AsyncAppContext,
+    ) -> impl Future<Output = Result<T::Response>> {
+        let client_id = self.id();
+        log::debug!(
+            "rpc request start. client_id:{}. name:{}",
+            client_id,
+            T::NAME
+        );
+        let timer = cx.background_executor().timer(timeout);
+        let response = self
+            .connection_id()
+            .map(|conn_id| self.peer.request_with_timeout(conn_id, request, timer));
+        async move {
+            let response = response?.await;
+            log::debug!(
+                "rpc request finish. client_id:{}. name:{}",
+                client_id,
+                T::NAME
+            );
+            response
+        }
+    }
+
     fn respond<T: RequestMessage>(&self, receipt: Receipt<T>, response: T::Response) -> Result<()> {
         log::debug!("rpc respond. client_id:{}. name:{}", self.id(), T::NAME);
         self.peer.respond(receipt, response)
@@ -1530,7 +1616,7 @@ pub fn parse_zed_link<'a>(link: &'a str, cx: &AppContext) -> Option<&'a str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test::FakeServer;
+    use crate::test::{Direction, FakeServer, TranscriptEntry};
 
     use clock::FakeSystemClock;
     use gpui::{BackgroundExecutor, Context, TestAppContext};
@@ -1580,6 +1666,150 @@ mod tests {
         assert_eq!(server.auth_count(), 2); // Client re-authenticated due to an invalid token
     }
 
+    #[gpui::test]
+    async fn test_reconnection_backoff(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        let mut status = client.status();
+        assert!(matches!(
+            status.next().await,
+            Some(Status::Connected { .. })
+        ));
+
+        server.forbid_connections();
+        server.disconnect();
+        let mut delays = Vec::new();
+        while delays.len() < 12 {
+            if let Some(delay) = status.next().await.unwrap().reconnection_delay() {
+                delays.push(delay);
+                cx.executor().advance_clock(delay);
+            }
+        }
+
+        // The delays double, give or take some jitter, until they reach the cap.
+        assert!(delays[0] <= INITIAL_RECONNECTION_DELAY, "{delays:?}");
+        assert!(
+            delays[..9].windows(2).all(|pair| pair[0] <= pair[1]),
+            "{delays:?}"
+        );
+        assert!(delays[11] >= MAX_RECONNECTION_DELAY / 2, "{delays:?}");
+        assert!(delays.iter().all(|delay| *delay <= MAX_RECONNECTION_DELAY));
+
+        server.allow_connections();
+        cx.executor().advance_clock(MAX_RECONNECTION_DELAY);
+        while !matches!(status.next().await, Some(Status::Connected { .. })) {}
+    }
+
+    #[gpui::test]
+    async fn test_reconnecting_when_server_stops_answering(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let keepalive = KeepaliveSettings {
+            interval: Duration::from_millis(10),
+            receive_timeout: Duration::from_millis(50),
+        };
+        client.set_keepalive(keepalive);
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        let mut status = client.status();
+        assert!(matches!(
+            status.next().await,
+            Some(Status::Connected { .. })
+        ));
+
+        // While the server answers, an idle connection stays up.
+        for _ in 0..20 {
+            cx.executor().advance_clock(keepalive.interval);
+            cx.run_until_parked();
+        }
+        assert!(matches!(*status.borrow(), Status::Connected { .. }));
+
+        server.forbid_pongs();
+        cx.executor().advance_clock(keepalive.receive_timeout);
+        while !matches!(
+            status.next().await,
+            Some(Status::ConnectionLost | Status::Reconnecting)
+        ) {}
+
+        server.allow_pongs();
+        cx.executor().advance_clock(INITIAL_RECONNECTION_DELAY);
+        while !matches!(status.next().await, Some(Status::Connected { .. })) {}
+    }
+
+    #[gpui::test]
+    async fn test_reconnecting_to_incompatible_server(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        let mut status = client.status();
+        assert!(matches!(
+            status.next().await,
+            Some(Status::Connected { .. })
+        ));
+
+        // A server that still supports the client's protocol accepts it after an upgrade.
+        server.set_protocol_version(rpc::PROTOCOL_VERSION + 1);
+        server.set_supported_protocol_versions(rpc::PROTOCOL_VERSION..=rpc::PROTOCOL_VERSION + 1);
+        server.disconnect();
+        cx.executor().advance_clock(INITIAL_RECONNECTION_DELAY);
+        while !matches!(status.next().await, Some(Status::Connected { .. })) {}
+
+        // The server drops support for the client's protocol while the client is connected.
+        server
+            .set_supported_protocol_versions(rpc::PROTOCOL_VERSION + 1..=rpc::PROTOCOL_VERSION + 1);
+        server.disconnect();
+        cx.executor().advance_clock(INITIAL_RECONNECTION_DELAY);
+        while !matches!(status.next().await, Some(Status::UpgradeRequired)) {}
+
+        // The client stops trying to reconnect.
+        let auth_count = server.auth_count();
+        cx.executor().advance_clock(MAX_RECONNECTION_DELAY);
+        cx.run_until_parked();
+        assert_eq!(*status.borrow(), Status::UpgradeRequired);
+        assert_eq!(server.auth_count(), auth_count);
+        assert!(matches!(
+            client.authenticate_and_connect(true, &cx.to_async()).await,
+            Err(error) if error.to_string() == "upgrade required"
+        ));
+
+        // Connecting anew reports both versions.
+        client.disconnect(&cx.to_async());
+        let error = client
+            .authenticate_and_connect(false, &cx.to_async())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<EstablishConnectionError>(),
+            Some(EstablishConnectionError::IncompatibleProtocolVersion {
+                server_version,
+                client_version,
+            }) if *server_version == rpc::PROTOCOL_VERSION + 1
+                && *client_version == rpc::PROTOCOL_VERSION
+        ));
+        assert_eq!(*status.borrow(), Status::UpgradeRequired);
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_connection_timeout(executor: BackgroundExecutor, cx: &mut TestAppContext) {
         init_test(cx);
@@ -1829,6 +2059,349 @@ mod tests {
         done_rx.next().await.unwrap();
     }
 
+    #[gpui::test]
+    async fn test_fake_server_with_multiple_clients(cx: &mut TestAppContext) {
+        init_test(cx);
+        let new_client = |cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                Client::new(
+                    Arc::new(FakeSystemClock::default()),
+                    FakeHttpClient::with_404_response(),
+                    cx,
+                )
+            })
+        };
+        let client1 = new_client(cx);
+        let client2 = new_client(cx);
+        let server = FakeServer::for_client(5, &client1, cx).await;
+        server.add_client(6, &client2, cx).await;
+
+        let model = cx.new_model(|_| TestModel::default());
+        let mut pings = Vec::new();
+        let mut subscriptions = Vec::new();
+        for client in [&client1, &client2] {
+            let (ping_tx, ping_rx) = smol::channel::unbounded();
+            pings.push(ping_rx);
+            subscriptions.push(client.add_message_handler(
+                model.downgrade(),
+                move |_, _: TypedEnvelope<proto::Ping>, _, _| {
+                    ping_tx.try_send(()).unwrap();
+                    async { Ok(()) }
+                },
+            ));
+        }
+
+        // Messages reach only the client they're sent to.
+        server.send_to(6, proto::Ping {});
+        pings[1].next().await.unwrap();
+        assert!(pings[0].try_recv().is_err());
+        server.send(proto::Ping {});
+        pings[0].next().await.unwrap();
+        assert!(pings[1].try_recv().is_err());
+
+        // Messages are received from the client that sent them.
+        client1.send(proto::LeaveProject { project_id: 1 }).unwrap();
+        client2.send(proto::LeaveProject { project_id: 2 }).unwrap();
+        let message = server.receive_from::<proto::LeaveProject>(6).await.unwrap();
+        assert_eq!(message.payload.project_id, 2);
+        let message = server.receive::<proto::LeaveProject>().await.unwrap();
+        assert_eq!(message.payload.project_id, 1);
+        server.assert_drained();
+    }
+
+    #[gpui::test]
+    async fn test_fake_server_transcript(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+
+        // Nothing is recorded until recording is enabled.
+        client.send(proto::LeaveProject { project_id: 1 }).unwrap();
+        server.receive::<proto::LeaveProject>().await.unwrap();
+        assert!(server.transcript().is_empty());
+
+        server.enable_recording();
+        server.send(proto::Ping {});
+        client.send(proto::LeaveProject { project_id: 7 }).unwrap();
+        server.receive::<proto::LeaveProject>().await.unwrap();
+        assert_eq!(
+            server.transcript(),
+            [
+                TranscriptEntry {
+                    direction: Direction::Sent,
+                    type_name: "Ping",
+                    payload: "{}".into(),
+                },
+                TranscriptEntry {
+                    direction: Direction::Received,
+                    type_name: "LeaveProject",
+                    payload: r#"{"project_id":7}"#.into(),
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_fake_server_stats(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        let before = server.stats();
+
+        client.send(proto::LeaveProject { project_id: 1 }).unwrap();
+        client.send(proto::LeaveProject { project_id: 2 }).unwrap();
+        server.receive::<proto::LeaveProject>().await.unwrap();
+        server.receive::<proto::LeaveProject>().await.unwrap();
+        server.send(proto::Ping {});
+        cx.run_until_parked();
+
+        // The client answers the ping, which it doesn't handle, with an error.
+        let after = server.stats();
+        assert_eq!(after.messages_received, before.messages_received + 3);
+        assert_eq!(after.messages_sent, before.messages_sent + 1);
+        assert!(after.bytes_received > before.bytes_received);
+        assert!(after.bytes_sent > before.bytes_sent);
+        assert!(after.last_received_at.is_some());
+        assert!(after.last_received_at >= before.last_received_at);
+    }
+
+    #[gpui::test]
+    async fn test_user_presence(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx));
+
+        server.send(proto::UpdateUserPresence {
+            user_id: 6,
+            presence: proto::UserPresence::Active as i32,
+            active_path: Some("src/main.rs".into()),
+        });
+        cx.run_until_parked();
+        user_store.read_with(cx, |user_store, _| {
+            assert_eq!(
+                user_store.presence(6),
+                Some(&Presence {
+                    status: PresenceStatus::Active,
+                    active_path: Some("src/main.rs".into()),
+                })
+            );
+            assert_eq!(user_store.presence(7), None);
+        });
+
+        // Typing wears off unless it's reported again.
+        server.send(proto::UpdateUserPresence {
+            user_id: 6,
+            presence: proto::UserPresence::Typing as i32,
+            active_path: Some("src/main.rs".into()),
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(TYPING_TIMEOUT / 2);
+        server.send(proto::UpdateUserPresence {
+            user_id: 6,
+            presence: proto::UserPresence::Typing as i32,
+            active_path: Some("src/lib.rs".into()),
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(TYPING_TIMEOUT * 3 / 4);
+        user_store.read_with(cx, |user_store, _| {
+            assert_eq!(
+                user_store.presence(6).map(|presence| presence.status),
+                Some(PresenceStatus::Typing)
+            );
+        });
+        cx.executor().advance_clock(TYPING_TIMEOUT / 2);
+        user_store.read_with(cx, |user_store, _| {
+            assert_eq!(
+                user_store.presence(6),
+                Some(&Presence {
+                    status: PresenceStatus::Active,
+                    active_path: Some("src/lib.rs".into()),
+                })
+            );
+        });
+
+        // Going idle stops the typing from wearing off.
+        server.send(proto::UpdateUserPresence {
+            user_id: 6,
+            presence: proto::UserPresence::Typing as i32,
+            active_path: None,
+        });
+        server.send(proto::UpdateUserPresence {
+            user_id: 6,
+            presence: proto::UserPresence::Idle as i32,
+            active_path: None,
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(TYPING_TIMEOUT * 2);
+        user_store.read_with(cx, |user_store, _| {
+            assert_eq!(
+                user_store.presence(6).map(|presence| presence.status),
+                Some(PresenceStatus::Idle)
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_request_with_timeout(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+
+        // A response that arrives in time is returned.
+        let response =
+            client.request_with_timeout(proto::Ping {}, Duration::from_secs(1), &cx.to_async());
+        let request = server.receive::<proto::Ping>().await.unwrap();
+        server.respond(request.receipt(), proto::Ack {});
+        response.await.unwrap();
+
+        // The client gives up on a silent server.
+        let response = cx.executor().spawn(client.request_with_timeout(
+            proto::Ping {},
+            Duration::from_secs(1),
+            &cx.to_async(),
+        ));
+        let request = server.receive::<proto::Ping>().await.unwrap();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        let error = response.await.unwrap_err();
+        assert_eq!(error.error_code(), rpc::ErrorCode::Timeout);
+
+        // Responding too late has no effect.
+        server.respond(request.receipt(), proto::Ack {});
+        cx.executor().run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_high_priority_messages(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+
+        // A high priority message overtakes normal ones that are still waiting to be written.
+        client.send(proto::Test { id: 1 }).unwrap();
+        client.send(proto::Test { id: 2 }).unwrap();
+        client
+            .send_with_priority(proto::Test { id: 3 }, SendPriority::High)
+            .unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            ids.push(server.receive::<proto::Test>().await.unwrap().payload.id);
+        }
+        assert_eq!(ids, [3, 1, 2]);
+
+        // Updates for followers are sent with high priority without asking.
+        client.send(proto::Test { id: 4 }).unwrap();
+        client
+            .send(proto::UpdateFollowers {
+                room_id: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        server.receive::<proto::UpdateFollowers>().await.unwrap();
+        assert_eq!(server.receive::<proto::Test>().await.unwrap().payload.id, 4);
+    }
+
+    #[gpui::test]
+    async fn test_fake_server_cancelled_receive(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+
+        // Giving up on a receive leaves the client's messages for the next one.
+        assert!(server.receive::<proto::Test>().now_or_never().is_none());
+        client.send(proto::Test { id: 1 }).unwrap();
+        assert_eq!(server.receive::<proto::Test>().await.unwrap().payload.id, 1);
+    }
+
+    #[gpui::test]
+    async fn test_request_while_disconnected(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+
+        let mut status = client.status();
+
+        // A request in flight when the connection is lost fails as disconnected.
+        let response = cx.executor().spawn(client.request(proto::Ping {}));
+        server.receive::<proto::Ping>().await.unwrap();
+        server.forbid_connections();
+        server.disconnect();
+        let error = response.await.unwrap_err();
+        assert_eq!(error.error_code(), rpc::ErrorCode::Disconnected);
+
+        // So do requests made while the client can't reconnect.
+        while !matches!(status.next().await, Some(Status::ReconnectionError { .. })) {}
+        let error = client.request(proto::Ping {}).await.unwrap_err();
+        assert_eq!(error.error_code(), rpc::ErrorCode::Disconnected);
+    }
+
+    #[gpui::test]
+    #[should_panic(expected = "LeaveProject from user 5")]
+    async fn test_fake_server_assert_drained(cx: &mut TestAppContext) {
+        init_test(cx);
+        let user_id = 5;
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let server = FakeServer::for_client(user_id, &client, cx).await;
+        client.send(proto::LeaveProject { project_id: 1 }).unwrap();
+        cx.executor().run_until_parked();
+        server.assert_drained();
+    }
+
     #[derive(Default)]
     struct TestModel {
         id: usize,
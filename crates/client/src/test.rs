@@ -1,13 +1,14 @@
 use crate::{Client, Connection, Credentials, EstablishConnectionError, UserStore};
 use anyhow::{anyhow, Result};
-use futures::{stream::BoxStream, StreamExt};
+use collections::HashMap;
+use futures::{stream::BoxStream, FutureExt, StreamExt};
 use gpui::{BackgroundExecutor, Context, Model, TestAppContext};
 use parking_lot::Mutex;
 use rpc::{
     proto::{self, GetPrivateUserInfo, GetPrivateUserInfoResponse},
-    ConnectionId, Peer, Receipt, TypedEnvelope,
+    ConnectionId, ConnectionStats, Peer, Receipt, TypedEnvelope,
 };
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
 pub struct FakeServer {
     peer: Arc<Peer>,
@@ -18,11 +19,73 @@ pub struct FakeServer {
 
 #[derive(Default)]
 struct FakeServerState {
-    incoming: Option<BoxStream<'static, Box<dyn proto::AnyTypedEnvelope>>>,
-    connection_id: Option<ConnectionId>,
+    /// The connection of each client, by the id of its user.
+    connections: HashMap<u64, FakeConnection>,
     forbid_connections: bool,
+    /// The protocol version the server speaks, when it isn't the client's.
+    protocol_version: Option<u32>,
+    /// The client protocol versions the server accepts, when not only the one it speaks.
+    supported_protocol_versions: Option<RangeInclusive<u32>>,
     auth_count: usize,
     access_token: usize,
+    /// The messages sent and received so far, once recording is enabled.
+    transcript: Option<Vec<TranscriptEntry>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// A message the fake server sent or received while recording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    pub direction: Direction,
+    pub type_name: &'static str,
+    /// The message's payload, as JSON.
+    pub payload: String,
+}
+
+struct FakeConnection {
+    id: ConnectionId,
+    /// The messages the client sent, taken while a test is waiting for one.
+    incoming: Option<BoxStream<'static, Box<dyn proto::AnyTypedEnvelope>>>,
+}
+
+/// A connection's incoming messages while a test waits for one. They're put back once it's done
+/// waiting, even when the wait is cancelled, so that the next receive can pick up from there.
+struct TakenIncoming<'a> {
+    server: &'a FakeServer,
+    user_id: u64,
+    connection_id: ConnectionId,
+    stream: Option<BoxStream<'static, Box<dyn proto::AnyTypedEnvelope>>>,
+}
+
+impl<'a> TakenIncoming<'a> {
+    fn take(server: &'a FakeServer, user_id: u64) -> Self {
+        let mut state = server.state.lock();
+        let connection = state.connections.get_mut(&user_id).expect("not connected");
+        let stream = connection.incoming.take().expect("already receiving");
+        server.executor.start_waiting();
+        Self {
+            server,
+            user_id,
+            connection_id: connection.id,
+            stream: Some(stream),
+        }
+    }
+}
+
+impl Drop for TakenIncoming<'_> {
+    fn drop(&mut self) {
+        self.server.executor.finish_waiting();
+        if let Some(connection) = self.server.state.lock().connections.get_mut(&self.user_id) {
+            if connection.id == self.connection_id {
+                connection.incoming = self.stream.take();
+            }
+        }
+    }
 }
 
 impl FakeServer {
@@ -37,10 +100,18 @@ impl FakeServer {
             user_id: client_user_id,
             executor: cx.executor(),
         };
+        // Keep the connection alive the way the client does, so that tests can shorten both
+        // sides' intervals at once.
+        server.peer.set_keepalive(client.keepalive());
+        server.add_client(client_user_id, client, cx).await;
+        server
+    }
 
+    /// Connects another client to this server, as the user with the given id.
+    pub async fn add_client(&self, client_user_id: u64, client: &Arc<Client>, cx: &TestAppContext) {
         client
             .override_authenticate({
-                let state = Arc::downgrade(&server.state);
+                let state = Arc::downgrade(&self.state);
                 move |cx| {
                     let state = state.clone();
                     cx.spawn(move |_| async move {
@@ -56,8 +127,8 @@ impl FakeServer {
                 }
             })
             .override_establish_connection({
-                let peer = Arc::downgrade(&server.peer);
-                let state = Arc::downgrade(&server.state);
+                let peer = Arc::downgrade(&self.peer);
+                let state = Arc::downgrade(&self.state);
                 move |credentials, cx| {
                     let peer = peer.clone();
                     let state = state.clone();
@@ -80,16 +151,36 @@ impl FakeServer {
                             Err(EstablishConnectionError::Unauthorized)?
                         }
 
+                        // Like the real server, reject clients built against a protocol it
+                        // doesn't support.
+                        let server_version = state
+                            .lock()
+                            .protocol_version
+                            .unwrap_or(rpc::PROTOCOL_VERSION);
+                        let supported_versions = state
+                            .lock()
+                            .supported_protocol_versions
+                            .clone()
+                            .unwrap_or(server_version..=server_version);
+                        if !supported_versions.contains(&rpc::PROTOCOL_VERSION) {
+                            Err(EstablishConnectionError::IncompatibleProtocolVersion {
+                                server_version,
+                                client_version: rpc::PROTOCOL_VERSION,
+                            })?
+                        }
+
                         let (client_conn, server_conn, _) =
                             Connection::in_memory(cx.background_executor().clone());
                         let (connection_id, io, incoming) =
                             peer.add_test_connection(server_conn, cx.background_executor().clone());
                         cx.background_executor().spawn(io).detach();
-                        {
-                            let mut state = state.lock();
-                            state.connection_id = Some(connection_id);
-                            state.incoming = Some(incoming);
-                        }
+                        state.lock().connections.insert(
+                            client_user_id,
+                            FakeConnection {
+                                id: connection_id,
+                                incoming: Some(incoming),
+                            },
+                        );
                         peer.send(
                             connection_id,
                             proto::Hello {
@@ -107,16 +198,16 @@ impl FakeServer {
             .authenticate_and_connect(false, &cx.to_async())
             .await
             .unwrap();
-
-        server
     }
 
     pub fn disconnect(&self) {
-        if self.state.lock().connection_id.is_some() {
-            self.peer.disconnect(self.connection_id());
-            let mut state = self.state.lock();
-            state.connection_id.take();
-            state.incoming.take();
+        self.disconnect_client(self.user_id);
+    }
+
+    pub fn disconnect_client(&self, user_id: u64) {
+        let connection = self.state.lock().connections.remove(&user_id);
+        if let Some(connection) = connection {
+            self.peer.disconnect(connection.id);
         }
     }
 
@@ -136,38 +227,72 @@ impl FakeServer {
         self.state.lock().forbid_connections = false;
     }
 
+    /// Stops the server from pinging its clients, so that they see their connections go quiet
+    /// and drop them once their receive timeout elapses.
+    pub fn forbid_pongs(&self) {
+        self.peer.forbid_keepalives();
+    }
+
+    pub fn allow_pongs(&self) {
+        self.peer.allow_keepalives();
+    }
+
+    /// Makes the server speak the given protocol version to clients that connect from now on.
+    ///
+    /// Unless [`Self::set_supported_protocol_versions`] says otherwise, only clients speaking the
+    /// same version are accepted.
+    pub fn set_protocol_version(&self, version: u32) {
+        self.state.lock().protocol_version = Some(version);
+    }
+
+    /// Makes the server accept clients speaking any of the given protocol versions.
+    pub fn set_supported_protocol_versions(&self, versions: RangeInclusive<u32>) {
+        self.state.lock().supported_protocol_versions = Some(versions);
+    }
+
     pub fn send<T: proto::EnvelopedMessage>(&self, message: T) {
-        self.peer.send(self.connection_id(), message).unwrap();
+        self.send_to(self.user_id, message);
+    }
+
+    pub fn send_to<T: proto::EnvelopedMessage>(&self, user_id: u64, message: T) {
+        self.record(Direction::Sent, &message);
+        self.peer
+            .send(self.connection_id_for(user_id), message)
+            .unwrap();
     }
 
-    #[allow(clippy::await_holding_lock)]
     pub async fn receive<M: proto::EnvelopedMessage>(&self) -> Result<TypedEnvelope<M>> {
-        self.executor.start_waiting();
+        self.receive_from(self.user_id).await
+    }
 
+    /// Waits for the next message from the given user's client, answering the requests every
+    /// client makes when it connects along the way.
+    pub async fn receive_from<M: proto::EnvelopedMessage>(
+        &self,
+        user_id: u64,
+    ) -> Result<TypedEnvelope<M>> {
         loop {
-            let message = self
-                .state
-                .lock()
-                .incoming
-                .as_mut()
-                .expect("not connected")
-                .next()
-                .await
-                .ok_or_else(|| anyhow!("other half hung up"))?;
-            self.executor.finish_waiting();
+            // Don't hold the lock while waiting, so that other clients can connect meanwhile.
+            let mut incoming = TakenIncoming::take(self, user_id);
+            let message = incoming.stream.as_mut().unwrap().next().await;
+            drop(incoming);
+            let message = message.ok_or_else(|| anyhow!("other half hung up"))?;
             let type_name = message.payload_type_name();
             let message = message.into_any();
 
             if message.is::<TypedEnvelope<M>>() {
-                return Ok(*message.downcast().unwrap());
+                let message = message.downcast::<TypedEnvelope<M>>().unwrap();
+                self.record(Direction::Received, &message.payload);
+                return Ok(*message);
             }
 
             if message.is::<TypedEnvelope<GetPrivateUserInfo>>() {
+                let message = message
+                    .downcast::<TypedEnvelope<GetPrivateUserInfo>>()
+                    .unwrap();
+                self.record(Direction::Received, &message.payload);
                 self.respond(
-                    message
-                        .downcast::<TypedEnvelope<GetPrivateUserInfo>>()
-                        .unwrap()
-                        .receipt(),
+                    message.receipt(),
                     GetPrivateUserInfoResponse {
                         metrics_id: "the-metrics-id".into(),
                         staff: false,
@@ -184,12 +309,73 @@ impl FakeServer {
         }
     }
 
+    /// Panics with the types of the messages clients sent that the test hasn't received, if
+    /// there are any.
+    pub fn assert_drained(&self) {
+        let mut unreceived = Vec::new();
+        for (user_id, connection) in self.state.lock().connections.iter_mut() {
+            let Some(incoming) = connection.incoming.as_mut() else {
+                continue;
+            };
+            while let Some(Some(message)) = incoming.next().now_or_never() {
+                unreceived.push((*user_id, message.payload_type_name()));
+            }
+        }
+        unreceived.sort_by_key(|(user_id, _)| *user_id);
+        assert!(
+            unreceived.is_empty(),
+            "fake server didn't receive some messages: {}",
+            unreceived
+                .iter()
+                .map(|(user_id, type_name)| format!("{type_name} from user {user_id}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
     pub fn respond<T: proto::RequestMessage>(&self, receipt: Receipt<T>, response: T::Response) {
+        self.record(Direction::Sent, &response);
         self.peer.respond(receipt, response).unwrap()
     }
 
-    fn connection_id(&self) -> ConnectionId {
-        self.state.lock().connection_id.expect("not connected")
+    /// Starts recording the messages this server sends and receives from now on.
+    pub fn enable_recording(&self) {
+        self.state.lock().transcript.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the messages recorded since [`Self::enable_recording`], in order.
+    pub fn transcript(&self) -> Vec<TranscriptEntry> {
+        self.state.lock().transcript.clone().unwrap_or_default()
+    }
+
+    /// Returns the traffic between this server and the primary client, as the server sees it.
+    pub fn stats(&self) -> ConnectionStats {
+        self.stats_for(self.user_id)
+    }
+
+    pub fn stats_for(&self, user_id: u64) -> ConnectionStats {
+        self.peer
+            .stats(self.connection_id_for(user_id))
+            .expect("not connected")
+    }
+
+    fn record<T: proto::EnvelopedMessage>(&self, direction: Direction, message: &T) {
+        if let Some(transcript) = self.state.lock().transcript.as_mut() {
+            transcript.push(TranscriptEntry {
+                direction,
+                type_name: T::NAME,
+                payload: serde_json::to_string(message).unwrap(),
+            });
+        }
+    }
+
+    fn connection_id_for(&self, user_id: u64) -> ConnectionId {
+        self.state
+            .lock()
+            .connections
+            .get(&user_id)
+            .expect("not connected")
+            .id
     }
 
     pub async fn build_user_store(
@@ -212,6 +398,9 @@ impl FakeServer {
 
 impl Drop for FakeServer {
     fn drop(&mut self) {
-        self.disconnect();
+        let connections = std::mem::take(&mut self.state.lock().connections);
+        for connection in connections.into_values() {
+            self.peer.disconnect(connection.id);
+        }
     }
 }
@@ -9,9 +9,12 @@ use gpui::{
 };
 use postage::{sink::Sink, watch};
 use rpc::proto::{RequestMessage, UsersResponse};
-use std::sync::{Arc, Weak};
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
 use text::ReplicaId;
-use util::TryFutureExt as _;
+use util::{ResultExt as _, TryFutureExt as _};
 
 pub type UserId = u64;
 
@@ -38,6 +41,24 @@ pub struct DevServerProjectId(pub u64);
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ParticipantIndex(pub u32);
 
+/// How long a user is shown as typing after the last update saying they are.
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresenceStatus {
+    Active,
+    Idle,
+    Typing,
+}
+
+/// What a collaborator is doing, as last reported by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presence {
+    pub status: PresenceStatus,
+    /// The path of the file they have focused.
+    pub active_path: Option<SharedString>,
+}
+
 #[derive(Default, Debug)]
 pub struct User {
     pub id: UserId,
@@ -90,6 +111,11 @@ pub enum ContactRequestStatus {
 pub struct UserStore {
     users: HashMap<u64, Arc<User>>,
     participant_indices: HashMap<u64, ParticipantIndex>,
+    presences: HashMap<u64, Presence>,
+    typing_timeouts: HashMap<u64, Task<()>>,
+    /// What the current user last reported doing, and, while typing, the task that lets it be
+    /// reported again once the report is about to wear off.
+    reported_presence: Option<(Presence, Option<Task<()>>)>,
     update_contacts_tx: mpsc::UnboundedSender<UpdateContacts>,
     current_user: watch::Receiver<Option<Arc<User>>>,
     contacts: Vec<Arc<Contact>>,
@@ -141,6 +167,7 @@ impl UserStore {
             client.add_message_handler(cx.weak_model(), Self::handle_update_contacts),
             client.add_message_handler(cx.weak_model(), Self::handle_update_invite_info),
             client.add_message_handler(cx.weak_model(), Self::handle_show_contacts),
+            client.add_message_handler(cx.weak_model(), Self::handle_update_user_presence),
         ];
         Self {
             users: Default::default(),
@@ -148,6 +175,9 @@ impl UserStore {
             contacts: Default::default(),
             incoming_contact_requests: Default::default(),
             participant_indices: Default::default(),
+            presences: Default::default(),
+            typing_timeouts: Default::default(),
+            reported_presence: None,
             outgoing_contact_requests: Default::default(),
             invite_info: None,
             client: Arc::downgrade(&client),
@@ -277,6 +307,51 @@ impl UserStore {
         Ok(())
     }
 
+    async fn handle_update_user_presence(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::UpdateUserPresence>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let message = message.payload;
+            let status = match message.presence() {
+                proto::UserPresence::Active => PresenceStatus::Active,
+                proto::UserPresence::Idle => PresenceStatus::Idle,
+                proto::UserPresence::Typing => PresenceStatus::Typing,
+            };
+            let user_id = message.user_id;
+            this.presences.insert(
+                user_id,
+                Presence {
+                    status,
+                    active_path: message.active_path.map(Into::into),
+                },
+            );
+
+            if status == PresenceStatus::Typing {
+                let timeout = cx.spawn(|this, mut cx| async move {
+                    cx.background_executor().timer(TYPING_TIMEOUT).await;
+                    this.update(&mut cx, |this, cx| {
+                        this.typing_timeouts.remove(&user_id);
+                        if let Some(presence) = this.presences.get_mut(&user_id) {
+                            if presence.status == PresenceStatus::Typing {
+                                presence.status = PresenceStatus::Active;
+                                cx.notify();
+                            }
+                        }
+                    })
+                    .ok();
+                });
+                this.typing_timeouts.insert(user_id, timeout);
+            } else {
+                this.typing_timeouts.remove(&user_id);
+            }
+            cx.notify();
+        })?;
+        Ok(())
+    }
+
     fn update_contacts(
         &mut self,
         message: UpdateContacts,
@@ -289,6 +364,8 @@ impl UserStore {
             }
             UpdateContacts::Clear(barrier) => {
                 self.contacts.clear();
+                self.presences.clear();
+                self.typing_timeouts.clear();
                 self.incoming_contact_requests.clear();
                 self.outgoing_contact_requests.clear();
                 drop(barrier);
@@ -692,6 +769,48 @@ impl UserStore {
         }
     }
 
+    pub fn presence(&self, user_id: u64) -> Option<&Presence> {
+        self.presences.get(&user_id)
+    }
+
+    /// Tells the current user's contacts what they're doing. Reports that change nothing aren't
+    /// sent, except that typing is reported again when the last report is about to wear off.
+    pub fn report_presence(&mut self, presence: Presence, cx: &mut ModelContext<Self>) {
+        if let Some((reported, throttle)) = &self.reported_presence {
+            let typing_again = presence.status == PresenceStatus::Typing && throttle.is_none();
+            if *reported == presence && !typing_again {
+                return;
+            }
+        }
+        let Some(client) = self.client.upgrade() else {
+            return;
+        };
+
+        client
+            .send(proto::UpdateUserPresence {
+                user_id: self.current_user().map_or(0, |user| user.id),
+                presence: match presence.status {
+                    PresenceStatus::Active => proto::UserPresence::Active,
+                    PresenceStatus::Idle => proto::UserPresence::Idle,
+                    PresenceStatus::Typing => proto::UserPresence::Typing,
+                } as i32,
+                active_path: presence.active_path.as_ref().map(ToString::to_string),
+            })
+            .log_err();
+        let throttle = (presence.status == PresenceStatus::Typing).then(|| {
+            cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(TYPING_TIMEOUT / 2).await;
+                this.update(&mut cx, |this, _| {
+                    if let Some((_, throttle)) = &mut this.reported_presence {
+                        throttle.take();
+                    }
+                })
+                .ok();
+            })
+        });
+        self.reported_presence = Some((presence, throttle));
+    }
+
     pub fn participant_indices(&self) -> &HashMap<u64, ParticipantIndex> {
         &self.participant_indices
     }
@@ -579,6 +579,7 @@ impl Server {
             .add_request_handler(user_handler(get_private_user_info))
             .add_message_handler(user_message_handler(acknowledge_channel_message))
             .add_message_handler(user_message_handler(acknowledge_buffer_version))
+            .add_message_handler(user_message_handler(update_user_presence))
             .add_request_handler(user_handler(get_supermaven_api_key))
             .add_streaming_request_handler({
                 let app_state = app_state.clone();
@@ -4179,6 +4180,36 @@ async fn acknowledge_buffer_version(
     Ok(())
 }
 
+/// Tells the user's contacts what one of their clients says they're doing.
+async fn update_user_presence(
+    request: proto::UpdateUserPresence,
+    session: UserSession,
+) -> Result<()> {
+    let user_id = session.user_id();
+    let contacts = session.db().await.get_contacts(user_id).await?;
+    let message = proto::UpdateUserPresence {
+        user_id: user_id.to_proto(),
+        ..request
+    };
+
+    let pool = session.connection_pool().await;
+    for contact in contacts {
+        if let db::Contact::Accepted {
+            user_id: contact_user_id,
+            ..
+        } = contact
+        {
+            for contact_conn_id in pool.user_connection_ids(contact_user_id) {
+                session
+                    .peer
+                    .send(contact_conn_id, message.clone())
+                    .trace_err();
+            }
+        }
+    }
+    Ok(())
+}
+
 struct CompleteWithLanguageModelRateLimit;
 
 impl RateLimit for CompleteWithLanguageModelRateLimit {
@@ -4701,18 +4732,21 @@ async fn get_channel_messages(
     session: UserSession,
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
+    let count = request.count.map_or(MESSAGE_COUNT_PER_PAGE, |count| {
+        (count as usize).min(MESSAGE_COUNT_PER_PAGE)
+    });
     let messages = session
         .db()
         .await
         .get_channel_messages(
             channel_id,
             session.user_id(),
-            MESSAGE_COUNT_PER_PAGE,
+            count,
             Some(MessageId::from_proto(request.before_message_id)),
         )
         .await?;
     response.send(proto::GetChannelMessagesResponse {
-        done: messages.len() < MESSAGE_COUNT_PER_PAGE,
+        done: messages.len() < count,
         messages,
     })?;
     Ok(())
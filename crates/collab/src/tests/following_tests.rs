@@ -3,7 +3,8 @@ use call::{ActiveCall, ParticipantLocation};
 use client::ChannelId;
 use collab_ui::{
     channel_view::ChannelView,
-    notifications::project_shared_notification::ProjectSharedNotification,
+    notifications::project_shared_notification::ProjectSharedNotification, CollabTitlebarItem,
+    MAX_VISIBLE_COLLABORATORS,
 };
 use editor::{Editor, ExcerptRange, MultiBuffer};
 use gpui::{
@@ -2148,3 +2149,85 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_titlebar_collaborator_overflow(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+    cx_e: &mut TestAppContext,
+    cx_f: &mut TestAppContext,
+    cx_g: &mut TestAppContext,
+) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+    let client_e = server.create_client(cx_e, "user_e").await;
+    let client_f = server.create_client(cx_f, "user_f").await;
+    let client_g = server.create_client(cx_g, "user_g").await;
+    server
+        .create_room(&mut [
+            (&client_a, cx_a),
+            (&client_b, cx_b),
+            (&client_c, cx_c),
+            (&client_d, cx_d),
+            (&client_e, cx_e),
+            (&client_f, cx_f),
+            (&client_g, cx_g),
+        ])
+        .await;
+    assert_eq!(MAX_VISIBLE_COLLABORATORS, 5);
+
+    let project_a = client_a.build_empty_local_project(cx_a);
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let visible_collaborators = |cx: &mut VisualTestContext| {
+        workspace_a.update(cx, |workspace, cx| {
+            let titlebar = workspace
+                .titlebar_item()
+                .unwrap()
+                .downcast::<CollabTitlebarItem>()
+                .unwrap();
+            titlebar.read(cx).visible_collaborators(cx)
+        })
+    };
+
+    // Collaborators past the limit are counted rather than shown, in the order they joined.
+    executor.run_until_parked();
+    assert_eq!(
+        visible_collaborators(cx_a),
+        (
+            vec![
+                client_b.peer_id().unwrap(),
+                client_c.peer_id().unwrap(),
+                client_d.peer_id().unwrap(),
+                client_e.peer_id().unwrap(),
+                client_f.peer_id().unwrap(),
+            ],
+            1
+        )
+    );
+
+    // When one leaves, everyone left fits.
+    cx_b.read(ActiveCall::global)
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        visible_collaborators(cx_a),
+        (
+            vec![
+                client_c.peer_id().unwrap(),
+                client_d.peer_id().unwrap(),
+                client_e.peer_id().unwrap(),
+                client_f.peer_id().unwrap(),
+                client_g.peer_id().unwrap(),
+            ],
+            0
+        )
+    );
+}
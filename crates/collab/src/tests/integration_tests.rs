@@ -7,14 +7,16 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use call::{room, ActiveCall, ParticipantLocation, Room};
-use client::{User, RECEIVE_TIMEOUT};
+use client::{Presence, PresenceStatus, User, RECEIVE_TIMEOUT};
+use collab_ui::collab_panel::CollabPanel;
 use collections::{HashMap, HashSet};
+use editor::Editor;
 use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
 use git::repository::GitFileStatus;
 use gpui::{
     px, size, AppContext, BackgroundExecutor, BorrowAppContext, Model, Modifiers, MouseButton,
-    MouseDownEvent, TestAppContext,
+    MouseDownEvent, TestAppContext, View, VisualTestContext,
 };
 use language::{
     language_settings::{AllLanguageSettings, Formatter},
@@ -5809,6 +5811,83 @@ async fn test_contacts(
     }
 }
 
+#[gpui::test]
+async fn test_contact_presence(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    client_b
+        .fs()
+        .insert_tree("/b", json!({ "one.rs": "", "two.rs": "" }))
+        .await;
+    let (project_b, worktree_id) = client_b.build_local_project("/b", cx_b).await;
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_b
+        .update(cx_b, |call, cx| call.share_project(project_b.clone(), cx))
+        .await
+        .unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    let panel_a = workspace_a.update(cx_a, |workspace, cx| CollabPanel::new(workspace, cx));
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+
+    let editor_b = workspace_b
+        .update(cx_b, |workspace, cx| {
+            workspace.open_path((worktree_id, "one.rs"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        contact_presence(&client_a, &panel_a, cx_a),
+        Some(Presence {
+            status: PresenceStatus::Active,
+            active_path: Some("one.rs".into()),
+        })
+    );
+
+    editor_b.update(cx_b, |editor, cx| editor.handle_input("a", cx));
+    executor.run_until_parked();
+    assert_eq!(
+        contact_presence(&client_a, &panel_a, cx_a),
+        Some(Presence {
+            status: PresenceStatus::Typing,
+            active_path: Some("one.rs".into()),
+        })
+    );
+
+    cx_b.deactivate_window();
+    executor.run_until_parked();
+    assert_eq!(
+        contact_presence(&client_a, &panel_a, cx_a),
+        Some(Presence {
+            status: PresenceStatus::Idle,
+            active_path: Some("one.rs".into()),
+        })
+    );
+
+    fn contact_presence(
+        client: &TestClient,
+        panel: &View<CollabPanel>,
+        cx: &mut VisualTestContext,
+    ) -> Option<Presence> {
+        let contact = client
+            .user_store()
+            .read_with(cx, |store, _| store.contacts()[0].clone());
+        panel.update(cx, |panel, cx| panel.contact_presence(&contact, cx))
+    }
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_contact_requests(
     executor: BackgroundExecutor,
@@ -8,7 +8,7 @@ use crate::{
 };
 use call::ActiveCall;
 use channel::{Channel, ChannelEvent, ChannelStore};
-use client::{ChannelId, Client, Contact, ProjectId, User, UserStore};
+use client::{ChannelId, Client, Contact, Presence, PresenceStatus, ProjectId, User, UserStore};
 use contact_finder::ContactFinder;
 use db::kvp::KEY_VALUE_STORE;
 use editor::{Editor, EditorElement, EditorStyle};
@@ -2299,6 +2299,7 @@ impl CollabPanel {
         let online = contact.online;
         let busy = contact.busy || calling;
         let github_login = SharedString::from(contact.user.github_login.clone());
+        let presence = self.contact_presence(contact, cx);
         let item = ListItem::new(github_login.clone())
             .indent_level(1)
             .indent_step_size(px(20.))
@@ -2307,7 +2308,24 @@ impl CollabPanel {
                 h_flex()
                     .w_full()
                     .justify_between()
-                    .child(Label::new(github_login.clone()))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .overflow_hidden()
+                            .child(Label::new(github_login.clone()))
+                            .when_some(presence, |el, presence| {
+                                el.child(render_presence_status(presence.status)).when_some(
+                                    presence.active_path,
+                                    |el, path| {
+                                        el.child(
+                                            Label::new(path)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    },
+                                )
+                            }),
+                    )
                     .when(calling, |el| {
                         el.child(Label::new("Calling").color(Color::Muted))
                     })
@@ -2513,6 +2531,7 @@ impl CollabPanel {
             has_children.then(|| self.collapsed_channels.binary_search(&channel.id).is_err());
 
         let has_messages_notification = channel_store.has_new_messages(channel_id);
+        let unread_count = channel_store.unread_count(channel_id);
         let has_notes_notification = channel_store.has_channel_buffer_changed(channel_id);
 
         const FACEPILE_LIMIT: usize = 3;
@@ -2624,6 +2643,15 @@ impl CollabPanel {
                         h_flex()
                             .id(channel_id.0 as usize)
                             .child(Label::new(channel.name.clone()))
+                            .when(unread_count > 0, |el| {
+                                el.child(
+                                    div().px_1().child(
+                                        Label::new(unread_count.to_string())
+                                            .size(LabelSize::XSmall)
+                                            .color(Color::Accent),
+                                    ),
+                                )
+                            })
                             .children(face_pile.map(|face_pile| face_pile.p_1())),
                     ),
             )
@@ -2704,6 +2732,24 @@ impl CollabPanel {
     }
 }
 
+impl CollabPanel {
+    /// What an online contact last reported doing, as shown next to their name.
+    pub fn contact_presence(&self, contact: &Contact, cx: &AppContext) -> Option<Presence> {
+        if !contact.online {
+            return None;
+        }
+        self.user_store.read(cx).presence(contact.user.id).cloned()
+    }
+}
+
+fn render_presence_status(status: PresenceStatus) -> Indicator {
+    match status {
+        PresenceStatus::Active => Indicator::dot().color(Color::Created),
+        PresenceStatus::Idle => Indicator::dot().color(Color::Muted),
+        PresenceStatus::Typing => Indicator::icon(Icon::new(IconName::Pencil)).color(Color::Accent),
+    }
+}
+
 fn render_tree_branch(is_last: bool, overdraw: bool, cx: &mut WindowContext) -> impl IntoElement {
     let rem_size = cx.rem_size();
     let line_height = cx.text_style().line_height_in_pixels(rem_size);
@@ -1,16 +1,16 @@
 use crate::face_pile::FacePile;
 use auto_update::AutoUpdateStatus;
-use call::{ActiveCall, ParticipantLocation, Room};
+use call::{participant::RemoteParticipant, ActiveCall, ParticipantLocation, Room};
 use client::{proto::PeerId, Client, User, UserStore};
 use gpui::{
     actions, canvas, div, point, px, Action, AnyElement, AppContext, Element, Hsla,
     InteractiveElement, IntoElement, Model, ParentElement, Path, Render,
     StatefulInteractiveElement, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
 };
-use project::{Project, RepositoryEntry};
+use project::{Project, ProjectPath, RepositoryEntry};
 use recent_projects::RecentProjects;
 use rpc::proto::{self, DevServerStatus};
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use theme::ActiveTheme;
 use ui::{
     h_flex, popover_menu, prelude::*, Avatar, AvatarAudioStatusIndicator, Button, ButtonLike,
@@ -22,6 +22,9 @@ use workspace::{notifications::NotifyResultExt, Workspace};
 
 const MAX_PROJECT_NAME_LENGTH: usize = 40;
 const MAX_BRANCH_NAME_LENGTH: usize = 40;
+const MAX_FILE_PATH_LENGTH: usize = 60;
+/// How many remote collaborators get an avatar before the rest are collapsed into a count.
+pub const MAX_VISIBLE_COLLABORATORS: usize = 5;
 
 actions!(
     collab,
@@ -73,7 +76,8 @@ impl Render for CollabTitlebarItem {
                     .gap_1()
                     .children(self.render_project_host(cx))
                     .child(self.render_project_name(cx))
-                    .children(self.render_project_branch(cx)),
+                    .children(self.render_project_branch(cx))
+                    .children(self.render_active_file_path(cx)),
             )
             .child(
                 h_flex()
@@ -86,9 +90,8 @@ impl Render for CollabTitlebarItem {
                         |this, ((current_user, peer_id), room)| {
                             let player_colors = cx.theme().players();
                             let room = room.read(cx);
-                            let mut remote_participants =
-                                room.remote_participants().values().collect::<Vec<_>>();
-                            remote_participants.sort_by_key(|p| p.participant_index.0);
+                            let (remote_participants, hidden_participants) =
+                                visible_remote_participants(room);
 
                             let current_user_face_pile = self.render_collaborator(
                                 &current_user,
@@ -108,57 +111,54 @@ impl Render for CollabTitlebarItem {
                                     .child(face_pile)
                                     .child(render_color_ribbon(player_colors.local().cursor))
                             }))
-                            .children(
-                                remote_participants.iter().filter_map(|collaborator| {
-                                    let player_color = player_colors
-                                        .color_for_participant(collaborator.participant_index.0);
-                                    let is_following = workspace
-                                        .as_ref()?
-                                        .read(cx)
-                                        .is_being_followed(collaborator.peer_id);
-                                    let is_present = project_id.map_or(false, |project_id| {
-                                        collaborator.location
-                                            == ParticipantLocation::SharedProject { project_id }
-                                    });
-
-                                    let face_pile = self.render_collaborator(
-                                        &collaborator.user,
-                                        collaborator.peer_id,
-                                        is_present,
-                                        collaborator.speaking,
-                                        collaborator.muted,
-                                        is_following.then_some(player_color.selection),
-                                        &room,
-                                        project_id,
-                                        &current_user,
-                                        cx,
-                                    )?;
-
-                                    Some(
-                                        v_flex()
-                                            .id(("collaborator", collaborator.user.id))
-                                            .child(face_pile)
-                                            .child(render_color_ribbon(player_color.cursor))
-                                            .cursor_pointer()
-                                            .on_click({
-                                                let peer_id = collaborator.peer_id;
-                                                cx.listener(move |this, _, cx| {
-                                                    this.workspace
-                                                        .update(cx, |workspace, cx| {
-                                                            workspace.follow(peer_id, cx);
-                                                        })
-                                                        .ok();
-                                                })
+                            .children(remote_participants.iter().filter_map(|collaborator| {
+                                let player_color = player_colors
+                                    .color_for_participant(collaborator.participant_index.0);
+                                let is_following = workspace
+                                    .as_ref()?
+                                    .read(cx)
+                                    .is_being_followed(collaborator.peer_id);
+                                let is_present = project_id.map_or(false, |project_id| {
+                                    collaborator.location
+                                        == ParticipantLocation::SharedProject { project_id }
+                                });
+
+                                let face_pile = self.render_collaborator(
+                                    &collaborator.user,
+                                    collaborator.peer_id,
+                                    is_present,
+                                    collaborator.speaking,
+                                    collaborator.muted,
+                                    is_following.then_some(player_color.selection),
+                                    &room,
+                                    project_id,
+                                    &current_user,
+                                    cx,
+                                )?;
+
+                                Some(
+                                    v_flex()
+                                        .id(("collaborator", collaborator.user.id))
+                                        .child(face_pile)
+                                        .child(render_color_ribbon(player_color.cursor))
+                                        .cursor_pointer()
+                                        .on_click({
+                                            let peer_id = collaborator.peer_id;
+                                            cx.listener(move |this, _, cx| {
+                                                this.workspace
+                                                    .update(cx, |workspace, cx| {
+                                                        workspace.follow(peer_id, cx);
+                                                    })
+                                                    .ok();
                                             })
-                                            .tooltip({
-                                                let login = collaborator.user.github_login.clone();
-                                                move |cx| {
-                                                    Tooltip::text(format!("Follow {login}"), cx)
-                                                }
-                                            }),
-                                    )
-                                }),
-                            )
+                                        })
+                                        .tooltip({
+                                            let login = collaborator.user.github_login.clone();
+                                            move |cx| Tooltip::text(format!("Follow {login}"), cx)
+                                        }),
+                                )
+                            }))
+                            .children(render_hidden_collaborators(&hidden_participants, cx))
                         },
                     ),
             )
@@ -519,6 +519,69 @@ impl CollabTitlebarItem {
         )
     }
 
+    /// Renders the path of the active item's file within its worktree, with each directory
+    /// revealing itself in the project panel when clicked.
+    pub fn render_active_file_path(&self, cx: &mut ViewContext<Self>) -> Option<impl Element> {
+        let workspace = self.workspace.upgrade()?;
+        let project_path = workspace.read(cx).active_item(cx)?.project_path(cx)?;
+        let names = project_path
+            .path
+            .iter()
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        let file_ix = names.len().checked_sub(1)?;
+        let segments = visible_path_segments(&names, MAX_FILE_PATH_LENGTH);
+
+        Some(h_flex().id("active_file_path").gap_0p5().children(
+            segments.into_iter().enumerate().map(|(ix, segment)| {
+                let crumb = match segment {
+                    None => Label::new("…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .into_any_element(),
+                    Some(segment_ix) if segment_ix == file_ix => {
+                        Label::new(names[segment_ix].clone())
+                            .size(LabelSize::Small)
+                            .into_any_element()
+                    }
+                    Some(segment_ix) => {
+                        let directory = ProjectPath {
+                            worktree_id: project_path.worktree_id,
+                            path: names[..=segment_ix].iter().collect::<PathBuf>().into(),
+                        };
+                        Button::new(
+                            ("active_file_path_segment", segment_ix),
+                            names[segment_ix].clone(),
+                        )
+                        .color(Color::Muted)
+                        .style(ButtonStyle::Subtle)
+                        .label_size(LabelSize::Small)
+                        .tooltip(|cx| Tooltip::text("Reveal in Project Panel", cx))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.reveal_in_project_panel(&directory, cx)
+                        }))
+                        .into_any_element()
+                    }
+                };
+                h_flex()
+                    .gap_0p5()
+                    .when(ix > 0, |this| {
+                        this.child(Label::new("/").size(LabelSize::Small).color(Color::Muted))
+                    })
+                    .child(crumb)
+            }),
+        ))
+    }
+
+    fn reveal_in_project_panel(&mut self, project_path: &ProjectPath, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.project.read(cx).entry_for_path(project_path, cx) else {
+            return;
+        };
+        self.project.update(cx, |_, cx| {
+            cx.emit(project::Event::RevealInProjectPanel(entry.id))
+        });
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn render_collaborator(
         &self,
@@ -610,6 +673,22 @@ impl CollabTitlebarItem {
         )
     }
 
+    /// Returns the collaborators shown with avatars, and how many more are counted after them.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn visible_collaborators(&self, cx: &AppContext) -> (Vec<PeerId>, usize) {
+        let Some(room) = ActiveCall::global(cx).read(cx).room() else {
+            return (Vec::new(), 0);
+        };
+        let (visible, hidden) = visible_remote_participants(room.read(cx));
+        (
+            visible
+                .into_iter()
+                .map(|participant| participant.peer_id)
+                .collect(),
+            hidden.len(),
+        )
+    }
+
     fn window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
         if cx.is_window_active() {
             ActiveCall::global(cx)
@@ -769,3 +848,109 @@ impl CollabTitlebarItem {
         }
     }
 }
+
+/// Renders the count of collaborators without an avatar, listing them in its tooltip.
+fn render_hidden_collaborators(
+    participants: &[&RemoteParticipant],
+    cx: &WindowContext,
+) -> Option<impl Element> {
+    if participants.is_empty() {
+        return None;
+    }
+    let logins = participants
+        .iter()
+        .map(|participant| participant.user.github_login.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(
+        div()
+            .id("hidden_collaborators")
+            .m_0p5()
+            .px_1()
+            .rounded_full()
+            .bg(cx.theme().colors().element_background)
+            .child(Label::new(format!("+{}", participants.len())).size(LabelSize::Small))
+            .tooltip(move |cx| Tooltip::text(logins.clone(), cx)),
+    )
+}
+
+/// Splits the room's remote participants, other than guests, into those shown with avatars in
+/// the order they joined, and those collapsed into a count after them.
+fn visible_remote_participants(room: &Room) -> (Vec<&RemoteParticipant>, Vec<&RemoteParticipant>) {
+    let mut participants = room
+        .remote_participants()
+        .values()
+        .filter(|participant| {
+            room.role_for_user(participant.user.id) != Some(proto::ChannelRole::Guest)
+        })
+        .collect::<Vec<_>>();
+    participants.sort_by_key(|participant| participant.participant_index.0);
+    let hidden = participants.split_off(participants.len().min(MAX_VISIBLE_COLLABORATORS));
+    (participants, hidden)
+}
+
+/// Chooses which of a path's components to show, given as their indices, so that the path fits
+/// in `max_chars`. The first component and as many of the last ones as fit are kept, with the
+/// ones between them collapsed into a single ellipsis, shown as `None`.
+fn visible_path_segments(names: &[String], max_chars: usize) -> Vec<Option<usize>> {
+    let len = |name: &String| name.chars().count() + 1;
+    let total = names.iter().map(len).sum::<usize>();
+    if total <= max_chars || names.len() <= 2 {
+        return (0..names.len()).map(Some).collect();
+    }
+
+    // The first component, and the ellipsis with its separator.
+    let mut kept_len = len(&names[0]) + 2;
+    let mut tail_start = names.len();
+    while tail_start > 1 {
+        let name_len = len(&names[tail_start - 1]);
+        if kept_len + name_len > max_chars && tail_start < names.len() {
+            break;
+        }
+        kept_len += name_len;
+        tail_start -= 1;
+    }
+
+    let mut segments = vec![Some(0)];
+    if tail_start > 1 {
+        segments.push(None);
+    }
+    segments.extend((tail_start..names.len()).map(Some));
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(path: &str, max_chars: usize) -> Vec<String> {
+        let names = path.split('/').map(str::to_string).collect::<Vec<_>>();
+        visible_path_segments(&names, max_chars)
+            .into_iter()
+            .map(|segment| segment.map_or("…".to_string(), |ix| names[ix].clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_visible_path_segments() {
+        assert_eq!(segments("src/main.rs", 10), ["src", "main.rs"]);
+        assert_eq!(
+            segments("crates/editor/src/editor.rs", 60),
+            ["crates", "editor", "src", "editor.rs"]
+        );
+        // Components are collapsed from the middle, keeping the ones nearest the file.
+        assert_eq!(
+            segments("crates/editor/src/display_map/wrap_map.rs", 35),
+            ["crates", "…", "display_map", "wrap_map.rs"]
+        );
+        assert_eq!(
+            segments("crates/editor/src/display_map/wrap_map.rs", 20),
+            ["crates", "…", "wrap_map.rs"]
+        );
+        // The file's name is always shown, however long it is.
+        assert_eq!(
+            segments("a/b/c/a_very_long_file_name.rs", 10),
+            ["a", "…", "a_very_long_file_name.rs"]
+        );
+    }
+}
@@ -6,12 +6,13 @@ mod face_pile;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+mod presence;
 
 use std::{rc::Rc, sync::Arc};
 
 use call::{report_call_event_for_room, ActiveCall};
 pub use collab_panel::CollabPanel;
-pub use collab_titlebar_item::CollabTitlebarItem;
+pub use collab_titlebar_item::{CollabTitlebarItem, MAX_VISIBLE_COLLABORATORS};
 use gpui::{
     actions, point, AppContext, DevicePixels, Pixels, PlatformDisplay, Size, Task,
     WindowBackgroundAppearance, WindowContext, WindowKind, WindowOptions,
@@ -41,6 +42,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     channel_view::init(cx);
     chat_panel::init(cx);
     notification_panel::init(cx);
+    presence::init(cx);
     notifications::init(&app_state, cx);
 }
 
@@ -0,0 +1,67 @@
+//! Reports what the user is doing in each workspace to their contacts: whether they're typing or
+//! have the window in the background, and, in shared projects, which file they have focused.
+use client::{Presence, PresenceStatus};
+use gpui::{AppContext, VisualContext as _, WindowContext};
+use workspace::{item::ItemEvent, Workspace};
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|_: &mut Workspace, cx| {
+        let mut active_item_subscription = None;
+        cx.subscribe(&cx.view().clone(), move |workspace, _, event, cx| {
+            if let workspace::Event::ActiveItemChanged = event {
+                let weak_workspace = cx.view().downgrade();
+                active_item_subscription = workspace.active_item(cx).map(|item| {
+                    item.subscribe_to_item_events(
+                        cx,
+                        Box::new(move |event, cx| {
+                            if let ItemEvent::Edit = event {
+                                weak_workspace
+                                    .update(cx, |workspace, cx| {
+                                        report_presence(workspace, PresenceStatus::Typing, cx)
+                                    })
+                                    .ok();
+                            }
+                        }),
+                    )
+                });
+                report_presence(workspace, PresenceStatus::Active, cx);
+            }
+        })
+        .detach();
+
+        cx.observe_window_activation(|workspace, cx| {
+            let status = if cx.is_window_active() {
+                PresenceStatus::Active
+            } else {
+                PresenceStatus::Idle
+            };
+            report_presence(workspace, status, cx);
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn report_presence(workspace: &Workspace, status: PresenceStatus, cx: &mut WindowContext) {
+    // Which file is focused is only shared with contacts while the project is.
+    let active_path = if workspace.project().read(cx).remote_id().is_some() {
+        workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .map(|project_path| project_path.path.to_string_lossy().into_owned().into())
+    } else {
+        None
+    };
+    workspace
+        .app_state()
+        .user_store
+        .update(cx, |user_store, cx| {
+            user_store.report_presence(
+                Presence {
+                    status,
+                    active_path,
+                },
+                cx,
+            )
+        });
+}
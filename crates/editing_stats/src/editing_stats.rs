@@ -0,0 +1,514 @@
+//! Opt-in editing stats: keystrokes, edits, edited files and active time, per language and day.
+//!
+//! The stats are only ever stored in a local database and shown in the [`EditingStatsPanel`].
+//! Nothing is reported through telemetry or sent to the server.
+
+mod editing_stats_panel;
+mod persistence;
+
+use chrono::{DateTime, Utc};
+use clock::SystemClock;
+use editor::{Editor, EditorEvent, EditorMode};
+use gpui::{AppContext, Context, Global, Model, ModelContext, Pixels, Task, ViewContext};
+use persistence::DB;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use util::ResultExt;
+use workspace::{dock::DockPosition, Workspace};
+
+pub use editing_stats_panel::{EditingStatsPanel, ToggleFocus};
+
+/// How long the editor can go without input before that time stops counting as active.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to wait after the last activity before writing the stats to the database.
+pub const SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+const ACTIVITY_TICK: Duration = Duration::from_secs(1);
+
+const PLAIN_TEXT: &str = "Plain Text";
+
+/// Sets up recording the stats, bucketing them by the local date `clock` reports.
+pub fn init(clock: Arc<dyn SystemClock>, cx: &mut AppContext) {
+    EditingStatsSettings::register(cx);
+
+    let stats = cx.new_model(|_| EditingStats::new(clock));
+    cx.set_global(GlobalEditingStats(stats));
+
+    cx.observe_new_views(observe_editor).detach();
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let panel = EditingStatsPanel::new(workspace, cx);
+        workspace.add_panel(panel, cx);
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<EditingStatsPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EditingStatsSettings {
+    pub enabled: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct EditingStatsSettingsContent {
+    /// Whether to record editing stats. They are only stored locally, and never sent anywhere.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Where to dock the editing stats panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the editing stats panel in pixels.
+    ///
+    /// Default: 240
+    pub default_width: Option<f32>,
+}
+
+impl Settings for EditingStatsSettings {
+    const KEY: Option<&'static str> = Some("editing_stats");
+
+    type FileContent = EditingStatsSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// What was done with a single language on a single day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LanguageStats {
+    pub keystrokes: u64,
+    pub edits: u64,
+    pub active_time: Duration,
+    pub files_edited: BTreeSet<PathBuf>,
+}
+
+impl LanguageStats {
+    fn merge(&mut self, other: LanguageStats) {
+        self.keystrokes += other.keystrokes;
+        self.edits += other.edits;
+        self.active_time += other.active_time;
+        self.files_edited.extend(other.files_edited);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Activity {
+    Keystroke,
+    Edit,
+}
+
+struct GlobalEditingStats(Model<EditingStats>);
+
+impl Global for GlobalEditingStats {}
+
+/// The stats recorded today, shared by all windows.
+pub struct EditingStats {
+    clock: Arc<dyn SystemClock>,
+    day: String,
+    languages: BTreeMap<Arc<str>, LanguageStats>,
+    /// Whether the stats stored earlier today were loaded, so that saving won't overwrite them.
+    loaded: bool,
+    /// The time since the last activity. It only counts as active once the next activity comes
+    /// in before [`IDLE_TIMEOUT`].
+    pending_active_time: Duration,
+    activity_timer: Option<Task<()>>,
+    load_task: Option<Task<()>>,
+    save_task: Option<Task<()>>,
+}
+
+impl EditingStats {
+    fn new(clock: Arc<dyn SystemClock>) -> Self {
+        Self {
+            day: local_day(clock.utc_now()),
+            clock,
+            languages: BTreeMap::new(),
+            loaded: false,
+            pending_active_time: Duration::ZERO,
+            activity_timer: None,
+            load_task: None,
+            save_task: None,
+        }
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalEditingStats>()
+            .map(|stats| stats.0.clone())
+    }
+
+    /// The local date the stats were recorded on, formatted as `YYYY-MM-DD`.
+    pub fn day(&self) -> &str {
+        &self.day
+    }
+
+    pub fn languages(&self) -> &BTreeMap<Arc<str>, LanguageStats> {
+        &self.languages
+    }
+
+    fn record(
+        &mut self,
+        activity: Activity,
+        language: Arc<str>,
+        path: Option<PathBuf>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.roll_over_day(cx);
+        self.load(cx);
+
+        let stats = self.languages.entry(language).or_default();
+        stats.active_time += mem::take(&mut self.pending_active_time);
+        match activity {
+            Activity::Keystroke => stats.keystrokes += 1,
+            Activity::Edit => stats.edits += 1,
+        }
+        stats.files_edited.extend(path);
+
+        self.track_activity(cx);
+        self.schedule_save(cx);
+        cx.notify();
+    }
+
+    /// Loads the stats stored earlier today, e.g. by a previous session.
+    pub(crate) fn load(&mut self, cx: &mut ModelContext<Self>) {
+        if self.loaded || self.load_task.is_some() {
+            return;
+        }
+
+        let day = self.day.clone();
+        self.load_task = Some(cx.spawn(|this, mut cx| async move {
+            let stored = cx
+                .background_executor()
+                .spawn({
+                    let day = day.clone();
+                    async move { DB.get_day(day) }
+                })
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                if this.day != day {
+                    return;
+                }
+                for (language, stats) in stored {
+                    this.languages.entry(language).or_default().merge(stats);
+                }
+                this.loaded = true;
+                this.load_task = None;
+                this.schedule_save(cx);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn roll_over_day(&mut self, cx: &mut ModelContext<Self>) {
+        let today = local_day(self.clock.utc_now());
+        if today == self.day {
+            return;
+        }
+
+        let day = mem::replace(&mut self.day, today);
+        let languages = mem::take(&mut self.languages);
+        if self.loaded {
+            cx.background_executor()
+                .spawn(async move { DB.save_day(day, languages).await.log_err() })
+                .detach();
+        }
+        self.loaded = false;
+        self.load_task = None;
+        self.save_task = None;
+    }
+
+    fn track_activity(&mut self, cx: &mut ModelContext<Self>) {
+        if self.activity_timer.is_some() {
+            return;
+        }
+
+        self.activity_timer = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(ACTIVITY_TICK).await;
+                let idle = this.update(&mut cx, |this, _| {
+                    this.pending_active_time += ACTIVITY_TICK;
+                    if this.pending_active_time < IDLE_TIMEOUT {
+                        return false;
+                    }
+                    this.pending_active_time = Duration::ZERO;
+                    this.activity_timer = None;
+                    true
+                });
+                if idle.unwrap_or(true) {
+                    break;
+                }
+            }
+        }));
+    }
+
+    fn schedule_save(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.loaded {
+            return;
+        }
+
+        self.save_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            let Some((day, languages)) = this
+                .update(&mut cx, |this, _| {
+                    (this.day.clone(), this.languages.clone())
+                })
+                .ok()
+            else {
+                return;
+            };
+            DB.save_day(day, languages).await.log_err();
+        }));
+    }
+}
+
+fn local_day(now: DateTime<Utc>) -> String {
+    now.with_timezone(&chrono::Local)
+        .date_naive()
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+fn observe_editor(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+
+    let view = cx.view().clone();
+    cx.subscribe(&view, |editor, _, event: &EditorEvent, cx| {
+        let activity = match event {
+            EditorEvent::InputHandled { .. } => Activity::Keystroke,
+            EditorEvent::Edited => Activity::Edit,
+            _ => return,
+        };
+        if !EditingStatsSettings::get_global(cx).enabled {
+            return;
+        }
+        let Some(stats) = EditingStats::global(cx) else {
+            return;
+        };
+
+        let cursor = editor.selections.newest_anchor().head();
+        let (language, path) = editor
+            .buffer()
+            .read(cx)
+            .point_to_buffer_offset(cursor, cx)
+            .map(|(buffer, offset, _)| {
+                let buffer = buffer.read(cx);
+                (
+                    buffer.language_at(offset).map(|language| language.name()),
+                    buffer.file().map(|file| file.full_path(cx)),
+                )
+            })
+            .unwrap_or_default();
+        let language = language.unwrap_or_else(|| PLAIN_TEXT.into());
+        stats.update(cx, |stats, cx| stats.record(activity, language, path, cx));
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::{test::FakeServer, Client};
+    use clock::FakeSystemClock;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::{Buffer, Language, LanguageConfig, LanguageMatcher};
+    use project::Project;
+    use settings::SettingsStore;
+    use std::ops::Deref;
+    use util::http::FakeHttpClient;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_tracking_active_time_per_language(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditingStatsSettings>(cx, |settings| {
+                    settings.enabled = Some(true);
+                });
+            });
+        });
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::local("fn main() {}", cx).with_language(Arc::new(rust_lang()), cx)
+        });
+        let window = cx.add_window(|cx| Editor::for_buffer(buffer, None, cx));
+        let editor = window.root_view(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window.deref(), cx);
+        let stats = cx.update(|cx| EditingStats::global(cx).unwrap());
+
+        editor.update(cx, |editor, cx| editor.handle_input("a", cx));
+        cx.executor().advance_clock(Duration::from_secs(5));
+        editor.update(cx, |editor, cx| editor.handle_input("b", cx));
+        cx.run_until_parked();
+        stats.update(cx, |stats, _| {
+            let rust = &stats.languages()["Rust"];
+            assert_eq!(rust.keystrokes, 2);
+            assert_eq!(rust.edits, 2);
+            assert_eq!(rust.active_time, Duration::from_secs(5));
+        });
+
+        // Time spent away from the editor doesn't count as active.
+        cx.executor().advance_clock(IDLE_TIMEOUT * 2);
+        editor.update(cx, |editor, cx| editor.handle_input("c", cx));
+        cx.executor().advance_clock(Duration::from_secs(2));
+        editor.update(cx, |editor, cx| editor.handle_input("d", cx));
+        stats.update(cx, |stats, _| {
+            let rust = &stats.languages()["Rust"];
+            assert_eq!(rust.keystrokes, 4);
+            assert_eq!(rust.active_time, Duration::from_secs(7));
+        });
+
+        // The stats are written to the local database once editing pauses.
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.run_until_parked();
+        let day = stats.update(cx, |stats, _| stats.day().to_string());
+        let stored = DB.get_day(day).unwrap();
+        assert_eq!(stored["Rust"].keystrokes, 4);
+        assert_eq!(stored["Rust"].edits, 4);
+        assert_eq!(stored["Rust"].active_time, Duration::from_secs(7));
+    }
+
+    #[gpui::test]
+    async fn test_stats_roll_over_to_the_next_day(cx: &mut TestAppContext) {
+        let clock = init_test(cx);
+        clock.set_now(fixed_time("2024-05-20T12:00:00Z"));
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditingStatsSettings>(cx, |settings| {
+                    settings.enabled = Some(true);
+                });
+            });
+        });
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::local("fn main() {}", cx).with_language(Arc::new(rust_lang()), cx)
+        });
+        let window = cx.add_window(|cx| Editor::for_buffer(buffer, None, cx));
+        let editor = window.root_view(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window.deref(), cx);
+        let stats = cx.update(|cx| EditingStats::global(cx).unwrap());
+
+        editor.update(cx, |editor, cx| editor.handle_input("a", cx));
+        cx.run_until_parked();
+        let first_day = local_day(fixed_time("2024-05-20T12:00:00Z"));
+        stats.update(cx, |stats, _| {
+            assert_eq!(stats.day(), first_day);
+            assert_eq!(stats.languages()["Rust"].keystrokes, 1);
+        });
+
+        // Editing on the next day starts over, and stores what was done the day before.
+        clock.advance(chrono::Duration::days(1));
+        editor.update(cx, |editor, cx| editor.handle_input("b", cx));
+        cx.run_until_parked();
+        stats.update(cx, |stats, _| {
+            assert_eq!(stats.day(), local_day(fixed_time("2024-05-21T12:00:00Z")));
+            assert_eq!(stats.languages()["Rust"].keystrokes, 1);
+        });
+        assert_eq!(DB.get_day(first_day).unwrap()["Rust"].keystrokes, 1);
+    }
+
+    #[gpui::test]
+    async fn test_editing_stats_are_opt_in(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let buffer = cx.new_model(|cx| Buffer::local("", cx));
+        let window = cx.add_window(|cx| Editor::for_buffer(buffer, None, cx));
+        let editor = window.root_view(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window.deref(), cx);
+
+        editor.update(cx, |editor, cx| editor.handle_input("a", cx));
+        cx.run_until_parked();
+        let stats = cx.update(|cx| EditingStats::global(cx).unwrap());
+        stats.update(cx, |stats, _| assert!(stats.languages().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_editing_stats_stay_local(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditingStatsSettings>(cx, |settings| {
+                    settings.enabled = Some(true);
+                });
+            });
+        });
+        let client = cx.update(|cx| {
+            let client = Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            );
+            Client::set_global(client.clone(), cx);
+            client
+        });
+        let server = FakeServer::for_client(5, &client, cx).await;
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::local("fn main() {}", cx).with_language(Arc::new(rust_lang()), cx)
+        });
+        let window = cx.add_window(|cx| Editor::for_buffer(buffer, None, cx));
+        let editor = window.root_view(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window.deref(), cx);
+        editor.update(cx, |editor, cx| editor.handle_input("a", cx));
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.run_until_parked();
+
+        // The stats are recorded, but nothing about them reaches the server.
+        let stats = cx.update(|cx| EditingStats::global(cx).unwrap());
+        stats.update(cx, |stats, _| {
+            assert_eq!(stats.languages()["Rust"].edits, 1)
+        });
+        server.assert_drained();
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<FakeSystemClock> {
+        let clock = Arc::new(FakeSystemClock::new(fixed_time("2024-03-01T12:00:00Z")));
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(clock.clone(), cx);
+        });
+        clock
+    }
+
+    fn fixed_time(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time).unwrap().into()
+    }
+
+    fn rust_lang() -> Language {
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{EditingStats, EditingStatsSettings, LanguageStats};
+use fs::Fs;
+use gpui::{
+    actions, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Subscription, View,
+    ViewContext, WindowContext,
+};
+use settings::{Settings, SettingsStore};
+use std::{sync::Arc, time::Duration};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(editing_stats, [ToggleFocus]);
+
+/// Summarizes what was edited today, per language.
+pub struct EditingStatsPanel {
+    stats: Model<EditingStats>,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EditingStatsPanel {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let stats = EditingStats::global(cx).expect("editing stats were not initialized");
+            Self {
+                _subscriptions: vec![
+                    cx.observe(&stats, |_, _, cx| cx.notify()),
+                    cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+                ],
+                stats,
+                fs,
+                width: None,
+                focus_handle: cx.focus_handle(),
+            }
+        })
+    }
+
+    fn render_language(
+        &self,
+        ix: usize,
+        language: &Arc<str>,
+        stats: &LanguageStats,
+    ) -> impl IntoElement {
+        let files = stats.files_edited.len();
+        ListItem::new(ix).spacing(ListItemSpacing::Sparse).child(
+            v_flex()
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .child(Label::new(language.clone()))
+                        .child(Label::new(format_duration(stats.active_time))),
+                )
+                .child(
+                    Label::new(format!(
+                        "{} keystrokes, {} edits, {} {}",
+                        stats.keystrokes,
+                        stats.edits,
+                        files,
+                        if files == 1 { "file" } else { "files" }
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                ),
+        )
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+impl Render for EditingStatsPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let enabled = EditingStatsSettings::get_global(cx).enabled;
+        let stats = self.stats.read(cx);
+        let mut languages = stats.languages().iter().collect::<Vec<_>>();
+        languages.sort_by(|(_, a), (_, b)| b.active_time.cmp(&a.active_time));
+
+        v_flex()
+            .key_context("EditingStatsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(format!("Today ({})", stats.day()))),
+            )
+            .map(|this| {
+                if !enabled {
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new(
+                                "Set `editing_stats.enabled` to start recording. \
+                                 The stats never leave this machine.",
+                            )
+                            .color(Color::Muted),
+                        ),
+                    )
+                } else if languages.is_empty() {
+                    this.child(
+                        v_flex()
+                            .p_4()
+                            .child(Label::new("Nothing edited yet today.").color(Color::Muted)),
+                    )
+                } else {
+                    this.children(
+                        languages
+                            .into_iter()
+                            .enumerate()
+                            .map(|(ix, (language, stats))| {
+                                self.render_language(ix, language, stats)
+                            }),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for EditingStatsPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for EditingStatsPanel {}
+
+impl Panel for EditingStatsPanel {
+    fn persistent_name() -> &'static str {
+        "EditingStatsPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        EditingStatsSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<EditingStatsSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| EditingStatsSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active && EditingStatsSettings::get_global(cx).enabled {
+            self.stats.update(cx, |stats, cx| stats.load(cx));
+        }
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        EditingStatsSettings::get_global(cx)
+            .enabled
+            .then_some(IconName::Dash)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Editing Stats")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+
+use crate::LanguageStats;
+
+define_connection! {
+    // Current schema shape using pseudo-rust syntax:
+    // editing_stats(
+    //   day: String, // Local date, formatted as YYYY-MM-DD
+    //   language: Arc<str>,
+    //   keystrokes: u64,
+    //   edits: u64,
+    //   active_ms: u64,
+    // )
+    //
+    // edited_files(
+    //   day: String,
+    //   language: Arc<str>,
+    //   path: PathBuf,
+    // )
+    pub static ref DB: EditingStatsDb<()> =
+        &[sql!(
+            CREATE TABLE editing_stats (
+                day TEXT NOT NULL,
+                language TEXT NOT NULL,
+                keystrokes INTEGER NOT NULL,
+                edits INTEGER NOT NULL,
+                active_ms INTEGER NOT NULL,
+                PRIMARY KEY(day, language)
+            ) STRICT;
+
+            CREATE TABLE edited_files (
+                day TEXT NOT NULL,
+                language TEXT NOT NULL,
+                path BLOB NOT NULL,
+                PRIMARY KEY(day, language, path)
+            ) STRICT;
+        )];
+}
+
+impl EditingStatsDb {
+    query! {
+        fn get_language_stats(day: String) -> Result<Vec<(Arc<str>, u64, u64, u64)>> {
+            SELECT language, keystrokes, edits, active_ms FROM editing_stats
+            WHERE day = ?
+        }
+    }
+
+    query! {
+        fn get_edited_files(day: String) -> Result<Vec<(Arc<str>, PathBuf)>> {
+            SELECT language, path FROM edited_files
+            WHERE day = ?
+        }
+    }
+
+    /// Returns the stats recorded for each language on the given day.
+    pub fn get_day(&self, day: String) -> Result<BTreeMap<Arc<str>, LanguageStats>> {
+        let mut languages = BTreeMap::<Arc<str>, LanguageStats>::new();
+        for (language, keystrokes, edits, active_ms) in self.get_language_stats(day.clone())? {
+            languages.insert(
+                language,
+                LanguageStats {
+                    keystrokes,
+                    edits,
+                    active_time: Duration::from_millis(active_ms),
+                    ..Default::default()
+                },
+            );
+        }
+        for (language, path) in self.get_edited_files(day)? {
+            languages
+                .entry(language)
+                .or_default()
+                .files_edited
+                .insert(path);
+        }
+        Ok(languages)
+    }
+
+    /// Replaces the stats stored for the given day.
+    pub async fn save_day(
+        &self,
+        day: String,
+        languages: BTreeMap<Arc<str>, LanguageStats>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_editing_stats", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM editing_stats WHERE day = ?
+                ))?(day.clone())?;
+                conn.exec_bound(sql!(
+                    DELETE FROM edited_files WHERE day = ?
+                ))?(day.clone())?;
+                for (language, stats) in languages {
+                    conn.exec_bound(sql!(
+                        INSERT INTO editing_stats(day, language, keystrokes, edits, active_ms)
+                        VALUES (?, ?, ?, ?, ?)
+                    ))?((
+                        day.clone(),
+                        language.clone(),
+                        stats.keystrokes,
+                        stats.edits,
+                        stats.active_time.as_millis() as u64,
+                    ))?;
+                    for path in stats.files_edited {
+                        conn.exec_bound(sql!(
+                            INSERT OR IGNORE INTO edited_files(day, language, path)
+                            VALUES (?, ?, ?)
+                        ))?((day.clone(), language.clone(), path))?;
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -107,6 +107,74 @@ pub struct SelectDownByLines {
     pub(super) lines: u32,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ReplayMacro {
+    #[serde(default)]
+    pub times: u32,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct JoinLines {
+    /// The text that replaces each line break and the whitespace around it. Defaults to a space.
+    #[serde(default)]
+    pub separator: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct SortLines {
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default = "default_true")]
+    pub case_sensitive: bool,
+    /// Compare runs of digits by their numeric value, so that `2` sorts before `10`.
+    #[serde(default)]
+    pub numeric: bool,
+}
+
+impl Default for SortLines {
+    fn default() -> Self {
+        Self {
+            descending: false,
+            case_sensitive: true,
+            numeric: false,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Deserialize)]
+pub struct UniqueLines {
+    /// Only remove lines that repeat the line right before them.
+    #[serde(default)]
+    pub adjacent: bool,
+    #[serde(default = "default_true")]
+    pub case_sensitive: bool,
+}
+
+impl Default for UniqueLines {
+    fn default() -> Self {
+        Self {
+            adjacent: false,
+            case_sensitive: true,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct SurroundSelections {
+    pub pair: String,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ChangeSurround {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct DeleteSurround {
+    pub pair: String,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct ExpandExcerpts {
     #[serde(default)]
@@ -116,25 +184,32 @@ pub struct ExpandExcerpts {
 impl_actions!(
     editor,
     [
+        ChangeSurround,
         ConfirmCodeAction,
         ConfirmCompletion,
+        DeleteSurround,
         ExpandExcerpts,
         FoldAt,
+        JoinLines,
         MoveDownByLines,
         MovePageDown,
         MovePageUp,
         MoveToBeginningOfLine,
         MoveToEndOfLine,
         MoveUpByLines,
+        ReplayMacro,
         SelectDownByLines,
         SelectNext,
         SelectPrevious,
         SelectToBeginningOfLine,
         SelectToEndOfLine,
         SelectUpByLines,
+        SortLines,
+        SurroundSelections,
         ToggleCodeActions,
         ToggleComments,
         UnfoldAt,
+        UniqueLines,
     ]
 );
 
@@ -143,15 +218,19 @@ gpui::actions!(
     [
         AcceptPartialCopilotSuggestion,
         AcceptPartialInlineCompletion,
+        AddCursorsToLineEnds,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
         Cancel,
+        CompareWithClipboard,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
         ContextMenuNext,
         ContextMenuPrev,
+        ConvertLineEndingsToCrlf,
+        ConvertLineEndingsToLf,
         ConvertToKebabCase,
         ConvertToLowerCamelCase,
         ConvertToLowerCase,
@@ -167,6 +246,7 @@ gpui::actions!(
         CopyRelativePath,
         Cut,
         CutToEndOfLine,
+        DecrementNumber,
         Delete,
         DeleteLine,
         DeleteToBeginningOfLine,
@@ -178,6 +258,7 @@ gpui::actions!(
         DisplayCursorNames,
         DuplicateLineDown,
         DuplicateLineUp,
+        DuplicateSelection,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
@@ -190,6 +271,7 @@ gpui::actions!(
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToMatchingPair,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -197,8 +279,8 @@ gpui::actions!(
         HalfPageDown,
         HalfPageUp,
         Hover,
+        IncrementNumber,
         Indent,
-        JoinLines,
         LineDown,
         LineUp,
         MoveDown,
@@ -207,6 +289,8 @@ gpui::actions!(
         MoveLineUp,
         MoveRight,
         MoveToBeginning,
+        MoveToEnclosingBlockEnd,
+        MoveToEnclosingBlockStart,
         MoveToEnclosingBracket,
         MoveToEnd,
         MoveToEndOfParagraph,
@@ -233,6 +317,7 @@ gpui::actions!(
         Redo,
         RedoSelection,
         Rename,
+        RepeatLastAction,
         RestartLanguageServer,
         RevealInFinder,
         ReverseLines,
@@ -263,7 +348,9 @@ gpui::actions!(
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SplitLine,
         SplitSelectionIntoLines,
+        SwitchToRelatedFile,
         Tab,
         TabPrev,
         ToggleGitBlame,
@@ -271,8 +358,12 @@ gpui::actions!(
         ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
+        ToggleMacroRecording,
+        ToggleReadOnly,
+        ToggleShowWhitespaces,
         ToggleSoftWrap,
         Transpose,
+        TransposeWords,
         Undo,
         UndoSelection,
         UnfoldLines,
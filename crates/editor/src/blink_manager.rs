@@ -2,7 +2,6 @@ use crate::EditorSettings;
 use gpui::ModelContext;
 use settings::Settings;
 use settings::SettingsStore;
-use smol::Timer;
 use std::time::Duration;
 
 pub struct BlinkManager {
@@ -18,6 +17,8 @@ impl BlinkManager {
     pub fn new(blink_interval: Duration, cx: &mut ModelContext<Self>) -> Self {
         // Make sure we blink the cursors if the setting is re-enabled
         cx.observe_global::<SettingsStore>(move |this, cx| {
+            this.blink_interval =
+                Duration::from_millis(EditorSettings::get_global(cx).cursor_blink_interval);
             this.blink_cursors(this.blink_epoch, cx)
         })
         .detach();
@@ -43,7 +44,7 @@ impl BlinkManager {
         let epoch = self.next_blink_epoch();
         let interval = self.blink_interval;
         cx.spawn(|this, mut cx| async move {
-            Timer::after(interval).await;
+            cx.background_executor().timer(interval).await;
             this.update(&mut cx, |this, cx| this.resume_cursor_blinking(epoch, cx))
         })
         .detach();
@@ -65,7 +66,7 @@ impl BlinkManager {
                 let epoch = self.next_blink_epoch();
                 let interval = self.blink_interval;
                 cx.spawn(|this, mut cx| async move {
-                    Timer::after(interval).await;
+                    cx.background_executor().timer(interval).await;
                     if let Some(this) = this.upgrade() {
                         this.update(&mut cx, |this, cx| this.blink_cursors(epoch, cx))
                             .ok();
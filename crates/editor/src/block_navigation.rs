@@ -0,0 +1,80 @@
+//! Jumping between matching brackets, and to the start or end of the block enclosing the cursor
+//! in the syntax tree.
+use crate::{
+    scroll::Autoscroll, Editor, GoToMatchingPair, MoveToEnclosingBlockEnd,
+    MoveToEnclosingBlockStart,
+};
+use gpui::ViewContext;
+use language::{Point, SelectionGoal};
+use multi_buffer::MultiBufferSnapshot;
+
+impl Editor {
+    /// Moves each cursor to the bracket matching the one under it, or, like vim's `%`, to the
+    /// bracket matching the next one on its line.
+    pub fn go_to_matching_pair(&mut self, _: &GoToMatchingPair, cx: &mut ViewContext<Self>) {
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.move_offsets_with(|snapshot, selection| {
+                if let Some(destination) = matching_pair(snapshot, selection.head()) {
+                    selection.collapse_to(destination, SelectionGoal::None);
+                }
+            })
+        });
+    }
+
+    /// Moves each cursor to the start of the innermost multiline syntax node around it. When
+    /// the cursor is already there, it moves to the start of the next one out.
+    pub fn move_to_enclosing_block_start(
+        &mut self,
+        _: &MoveToEnclosingBlockStart,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.move_to_enclosing_block(false, cx);
+    }
+
+    /// Moves each cursor to the end of the innermost multiline syntax node around it. When the
+    /// cursor is already there, it moves to the end of the next one out.
+    pub fn move_to_enclosing_block_end(
+        &mut self,
+        _: &MoveToEnclosingBlockEnd,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.move_to_enclosing_block(true, cx);
+    }
+
+    fn move_to_enclosing_block(&mut self, to_end: bool, cx: &mut ViewContext<Self>) {
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.move_offsets_with(|snapshot, selection| {
+                let head = selection.head();
+                let mut range = head..head;
+                while let Some(ancestor) = snapshot.range_for_syntax_ancestor(range.clone()) {
+                    let destination = if to_end { ancestor.end } else { ancestor.start };
+                    let start_row = snapshot.offset_to_point(ancestor.start).row;
+                    let end_row = snapshot.offset_to_point(ancestor.end).row;
+                    if destination != head && start_row != end_row {
+                        selection.collapse_to(destination, SelectionGoal::None);
+                        return;
+                    }
+                    range = ancestor;
+                }
+            })
+        });
+    }
+}
+
+/// Returns the start of the bracket matching the closest one at or after `offset` on its line.
+fn matching_pair(snapshot: &MultiBufferSnapshot, offset: usize) -> Option<usize> {
+    let point = snapshot.offset_to_point(offset);
+    let line_end = snapshot.point_to_offset(Point::new(point.row, snapshot.line_len(point.row)));
+    let mut closest: Option<(usize, usize)> = None;
+    for (open, close) in snapshot.bracket_ranges(offset..line_end)? {
+        for (bracket, destination) in [(open.start, close.start), (close.start, open.start)] {
+            if bracket >= offset
+                && bracket < line_end
+                && closest.map_or(true, |(closest, _)| bracket < closest)
+            {
+                closest = Some((bracket, destination));
+            }
+        }
+    }
+    closest.map(|(_, destination)| destination)
+}
@@ -0,0 +1,82 @@
+//! Animates the newest cursor towards its new position over a few frames, instead of having it
+//! jump there.
+use crate::{scroll::SMOOTH_SCROLL_FRAME_DURATION, Editor, EditorMode, EditorSettings};
+use gpui::{point, Pixels, Point, Task, ViewContext};
+use settings::Settings;
+
+/// The number of frames the cursor takes to reach its new position.
+pub const CURSOR_ANIMATION_FRAMES: u32 = 6;
+
+#[derive(Default)]
+pub(crate) struct CursorAnimation {
+    /// Where the animation started, and where it's heading, in pixels from the start of the
+    /// buffer's text.
+    from: Point<Pixels>,
+    to: Option<Point<Pixels>>,
+    frame: u32,
+    _task: Option<Task<()>>,
+}
+
+impl CursorAnimation {
+    fn position(&self) -> Option<Point<Pixels>> {
+        let to = self.to?;
+        let t = self.frame as f32 / CURSOR_ANIMATION_FRAMES as f32;
+        let progress = 1. - (1. - t).powi(3);
+        Some(point(
+            self.from.x + (to.x - self.from.x) * progress,
+            self.from.y + (to.y - self.from.y) * progress,
+        ))
+    }
+}
+
+impl Editor {
+    /// Returns where to draw the newest cursor, given where it really is. When it has moved, it
+    /// starts moving from where it was last drawn.
+    pub(crate) fn animated_cursor_position(
+        &mut self,
+        target: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) -> Point<Pixels> {
+        if self.mode != EditorMode::Full
+            || !EditorSettings::get_global(cx).cursor_animation
+            || cx.should_reduce_motion()
+        {
+            self.cursor_animation = CursorAnimation::default();
+            return target;
+        }
+
+        let animation = &mut self.cursor_animation;
+        if animation.to == Some(target) {
+            return animation.position().unwrap_or(target);
+        }
+        let Some(from) = animation.position() else {
+            animation.to = Some(target);
+            animation.frame = CURSOR_ANIMATION_FRAMES;
+            return target;
+        };
+
+        animation.from = from;
+        animation.to = Some(target);
+        animation.frame = 0;
+        animation._task = Some(cx.spawn(|editor, mut cx| async move {
+            for _ in 0..CURSOR_ANIMATION_FRAMES {
+                cx.background_executor()
+                    .timer(SMOOTH_SCROLL_FRAME_DURATION)
+                    .await;
+                let updated = editor.update(&mut cx, |editor, cx| {
+                    editor.cursor_animation.frame += 1;
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+        }));
+        from
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn animated_cursor(&self) -> Option<Point<Pixels>> {
+        self.cursor_animation.position()
+    }
+}
@@ -0,0 +1,84 @@
+//! Opens the differences between two texts in an editor, showing the new text with every change
+//! expanded against the old one.
+use crate::{CompareWithClipboard, Editor, ExpandAllHunkDiffs};
+use gpui::{Context, View, ViewContext, VisualContext};
+use language::{Buffer, Language};
+use multi_buffer::MultiBuffer;
+use std::sync::Arc;
+use text::Rope;
+use workspace::Workspace;
+
+impl Editor {
+    /// Opens a read-only editor in the active pane, showing `new_text` with the hunks that
+    /// differ from `old_text` expanded.
+    pub fn open_diff_view(
+        workspace: &mut Workspace,
+        title: String,
+        old_text: String,
+        new_text: String,
+        language: Option<Arc<Language>>,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Editor> {
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(new_text, cx);
+            buffer.set_language(language, cx);
+            buffer.set_diff_base(Some(Rope::from(old_text.as_str())), cx);
+            buffer
+        });
+        let multibuffer =
+            cx.new_model(|cx| MultiBuffer::singleton(buffer.clone(), cx).with_title(title));
+        let project = workspace.project().clone();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        workspace.add_item_to_active_pane(Box::new(editor.clone()), None, cx);
+
+        let diff = buffer.update(cx, |buffer, cx| buffer.git_diff_recalc(cx));
+        let diff_editor = editor.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            if let Some(diff) = diff {
+                diff.await;
+            }
+            diff_editor.update(&mut cx, |editor, cx| {
+                editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+
+        editor
+    }
+
+    /// Opens how the buffer differs from the text in the clipboard.
+    pub fn compare_with_clipboard(&mut self, _: &CompareWithClipboard, cx: &mut ViewContext<Self>) {
+        let Some(clipboard) = cx.read_from_clipboard() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let buffer = self.buffer.read(cx);
+        let title = format!("Clipboard ↔ {}", buffer.title(cx));
+        let text = buffer.snapshot(cx).text();
+        let language = buffer
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).language().cloned());
+
+        // The workspace deactivates this editor when the diff opens, so it can't be updated from
+        // within this one.
+        cx.spawn(|_, mut cx| async move {
+            workspace.update(&mut cx, |workspace, cx| {
+                Editor::open_diff_view(
+                    workspace,
+                    title,
+                    clipboard.text().clone(),
+                    text,
+                    language,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
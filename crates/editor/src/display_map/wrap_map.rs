@@ -24,6 +24,8 @@ pub struct WrapMap {
     interpolated_edits: Patch<u32>,
     edits_since_sync: Patch<u32>,
     wrap_width: Option<Pixels>,
+    /// How much more than the line they continue wrapped lines are indented, if at all.
+    wrap_indent: Option<u32>,
     background_task: Option<Task<()>>,
     font_with_size: (Font, Pixels),
 }
@@ -80,6 +82,7 @@ impl WrapMap {
             let mut this = Self {
                 font_with_size: (font, font_size),
                 wrap_width: None,
+                wrap_indent: Some(0),
                 pending_edits: Default::default(),
                 interpolated_edits: Default::default(),
                 edits_since_sync: Default::default(),
@@ -149,6 +152,22 @@ impl WrapMap {
         true
     }
 
+    /// Sets how much more than the line they continue wrapped lines are indented, or that they
+    /// start at column zero if `None`.
+    pub fn set_wrap_indent(
+        &mut self,
+        wrap_indent: Option<u32>,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        if wrap_indent == self.wrap_indent {
+            return false;
+        }
+
+        self.wrap_indent = wrap_indent;
+        self.rewrap(cx);
+        true
+    }
+
     fn rewrap(&mut self, cx: &mut ModelContext<Self>) {
         self.background_task.take();
         self.interpolated_edits.clear();
@@ -159,6 +178,7 @@ impl WrapMap {
 
             let text_system = cx.text_system().clone();
             let (font, font_size) = self.font_with_size.clone();
+            let wrap_indent = self.wrap_indent;
             let task = cx.background_executor().spawn(async move {
                 let mut line_wrapper = text_system.line_wrapper(font, font_size);
                 let tab_snapshot = new_snapshot.tab_snapshot.clone();
@@ -171,6 +191,7 @@ impl WrapMap {
                             new: range.clone(),
                         }],
                         wrap_width,
+                        wrap_indent,
                         &mut line_wrapper,
                     )
                     .await;
@@ -243,12 +264,19 @@ impl WrapMap {
                 let mut snapshot = self.snapshot.clone();
                 let text_system = cx.text_system().clone();
                 let (font, font_size) = self.font_with_size.clone();
+                let wrap_indent = self.wrap_indent;
                 let update_task = cx.background_executor().spawn(async move {
                     let mut edits = Patch::default();
                     let mut line_wrapper = text_system.line_wrapper(font, font_size);
                     for (tab_snapshot, tab_edits) in pending_edits {
                         let wrap_edits = snapshot
-                            .update(tab_snapshot, &tab_edits, wrap_width, &mut line_wrapper)
+                            .update(
+                                tab_snapshot,
+                                &tab_edits,
+                                wrap_width,
+                                wrap_indent,
+                                &mut line_wrapper,
+                            )
                             .await;
                         edits = edits.compose(&wrap_edits);
                     }
@@ -390,6 +418,7 @@ impl WrapSnapshot {
         new_tab_snapshot: TabSnapshot,
         tab_edits: &[TabEdit],
         wrap_width: Pixels,
+        wrap_indent: Option<u32>,
         line_wrapper: &mut LineWrapper,
     ) -> Patch<u32> {
         #[derive(Debug)]
@@ -467,7 +496,9 @@ impl WrapSnapshot {
                     }
 
                     let mut prev_boundary_ix = 0;
-                    for boundary in line_wrapper.wrap_line(&line, wrap_width) {
+                    for boundary in
+                        line_wrapper.wrap_line_with_indent(&line, wrap_width, wrap_indent)
+                    {
                         let wrapped = &line[prev_boundary_ix..boundary.ix];
                         push_isomorphic(&mut edit_transforms, TextSummary::from(wrapped));
                         edit_transforms.push(Transform::wrap(boundary.next_indent));
@@ -1272,6 +1303,34 @@ mod tests {
         wrap_map.read_with(cx, |map, _| assert!(map.pending_edits.is_empty()));
     }
 
+    #[gpui::test]
+    async fn test_wrap_indent(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let buffer = cx.update(|cx| MultiBuffer::build_simple("  aaa bbb ccc\nxyz", cx));
+        let buffer_snapshot = buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot);
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tab_snapshot) = TabMap::new(fold_snapshot, NonZeroU32::new(4).unwrap());
+        let (wrap_map, _) =
+            cx.update(|cx| WrapMap::new(tab_snapshot, font("Courier"), px(16.), Some(px(72.)), cx));
+        cx.run_until_parked();
+
+        // Wrapped lines are indented like the line they continue.
+        assert_eq!(
+            wrap_map.read_with(cx, |map, _| map.snapshot.text()),
+            "  aaa \n  bbb \n  ccc\nxyz"
+        );
+
+        // Without indentation, they start at column zero.
+        wrap_map.update(cx, |map, cx| map.set_wrap_indent(None, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            wrap_map.read_with(cx, |map, _| map.snapshot.text()),
+            "  aaa \nbbb ccc\nxyz"
+        );
+    }
+
     fn init_test(cx: &mut gpui::TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
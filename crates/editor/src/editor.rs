@@ -15,6 +15,8 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod block_navigation;
+mod cursor_animation;
 pub mod display_map;
 mod editor_settings;
 mod element;
@@ -22,18 +24,28 @@ mod hunk_diff;
 mod inlay_hint_cache;
 
 mod debounced_delay;
+mod diff_view;
 mod git;
 mod highlight_matching_bracket;
+mod highlight_occurrences;
 mod hover_links;
 mod hover_popover;
+mod increment;
 mod inline_completion_provider;
 pub mod items;
+mod keyboard_macro;
+mod line_ending_indicator;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod read_only_indicator;
+mod related_file;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_drag;
 mod selections_collection;
+mod surround;
+pub mod swap_file;
 
 #[cfg(test)]
 mod editor_tests;
@@ -66,11 +78,12 @@ use gpui::{
     AppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, Bounds, ClipboardItem,
     Context, DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
     FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton, PaintQuad,
-    ParentElement, Pixels, Render, SharedString, Size, StrikethroughStyle, Styled, StyledText,
-    Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View, ViewContext,
-    ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
+    ParentElement, Pixels, PromptLevel, Render, SharedString, Size, StrikethroughStyle, Styled,
+    StyledText, Subscription, Task, TextStyle, UnderlineStyle, UniformListScrollHandle, View,
+    ViewContext, ViewInputHandler, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
+use highlight_occurrences::{refresh_occurrence_highlights, OccurrenceHighlight};
 use hover_popover::{hide_hover, HoverState};
 use hunk_diff::ExpandedHunks;
 pub(crate) use hunk_diff::HunkToExpand;
@@ -80,11 +93,13 @@ pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{
     char_kind,
-    language_settings::{self, all_language_settings, InlayHintSettings},
+    language_settings::{self, all_language_settings, InlayHintSettings, ShowWhitespaceSetting},
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
-    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, LineEnding,
+    OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
 };
+pub use line_ending_indicator::LineEndingIndicator;
+pub use read_only_indicator::ReadOnlyIndicator;
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
 use lsp::{DiagnosticSeverity, LanguageServerId};
@@ -117,7 +132,7 @@ use std::{
     mem,
     num::NonZeroU32,
     ops::{ControlFlow, Deref, DerefMut, Range, RangeInclusive},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -143,7 +158,6 @@ use workspace::{OpenInTerminal, OpenTerminal, Toast};
 use crate::hover_links::find_url;
 
 pub const DEFAULT_MULTIBUFFER_CONTEXT: u32 = 2;
-const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
 const MIN_NAVIGATION_HISTORY_ROW_DELTA: i64 = 10;
 const MAX_SELECTION_HISTORY_LEN: usize = 1024;
@@ -250,14 +264,16 @@ pub fn init_settings(cx: &mut AppContext) {
 
 pub fn init(cx: &mut AppContext) {
     init_settings(cx);
+    keyboard_macro::init(cx);
 
     workspace::register_project_item::<Editor>(cx);
     workspace::register_followable_item::<Editor>(cx);
     workspace::register_deserializable_item::<Editor>(cx);
     cx.observe_new_views(
-        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_in_direction);
+            swap_file::recover_swap_files(workspace, cx).detach_and_log_err(cx);
         },
     )
     .detach();
@@ -418,6 +434,7 @@ pub struct Editor {
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
+    show_whitespaces_override: Option<ShowWhitespaceSetting>,
     project: Option<Model<Project>>,
     completion_provider: Option<Box<dyn CompletionProvider>>,
     collaboration_hub: Option<Box<dyn CollaborationHub>>,
@@ -444,9 +461,14 @@ pub struct Editor {
     available_code_actions: Option<(Model<Buffer>, Arc<[CodeAction]>)>,
     code_actions_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
+    occurrence_highlights_task: Option<Task<()>>,
+    selection_drag: Option<selection_drag::SelectionDrag>,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
+    /// Whether something other than the settings, like vim's modes, decides the cursor's shape.
+    cursor_shape_overridden: bool,
+    cursor_animation: cursor_animation::CursorAnimation,
     collapse_matches: bool,
     autoindent_mode: Option<AutoindentMode>,
     workspace: Option<(WeakView<Workspace>, WorkspaceId)>,
@@ -454,6 +476,8 @@ pub struct Editor {
     input_enabled: bool,
     use_modal_editing: bool,
     read_only: bool,
+    /// Added to the buffer font size, so that the editors of one pane can be zoomed independently.
+    font_size_adjustment: Pixels,
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
@@ -487,6 +511,7 @@ pub struct Editor {
     >,
     last_bounds: Option<Bounds<Pixels>>,
     expect_bounds_change: Option<Bounds<Pixels>>,
+    swap_file_task: Option<Task<()>>,
 }
 
 #[derive(Clone)]
@@ -1421,7 +1446,10 @@ impl Editor {
 
         let selections = SelectionsCollection::new(display_map.clone(), buffer.clone());
 
-        let blink_manager = cx.new_model(|cx| BlinkManager::new(CURSOR_BLINK_INTERVAL, cx));
+        let editor_settings = EditorSettings::get_global(cx);
+        let cursor_shape = editor_settings.cursor_shape;
+        let blink_interval = Duration::from_millis(editor_settings.cursor_blink_interval);
+        let blink_manager = cx.new_model(|cx| BlinkManager::new(blink_interval, cx));
 
         let soft_wrap_mode_override =
             (mode == EditorMode::SingleLine).then(|| language_settings::SoftWrap::PreferLine);
@@ -1469,6 +1497,7 @@ impl Editor {
             ime_transaction: Default::default(),
             active_diagnostics: None,
             soft_wrap_mode_override,
+            show_whitespaces_override: None,
             completion_provider: project.clone().map(|project| Box::new(project) as _),
             collaboration_hub: project.clone().map(|project| Box::new(project) as _),
             project,
@@ -1494,9 +1523,13 @@ impl Editor {
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
+            occurrence_highlights_task: Default::default(),
+            selection_drag: None,
             pending_rename: Default::default(),
             searchable: true,
-            cursor_shape: Default::default(),
+            cursor_shape,
+            cursor_shape_overridden: false,
+            cursor_animation: Default::default(),
             autoindent_mode: Some(AutoindentMode::EachLine),
             collapse_matches: false,
             workspace: None,
@@ -1504,6 +1537,7 @@ impl Editor {
             input_enabled: true,
             use_modal_editing: mode == EditorMode::Full,
             read_only: false,
+            font_size_adjustment: Pixels::ZERO,
             use_autoclose: true,
             auto_replace_emoji_shortcode: false,
             leader_peer_id: None,
@@ -1532,12 +1566,14 @@ impl Editor {
             git_blame_inline_enabled: ProjectSettings::get_global(cx).git.inline_blame_enabled(),
             blame: None,
             blame_subscription: None,
+            swap_file_task: None,
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
                 cx.subscribe(&buffer, Self::on_buffer_event),
                 cx.observe(&display_map, Self::on_display_map_changed),
                 cx.observe(&blink_manager, |_, _, cx| cx.notify()),
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
+                cx.on_release(|editor, _, cx| editor.release_swap_file(cx)),
                 observe_buffer_font_size_adjustment(cx, |_, cx| cx.notify()),
                 cx.observe_window_activation(|editor, cx| {
                     let active = cx.is_window_active();
@@ -1792,8 +1828,22 @@ impl Editor {
         }
     }
 
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.cursor_shape
+    }
+
+    /// Shows the cursor with the given shape, rather than the one from the settings, until
+    /// [`Self::reset_cursor_shape`] is called.
     pub fn set_cursor_shape(&mut self, cursor_shape: CursorShape, cx: &mut ViewContext<Self>) {
         self.cursor_shape = cursor_shape;
+        self.cursor_shape_overridden = true;
+        cx.notify();
+    }
+
+    /// Goes back to showing the cursor with the shape from the settings.
+    pub fn reset_cursor_shape(&mut self, cx: &mut ViewContext<Self>) {
+        self.cursor_shape = EditorSettings::get_global(cx).cursor_shape;
+        self.cursor_shape_overridden = false;
         cx.notify();
     }
 
@@ -1850,6 +1900,93 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// The font size this editor renders its buffer with, including the adjustment of its pane.
+    pub fn buffer_font_size(&self, cx: &AppContext) -> Pixels {
+        (ThemeSettings::get_global(cx).buffer_font_size(cx) + self.font_size_adjustment)
+            .max(theme::MIN_FONT_SIZE)
+    }
+
+    /// Whether the editor can be made editable, which it can't if its buffer is read-only,
+    /// such as a guest's buffer in a project they can't edit.
+    pub fn can_toggle_read_only(&self, cx: &AppContext) -> bool {
+        !self.buffer.read(cx).read_only()
+    }
+
+    pub fn toggle_read_only(&mut self, _: &ToggleReadOnly, cx: &mut ViewContext<Self>) {
+        if !self.can_toggle_read_only(cx) {
+            return;
+        }
+        self.read_only = !self.read_only;
+        cx.notify();
+    }
+
+    pub fn convert_line_endings_to_lf(
+        &mut self,
+        _: &ConvertLineEndingsToLf,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.convert_line_endings(LineEnding::Unix, cx);
+    }
+
+    pub fn convert_line_endings_to_crlf(
+        &mut self,
+        _: &ConvertLineEndingsToCrlf,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.convert_line_endings(LineEnding::Windows, cx);
+    }
+
+    /// Gives every buffer in the editor `line_ending`. Those that are saved to a file without
+    /// unsaved edits of their own are saved again, so the conversion is written to disk; the rest
+    /// are written with it when they're next saved.
+    fn convert_line_endings(&mut self, line_ending: LineEnding, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            self.notify_read_only(cx);
+            return;
+        }
+        let mut buffers = HashSet::default();
+        for buffer in self.buffer.read(cx).all_buffers() {
+            let (changed, clean_file) = buffer.update(cx, |buffer, cx| {
+                let changed =
+                    buffer.line_ending() != line_ending || buffer.has_mixed_line_endings();
+                buffer.set_line_ending(line_ending, cx);
+                (changed, buffer.file().is_some() && !buffer.is_dirty())
+            });
+            if changed && clean_file {
+                buffers.insert(buffer);
+            }
+        }
+
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if !buffers.is_empty() {
+            project
+                .update(cx, |project, cx| project.save_buffers(buffers, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Lets the user know why their edit had no effect.
+    fn notify_read_only(&self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        if let Some(workspace) = self.workspace() {
+            workspace.update(cx, |workspace, cx| {
+                struct ReadOnlyEdit;
+
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<ReadOnlyEdit>(),
+                        "This buffer is read-only",
+                    ),
+                    cx,
+                )
+            })
+        }
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -1994,6 +2131,7 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            refresh_occurrence_highlights(self, cx);
             self.discard_inline_completion(cx);
             if self.git_blame_inline_enabled {
                 self.start_inline_blame_timer(cx);
@@ -2446,6 +2584,7 @@ impl Editor {
         let text: Arc<str> = text.into();
 
         if self.read_only(cx) {
+            self.notify_read_only(cx);
             return;
         }
 
@@ -3017,6 +3156,7 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         if self.read_only(cx) {
+            self.notify_read_only(cx);
             return;
         }
 
@@ -3987,6 +4127,9 @@ impl Editor {
                         |theme| theme.editor_document_highlight_write_background,
                         cx,
                     );
+                    if !read_ranges.is_empty() || !write_ranges.is_empty() {
+                        this.clear_background_highlights::<OccurrenceHighlight>(cx);
+                    }
                     cx.notify();
                 })
                 .log_err();
@@ -4872,10 +5015,11 @@ impl Editor {
         });
     }
 
-    pub fn join_lines(&mut self, _: &JoinLines, cx: &mut ViewContext<Self>) {
+    pub fn join_lines(&mut self, action: &JoinLines, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
             return;
         }
+        let separator = action.separator.as_deref().unwrap_or(" ");
         let mut row_ranges = Vec::<Range<u32>>::new();
         for selection in self.selections.all::<Point>(cx) {
             let start = selection.start.row;
@@ -4907,12 +5051,23 @@ impl Editor {
         self.transact(cx, |this, cx| {
             for row_range in row_ranges.into_iter().rev() {
                 for row in row_range.rev() {
-                    let end_of_line = Point::new(row, snapshot.line_len(row));
+                    // Trim trailing whitespace, stopping at the indentation, since joining the
+                    // previous line already removes that.
+                    let line_len = snapshot.line_len(row);
+                    let trailing_whitespace = snapshot
+                        .reversed_chars_at(Point::new(row, line_len))
+                        .take_while(|c| *c == ' ' || *c == '\t')
+                        .count() as u32;
+                    let end_of_line = Point::new(
+                        row,
+                        (line_len - trailing_whitespace.min(line_len))
+                            .max(snapshot.indent_size_for_line(row).len),
+                    );
                     let indent = snapshot.indent_size_for_line(row + 1);
                     let start_of_next_line = Point::new(row + 1, indent.len);
 
                     let replace = if snapshot.line_len(row + 1) > indent.len {
-                        " "
+                        separator
                     } else {
                         ""
                     };
@@ -4929,6 +5084,33 @@ impl Editor {
         });
     }
 
+    pub fn split_line(&mut self, _: &SplitLine, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<Point>(cx);
+        let mut edits = Vec::new();
+        let mut cursor_positions = Vec::new();
+        for selection in &selections {
+            let indent = snapshot.indent_size_for_line(selection.start.row);
+            let text = format!("\n{}", indent.chars().collect::<String>());
+            edits.push((selection.start..selection.end, text));
+            let anchor = snapshot.anchor_before(selection.start);
+            cursor_positions.push(anchor..anchor);
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            // Unlike inserting a newline, splitting leaves the cursors where they were.
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges(cursor_positions)
+            });
+        });
+    }
+
     pub fn sort_lines_case_sensitive(
         &mut self,
         _: &SortLinesCaseSensitive,
@@ -4967,6 +5149,55 @@ impl Editor {
         })
     }
 
+    pub fn sort_lines(&mut self, action: &SortLines, cx: &mut ViewContext<Self>) {
+        let SortLines {
+            descending,
+            case_sensitive,
+            numeric,
+        } = *action;
+        self.manipulate_lines(cx, |lines| {
+            lines.sort_by(|a, b| {
+                let (a, b) = if case_sensitive {
+                    (Cow::Borrowed(*a), Cow::Borrowed(*b))
+                } else {
+                    (Cow::Owned(a.to_lowercase()), Cow::Owned(b.to_lowercase()))
+                };
+                let ordering = if numeric {
+                    compare_numerically(&a, &b)
+                } else {
+                    a.cmp(&b)
+                };
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+        })
+    }
+
+    pub fn unique_lines(&mut self, action: &UniqueLines, cx: &mut ViewContext<Self>) {
+        let UniqueLines {
+            adjacent,
+            case_sensitive,
+        } = *action;
+        let key = move |line: &str| {
+            if case_sensitive {
+                line.to_string()
+            } else {
+                line.to_lowercase()
+            }
+        };
+        self.manipulate_lines(cx, |lines| {
+            if adjacent {
+                lines.dedup_by(|line, previous| key(line) == key(previous));
+            } else {
+                let mut seen = HashSet::default();
+                lines.retain(|line| seen.insert(key(line)));
+            }
+        })
+    }
+
     pub fn revert_selected_hunks(&mut self, _: &RevertSelectedHunks, cx: &mut ViewContext<Self>) {
         let revert_changes = self.gather_revert_changes(&self.selections.disjoint_anchors(), cx);
         if !revert_changes.is_empty() {
@@ -5319,6 +5550,41 @@ impl Editor {
         self.duplicate_line(false, cx);
     }
 
+    /// Inserts a copy of each selection's text right after it, and selects the copies. Without
+    /// any selected text, the selected lines are duplicated instead.
+    pub fn duplicate_selection(&mut self, _: &DuplicateSelection, cx: &mut ViewContext<Self>) {
+        let selections = self.selections.all::<usize>(cx);
+        if selections.iter().all(|selection| selection.is_empty()) {
+            self.duplicate_line(false, cx);
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut delta = 0;
+        for selection in selections {
+            if selection.is_empty() {
+                new_selections.push(selection.start + delta..selection.start + delta);
+                continue;
+            }
+            let text = buffer.text_for_range(selection.range()).collect::<String>();
+            let start = selection.end + delta;
+            new_selections.push(start..start + text.len());
+            delta += text.len();
+            edits.push((selection.end..selection.end, text));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_selections);
+            });
+        });
+    }
+
     pub fn move_line_up(&mut self, _: &MoveLineUp, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
@@ -5564,6 +5830,57 @@ impl Editor {
         });
     }
 
+    pub fn transpose_words(&mut self, _: &TransposeWords, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        let mut new_selections = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let words = if selection.is_empty() {
+                words_to_transpose(&buffer, selection.head())
+            } else {
+                None
+            };
+            let Some((first, second)) = words.filter(|(first, _)| {
+                edits
+                    .last()
+                    .map_or(true, |(last, _)| last.end <= first.start)
+            }) else {
+                new_selections.push(selection);
+                continue;
+            };
+
+            edits.push((
+                first.clone(),
+                buffer.text_for_range(second.clone()).collect(),
+            ));
+            edits.push((second.clone(), buffer.text_for_range(first).collect()));
+            // The words swap places without changing the total length, so the cursor lands
+            // after the second one.
+            new_selections.push(Selection {
+                start: second.end,
+                end: second.end,
+                reversed: false,
+                goal: SelectionGoal::None,
+                ..selection
+            });
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        });
+    }
+
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
@@ -5646,76 +5963,102 @@ impl Editor {
 
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
+            self.notify_read_only(cx);
             return;
         }
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
 
-        self.transact(cx, |this, cx| {
-            if let Some(item) = cx.read_from_clipboard() {
-                let clipboard_text = Cow::Borrowed(item.text());
-                if let Some(mut clipboard_selections) = item.metadata::<Vec<ClipboardSelection>>() {
-                    let old_selections = this.selections.all::<usize>(cx);
-                    let all_selections_were_entire_line =
-                        clipboard_selections.iter().all(|s| s.is_entire_line);
-                    let first_selection_indent_column =
-                        clipboard_selections.first().map(|s| s.first_line_indent);
-                    if clipboard_selections.len() != old_selections.len() {
-                        clipboard_selections.drain(..);
-                    }
+        // Confirm pasting a lot of text, in case it was copied by mistake.
+        let threshold = EditorSettings::get_global(cx).large_paste_threshold;
+        let len = item.text().len();
+        if threshold > 0 && len > threshold {
+            let message = format!("Paste {} of text?", format_text_size(len));
+            let answer = cx.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some("It's larger than the `large_paste_threshold` setting."),
+                &["Paste", "Cancel"],
+            );
+            cx.spawn(|editor, mut cx| async move {
+                if answer.await == Ok(0) {
+                    editor.update(&mut cx, |editor, cx| editor.paste_item(item, cx))?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+            return;
+        }
 
-                    this.buffer.update(cx, |buffer, cx| {
-                        let snapshot = buffer.read(cx);
-                        let mut start_offset = 0;
-                        let mut edits = Vec::new();
-                        let mut original_indent_columns = Vec::new();
-                        let line_mode = this.selections.line_mode;
-                        for (ix, selection) in old_selections.iter().enumerate() {
-                            let to_insert;
-                            let entire_line;
-                            let original_indent_column;
-                            if let Some(clipboard_selection) = clipboard_selections.get(ix) {
-                                let end_offset = start_offset + clipboard_selection.len;
-                                to_insert = &clipboard_text[start_offset..end_offset];
-                                entire_line = clipboard_selection.is_entire_line;
-                                start_offset = end_offset + 1;
-                                original_indent_column =
-                                    Some(clipboard_selection.first_line_indent);
-                            } else {
-                                to_insert = clipboard_text.as_str();
-                                entire_line = all_selections_were_entire_line;
-                                original_indent_column = first_selection_indent_column
-                            }
+        self.paste_item(item, cx);
+    }
 
-                            // If the corresponding selection was empty when this slice of the
-                            // clipboard text was written, then the entire line containing the
-                            // selection was copied. If this selection is also currently empty,
-                            // then paste the line before the current line of the buffer.
-                            let range = if selection.is_empty() && !line_mode && entire_line {
-                                let column = selection.start.to_point(&snapshot).column as usize;
-                                let line_start = selection.start - column;
-                                line_start..line_start
-                            } else {
-                                selection.range()
-                            };
+    fn paste_item(&mut self, item: ClipboardItem, cx: &mut ViewContext<Self>) {
+        self.transact(cx, |this, cx| {
+            let clipboard_text = Cow::Borrowed(item.text());
+            if let Some(mut clipboard_selections) = item.metadata::<Vec<ClipboardSelection>>() {
+                let old_selections = this.selections.all::<usize>(cx);
+                let all_selections_were_entire_line =
+                    clipboard_selections.iter().all(|s| s.is_entire_line);
+                let first_selection_indent_column =
+                    clipboard_selections.first().map(|s| s.first_line_indent);
+                if clipboard_selections.len() != old_selections.len() {
+                    clipboard_selections.drain(..);
+                }
 
-                            edits.push((range, to_insert));
-                            original_indent_columns.extend(original_indent_column);
+                this.buffer.update(cx, |buffer, cx| {
+                    let snapshot = buffer.read(cx);
+                    let mut start_offset = 0;
+                    let mut edits = Vec::new();
+                    let mut original_indent_columns = Vec::new();
+                    let line_mode = this.selections.line_mode;
+                    for (ix, selection) in old_selections.iter().enumerate() {
+                        let to_insert;
+                        let entire_line;
+                        let original_indent_column;
+                        if let Some(clipboard_selection) = clipboard_selections.get(ix) {
+                            let end_offset = start_offset + clipboard_selection.len;
+                            to_insert = &clipboard_text[start_offset..end_offset];
+                            entire_line = clipboard_selection.is_entire_line;
+                            start_offset = end_offset + 1;
+                            original_indent_column = Some(clipboard_selection.first_line_indent);
+                        } else {
+                            to_insert = clipboard_text.as_str();
+                            entire_line = all_selections_were_entire_line;
+                            original_indent_column = first_selection_indent_column
                         }
-                        drop(snapshot);
 
-                        buffer.edit(
-                            edits,
-                            Some(AutoindentMode::Block {
-                                original_indent_columns,
-                            }),
-                            cx,
-                        );
-                    });
+                        // If the corresponding selection was empty when this slice of the
+                        // clipboard text was written, then the entire line containing the
+                        // selection was copied. If this selection is also currently empty,
+                        // then paste the line before the current line of the buffer.
+                        let range = if selection.is_empty() && !line_mode && entire_line {
+                            let column = selection.start.to_point(&snapshot).column as usize;
+                            let line_start = selection.start - column;
+                            line_start..line_start
+                        } else {
+                            selection.range()
+                        };
 
-                    let selections = this.selections.all::<usize>(cx);
-                    this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
-                } else {
-                    this.insert(&clipboard_text, cx);
-                }
+                        edits.push((range, to_insert));
+                        original_indent_columns.extend(original_indent_column);
+                    }
+                    drop(snapshot);
+
+                    buffer.edit(
+                        edits,
+                        Some(AutoindentMode::Block {
+                            original_indent_columns,
+                        }),
+                        cx,
+                    );
+                });
+
+                let selections = this.selections.all::<usize>(cx);
+                this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+            } else {
+                this.insert(&clipboard_text, cx);
             }
         });
     }
@@ -6262,10 +6605,11 @@ impl Editor {
         action: &MoveToBeginningOfLine,
         cx: &mut ViewContext<Self>,
     ) {
+        let smart_home = EditorSettings::get_global(cx).smart_home;
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
             s.move_cursors_with(|map, head, _| {
                 (
-                    movement::indented_line_beginning(map, head, action.stop_at_soft_wraps),
+                    line_beginning(map, head, smart_home, action.stop_at_soft_wraps),
                     SelectionGoal::None,
                 )
             });
@@ -6277,10 +6621,11 @@ impl Editor {
         action: &SelectToBeginningOfLine,
         cx: &mut ViewContext<Self>,
     ) {
+        let smart_home = EditorSettings::get_global(cx).smart_home;
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
             s.move_heads_with(|map, head, _| {
                 (
-                    movement::indented_line_beginning(map, head, action.stop_at_soft_wraps),
+                    line_beginning(map, head, smart_home, action.stop_at_soft_wraps),
                     SelectionGoal::None,
                 )
             });
@@ -6499,6 +6844,15 @@ impl Editor {
                 }
             }
 
+            // Jumping back and forth from the same spot shouldn't fill the history with copies of it,
+            // but it's still a new navigation that leaves nothing to go forward to.
+            if nav_history.is_last_entry(|data: &NavigationData| {
+                data.cursor_position.row == cursor_position.row
+            }) {
+                nav_history.clear_forward_stack(cx);
+                return;
+            }
+
             nav_history.push(
                 Some(NavigationData {
                     cursor_anchor,
@@ -6567,6 +6921,38 @@ impl Editor {
         });
     }
 
+    /// Replaces each selection with a cursor at the end of every line it spans.
+    pub fn add_cursors_to_line_ends(
+        &mut self,
+        _: &AddCursorsToLineEnds,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut to_unfold = Vec::new();
+        let mut new_selection_ranges = Vec::new();
+        {
+            let selections = self.selections.all::<Point>(cx);
+            let buffer = self.buffer.read(cx).read(cx);
+            for selection in selections {
+                // A selection ending at the start of a line doesn't span it.
+                let end_row =
+                    if selection.end.column == 0 && selection.end.row > selection.start.row {
+                        selection.end.row - 1
+                    } else {
+                        selection.end.row
+                    };
+                for row in selection.start.row..=end_row {
+                    let cursor = Point::new(row, buffer.line_len(row));
+                    new_selection_ranges.push(cursor..cursor);
+                }
+                to_unfold.push(selection.start..selection.end);
+            }
+        }
+        self.unfold_ranges(to_unfold, true, true, cx);
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges(new_selection_ranges);
+        });
+    }
+
     pub fn add_selection_above(&mut self, _: &AddSelectionAbove, cx: &mut ViewContext<Self>) {
         self.add_selection(true, cx);
     }
@@ -7400,6 +7786,23 @@ impl Editor {
         self.select_larger_syntax_node_stack = stack;
     }
 
+    /// Moves the cursor to the start of the `ix`th symbol containing it, outermost first, as
+    /// listed after the file name in the breadcrumbs.
+    pub fn go_to_enclosing_symbol(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let cursor = self.selections.newest_anchor().head();
+        let Some(symbol) = self
+            .buffer
+            .read(cx)
+            .symbols_containing(cursor, None, cx)
+            .and_then(|(_, symbols)| symbols.into_iter().nth(ix))
+        else {
+            return;
+        };
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_anchor_ranges([symbol.range.start..symbol.range.start]);
+        });
+    }
+
     pub fn move_to_enclosing_bracket(
         &mut self,
         _: &MoveToEnclosingBracket,
@@ -7526,7 +7929,8 @@ impl Editor {
             if let Some(popover) = self.hover_state.diagnostic_popover.as_ref() {
                 let (group_id, jump_to) = popover.activation_info();
                 if self.activate_diagnostics(group_id, cx) {
-                    self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    let autoscroll = Autoscroll::navigation(Autoscroll::fit(), cx);
+                    self.change_selections(Some(autoscroll), cx, |s| {
                         let mut new_selection = s.newest_anchor().clone();
                         new_selection.collapse_to(jump_to, SelectionGoal::None);
                         s.select_anchors(vec![new_selection.clone()]);
@@ -7574,7 +7978,8 @@ impl Editor {
 
             if let Some((primary_range, group_id)) = group {
                 if self.activate_diagnostics(group_id, cx) {
-                    self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    let autoscroll = Autoscroll::navigation(Autoscroll::fit(), cx);
+                    self.change_selections(Some(autoscroll), cx, |s| {
                         s.select(vec![Selection {
                             id: selection.id,
                             start: primary_range.start,
@@ -7839,7 +8244,8 @@ impl Editor {
                         let range = target.range.to_offset(target.buffer.read(cx));
                         let range = editor.range_for_match(&range);
                         if Some(&target.buffer) == editor.buffer.read(cx).as_singleton().as_ref() {
-                            editor.change_selections(Some(Autoscroll::focused()), cx, |s| {
+                            let autoscroll = Autoscroll::navigation(Autoscroll::focused(), cx);
+                            editor.change_selections(Some(autoscroll), cx, |s| {
                                 s.select_ranges([range]);
                             });
                         } else {
@@ -7858,13 +8264,11 @@ impl Editor {
                                     // When selecting a definition in a different buffer, disable the nav history
                                     // to avoid creating a history entry at the previous cursor location.
                                     pane.update(cx, |pane, _| pane.disable_history());
-                                    target_editor.change_selections(
-                                        Some(Autoscroll::focused()),
-                                        cx,
-                                        |s| {
-                                            s.select_ranges([range]);
-                                        },
-                                    );
+                                    let autoscroll =
+                                        Autoscroll::navigation(Autoscroll::focused(), cx);
+                                    target_editor.change_selections(Some(autoscroll), cx, |s| {
+                                        s.select_ranges([range]);
+                                    });
                                     pane.update(cx, |pane, _| pane.enable_history());
                                 });
                             });
@@ -8957,6 +9361,15 @@ impl Editor {
         }
     }
 
+    /// How much more than the line they continue soft-wrapped lines are indented, or `None` if
+    /// they start at column zero.
+    pub fn soft_wrap_indent(&self, cx: &AppContext) -> Option<u32> {
+        let settings = self.buffer.read(cx).settings_at(0, cx);
+        settings
+            .soft_wrap_indent
+            .then_some(settings.soft_wrap_extra_indent)
+    }
+
     pub fn set_soft_wrap_mode(
         &mut self,
         mode: language_settings::SoftWrap,
@@ -8989,6 +9402,12 @@ impl Editor {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    // Called by the element, like `set_wrap_width`.
+    pub(crate) fn set_wrap_indent(&self, indent: Option<u32>, cx: &mut AppContext) -> bool {
+        self.display_map
+            .update(cx, |map, cx| map.set_wrap_indent(indent, cx))
+    }
+
     pub fn toggle_soft_wrap(&mut self, _: &ToggleSoftWrap, cx: &mut ViewContext<Self>) {
         if self.soft_wrap_mode_override.is_some() {
             self.soft_wrap_mode_override.take();
@@ -9004,6 +9423,30 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn show_whitespaces(&self, cx: &AppContext) -> ShowWhitespaceSetting {
+        self.show_whitespaces_override
+            .unwrap_or_else(|| self.buffer.read(cx).settings_at(0, cx).show_whitespaces)
+    }
+
+    pub fn toggle_show_whitespaces(
+        &mut self,
+        _: &ToggleShowWhitespaces,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.show_whitespaces_override.is_some() {
+            self.show_whitespaces_override.take();
+        } else {
+            let show_whitespaces = match self.show_whitespaces(cx) {
+                ShowWhitespaceSetting::All => ShowWhitespaceSetting::None,
+                ShowWhitespaceSetting::Selection
+                | ShowWhitespaceSetting::Boundary
+                | ShowWhitespaceSetting::None => ShowWhitespaceSetting::All,
+            };
+            self.show_whitespaces_override = Some(show_whitespaces);
+        }
+        cx.notify();
+    }
+
     pub fn toggle_line_numbers(&mut self, _: &ToggleLineNumbers, cx: &mut ViewContext<Self>) {
         let mut editor_settings = EditorSettings::get_global(cx).clone();
         editor_settings.gutter.line_numbers = !editor_settings.gutter.line_numbers;
@@ -9646,6 +10089,7 @@ impl Editor {
                 }
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                self.schedule_swap_file_write(cx);
 
                 if *singleton_buffer_edited {
                     if let Some(project) = &self.project {
@@ -9701,8 +10145,16 @@ impl Editor {
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
-            multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
-            multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
+            multi_buffer::Event::DirtyChanged => {
+                if !multibuffer.read(cx).is_dirty(cx) {
+                    self.remove_swap_file(cx);
+                }
+                cx.emit(EditorEvent::DirtyChanged)
+            }
+            multi_buffer::Event::Saved => {
+                self.remove_swap_file(cx);
+                cx.emit(EditorEvent::Saved)
+            }
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
                 cx.emit(EditorEvent::TitleChanged)
             }
@@ -9725,6 +10177,79 @@ impl Editor {
         };
     }
 
+    /// Returns the absolute path and the [`Fs`](project::Fs) of the local file being edited,
+    /// if this editor should keep a swap file for it.
+    fn swap_file_target(&self, cx: &AppContext) -> Option<(PathBuf, Arc<dyn project::Fs>)> {
+        if self.mode != EditorMode::Full {
+            return None;
+        }
+        let project = self.project.as_ref()?.read(cx);
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        let abs_path = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())?
+            .abs_path(cx);
+        Some((abs_path, project.fs().clone()))
+    }
+
+    fn schedule_swap_file_write(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((abs_path, fs)) = self.swap_file_target(cx) else {
+            return;
+        };
+        self.swap_file_task = Some(cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(swap_file::SWAP_FILE_DEBOUNCE)
+                .await;
+            let Some(text) = editor
+                .update(&mut cx, |editor, cx| {
+                    let buffer = editor.buffer.read(cx);
+                    buffer.is_dirty(cx).then(|| buffer.snapshot(cx).text())
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            swap_file::write_swap_file(fs.as_ref(), abs_path, text)
+                .await
+                .log_err();
+        }));
+    }
+
+    fn remove_swap_file(&mut self, cx: &mut AppContext) {
+        self.swap_file_task.take();
+        if let Some((abs_path, fs)) = self.swap_file_target(cx) {
+            cx.background_executor()
+                .spawn(async move { swap_file::remove_swap_file(fs.as_ref(), &abs_path).await })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Removes the swap file when this editor goes away, unless its buffer is still open and
+    /// dirty in another editor, which keeps writing to it.
+    fn release_swap_file(&mut self, cx: &mut AppContext) {
+        self.swap_file_task.take();
+        let Some((abs_path, fs)) = self.swap_file_target(cx) else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.downgrade();
+        // The buffer is only dropped after this editor, so check on it once the release settles.
+        cx.spawn(|mut cx| async move {
+            let still_dirty = buffer
+                .update(&mut cx, |buffer, _| buffer.is_dirty())
+                .unwrap_or(false);
+            if !still_dirty {
+                swap_file::remove_swap_file(fs.as_ref(), &abs_path).await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn on_display_map_changed(&mut self, _: Model<DisplayMap>, cx: &mut ViewContext<Self>) {
         cx.notify();
     }
@@ -9742,6 +10267,9 @@ impl Editor {
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        if !self.cursor_shape_overridden {
+            self.cursor_shape = editor_settings.cursor_shape;
+        }
 
         if self.mode == EditorMode::Full {
             let inline_blame_enabled = ProjectSettings::get_global(cx).git.inline_blame_enabled();
@@ -10320,6 +10848,19 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Describes an amount of text by its size, in the largest unit it's at least one of.
+fn format_text_size(len: usize) -> String {
+    const KB: f64 = 1024.;
+    let len = len as f64;
+    if len >= KB * KB {
+        format!("{:.1} MB", len / (KB * KB))
+    } else if len >= KB {
+        format!("{:.1} KB", len / KB)
+    } else {
+        format!("{len} bytes")
+    }
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -10539,7 +11080,7 @@ impl Render for Editor {
                 color: cx.theme().colors().editor_foreground,
                 font_family: settings.buffer_font.family.clone(),
                 font_features: settings.buffer_font.features.clone(),
-                font_size: settings.buffer_font_size(cx).into(),
+                font_size: self.buffer_font_size(cx).into(),
                 font_weight: FontWeight::NORMAL,
                 font_style: FontStyle::Normal,
                 line_height: relative(settings.buffer_line_height.value()),
@@ -11101,6 +11642,126 @@ pub fn styled_runs_for_code_label<'a>(
         })
 }
 
+fn line_beginning(
+    map: &DisplaySnapshot,
+    head: DisplayPoint,
+    smart_home: bool,
+    stop_at_soft_wraps: bool,
+) -> DisplayPoint {
+    if smart_home {
+        movement::indented_line_beginning(map, head, stop_at_soft_wraps)
+    } else {
+        movement::line_beginning(map, head, stop_at_soft_wraps)
+    }
+}
+
+/// Returns the ranges of the words to swap around `offset`: the word the cursor is in (or the one
+/// before it) and the next word after that.
+fn words_to_transpose(
+    buffer: &MultiBufferSnapshot,
+    offset: usize,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let scope = buffer.language_scope_at(offset);
+    let is_word = |c: &char| char_kind(&scope, *c) == CharKind::Word;
+    let word_len_after = |offset: usize| -> usize {
+        buffer
+            .chars_at(offset)
+            .take_while(is_word)
+            .map(char::len_utf8)
+            .sum()
+    };
+    let word_start_before = |offset: usize| -> usize {
+        offset
+            - buffer
+                .reversed_chars_at(offset)
+                .take_while(is_word)
+                .map(char::len_utf8)
+                .sum::<usize>()
+    };
+    let word_end_before = |offset: usize| -> usize {
+        offset
+            - buffer
+                .reversed_chars_at(offset)
+                .take_while(|c| !is_word(c))
+                .map(char::len_utf8)
+                .sum::<usize>()
+    };
+
+    let inside_word = buffer
+        .reversed_chars_at(offset)
+        .next()
+        .is_some_and(|c| is_word(&c))
+        && buffer.chars_at(offset).next().is_some_and(|c| is_word(&c));
+    let first_end = if inside_word {
+        offset + word_len_after(offset)
+    } else {
+        word_end_before(offset)
+    };
+    let first_start = word_start_before(first_end);
+    let second_start = first_end
+        + buffer
+            .chars_at(first_end)
+            .take_while(|c| !is_word(c))
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let second_end = second_start + word_len_after(second_start);
+
+    // With no word after the cursor, the two words before it are swapped, as in Emacs.
+    if second_start == second_end {
+        let second = first_start..first_end;
+        let first_end = word_end_before(second.start);
+        let first_start = word_start_before(first_end);
+        return (first_start < first_end && second.start < second.end)
+            .then_some((first_start..first_end, second));
+    }
+
+    (first_start < first_end && second_start < second_end)
+        .then_some((first_start..first_end, second_start..second_end))
+}
+
+/// Compares two strings, treating each run of ASCII digits as a single number.
+fn compare_numerically(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let a_digits = take_digits(&mut a_chars);
+                let b_digits = take_digits(&mut b_chars);
+                let a_number = a_digits.trim_start_matches('0');
+                let b_number = b_digits.trim_start_matches('0');
+                let ordering = a_number
+                    .len()
+                    .cmp(&b_number.len())
+                    .then_with(|| a_number.cmp(b_number))
+                    .then_with(|| a_digits.len().cmp(&b_digits.len()));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char.cmp(&b_char);
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits
+}
+
 pub(crate) fn split_words(text: &str) -> impl std::iter::Iterator<Item = &str> + '_ {
     let mut prev_index = 0;
     let mut prev_codepoint: Option<char> = None;
@@ -1,11 +1,23 @@
 use gpui::AppContext;
+use language::CursorShape;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
+/// The shortest interval the cursor blinks at, so that it can't be made to redraw the editor
+/// constantly.
+pub const MIN_CURSOR_BLINK_INTERVAL_MS: u64 = 100;
+
 #[derive(Deserialize, Clone)]
 pub struct EditorSettings {
     pub cursor_blink: bool,
+    pub cursor_blink_interval: u64,
+    pub cursor_shape: CursorShape,
+    pub selection_cursor_shape: Option<CursorShape>,
+    pub cursor_animation: bool,
+    pub current_line_highlight: bool,
+    pub current_column_highlight: bool,
+    pub highlight_occurrences: bool,
     pub hover_popover_enabled: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
@@ -16,10 +28,14 @@ pub struct EditorSettings {
     pub gutter: Gutter,
     pub vertical_scroll_margin: f32,
     pub scroll_sensitivity: f32,
+    pub smooth_scroll: bool,
+    pub smart_home: bool,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
+    pub navigation_reveal: NavigationReveal,
     pub multi_cursor_modifier: MultiCursorModifier,
     pub redact_private_values: bool,
+    pub large_paste_threshold: usize,
     #[serde(default)]
     pub double_click_in_multibuffer: DoubleClickInMultibuffer,
 }
@@ -36,6 +52,21 @@ pub enum SeedQuerySetting {
     Never,
 }
 
+/// How to scroll to a search result, diagnostic or definition that's been navigated to.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NavigationReveal {
+    /// Scroll search results and diagnostics just enough to be visible, and definitions to
+    /// near the top of the editor.
+    Auto,
+    /// Scroll the target to the middle of the editor.
+    Center,
+    /// Scroll the target to near the top of the editor.
+    Top,
+    /// Scroll only as much as needed for the target to be visible.
+    Minimal,
+}
+
 /// What to do when multibuffer is double clicked in some of its excerpts (parts of singleton buffers).
 #[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -105,6 +136,37 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub cursor_blink: Option<bool>,
+    /// How long the cursor stays shown or hidden while blinking, in milliseconds. Intervals
+    /// shorter than 100 milliseconds are raised to it.
+    ///
+    /// Default: 500
+    pub cursor_blink_interval: Option<u64>,
+    /// The shape of the cursor.
+    ///
+    /// Default: bar
+    pub cursor_shape: Option<CursorShape>,
+    /// The shape of the cursor while it's selecting text, if different from `cursor_shape`.
+    ///
+    /// Default: null
+    pub selection_cursor_shape: Option<CursorShape>,
+    /// Whether the cursor glides to its new position over a few frames when it moves.
+    /// Disabled when the system asks to reduce motion.
+    ///
+    /// Default: false
+    pub cursor_animation: Option<bool>,
+    /// Whether to highlight the background of the lines containing a cursor.
+    ///
+    /// Default: true
+    pub current_line_highlight: Option<bool>,
+    /// Whether to highlight the columns containing a cursor.
+    ///
+    /// Default: false
+    pub current_column_highlight: Option<bool>,
+    /// Whether to highlight the other occurrences of the word under the cursor,
+    /// or of the selected text, when the language server doesn't highlight them.
+    ///
+    /// Default: false
+    pub highlight_occurrences: Option<bool>,
     /// Whether to show the informational hover box when moving the mouse
     /// over symbols in the editor.
     ///
@@ -145,6 +207,16 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 1.0
     pub scroll_sensitivity: Option<f32>,
+    /// Whether to animate scrolling over a few frames instead of jumping
+    /// to the new position. Disabled when the system asks to reduce motion.
+    ///
+    /// Default: false
+    pub smooth_scroll: Option<bool>,
+    /// Whether moving to the beginning of a line goes to its first non-whitespace
+    /// character first, and to column zero when already there.
+    ///
+    /// Default: true
+    pub smart_home: Option<bool>,
     /// Whether the line numbers on editors gutter are relative or not.
     ///
     /// Default: false
@@ -153,6 +225,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: always
     pub seed_search_query_from_cursor: Option<SeedQuerySetting>,
+    /// How to scroll to a search result, diagnostic or definition that's been
+    /// navigated to.
+    ///
+    /// Default: auto
+    pub navigation_reveal: Option<NavigationReveal>,
     /// The key to use for adding multiple cursors
     ///
     /// Default: alt
@@ -163,6 +240,11 @@ pub struct EditorSettingsContent {
     ///
     /// Default: false
     pub redact_private_values: Option<bool>,
+    /// The size of clipboard text, in bytes, above which pasting it asks for
+    /// confirmation first. Set to 0 to never ask.
+    ///
+    /// Default: 1048576
+    pub large_paste_threshold: Option<usize>,
 
     /// What to do when multibuffer is double clicked in some of its excerpts
     /// (parts of singleton buffers).
@@ -239,6 +321,10 @@ impl Settings for EditorSettings {
         sources: SettingsSources<Self::FileContent>,
         _: &mut AppContext,
     ) -> anyhow::Result<Self> {
-        sources.json_merge()
+        let mut settings: Self = sources.json_merge()?;
+        settings.cursor_blink_interval = settings
+            .cursor_blink_interval
+            .max(MIN_CURSOR_BLINK_INTERVAL_MS);
+        Ok(settings)
     }
 }
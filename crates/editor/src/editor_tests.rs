@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    editor_settings::NavigationReveal,
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
@@ -12,14 +13,17 @@ use futures::StreamExt;
 use gpui::{div, TestAppContext, VisualTestContext, WindowOptions};
 use indoc::indoc;
 use language::{
-    language_settings::{AllLanguageSettings, AllLanguageSettingsContent, LanguageSettingsContent},
+    language_settings::{
+        AllLanguageSettings, AllLanguageSettingsContent, FinalNewlineOnSave,
+        LanguageSettingsContent,
+    },
     BracketPairConfig,
     Capability::ReadWrite,
     FakeLspAdapter, LanguageConfig, LanguageConfigOverride, LanguageMatcher, Override, Point,
 };
 use parking_lot::Mutex;
 use project::project_settings::{LspSettings, ProjectSettings};
-use project::FakeFs;
+use project::{FakeFs, Fs as _};
 use serde_json::{self, json};
 use std::sync::atomic;
 use std::sync::atomic::AtomicUsize;
@@ -31,7 +35,8 @@ use util::{
 };
 use workspace::{
     item::{FollowEvent, FollowableItem, Item, ItemHandle},
-    NavigationEntry, ViewId,
+    searchable::SearchableItem,
+    NavigationEntry, NavigationMode, StatusItemView, ViewId,
 };
 
 #[gpui::test]
@@ -423,6 +428,84 @@ fn test_selection_with_mouse(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_word_and_line_selection_with_mouse(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("ˇlet foo_bar = 1;\nlet baz = 2;\n");
+    cx.update_editor(|editor, cx| {
+        editor.begin_selection(DisplayPoint::new(0, 6), false, 2, cx);
+        editor.end_selection(cx);
+    });
+    cx.assert_editor_state("let «foo_barˇ» = 1;\nlet baz = 2;\n");
+
+    cx.update_editor(|editor, cx| {
+        editor.begin_selection(DisplayPoint::new(0, 6), false, 3, cx);
+        editor.end_selection(cx);
+    });
+    cx.assert_editor_state("«let foo_bar = 1;\nˇ»let baz = 2;\n");
+}
+
+#[gpui::test]
+async fn test_drag_selection_to_move_text(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("one «twoˇ» three\n");
+    cx.update_editor(|editor, cx| {
+        assert!(editor.begin_selection_drag(DisplayPoint::new(0, 5), cx));
+        editor.update_selection_drag(DisplayPoint::new(0, 10), cx);
+        editor.update_selection_drag(DisplayPoint::new(0, 13), cx);
+        editor.end_selection_drag(DisplayPoint::new(0, 13), false, cx);
+    });
+    cx.assert_editor_state("one  three«twoˇ»\n");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("one «twoˇ» three\n");
+
+    // Dropping with the copy modifier keeps the original.
+    cx.update_editor(|editor, cx| {
+        assert!(editor.begin_selection_drag(DisplayPoint::new(0, 5), cx));
+        editor.update_selection_drag(DisplayPoint::new(0, 0), cx);
+        editor.end_selection_drag(DisplayPoint::new(0, 0), true, cx);
+    });
+    cx.assert_editor_state("«twoˇ»one two three\n");
+
+    // Pressing outside a selection doesn't start a drag, and releasing without moving places
+    // the cursor where the mouse was pressed.
+    cx.set_state("one «twoˇ» three\n");
+    cx.update_editor(|editor, cx| {
+        assert!(!editor.begin_selection_drag(DisplayPoint::new(0, 1), cx));
+        assert!(editor.begin_selection_drag(DisplayPoint::new(0, 5), cx));
+        editor.end_selection_drag(DisplayPoint::new(0, 5), false, cx);
+    });
+    cx.assert_editor_state("one tˇwo three\n");
+}
+
+#[cfg(target_os = "linux")]
+#[gpui::test]
+async fn test_primary_selection_and_middle_click_paste(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.write_to_clipboard(ClipboardItem::new("from the clipboard".into()));
+    cx.set_state("one «twoˇ» three\n");
+    assert_eq!(
+        cx.update(|cx| cx.read_from_primary())
+            .map(|item| item.text().clone()),
+        Some("two".into())
+    );
+
+    let position = cx.pixel_position("ˇone two three\n");
+    cx.simulate_mouse_down(position, gpui::MouseButton::Middle, gpui::Modifiers::none());
+    cx.simulate_mouse_up(position, gpui::MouseButton::Middle, gpui::Modifiers::none());
+    cx.assert_editor_state("twoˇone two three\n");
+    assert_eq!(
+        cx.read_from_clipboard().map(|item| item.text().clone()),
+        Some("from the clipboard".into())
+    );
+}
+
 #[gpui::test]
 fn test_canceling_pending_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -657,6 +740,82 @@ async fn test_navigation_history(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_navigation_history_deduplicates_entries(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    use workspace::item::Item;
+
+    let fs = FakeFs::new(cx.executor());
+    let project = Project::test(fs, [], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project, cx));
+    let pane = workspace
+        .update(cx, |workspace, _| workspace.active_pane().clone())
+        .unwrap();
+
+    _ = workspace.update(cx, |_v, cx| {
+        cx.new_view(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(300, 5, 'a'), cx);
+            let mut editor = build_editor(buffer.clone(), cx);
+            let handle = cx.view();
+            editor.set_nav_history(Some(pane.read(cx).nav_history_for_item(&handle)));
+
+            fn pop_history(editor: &mut Editor, cx: &mut WindowContext) -> Option<NavigationEntry> {
+                editor.nav_history.as_mut().unwrap().pop_backward(cx)
+            }
+
+            // Leaving the same location repeatedly records it only once.
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(3, 0)..DisplayPoint::new(3, 0)])
+            });
+            editor.deactivated(cx);
+            editor.deactivated(cx);
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(40, 0)..DisplayPoint::new(40, 0)])
+            });
+
+            let nav_entry = pop_history(&mut editor, cx).unwrap();
+            editor.navigate(nav_entry.data.unwrap(), cx);
+            assert_eq!(
+                editor.selections.display_ranges(cx),
+                &[DisplayPoint::new(3, 0)..DisplayPoint::new(3, 0)]
+            );
+            assert!(pop_history(&mut editor, cx).is_none());
+
+            // Distinct locations are still recorded separately.
+            editor.deactivated(cx);
+            editor.change_selections(None, cx, |s| {
+                s.select_display_ranges([DisplayPoint::new(40, 0)..DisplayPoint::new(40, 0)])
+            });
+            editor.deactivated(cx);
+            assert!(pop_history(&mut editor, cx).is_some());
+            assert!(pop_history(&mut editor, cx).is_some());
+            assert!(pop_history(&mut editor, cx).is_none());
+
+            // Recording the location on top of the history again still discards the forward history.
+            editor.deactivated(cx);
+            pane.update(cx, |pane, _| {
+                pane.nav_history_mut().set_mode(NavigationMode::GoingBack)
+            });
+            editor.deactivated(cx);
+            pane.update(cx, |pane, _| {
+                pane.nav_history_mut().set_mode(NavigationMode::Normal)
+            });
+            assert!(pane.read(cx).can_navigate_forward());
+            editor.deactivated(cx);
+            assert!(!pane.read(cx).can_navigate_forward());
+            assert!(editor
+                .nav_history
+                .as_mut()
+                .unwrap()
+                .pop_forward(cx)
+                .is_none());
+
+            editor
+        })
+    });
+}
+
 #[gpui::test]
 fn test_cancel(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1301,6 +1460,43 @@ fn test_beginning_end_of_line_ignore_soft_wrap(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_smart_home(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let move_to_beginning = MoveToBeginningOfLine {
+        stop_at_soft_wraps: true,
+    };
+    let select_to_beginning = SelectToBeginningOfLine {
+        stop_at_soft_wraps: true,
+    };
+
+    cx.set_state("    let a = ˇb;\n");
+    cx.update_editor(|editor, cx| editor.move_to_beginning_of_line(&move_to_beginning, cx));
+    cx.assert_editor_state("    ˇlet a = b;\n");
+    cx.update_editor(|editor, cx| editor.move_to_beginning_of_line(&move_to_beginning, cx));
+    cx.assert_editor_state("ˇ    let a = b;\n");
+    cx.update_editor(|editor, cx| editor.move_to_beginning_of_line(&move_to_beginning, cx));
+    cx.assert_editor_state("    ˇlet a = b;\n");
+
+    cx.set_state("    let a = ˇb;\n");
+    cx.update_editor(|editor, cx| editor.select_to_beginning_of_line(&select_to_beginning, cx));
+    cx.assert_editor_state("    «ˇlet a = »b;\n");
+    cx.update_editor(|editor, cx| editor.select_to_beginning_of_line(&select_to_beginning, cx));
+    cx.assert_editor_state("«ˇ    let a = »b;\n");
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.smart_home = Some(false);
+            });
+        })
+    });
+    cx.set_state("    let a = ˇb;\n");
+    cx.update_editor(|editor, cx| editor.move_to_beginning_of_line(&move_to_beginning, cx));
+    cx.assert_editor_state("ˇ    let a = b;\n");
+}
+
 #[gpui::test]
 fn test_prev_next_word_boundary(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1661,6 +1857,341 @@ async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_navigation_reveal(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(2, cx);
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+
+    let text = (0..40)
+        .map(|row| format!("line {row}\n"))
+        .collect::<String>();
+    cx.set_state(&format!("ˇ{text}"));
+
+    let set_navigation_reveal = |reveal: NavigationReveal, cx: &mut EditorTestContext| {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.navigation_reveal = Some(reveal);
+                });
+            })
+        });
+    };
+    let activate_match_at_row = |row: u32, cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let range = snapshot.anchor_before(Point::new(row, 0))
+                ..snapshot.anchor_after(Point::new(row, 4));
+            editor.activate_match(0, &[range], cx);
+        });
+        cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().y)
+    };
+
+    // The target line is scrolled to the middle of the editor.
+    set_navigation_reveal(NavigationReveal::Center, &mut cx);
+    assert_eq!(activate_match_at_row(20, &mut cx), 16.);
+
+    // The editor only scrolls for the target line to be visible, keeping the vertical scroll
+    // margin below it, and doesn't scroll at all when it's already visible.
+    cx.update_editor(|editor, cx| editor.set_scroll_position(gpui::Point::new(0., 0.), cx));
+    set_navigation_reveal(NavigationReveal::Minimal, &mut cx);
+    assert_eq!(activate_match_at_row(20, &mut cx), 13.);
+    assert_eq!(activate_match_at_row(15, &mut cx), 13.);
+}
+
+#[gpui::test]
+async fn test_vertical_scroll_margin_when_moving_cursor(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(2, cx);
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 6. * line_height));
+
+    cx.set_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+        five
+        six
+        seven
+        eight
+        nine
+        ten"});
+
+    // Moving towards the bottom of the viewport scrolls once fewer than
+    // two lines would remain visible below the cursor.
+    let mut scroll_positions = Vec::new();
+    for _ in 0..9 {
+        cx.update_editor(|editor, cx| editor.move_down(&Default::default(), cx));
+        scroll_positions
+            .push(cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().y));
+    }
+    // Close to the end of the file, the margin shrinks instead of scrolling
+    // past the last line.
+    assert_eq!(scroll_positions, [0., 0., 0., 1., 2., 3., 4., 4., 4.]);
+
+    // The same applies when moving back up to the start of the file.
+    let mut scroll_positions = Vec::new();
+    for _ in 0..9 {
+        cx.update_editor(|editor, cx| editor.move_up(&Default::default(), cx));
+        scroll_positions
+            .push(cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().y));
+    }
+    assert_eq!(scroll_positions, [4., 4., 4., 3., 2., 1., 0., 0., 0.]);
+}
+
+#[gpui::test]
+async fn test_scroll_cursor_center_top_bottom(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(2, cx);
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+
+    cx.set_state(&format!("ˇ{}", sample_text(30, 3, 'a')));
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(15, 0)..Point::new(15, 0)])
+        });
+    });
+
+    // The cursor line ends up in the middle, at the top, or at the bottom of the
+    // viewport, with the top and bottom variants respecting the scroll margin.
+    let scroll_top_after =
+        |cx: &mut EditorTestContext, scroll: fn(&mut Editor, &mut ViewContext<Editor>)| {
+            cx.update_editor(|editor, cx| scroll(editor, cx));
+            cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().y)
+        };
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_center(&ScrollCursorCenter, cx)),
+        10.
+    );
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_top(&ScrollCursorTop, cx)),
+        13.
+    );
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_bottom(&ScrollCursorBottom, cx)),
+        7.
+    );
+
+    // Near the start of the buffer, the viewport can't scroll above the first line.
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+    });
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_center(&ScrollCursorCenter, cx)),
+        0.
+    );
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_top(&ScrollCursorTop, cx)),
+        0.
+    );
+    assert_eq!(
+        scroll_top_after(&mut cx, |editor, cx| editor
+            .scroll_cursor_bottom(&ScrollCursorBottom, cx)),
+        0.
+    );
+}
+
+#[gpui::test]
+async fn test_smooth_scroll(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(&format!("ˇ{}", sample_text(100, 3, 'a')));
+
+    fn scroll_top(cx: &mut EditorTestContext) -> f32 {
+        cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().y)
+    }
+
+    fn set_smooth_scroll(enabled: bool, cx: &mut EditorTestContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.smooth_scroll = Some(enabled);
+                });
+            })
+        });
+    }
+
+    // With smooth scrolling, the viewport moves towards the target over several frames,
+    // covering less distance in each of them.
+    set_smooth_scroll(true, &mut cx);
+    cx.update_editor(|editor, cx| editor.scroll_screen(&ScrollAmount::Line(8.), cx));
+    assert_eq!(scroll_top(&mut cx), 0.);
+    let mut positions = Vec::new();
+    for _ in 0..crate::scroll::SMOOTH_SCROLL_FRAMES {
+        cx.executor()
+            .advance_clock(crate::scroll::SMOOTH_SCROLL_FRAME_DURATION);
+        cx.run_until_parked();
+        positions.push(scroll_top(&mut cx));
+    }
+    assert_eq!(positions.last(), Some(&8.));
+    for (previous, next) in [0.].iter().chain(&positions).zip(&positions) {
+        assert!(
+            previous < next,
+            "scroll positions should increase: {positions:?}"
+        );
+    }
+    let steps = [0.]
+        .iter()
+        .chain(&positions)
+        .zip(&positions)
+        .map(|(previous, next)| next - previous)
+        .collect::<Vec<_>>();
+    for (previous, next) in steps.iter().zip(&steps[1..]) {
+        assert!(previous > next, "scrolling should ease out: {steps:?}");
+    }
+    cx.update_editor(|editor, _| assert!(!editor.scroll_manager.is_animating()));
+
+    // Scrolling again while an animation is in progress continues from its target.
+    cx.update_editor(|editor, cx| editor.scroll_screen(&ScrollAmount::Line(2.), cx));
+    cx.executor()
+        .advance_clock(crate::scroll::SMOOTH_SCROLL_FRAME_DURATION);
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| editor.scroll_screen(&ScrollAmount::Line(2.), cx));
+    cx.executor().advance_clock(
+        crate::scroll::SMOOTH_SCROLL_FRAME_DURATION * crate::scroll::SMOOTH_SCROLL_FRAMES,
+    );
+    cx.run_until_parked();
+    assert_eq!(scroll_top(&mut cx), 12.);
+
+    // Reduced motion makes scrolling instant, as does turning the setting off.
+    cx.simulate_reduced_motion(true);
+    cx.update_editor(|editor, cx| editor.scroll_screen(&ScrollAmount::Line(8.), cx));
+    assert_eq!(scroll_top(&mut cx), 20.);
+    cx.simulate_reduced_motion(false);
+
+    set_smooth_scroll(false, &mut cx);
+    cx.update_editor(|editor, cx| editor.scroll_screen(&ScrollAmount::Line(8.), cx));
+    assert_eq!(scroll_top(&mut cx), 28.);
+
+    // Scrolling to an anchor, as navigating does, is animated too.
+    set_smooth_scroll(true, &mut cx);
+    cx.update_editor(|editor, cx| {
+        let anchor = editor
+            .buffer()
+            .read(cx)
+            .read(cx)
+            .anchor_before(Point::new(50, 0));
+        editor.set_scroll_anchor(
+            ScrollAnchor {
+                anchor,
+                offset: Default::default(),
+            },
+            cx,
+        );
+    });
+    assert_eq!(scroll_top(&mut cx), 28.);
+    cx.update_editor(|editor, _| assert!(editor.scroll_manager.is_animating()));
+    cx.executor().advance_clock(
+        crate::scroll::SMOOTH_SCROLL_FRAME_DURATION * crate::scroll::SMOOTH_SCROLL_FRAMES,
+    );
+    cx.run_until_parked();
+    assert_eq!(scroll_top(&mut cx), 50.);
+}
+
+#[gpui::test]
+async fn test_cursor_animation(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    fn set_cursor_animation(enabled: bool, cx: &mut EditorTestContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.cursor_animation = Some(enabled);
+                });
+            })
+        });
+    }
+
+    set_cursor_animation(true, &mut cx);
+    cx.set_state(&format!("ˇ{}", sample_text(10, 3, 'a')));
+    let line_height = cx.update_editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+
+    fn move_to_row(row: u32, cx: &mut EditorTestContext) {
+        cx.update_editor(|editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+            });
+        });
+        cx.run_until_parked();
+    }
+
+    fn cursor_y(cx: &mut EditorTestContext) -> Option<Pixels> {
+        cx.update_editor(|editor, _| editor.animated_cursor().map(|position| position.y))
+    }
+
+    // The cursor is drawn where it is to begin with.
+    assert_eq!(cursor_y(&mut cx), Some(px(0.)));
+
+    // After a move, it travels to its new position over several frames.
+    move_to_row(5, &mut cx);
+    let mut positions = vec![cursor_y(&mut cx).unwrap()];
+    for _ in 0..crate::cursor_animation::CURSOR_ANIMATION_FRAMES {
+        cx.executor()
+            .advance_clock(crate::scroll::SMOOTH_SCROLL_FRAME_DURATION);
+        cx.run_until_parked();
+        positions.push(cursor_y(&mut cx).unwrap());
+    }
+    assert_eq!(positions[0], px(0.));
+    for (previous, next) in positions.iter().zip(&positions[1..]) {
+        assert!(previous < next, "cursor should move down: {positions:?}");
+    }
+    assert_eq!(positions.last(), Some(&(line_height * 5.)));
+
+    // Reduced motion makes the cursor jump, as does turning the setting off.
+    cx.simulate_reduced_motion(true);
+    move_to_row(1, &mut cx);
+    assert_eq!(cursor_y(&mut cx), None);
+    cx.simulate_reduced_motion(false);
+
+    set_cursor_animation(false, &mut cx);
+    move_to_row(8, &mut cx);
+    assert_eq!(cursor_y(&mut cx), None);
+}
+
 #[gpui::test]
 async fn test_move_page_up_page_down(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -1829,6 +2360,34 @@ fn test_delete_to_word_boundary(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_delete_to_subword_boundary(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("let fooBarBazˇ = 1;");
+    cx.update_editor(|editor, cx| {
+        editor.delete_to_previous_subword_start(&DeleteToPreviousSubwordStart, cx)
+    });
+    cx.assert_editor_state("let fooBarˇ = 1;");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("let fooBarBazˇ = 1;");
+
+    cx.update_editor(|editor, cx| {
+        editor.delete_to_previous_word_start(&DeleteToPreviousWordStart, cx)
+    });
+    cx.assert_editor_state("let ˇ = 1;");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("let fooBarBazˇ = 1;");
+
+    cx.set_state("let ˇfoo_bar = 1;");
+    cx.update_editor(|editor, cx| editor.delete_to_next_subword_end(&DeleteToNextSubwordEnd, cx));
+    cx.assert_editor_state("let ˇ_bar = 1;");
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.update_editor(|editor, cx| editor.delete_to_next_word_end(&DeleteToNextWordEnd, cx));
+    cx.assert_editor_state("let ˇ = 1;");
+}
+
 #[gpui::test]
 fn test_newline(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2592,6 +3151,306 @@ async fn test_delete(cx: &mut gpui::TestAppContext) {
     cx.assert_editor_state("ˇthe lazy dogˇ");
 }
 
+#[gpui::test]
+async fn test_cursor_blink_interval(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let interval = Duration::from_millis(300);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.cursor_blink_interval = Some(interval.as_millis() as u64);
+            });
+        })
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+    let cursor_visible = |cx: &mut EditorTestContext| {
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| editor.blink_manager.read(cx).visible())
+    };
+
+    // Moving the cursor shows it, and it starts blinking once it rests.
+    cx.set_state("abˇc");
+    assert!(cursor_visible(&mut cx));
+    cx.executor().advance_clock(interval);
+    assert!(!cursor_visible(&mut cx));
+    cx.executor().advance_clock(interval);
+    assert!(cursor_visible(&mut cx));
+    cx.executor().advance_clock(interval);
+    assert!(!cursor_visible(&mut cx));
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.cursor_blink = Some(false);
+            });
+        })
+    });
+    for _ in 0..3 {
+        assert!(cursor_visible(&mut cx));
+        cx.executor().advance_clock(interval);
+    }
+
+    // The cursor can't blink faster than the minimum interval.
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.cursor_blink_interval = Some(0);
+            });
+        });
+        assert_eq!(
+            EditorSettings::get_global(cx).cursor_blink_interval,
+            crate::editor_settings::MIN_CURSOR_BLINK_INTERVAL_MS
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_keyboard_macro(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update(|cx| {
+        cx.bind_keys([
+            gpui::KeyBinding::new("ctrl-q", ToggleMacroRecording, Some("Editor")),
+            gpui::KeyBinding::new("ctrl-e", ReplayMacro { times: 2 }, Some("Editor")),
+            gpui::KeyBinding::new("down", MoveDown, Some("Editor")),
+            gpui::KeyBinding::new(
+                "home",
+                MoveToBeginningOfLine {
+                    stop_at_soft_wraps: true,
+                },
+                Some("Editor"),
+            ),
+        ])
+    });
+    cx.set_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+    "});
+
+    cx.simulate_keystrokes(["ctrl-q", "/", "/", "space", "down", "home", "ctrl-q"]);
+    cx.assert_editor_state(indoc! {"
+        // one
+        ˇtwo
+        three
+        four
+    "});
+    assert!(!cx.update(|cx| Editor::is_recording_macro(cx)));
+
+    // Replaying applies the same edits at the cursor positions the macro moves to.
+    cx.simulate_keystrokes(["ctrl-e"]);
+    cx.assert_editor_state(indoc! {"
+        // one
+        // two
+        // three
+        ˇfour
+    "});
+}
+
+#[gpui::test]
+async fn test_repeat_last_action(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update(|cx| {
+        cx.bind_keys([
+            gpui::KeyBinding::new("ctrl-.", RepeatLastAction, Some("Editor")),
+            gpui::KeyBinding::new("ctrl-shift-k", DeleteLine, Some("Editor")),
+            gpui::KeyBinding::new("down", MoveDown, Some("Editor")),
+        ])
+    });
+    cx.set_state(indoc! {"
+        oˇne
+        two
+        three
+        four
+        five
+    "});
+
+    cx.simulate_keystrokes(["ctrl-shift-k"]);
+    cx.assert_editor_state(indoc! {"
+        tˇwo
+        three
+        four
+        five
+    "});
+
+    // Navigating doesn't replace the action to repeat.
+    cx.simulate_keystrokes(["down", "ctrl-."]);
+    cx.assert_editor_state(indoc! {"
+        two
+        fˇour
+        five
+    "});
+
+    cx.simulate_keystrokes(["ctrl-."]);
+    cx.assert_editor_state(indoc! {"
+        two
+        fˇive
+    "});
+}
+
+#[gpui::test]
+async fn test_surround(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        let a = «helloˇ» + «worldˇ»;
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(
+            &SurroundSelections {
+                pair: "<span class=\"greeting\">".into(),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = <span class=\"greeting\">«helloˇ»</span> + <span class=\"greeting\">«worldˇ»</span>;
+    "});
+
+    // An empty selection surrounds the word under the cursor.
+    cx.set_state(indoc! {"
+        let a = heˇllo;
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(&SurroundSelections { pair: "`".into() }, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = `«helloˇ»`;
+    "});
+
+    cx.set_state(indoc! {r#"
+        let a = ("heˇllo", "world");
+    "#});
+    cx.update_editor(|editor, cx| {
+        editor.change_surround(
+            &ChangeSurround {
+                from: "\"".into(),
+                to: "'".into(),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {r#"
+        let a = ('heˇllo', "world");
+    "#});
+
+    cx.update_editor(|editor, cx| editor.delete_surround(&DeleteSurround { pair: ")".into() }, cx));
+    cx.assert_editor_state(indoc! {r#"
+        let a = 'heˇllo', "world";
+    "#});
+
+    // Quotes are paired from the start of the line, so the text between two strings isn't
+    // mistaken for one, and escaped quotes are skipped.
+    cx.set_state(indoc! {r#"
+        say("one", tˇwo, "three");
+    "#});
+    cx.update_editor(|editor, cx| {
+        editor.delete_surround(&DeleteSurround { pair: "\"".into() }, cx)
+    });
+    cx.assert_editor_state(indoc! {r#"
+        say("one", tˇwo, "three");
+    "#});
+    cx.set_state(indoc! {r#"
+        say("one", "a \"quoted\" tˇwo");
+    "#});
+    cx.update_editor(|editor, cx| {
+        editor.change_surround(
+            &ChangeSurround {
+                from: "\"".into(),
+                to: "'".into(),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {r#"
+        say("one", 'a \"quoted\" tˇwo');
+    "#});
+
+    cx.set_state(indoc! {"
+        <div>
+            <div class=\"inner\">heˇllo</div>
+        </div>
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.delete_surround(
+            &DeleteSurround {
+                pair: "<div>".into(),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        <div>
+            heˇllo
+        </div>
+    "});
+
+    // Read-only editors are left as they are.
+    cx.set_state(indoc! {"
+        let a = («helloˇ»);
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.set_read_only(true);
+        editor.surround_selections(&SurroundSelections { pair: "[".into() }, cx);
+        editor.delete_surround(&DeleteSurround { pair: "(".into() }, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = («helloˇ»);
+    "});
+}
+
+#[gpui::test]
+fn test_toggle_read_only(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("abc", cx);
+        build_editor(buffer, cx)
+    });
+    _ = view.update(cx, |view, cx| {
+        view.toggle_read_only(&ToggleReadOnly, cx);
+        assert!(view.read_only(cx));
+
+        view.change_selections(None, cx, |s| s.select_ranges([3..3]));
+        view.handle_input("d", cx);
+        view.backspace(&Backspace, cx);
+        assert_eq!(view.text(cx), "abc");
+
+        // Selecting and copying still work.
+        view.select_all(&SelectAll, cx);
+        view.copy(&Copy, cx);
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().to_owned()),
+            Some("abc".to_owned())
+        );
+
+        view.toggle_read_only(&ToggleReadOnly, cx);
+        assert!(!view.read_only(cx));
+        view.change_selections(None, cx, |s| s.select_ranges([3..3]));
+        view.handle_input("d", cx);
+        assert_eq!(view.text(cx), "abcd");
+    });
+
+    // A buffer that can't be written to can't be made editable.
+    _ = view.update(cx, |view, cx| {
+        let buffer = view.buffer().read(cx).as_singleton().unwrap();
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_capability(Capability::ReadOnly, cx)
+        });
+    });
+    _ = view.update(cx, |view, cx| {
+        assert!(view.read_only(cx));
+        assert!(!view.can_toggle_read_only(cx));
+        view.toggle_read_only(&ToggleReadOnly, cx);
+        assert!(view.read_only(cx));
+        view.handle_input("e", cx);
+        assert_eq!(view.text(cx), "abcd");
+    });
+}
+
 #[gpui::test]
 fn test_delete_line(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2651,7 +3510,7 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         );
 
         // When on single line, replace newline at end by space
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb\nccc\nddd\n\n");
         assert_eq!(
             editor.selections.ranges::<Point>(cx),
@@ -2662,7 +3521,7 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         editor.change_selections(None, cx, |s| {
             s.select_ranges([Point::new(0, 5)..Point::new(2, 2)])
         });
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb ccc ddd\n\n");
         assert_eq!(
             editor.selections.ranges::<Point>(cx),
@@ -2681,7 +3540,7 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         editor.change_selections(None, cx, |s| {
             s.select_ranges([Point::new(2, 1)..Point::new(2, 2)])
         });
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb\nccc\nddd\n");
         assert_eq!(
             editor.selections.ranges::<Point>(cx),
@@ -2689,7 +3548,7 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         );
 
         // We can remove trailing newlines
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb\nccc\nddd");
         assert_eq!(
             editor.selections.ranges::<Point>(cx),
@@ -2697,7 +3556,7 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         );
 
         // We don't blow up on the last line
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb\nccc\nddd");
         assert_eq!(
             editor.selections.ranges::<Point>(cx),
@@ -2721,15 +3580,15 @@ fn test_join_lines_with_single_selection(cx: &mut TestAppContext) {
         editor.change_selections(None, cx, |s| {
             s.select_ranges([Point::new(0, 1)..Point::new(0, 1)])
         });
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb c\n  \n\td");
 
         // We don't insert a space for a line containing only spaces
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb c\n\td");
 
         // We ignore any leading tabs
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb c d");
 
         editor
@@ -2753,7 +3612,7 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
             ])
         });
 
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
         assert_eq!(buffer.read(cx).text(), "aaa bbb ccc\nddd\n");
 
         assert_eq!(
@@ -2767,6 +3626,70 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_join_lines_normalizes_whitespace(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("let a = ˇfoo(  \n        bar,\t\n    baz);\n");
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines::default(), cx));
+    cx.assert_editor_state("let a = foo(ˇ bar,\t\n    baz);\n");
+
+    cx.set_state("«let a = foo(\n    bar,\n    baz);ˇ»\n");
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines::default(), cx));
+    cx.assert_editor_state("let a = foo( bar,ˇ baz);\n");
+
+    cx.set_state("«a\n  b\n   cˇ»\n");
+    cx.update_editor(|editor, cx| {
+        editor.join_lines(
+            &JoinLines {
+                separator: Some(", ".into()),
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state("a, bˇ, c\n");
+}
+
+#[gpui::test]
+async fn test_split_line(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("    let a = ˇb + c;\nfoo«barˇ»\n");
+    cx.update_editor(|editor, cx| editor.split_line(&SplitLine, cx));
+    cx.assert_editor_state("    let a = ˇ\n    b + c;\nfooˇ\n\n");
+}
+
+#[gpui::test]
+async fn test_increment_and_decrement_numbers(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("let a = ˇ9;\n");
+    cx.update_editor(|editor, cx| editor.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("let a = 10ˇ;\n");
+
+    cx.set_state("let mask = 0xˇ0f;\n");
+    cx.update_editor(|editor, cx| editor.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state("let mask = 0x10ˇ;\n");
+
+    cx.set_state("let b = ˇ0;\n");
+    cx.update_editor(|editor, cx| editor.decrement_number(&DecrementNumber, cx));
+    cx.assert_editor_state("let b = -1ˇ;\n");
+
+    // Each cursor adjusts the number under or after it.
+    cx.set_state(indoc! {"
+        [ˇ1, 99ˇ, ˇ-5]
+        ˇversion = 0.9.9
+    "});
+    cx.update_editor(|editor, cx| editor.increment_number(&IncrementNumber, cx));
+    cx.assert_editor_state(indoc! {"
+        [2ˇ, 100ˇ, -4ˇ]
+        version = 1ˇ.9.9
+    "});
+}
+
 #[gpui::test]
 async fn test_join_lines_with_git_diff_base(
     executor: BackgroundExecutor,
@@ -2799,7 +3722,7 @@ async fn test_join_lines_with_git_diff_base(
 
     // Join lines
     cx.update_editor(|editor, cx| {
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
     });
     executor.run_until_parked();
 
@@ -2813,7 +3736,7 @@ async fn test_join_lines_with_git_diff_base(
     );
     // Join again
     cx.update_editor(|editor, cx| {
-        editor.join_lines(&JoinLines, cx);
+        editor.join_lines(&JoinLines::default(), cx);
     });
     executor.run_until_parked();
 
@@ -3026,24 +3949,129 @@ async fn test_unique_lines_single_selection(cx: &mut TestAppContext) {
     let mut cx = EditorTestContext::new(cx).await;
 
     cx.set_state(indoc! {"
-        «Aaa
-        aAa
-        Aaaˇ»
+        «Aaa
+        aAa
+        Aaaˇ»
+    "});
+    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
+    cx.assert_editor_state(indoc! {"
+        «Aaa
+        aAaˇ»
+    "});
+
+    cx.set_state(indoc! {"
+        «Aaa
+        aAa
+        aaAˇ»
+    "});
+    cx.update_editor(|e, cx| e.unique_lines_case_insensitive(&UniqueLinesCaseInsensitive, cx));
+    cx.assert_editor_state(indoc! {"
+        «Aaaˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_sort_and_unique_lines_with_options(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    // Actions built in code get the same options as ones from keymaps that leave them out.
+    assert!(SortLines::default() == serde_json::from_value(json!({})).unwrap());
+    assert!(UniqueLines::default() == serde_json::from_value(json!({})).unwrap());
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        «use std::sync;
+        use anyhow::Result;
+        use gpui::View;ˇ»
     "});
-    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
+    cx.update_editor(|e, cx| {
+        e.sort_lines(
+            &SortLines {
+                case_sensitive: true,
+                ..Default::default()
+            },
+            cx,
+        )
+    });
     cx.assert_editor_state(indoc! {"
-        «Aaa
-        aAaˇ»
+        «use anyhow::Result;
+        use gpui::View;
+        use std::sync;ˇ»
     "});
 
     cx.set_state(indoc! {"
-        «Aaa
-        aAa
-        aaAˇ»
+        «item10
+        item2
+        Item1ˇ»
     "});
-    cx.update_editor(|e, cx| e.unique_lines_case_insensitive(&UniqueLinesCaseInsensitive, cx));
+    cx.update_editor(|e, cx| {
+        e.sort_lines(
+            &SortLines {
+                numeric: true,
+                ..Default::default()
+            },
+            cx,
+        )
+    });
     cx.assert_editor_state(indoc! {"
-        «Aaaˇ»
+        «Item1
+        item2
+        item10ˇ»
+    "});
+    cx.update_editor(|e, cx| {
+        e.sort_lines(
+            &SortLines {
+                numeric: true,
+                descending: true,
+                ..Default::default()
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        «item10
+        item2
+        Item1ˇ»
+    "});
+
+    cx.set_state(indoc! {"
+        «b
+        a
+        b
+        b
+        c
+        aˇ»
+    "});
+    cx.update_editor(|e, cx| {
+        e.unique_lines(
+            &UniqueLines {
+                adjacent: true,
+                case_sensitive: true,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        «b
+        a
+        b
+        c
+        aˇ»
+    "});
+    cx.update_editor(|e, cx| {
+        e.unique_lines(
+            &UniqueLines {
+                adjacent: false,
+                case_sensitive: true,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        «b
+        a
+        cˇ»
     "});
 }
 
@@ -3450,6 +4478,53 @@ fn test_move_line_up_down(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_move_and_duplicate_lines_are_single_transactions(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        one
+        t«woˇ»
+        three
+        four
+    "});
+    cx.update_editor(|editor, cx| editor.move_line_down(&MoveLineDown, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        three
+        t«woˇ»
+        four
+    "});
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        t«woˇ»
+        three
+        four
+    "});
+
+    cx.set_state(indoc! {"
+        oˇne
+        two
+        thˇree
+    "});
+    cx.update_editor(|editor, cx| editor.duplicate_line_down(&DuplicateLineDown, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        oˇne
+        two
+        three
+        thˇree
+    "});
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        oˇne
+        two
+        thˇree
+    "});
+}
+
 #[gpui::test]
 fn test_move_line_up_down_with_blocks(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3572,6 +4647,39 @@ fn test_transpose(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_transpose_chars_and_words(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("aˇbc\n");
+    cx.update_editor(|editor, cx| editor.transpose(&Transpose, cx));
+    cx.assert_editor_state("baˇc\n");
+
+    // Swaps the word before the cursor and the word after it, keeping what's between them.
+    cx.set_state("let foo,ˇ  bar = baz;\n");
+    cx.update_editor(|editor, cx| editor.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("let bar,  fooˇ = baz;\n");
+
+    // Inside a word, that word is swapped with the next one.
+    cx.set_state("fˇoo bar baz\nqˇux quux\n");
+    cx.update_editor(|editor, cx| editor.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("bar fooˇ baz\nquux quxˇ\n");
+
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state("fˇoo bar baz\nqˇux quux\n");
+
+    // After the last word, the two words before the cursor are swapped.
+    cx.set_state("foo bar bazˇ\n");
+    cx.update_editor(|editor, cx| editor.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("foo baz barˇ\n");
+
+    // A single word has nothing to swap with.
+    cx.set_state("fooˇ\n");
+    cx.update_editor(|editor, cx| editor.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("fooˇ\n");
+}
+
 #[gpui::test]
 async fn test_clipboard(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3771,6 +4879,42 @@ async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_large_paste_confirmation(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.large_paste_threshold = Some(10);
+            });
+        })
+    });
+
+    // Text within the threshold is pasted right away.
+    cx.set_state("ˇ");
+    cx.write_to_clipboard(ClipboardItem::new("small".into()));
+    cx.update_editor(|editor, cx| editor.paste(&Paste, cx));
+    assert!(!cx.has_pending_prompt());
+    cx.assert_editor_state("smallˇ");
+
+    // Larger text is only pasted once confirmed.
+    cx.write_to_clipboard(ClipboardItem::new(" and much larger".into()));
+    cx.update_editor(|editor, cx| editor.paste(&Paste, cx));
+    assert!(cx.has_pending_prompt());
+    cx.assert_editor_state("smallˇ");
+    cx.simulate_prompt_answer(0);
+    cx.run_until_parked();
+    cx.assert_editor_state("small and much largerˇ");
+
+    // Cancelling leaves the buffer as it was.
+    cx.update_editor(|editor, cx| editor.paste(&Paste, cx));
+    cx.simulate_prompt_answer(1);
+    cx.run_until_parked();
+    cx.assert_editor_state("small and much largerˇ");
+}
+
 #[gpui::test]
 fn test_select_all(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3906,6 +5050,61 @@ fn test_split_selection_into_lines(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_add_cursors_to_line_ends(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        o«ne
+        two
+        thrˇ»ee
+        four
+    "});
+    cx.update_editor(|editor, cx| editor.add_cursors_to_line_ends(&AddCursorsToLineEnds, cx));
+    cx.assert_editor_state(indoc! {"
+        oneˇ
+        twoˇ
+        threeˇ
+        four
+    "});
+    cx.simulate_input(";");
+    cx.assert_editor_state(indoc! {"
+        one;ˇ
+        two;ˇ
+        three;ˇ
+        four
+    "});
+
+    // A selection ending at the start of a line doesn't add a cursor to it.
+    cx.set_state(indoc! {"
+        «one
+        two
+        ˇ»three
+    "});
+    cx.update_editor(|editor, cx| editor.add_cursors_to_line_ends(&AddCursorsToLineEnds, cx));
+    cx.assert_editor_state(indoc! {"
+        oneˇ
+        twoˇ
+        three
+    "});
+}
+
+#[gpui::test]
+async fn test_duplicate_selection(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("«abcˇ» d«efˇ» ghi");
+    cx.update_editor(|editor, cx| editor.duplicate_selection(&DuplicateSelection, cx));
+    cx.assert_editor_state("abc«abcˇ» def«efˇ» ghi");
+
+    // Without selected text, the lines are duplicated.
+    cx.set_state("abˇc\ndef");
+    cx.update_editor(|editor, cx| editor.duplicate_selection(&DuplicateSelection, cx));
+    cx.assert_editor_state("abc\nabˇc\ndef");
+}
+
 #[gpui::test]
 async fn test_add_selection_above_below(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -4519,6 +5718,46 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_select_larger_syntax_node_from_identifier(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig::default(),
+        Some(tree_sitter_rust::language()),
+    ));
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        fn main() {
+            let total = first + secˇond * 2;
+        }
+    "});
+    cx.condition(|editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let total = first + «secondˇ» * 2;
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.select_larger_syntax_node(&SelectLargerSyntaxNode, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let total = first + «second * 2ˇ»;
+        }
+    "});
+
+    cx.update_editor(|editor, cx| editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let total = first + «secondˇ» * 2;
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_autoindent_selections(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -5844,6 +7083,61 @@ async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     save.await;
 }
 
+#[gpui::test]
+async fn test_final_newline_on_save(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file("/file.txt", Default::default()).await;
+
+    let project = Project::test(fs.clone(), ["/file.txt".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/file.txt", cx))
+        .await
+        .unwrap();
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    let mut save_text = |text: &str, final_newline: FinalNewlineOnSave| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.final_newline_on_save = Some(final_newline);
+            });
+        });
+        editor.update(cx, |editor, cx| editor.set_text(text, cx));
+        editor
+            .update(cx, |editor, cx| editor.save(true, project.clone(), cx))
+            .unwrap()
+    };
+
+    // A missing newline is added, and extra ones are removed.
+    save_text("one\ntwo", FinalNewlineOnSave::EnsureSingle)
+        .await
+        .unwrap();
+    assert_eq!(fs.load("/file.txt".as_ref()).await.unwrap(), "one\ntwo\n");
+    save_text("one\ntwo\n\n\n", FinalNewlineOnSave::EnsureSingle)
+        .await
+        .unwrap();
+    assert_eq!(fs.load("/file.txt".as_ref()).await.unwrap(), "one\ntwo\n");
+
+    save_text("one\ntwo\n\n", FinalNewlineOnSave::Trim)
+        .await
+        .unwrap();
+    assert_eq!(fs.load("/file.txt".as_ref()).await.unwrap(), "one\ntwo");
+
+    save_text("one\ntwo\n\n\n", FinalNewlineOnSave::LeaveUnchanged)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load("/file.txt".as_ref()).await.unwrap(),
+        "one\ntwo\n\n\n"
+    );
+    save_text("one\ntwo", FinalNewlineOnSave::LeaveUnchanged)
+        .await
+        .unwrap();
+    assert_eq!(fs.load("/file.txt".as_ref()).await.unwrap(), "one\ntwo");
+}
+
 #[gpui::test]
 async fn test_multibuffer_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8037,6 +9331,87 @@ fn test_split_words() {
     assert_eq!(split(":do_the_thing"), &[":", "do_", "the_", "thing"]);
 }
 
+#[gpui::test]
+async fn test_breadcrumb_symbols(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_outline_query(
+            r#"
+            (impl_item "impl" @context type: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        impl Workspace {
+            fn render() {
+                let a = 1;
+                fn helper() {
+                    let b = ˇ2;
+                }
+            }
+        }
+    "});
+    cx.run_until_parked();
+
+    let symbols = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            editor
+                .breadcrumbs(cx.theme(), cx)
+                .unwrap()
+                .into_iter()
+                .skip(1)
+                .map(|segment| segment.text)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The breadcrumbs follow the cursor into nested symbols.
+    assert_eq!(
+        symbols(&mut cx),
+        ["impl Workspace", "fn render", "fn helper"]
+    );
+    cx.update_editor(|editor, cx| {
+        editor.move_up(&MoveUp, cx);
+        editor.move_up(&MoveUp, cx);
+    });
+    assert_eq!(symbols(&mut cx), ["impl Workspace", "fn render"]);
+
+    // Choosing a symbol jumps to its start.
+    cx.update_editor(|editor, cx| editor.go_to_enclosing_symbol(1, cx));
+    cx.assert_editor_state(indoc! {"
+        impl Workspace {
+            ˇfn render() {
+                let a = 1;
+                fn helper() {
+                    let b = 2;
+                }
+            }
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_enclosing_symbol(0, cx));
+    cx.assert_editor_state(indoc! {"
+        ˇimpl Workspace {
+            fn render() {
+                let a = 1;
+                fn helper() {
+                    let b = 2;
+                }
+            }
+        }
+    "});
+    assert_eq!(symbols(&mut cx), ["impl Workspace"]);
+}
+
 #[gpui::test]
 async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8087,6 +9462,94 @@ async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_go_to_matching_pair_and_enclosing_block(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+
+    // From inside a block, jump to the brace opening it, and then further out.
+    cx.set_state(indoc! {"
+        fn main() {
+            if true {
+                let ˇa = 1;
+            }
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.move_to_enclosing_block_start(&MoveToEnclosingBlockStart, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            if true ˇ{
+                let a = 1;
+            }
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.move_to_enclosing_block_start(&MoveToEnclosingBlockStart, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            ˇif true {
+                let a = 1;
+            }
+        }
+    "});
+
+    cx.set_state(indoc! {"
+        fn main() {
+            if true {
+                let ˇa = 1;
+            }
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.move_to_enclosing_block_end(&MoveToEnclosingBlockEnd, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            if true {
+                let a = 1;
+            }ˇ
+        }
+    "});
+
+    // On a closing brace, jump to its opener and back.
+    cx.set_state(indoc! {"
+        fn main() {
+            if true {
+                let a = 1;
+            ˇ}
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_matching_pair(&GoToMatchingPair, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            if true ˇ{
+                let a = 1;
+            }
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_matching_pair(&GoToMatchingPair, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            if true {
+                let a = 1;
+            ˇ}
+        }
+    "});
+
+    // Elsewhere, the next bracket on the line is matched.
+    cx.set_state(indoc! {"
+        ˇfn main() {
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_matching_pair(&GoToMatchingPair, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main(ˇ) {
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -11061,6 +12524,218 @@ async fn test_edits_around_toggled_modifications(
     });
 }
 
+#[gpui::test]
+async fn test_compare_with_clipboard(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/a", json!({ "main.rs": "one\ntwo\nthree\n" }))
+        .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+
+    cx.write_to_clipboard(ClipboardItem::new("one\n2\nthree\n".to_string()));
+    editor.update(cx, |editor, cx| {
+        editor.compare_with_clipboard(&CompareWithClipboard, cx)
+    });
+    cx.executor().run_until_parked();
+
+    let diff_editor = workspace
+        .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))
+        .unwrap()
+        .unwrap();
+    assert_ne!(diff_editor, editor);
+    diff_editor.update(cx, |diff_editor, cx| {
+        assert_eq!(diff_editor.title(cx), "Clipboard ↔ main.rs");
+        assert_eq!(diff_editor.text(cx), "one\ntwo\nthree\n");
+        assert!(diff_editor.read_only(cx));
+        let snapshot = diff_editor.snapshot(cx);
+        assert_eq!(
+            expanded_hunks(diff_editor, &snapshot, cx),
+            vec![("2\n".to_string(), DiffHunkStatus::Modified, 2..3)]
+        );
+    });
+
+    // The buffer itself is untouched.
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "one\ntwo\nthree\n");
+        assert!(!editor.read_only(cx));
+    });
+}
+
+#[gpui::test]
+async fn test_switch_to_related_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.related_files = Some(vec![
+            ("*.rs".to_string(), "*_tests.rs".to_string()),
+            ("*.c".to_string(), "*.h".to_string()),
+        ]);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/a",
+        json!({
+            "include": { "parser.h": "" },
+            "other": { "editor_tests.rs": "" },
+            "src": {
+                "editor.rs": "",
+                "editor_tests.rs": "",
+                "lonely.rs": "",
+                "parser.c": "",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let open = |path: &'static str, cx: &mut VisualTestContext| {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .unwrap()
+    };
+    let switch = |cx: &mut VisualTestContext| {
+        let editor = workspace
+            .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))
+            .unwrap()
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            editor.switch_to_related_file(&SwitchToRelatedFile, cx)
+        });
+        cx.executor().run_until_parked();
+        workspace
+            .update(cx, |workspace, cx| {
+                let path = workspace.active_item(cx)?.project_path(cx)?.path;
+                Some(path.to_string_lossy().into_owned())
+            })
+            .unwrap()
+    };
+
+    // A module's tests in the same directory are preferred over ones elsewhere, and switching
+    // again goes back to the module.
+    open("src/editor.rs", cx).await.unwrap();
+    assert_eq!(switch(cx).as_deref(), Some("src/editor_tests.rs"));
+    assert_eq!(switch(cx).as_deref(), Some("src/editor.rs"));
+
+    // Related files elsewhere in the worktree are found too.
+    open("src/parser.c", cx).await.unwrap();
+    assert_eq!(switch(cx).as_deref(), Some("include/parser.h"));
+
+    // The user is told when there's no related file.
+    open("src/lonely.rs", cx).await.unwrap();
+    workspace
+        .update(cx, |workspace, _| {
+            assert!(workspace.notification_ids().is_empty())
+        })
+        .unwrap();
+    assert_eq!(switch(cx).as_deref(), Some("src/lonely.rs"));
+    workspace
+        .update(cx, |workspace, _| {
+            assert_eq!(workspace.notification_ids().len(), 1)
+        })
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_line_ending_conversion(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/a", json!({ "crlf.txt": "one\r\ntwo\r\n" }))
+        .await;
+    let project = Project::test(fs.clone(), ["/a".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "crlf.txt"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let indicator = cx.new_view(|_| LineEndingIndicator::new());
+    indicator.update(cx, |indicator, cx| {
+        indicator.set_active_pane_item(Some(&editor), cx);
+        assert_eq!(indicator.label(), Some("CRLF"));
+    });
+
+    // Saving keeps the file's line endings.
+    editor.update(cx, |editor, cx| {
+        editor.handle_input("zero\n", cx);
+    });
+    let buffer = editor.update(cx, |editor, cx| {
+        editor.buffer().read(cx).as_singleton().unwrap()
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load("/a/crlf.txt".as_ref()).await.unwrap(),
+        "zero\r\none\r\ntwo\r\n"
+    );
+
+    editor.update(cx, |editor, cx| {
+        editor.convert_line_endings_to_lf(&ConvertLineEndingsToLf, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        fs.load("/a/crlf.txt".as_ref()).await.unwrap(),
+        "zero\none\ntwo\n"
+    );
+    assert!(!buffer.read_with(cx, |buffer, _| buffer.is_dirty()));
+    indicator.update(cx, |indicator, _| {
+        assert_eq!(indicator.label(), Some("LF"));
+    });
+
+    // Reloading the file notices that it now mixes line endings.
+    fs.insert_file("/a/crlf.txt", "zero\none\r\ntwo\n".into())
+        .await;
+    cx.executor().run_until_parked();
+    indicator.update(cx, |indicator, _| {
+        assert_eq!(indicator.label(), Some("Mixed"));
+    });
+
+    // Converting a buffer with unsaved edits doesn't save them.
+    editor.update(cx, |editor, cx| {
+        editor.handle_input("minus one\n", cx);
+        editor.convert_line_endings_to_crlf(&ConvertLineEndingsToCrlf, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        fs.load("/a/crlf.txt".as_ref()).await.unwrap(),
+        "zero\none\r\ntwo\n"
+    );
+    assert!(buffer.read_with(cx, |buffer, _| buffer.is_dirty()));
+    indicator.update(cx, |indicator, _| {
+        assert_eq!(indicator.label(), Some("CRLF"));
+    });
+}
+
 #[gpui::test]
 async fn test_multiple_expanded_hunks_merge(
     executor: BackgroundExecutor,
@@ -11187,6 +12862,157 @@ async fn test_multiple_expanded_hunks_merge(
     );
 }
 
+#[gpui::test]
+async fn test_pane_font_size_is_clamped(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/project", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+    let project = Project::test(fs, ["/project".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_abs_path(PathBuf::from("/project/main.rs"), true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let pane = workspace
+        .update(cx, |workspace, _| workspace.active_pane().clone())
+        .unwrap();
+    let buffer_font_size = cx.update(|cx| ThemeSettings::get_global(cx).buffer_font_size(cx));
+
+    // Decreasing the font size stops at the smallest one the editor renders with.
+    for _ in 0..30 {
+        cx.dispatch_action(workspace::pane::DecreaseFontSize);
+    }
+    cx.run_until_parked();
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.buffer_font_size(cx)),
+        theme::MIN_FONT_SIZE
+    );
+    assert_eq!(
+        pane.update(cx, |pane, _| pane.font_size_adjustment()),
+        theme::MIN_FONT_SIZE - buffer_font_size
+    );
+
+    // The steps taken below the minimum don't have to be undone before it grows again.
+    cx.dispatch_action(workspace::pane::IncreaseFontSize);
+    cx.run_until_parked();
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.buffer_font_size(cx)),
+        theme::MIN_FONT_SIZE + gpui::px(1.)
+    );
+}
+
+#[gpui::test]
+async fn test_swap_file_lifecycle(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/project", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+    let project = Project::test(fs.clone(), ["/project".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/project/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let editor = cx.add_window(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+    let swap_file_path = swap_file::swap_file_path(Path::new("/project/main.rs"));
+
+    // A dirty buffer is written to its swap file once edits settle.
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_text("fn main() { println!(\"hi\"); }\n", cx)
+    });
+    cx.executor().run_until_parked();
+    assert!(!fs.is_file(&swap_file_path).await);
+    cx.executor().advance_clock(swap_file::SWAP_FILE_DEBOUNCE);
+    cx.executor().run_until_parked();
+    let swap_file =
+        serde_json::from_str::<swap_file::SwapFile>(&fs.load(&swap_file_path).await.unwrap())
+            .unwrap();
+    assert_eq!(
+        swap_file,
+        swap_file::SwapFile {
+            abs_path: PathBuf::from("/project/main.rs"),
+            text: "fn main() { println!(\"hi\"); }\n".to_string(),
+        }
+    );
+
+    // Saving the buffer removes the swap file.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(!fs.is_file(&swap_file_path).await);
+
+    // Closing one of two editors on a dirty buffer keeps the swap file for the other.
+    let other_editor =
+        cx.add_window(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+    _ = editor.update(cx, |editor, cx| editor.set_text("fn main() {}\n\n", cx));
+    cx.executor().advance_clock(swap_file::SWAP_FILE_DEBOUNCE);
+    cx.executor().run_until_parked();
+    assert!(fs.is_file(&swap_file_path).await);
+    _ = editor.update(cx, |_, cx| cx.remove_window());
+    cx.executor().run_until_parked();
+    assert!(fs.is_file(&swap_file_path).await);
+
+    // Closing the last editor discards the buffer, and with it the swap file.
+    drop(buffer);
+    _ = other_editor.update(cx, |_, cx| cx.remove_window());
+    cx.executor().run_until_parked();
+    assert!(!fs.is_file(&swap_file_path).await);
+}
+
+#[gpui::test]
+async fn test_swap_file_recovery(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    // Simulate a crash that left a swap file behind.
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/project", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+    let swap_file_path = swap_file::swap_file_path(Path::new("/project/main.rs"));
+    fs.insert_tree(swap_file_path.parent().unwrap(), json!({}))
+        .await;
+    fs.insert_file(
+        &swap_file_path,
+        serde_json::to_vec(&swap_file::SwapFile {
+            abs_path: PathBuf::from("/project/main.rs"),
+            text: "fn main() { recovered(); }\n".to_string(),
+        })
+        .unwrap(),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/project".as_ref()], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    cx.executor().run_until_parked();
+    assert!(cx.has_pending_prompt());
+
+    cx.simulate_prompt_answer(0);
+    cx.executor().run_until_parked();
+    let editor = workspace
+        .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))
+        .unwrap()
+        .unwrap();
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "fn main() { recovered(); }\n");
+        assert!(editor.buffer().read(cx).is_dirty(cx));
+    });
+    assert_eq!(
+        fs.load(Path::new("/project/main.rs")).await.unwrap(),
+        "fn main() {}\n"
+    );
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(row as u32, column as u32);
     point..point
@@ -165,8 +165,16 @@ impl EditorElement {
         register_action(view, cx, Editor::outdent);
         register_action(view, cx, Editor::delete_line);
         register_action(view, cx, Editor::join_lines);
+        register_action(view, cx, Editor::split_line);
+        register_action(view, cx, Editor::increment_number);
+        register_action(view, cx, Editor::compare_with_clipboard);
+        register_action(view, cx, Editor::switch_to_related_file);
+        register_action(view, cx, Editor::convert_line_endings_to_lf);
+        register_action(view, cx, Editor::convert_line_endings_to_crlf);
+        register_action(view, cx, Editor::decrement_number);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
         register_action(view, cx, Editor::sort_lines_case_insensitive);
+        register_action(view, cx, Editor::sort_lines);
         register_action(view, cx, Editor::reverse_lines);
         register_action(view, cx, Editor::shuffle_lines);
         register_action(view, cx, Editor::convert_to_upper_case);
@@ -186,9 +194,11 @@ impl EditorElement {
         register_action(view, cx, Editor::cut_to_end_of_line);
         register_action(view, cx, Editor::duplicate_line_up);
         register_action(view, cx, Editor::duplicate_line_down);
+        register_action(view, cx, Editor::duplicate_selection);
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
+        register_action(view, cx, Editor::transpose_words);
         register_action(view, cx, Editor::cut);
         register_action(view, cx, Editor::copy);
         register_action(view, cx, Editor::paste);
@@ -248,6 +258,7 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::select_line);
         register_action(view, cx, Editor::split_selection_into_lines);
+        register_action(view, cx, Editor::add_cursors_to_line_ends);
         register_action(view, cx, Editor::add_selection_above);
         register_action(view, cx, Editor::add_selection_below);
         register_action(view, cx, |editor, action, cx| {
@@ -260,6 +271,9 @@ impl EditorElement {
         register_action(view, cx, Editor::select_larger_syntax_node);
         register_action(view, cx, Editor::select_smaller_syntax_node);
         register_action(view, cx, Editor::move_to_enclosing_bracket);
+        register_action(view, cx, Editor::go_to_matching_pair);
+        register_action(view, cx, Editor::move_to_enclosing_block_start);
+        register_action(view, cx, Editor::move_to_enclosing_block_end);
         register_action(view, cx, Editor::undo_selection);
         register_action(view, cx, Editor::redo_selection);
         if !view.read(cx).is_singleton(cx) {
@@ -303,6 +317,14 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_show_whitespaces);
+        register_action(view, cx, Editor::toggle_read_only);
+        register_action(view, cx, Editor::toggle_macro_recording);
+        register_action(view, cx, Editor::replay_macro);
+        register_action(view, cx, Editor::repeat_last_action);
+        register_action(view, cx, Editor::surround_selections);
+        register_action(view, cx, Editor::change_surround);
+        register_action(view, cx, Editor::delete_surround);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
@@ -369,6 +391,7 @@ impl EditorElement {
         register_action(view, cx, Editor::display_cursor_names);
         register_action(view, cx, Editor::unique_lines_case_insensitive);
         register_action(view, cx, Editor::unique_lines_case_sensitive);
+        register_action(view, cx, Editor::unique_lines);
         register_action(view, cx, Editor::accept_partial_inline_completion);
         register_action(view, cx, Editor::revert_selected_hunks);
         register_action(view, cx, Editor::open_active_item_in_terminal)
@@ -477,6 +500,14 @@ impl EditorElement {
                 cx,
             );
         } else {
+            if click_count == 1
+                && !modifiers.modified()
+                && editor.begin_selection_drag(position, cx)
+            {
+                cx.stop_propagation();
+                return;
+            }
+
             let multi_cursor_setting = EditorSettings::get_global(cx).multi_cursor_modifier;
             let multi_cursor_modifier = match multi_cursor_setting {
                 MultiCursorModifier::Alt => modifiers.alt,
@@ -551,6 +582,13 @@ impl EditorElement {
         text_hitbox: &Hitbox,
         cx: &mut ViewContext<Editor>,
     ) {
+        if editor.has_selection_drag() {
+            let point = position_map.point_for_position(text_hitbox.bounds, event.position);
+            editor.end_selection_drag(point.previous_valid, event.modifiers.alt, cx);
+            cx.stop_propagation();
+            return;
+        }
+
         let end_selection = editor.has_pending_selection();
         let pending_nonempty_selections = editor.has_pending_nonempty_selection();
 
@@ -581,6 +619,11 @@ impl EditorElement {
         text_bounds: Bounds<Pixels>,
         cx: &mut ViewContext<Editor>,
     ) {
+        if editor.has_selection_drag() {
+            let point_for_position = position_map.point_for_position(text_bounds, event.position);
+            editor.update_selection_drag(point_for_position.previous_valid, cx);
+            return;
+        }
         if !editor.has_pending_selection() {
             return;
         }
@@ -721,14 +764,18 @@ impl EditorElement {
             local_selections.extend(editor.selections.pending(cx));
             let mut layouts = Vec::new();
             let newest = editor.selections.newest(cx);
+            let selection_cursor_shape = EditorSettings::get_global(cx).selection_cursor_shape;
             for selection in local_selections.drain(..) {
                 let is_empty = selection.start == selection.end;
                 let is_newest = selection == newest;
+                let cursor_shape = selection_cursor_shape
+                    .filter(|_| !is_empty)
+                    .unwrap_or(editor.cursor_shape);
 
                 let layout = SelectionLayout::new(
                     selection,
                     editor.selections.line_mode,
-                    editor.cursor_shape,
+                    cursor_shape,
                     &snapshot.display_snapshot,
                     is_newest,
                     editor.leader_peer_id.is_none(),
@@ -747,6 +794,19 @@ impl EditorElement {
                 layouts.push(layout);
             }
 
+            // Show where dragged text would be dropped.
+            if let Some(drop_position) = editor.selection_drag_drop_position() {
+                layouts.push(SelectionLayout {
+                    head: drop_position,
+                    cursor_shape: CursorShape::Bar,
+                    is_newest: false,
+                    is_local: true,
+                    range: drop_position..drop_position,
+                    active_rows: drop_position.row()..drop_position.row(),
+                    user_name: None,
+                });
+            }
+
             let player = if editor.read_only(cx) {
                 cx.theme().players().read_only()
             } else {
@@ -917,6 +977,34 @@ impl EditorElement {
         cursors
     }
 
+    /// Returns the horizontal position of the columns containing a local cursor, relative to the
+    /// start of the line.
+    fn layout_active_columns(
+        &self,
+        selections: &[(PlayerColor, Vec<SelectionLayout>)],
+        visible_display_row_range: Range<u32>,
+        line_layouts: &[LineWithInvisibles],
+    ) -> Vec<Pixels> {
+        let mut columns = selections
+            .iter()
+            .flat_map(|(_, selections)| selections)
+            .filter(|selection| {
+                selection.is_local
+                    && selection.range.is_empty()
+                    && visible_display_row_range.contains(&selection.head.row())
+            })
+            .map(|selection| {
+                let line = &line_layouts
+                    [(selection.head.row() - visible_display_row_range.start) as usize]
+                    .line;
+                line.x_for_index(selection.head.column() as usize)
+            })
+            .collect::<Vec<_>>();
+        columns.sort();
+        columns.dedup();
+        columns
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_visible_cursors(
         &self,
@@ -1023,10 +1111,22 @@ impl EditorElement {
                         }
                     }
 
+                    let mut origin = point(x, y);
+                    if selection.is_newest && selection.is_local {
+                        let position = editor.animated_cursor_position(
+                            point(
+                                cursor_character_x,
+                                cursor_position.row() as f32 * line_height,
+                            ),
+                            cx,
+                        );
+                        origin = position - scroll_pixel_position;
+                    }
+
                     let mut cursor = CursorLayout {
                         color: player_color.cursor,
                         block_width,
-                        origin: point(x, y),
+                        origin,
                         line_height,
                         shape: selection.cursor_shape,
                         block_text,
@@ -2201,7 +2301,13 @@ impl EditorElement {
             cx.paint_quad(fill(layout.text_hitbox.bounds, self.style.background));
 
             if let EditorMode::Full = layout.mode {
-                let mut active_rows = layout.active_rows.iter().peekable();
+                let current_line_highlight = EditorSettings::get_global(cx).current_line_highlight;
+                let active_line_bg = cx.theme().colors().editor_active_line_background;
+                let mut active_rows = layout
+                    .active_rows
+                    .iter()
+                    .filter(|_| current_line_highlight)
+                    .peekable();
                 while let Some((start_row, contains_non_empty_selection)) = active_rows.next() {
                     let mut end_row = *start_row;
                     while active_rows.peek().map_or(false, |r| {
@@ -2222,11 +2328,28 @@ impl EditorElement {
                             layout.hitbox.size.width,
                             layout.position_map.line_height * (end_row - start_row + 1) as f32,
                         );
-                        let active_line_bg = cx.theme().colors().editor_active_line_background;
                         cx.paint_quad(fill(Bounds { origin, size }, active_line_bg));
                     }
                 }
 
+                for x in &layout.active_columns {
+                    let x =
+                        layout.content_origin.x + *x - layout.position_map.scroll_pixel_position.x;
+                    if x < layout.text_hitbox.origin.x {
+                        continue;
+                    }
+                    cx.paint_quad(fill(
+                        Bounds {
+                            origin: point(x, layout.text_hitbox.origin.y),
+                            size: size(
+                                layout.position_map.em_width,
+                                layout.text_hitbox.size.height,
+                            ),
+                        },
+                        active_line_bg,
+                    ));
+                }
+
                 let mut paint_highlight =
                     |highlight_row_start: u32, highlight_row_end: u32, color| {
                         let origin = point(
@@ -2573,13 +2696,7 @@ impl EditorElement {
         layout: &EditorLayout,
         cx: &mut WindowContext,
     ) {
-        let whitespace_setting = self
-            .editor
-            .read(cx)
-            .buffer
-            .read(cx)
-            .settings_at(0, cx)
-            .show_whitespaces;
+        let whitespace_setting = self.editor.read(cx).show_whitespaces(cx);
 
         for (ix, line_with_invisibles) in layout.position_map.line_layouts.iter().enumerate() {
             let row = layout.visible_display_row_range.start + ix as u32;
@@ -3507,13 +3624,7 @@ impl LineWithInvisibles {
         whitespace_setting: ShowWhitespaceSetting,
         cx: &mut WindowContext,
     ) {
-        let allowed_invisibles_regions = match whitespace_setting {
-            ShowWhitespaceSetting::None => return,
-            ShowWhitespaceSetting::Selection => Some(selection_ranges),
-            ShowWhitespaceSetting::All => None,
-        };
-
-        for invisible in &self.invisibles {
+        for invisible in self.visible_invisibles(row, whitespace_setting, selection_ranges) {
             let (&token_offset, invisible_symbol) = match invisible {
                 Invisible::Tab { line_start_offset } => (line_start_offset, &layout.tab_invisible),
                 Invisible::Whitespace { line_offset } => (line_offset, &layout.space_invisible),
@@ -3527,18 +3638,40 @@ impl LineWithInvisibles {
                     x_offset + invisible_offset - layout.position_map.scroll_pixel_position.x,
                     line_y,
                 );
+            invisible_symbol.paint(origin, line_height, cx).log_err();
+        }
+    }
 
-            if let Some(allowed_regions) = allowed_invisibles_regions {
-                let invisible_point = DisplayPoint::new(row, token_offset as u32);
-                if !allowed_regions
-                    .iter()
-                    .any(|region| region.start <= invisible_point && invisible_point < region.end)
-                {
-                    continue;
+    /// Returns the invisibles on this line that the whitespace setting asks to draw.
+    fn visible_invisibles<'a>(
+        &'a self,
+        row: u32,
+        whitespace_setting: ShowWhitespaceSetting,
+        selection_ranges: &'a [Range<DisplayPoint>],
+    ) -> impl Iterator<Item = &'a Invisible> {
+        let text: &str = &self.line.text;
+        self.invisibles.iter().filter(move |invisible| {
+            let offset = invisible.offset();
+            match whitespace_setting {
+                ShowWhitespaceSetting::None => false,
+                ShowWhitespaceSetting::All => true,
+                ShowWhitespaceSetting::Selection => {
+                    let point = DisplayPoint::new(row, offset as u32);
+                    selection_ranges
+                        .iter()
+                        .any(|range| range.start <= point && point < range.end)
+                }
+                ShowWhitespaceSetting::Boundary => {
+                    let is_leading = text
+                        .get(..offset)
+                        .map_or(false, |before| before.trim_start().is_empty());
+                    let is_trailing = text
+                        .get(offset..)
+                        .map_or(false, |after| after.trim_end().is_empty());
+                    is_leading || is_trailing
                 }
             }
-            invisible_symbol.paint(origin, line_height, cx).log_err();
-        }
+        })
     }
 }
 
@@ -3548,6 +3681,15 @@ enum Invisible {
     Whitespace { line_offset: usize },
 }
 
+impl Invisible {
+    fn offset(&self) -> usize {
+        match self {
+            Invisible::Tab { line_start_offset } => *line_start_offset,
+            Invisible::Whitespace { line_offset } => *line_offset,
+        }
+    }
+}
+
 impl Element for EditorElement {
     type RequestLayoutState = ();
     type PrepaintState = EditorLayout;
@@ -3661,7 +3803,9 @@ impl Element for EditorElement {
                         }
                     };
 
-                    if editor.set_wrap_width(wrap_width, cx) {
+                    let wrap_indent = editor.soft_wrap_indent(cx);
+                    let rewrapped = editor.set_wrap_indent(wrap_indent, cx);
+                    if editor.set_wrap_width(wrap_width, cx) || rewrapped {
                         editor.snapshot(cx)
                     } else {
                         snapshot
@@ -3877,6 +4021,12 @@ impl Element for EditorElement {
                     );
                 });
 
+                let active_columns = if EditorSettings::get_global(cx).current_column_highlight {
+                    self.layout_active_columns(&selections, start_row..end_row, &line_layouts)
+                } else {
+                    Vec::new()
+                };
+
                 let cursors = self.collect_cursors(&snapshot, cx);
                 let visible_row_range = start_row..end_row;
                 let non_visible_cursors = cursors
@@ -4038,6 +4188,7 @@ impl Element for EditorElement {
                     scrollbar_layout,
                     max_row,
                     active_rows,
+                    active_columns,
                     highlighted_rows,
                     highlighted_ranges,
                     redacted_ranges,
@@ -4156,6 +4307,8 @@ pub struct EditorLayout {
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     visible_display_row_range: Range<u32>,
     active_rows: BTreeMap<u32, bool>,
+    /// The horizontal position of each column containing a cursor, when highlighted.
+    active_columns: Vec<Pixels>,
     highlighted_rows: BTreeMap<u32, Hsla>,
     line_numbers: Vec<Option<ShapedLine>>,
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
@@ -4681,12 +4834,13 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockDisposition, BlockProperties},
-        editor_tests::{init_test, update_test_language_settings},
-        Editor, MultiBuffer,
+        editor_tests::{init_test, rust_lang, update_test_language_settings},
+        Editor, MultiBuffer, ToggleShowWhitespaces,
     };
-    use gpui::{TestAppContext, VisualTestContext};
+    use gpui::{Model, TestAppContext, VisualTestContext};
     use language::language_settings;
     use log::info;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use ui::Context;
     use util::test::sample_text;
@@ -4979,6 +5133,214 @@ mod tests {
         assert_eq!(expected_invisibles, actual_invisibles);
     }
 
+    #[gpui::test]
+    fn test_active_lines_and_columns_follow_cursors(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.current_column_highlight = Some(true);
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("aaaaaa\nbbbbbb\ncccccc\n", cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        let mut layout_with_cursors = |cursors: Vec<DisplayPoint>| {
+            window
+                .update(cx, |editor, cx| {
+                    editor.change_selections(None, cx, |s| {
+                        s.select_display_ranges(cursors.into_iter().map(|cursor| cursor..cursor))
+                    });
+                })
+                .unwrap();
+            let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+                EditorElement::new(&editor, style.clone())
+            });
+            let em_advance = state.position_map.em_advance;
+            (
+                state.active_rows.keys().copied().collect::<Vec<_>>(),
+                state
+                    .active_columns
+                    .iter()
+                    .map(|x| (*x / em_advance).round() as u32)
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            layout_with_cursors(vec![DisplayPoint::new(0, 2)]),
+            (vec![0], vec![2])
+        );
+        assert_eq!(
+            layout_with_cursors(vec![DisplayPoint::new(1, 4)]),
+            (vec![1], vec![4])
+        );
+        assert_eq!(
+            layout_with_cursors(vec![DisplayPoint::new(0, 3), DisplayPoint::new(2, 3)]),
+            (vec![0, 2], vec![3])
+        );
+        assert_eq!(
+            layout_with_cursors(vec![DisplayPoint::new(0, 1), DisplayPoint::new(2, 5)]),
+            (vec![0, 2], vec![1, 5])
+        );
+    }
+
+    #[gpui::test]
+    fn test_configured_cursor_shapes(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.cursor_shape = Some(CursorShape::Block);
+                    settings.selection_cursor_shape = Some(CursorShape::Underscore);
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("abc def\n", cx);
+            let mut editor = Editor::new(EditorMode::Full, buffer, None, cx);
+            editor.focus(cx);
+            editor
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        let mut layout_cursors = |selection: Range<DisplayPoint>| {
+            window
+                .update(cx, |editor, cx| {
+                    editor.change_selections(None, cx, |s| s.select_display_ranges([selection]));
+                })
+                .unwrap();
+            let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+                EditorElement::new(&editor, style.clone())
+            });
+            let em_advance = state.position_map.em_advance;
+            state
+                .visible_cursors
+                .iter()
+                .map(|cursor| {
+                    (
+                        cursor.shape,
+                        (cursor.block_width / em_advance - 1.).abs() < 0.01,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The block covers the whole character cell.
+        assert_eq!(
+            layout_cursors(DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1)),
+            [(CursorShape::Block, true)]
+        );
+        assert_eq!(
+            layout_cursors(DisplayPoint::new(0, 0)..DisplayPoint::new(0, 3)),
+            [(CursorShape::Underscore, true)]
+        );
+    }
+
+    #[gpui::test]
+    fn test_boundary_and_selection_invisibles(cx: &mut TestAppContext) {
+        const TAB_SIZE: u32 = 4;
+        init_test(cx, |s| {
+            s.defaults.show_whitespaces = Some(ShowWhitespaceSetting::Boundary);
+            s.defaults.tab_size = NonZeroU32::new(TAB_SIZE);
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("\tfoo bar  ", cx);
+            Editor::new(EditorMode::Full, buffer, None, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        let line = &state.position_map.line_layouts[0];
+        let tab = Invisible::Tab {
+            line_start_offset: 0,
+        };
+        let space = |line_offset| Invisible::Whitespace { line_offset };
+        let inner_space = space(TAB_SIZE as usize + 3);
+        let trailing_spaces = [space(TAB_SIZE as usize + 7), space(TAB_SIZE as usize + 8)];
+        assert_eq!(
+            line.invisibles,
+            [tab, inner_space, trailing_spaces[0], trailing_spaces[1]]
+        );
+
+        let whitespace_setting = cx.update(|cx| editor.read(cx).show_whitespaces(cx));
+        assert_eq!(
+            line.visible_invisibles(0, whitespace_setting, &[])
+                .copied()
+                .collect::<Vec<_>>(),
+            [tab, trailing_spaces[0], trailing_spaces[1]]
+        );
+
+        let selection = DisplayPoint::new(0, 6)..DisplayPoint::new(0, TAB_SIZE + 8);
+        assert_eq!(
+            line.visible_invisibles(0, ShowWhitespaceSetting::Selection, &[selection])
+                .copied()
+                .collect::<Vec<_>>(),
+            [inner_space, trailing_spaces[0]]
+        );
+
+        editor.update(cx, |editor, cx| {
+            editor.toggle_show_whitespaces(&ToggleShowWhitespaces, cx);
+            assert_eq!(editor.show_whitespaces(cx), ShowWhitespaceSetting::All);
+            editor.toggle_show_whitespaces(&ToggleShowWhitespaces, cx);
+            assert_eq!(editor.show_whitespaces(cx), ShowWhitespaceSetting::Boundary);
+        });
+    }
+
+    #[gpui::test]
+    fn test_wrap_guides_use_language_columns(cx: &mut TestAppContext) {
+        init_test(cx, |s| {
+            s.defaults.wrap_guides = Some(vec![80]);
+            s.languages.insert(
+                "Rust".into(),
+                language_settings::LanguageSettingsContent {
+                    wrap_guides: Some(vec![100, 120]),
+                    ..Default::default()
+                },
+            );
+        });
+
+        let wrap_guides_for = |buffer: Model<MultiBuffer>, cx: &mut TestAppContext| {
+            let window = cx.add_window(|cx| Editor::new(EditorMode::Full, buffer, None, cx));
+            let cx = &mut VisualTestContext::from_window(*window, cx);
+            let editor = window.root(cx).unwrap();
+            let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+            let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+                EditorElement::new(&editor, style)
+            });
+            let em_advance = state.position_map.em_advance;
+            state
+                .wrap_guides
+                .iter()
+                .map(|(x, active)| {
+                    assert!(!active, "no guide should be active without soft wrap");
+                    (*x / em_advance).round() as usize
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let plain_text = cx.update(|cx| MultiBuffer::build_simple("a\nb\n", cx));
+        assert_eq!(wrap_guides_for(plain_text, cx), [80]);
+
+        let rust_buffer = cx.new_model(|cx| {
+            language::Buffer::local("fn main() {}\n", cx).with_language(rust_lang(), cx)
+        });
+        let rust = cx.new_model(|cx| MultiBuffer::singleton(rust_buffer, cx));
+        assert_eq!(wrap_guides_for(rust, cx), [100, 120]);
+    }
+
     #[gpui::test]
     fn test_invisibles_dont_appear_in_certain_editors(cx: &mut TestAppContext) {
         init_test(cx, |s| {
@@ -0,0 +1,176 @@
+use std::{ops::Range, time::Duration};
+
+use gpui::ViewContext;
+use language::{char_kind, Bias, CharKind, Point};
+use multi_buffer::{Anchor, MultiBufferSnapshot, ToOffset, ToPoint};
+use settings::Settings;
+
+use crate::{
+    DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
+    RangeToAnchorExt,
+};
+
+pub(crate) enum OccurrenceHighlight {}
+
+#[doc(hidden)]
+pub const OCCURRENCE_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Longer selections are unlikely to be repeated, and not worth searching for.
+const MAX_SELECTION_LEN: usize = 256;
+
+/// Highlights the other visible occurrences of the word under the newest cursor, or of the text
+/// it selects, once the cursor has rested for a moment. The language server's highlights of the
+/// symbol under the cursor are preferred when there are any.
+pub fn refresh_occurrence_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full || !EditorSettings::get_global(cx).highlight_occurrences {
+        editor.occurrence_highlights_task.take();
+        editor.clear_background_highlights::<OccurrenceHighlight>(cx);
+        return;
+    }
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let selection = editor.selections.newest::<usize>(cx);
+    let query = if selection.is_empty() {
+        let (range, kind) = snapshot.surrounding_word(selection.head());
+        (kind == Some(CharKind::Word)).then_some((range, true))
+    } else {
+        let range = selection.range();
+        let is_searchable = range.len() <= MAX_SELECTION_LEN
+            && snapshot
+                .text_for_range(range.clone())
+                .all(|chunk| !chunk.contains('\n'))
+            && snapshot
+                .chars_at(range.start)
+                .take(range.len())
+                .any(|c| !c.is_whitespace());
+        is_searchable.then_some((range, false))
+    };
+    let visible_start = editor.scroll_manager.anchor().anchor.to_point(&snapshot);
+    let visible_end = snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+    let visible_range = visible_start.to_offset(&snapshot)..visible_end.to_offset(&snapshot);
+
+    editor.occurrence_highlights_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(OCCURRENCE_HIGHLIGHTS_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let occurrences = match query {
+            Some((range, whole_word)) => cx
+                .background_executor()
+                .spawn(async move { find_occurrences(&snapshot, range, visible_range, whole_word) })
+                .await,
+            None => Vec::new(),
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                if occurrences.is_empty()
+                    || editor.has_background_highlights::<DocumentHighlightRead>()
+                    || editor.has_background_highlights::<DocumentHighlightWrite>()
+                {
+                    editor.clear_background_highlights::<OccurrenceHighlight>(cx);
+                } else {
+                    editor.highlight_background::<OccurrenceHighlight>(
+                        &occurrences,
+                        |theme| theme.editor_document_highlight_read_background,
+                        cx,
+                    );
+                }
+            })
+            .ok();
+    }));
+}
+
+/// Finds the occurrences of the text in `range` elsewhere in `visible_range`. When `whole_word`
+/// is set, occurrences that are part of a longer word are skipped.
+fn find_occurrences(
+    snapshot: &MultiBufferSnapshot,
+    range: Range<usize>,
+    visible_range: Range<usize>,
+    whole_word: bool,
+) -> Vec<Range<Anchor>> {
+    let query = snapshot.text_for_range(range.clone()).collect::<String>();
+    let text = snapshot
+        .text_for_range(visible_range.clone())
+        .collect::<String>();
+    let scope = snapshot.language_scope_at(range.start);
+    let is_word_char = |c: char| char_kind(&scope, c) == CharKind::Word;
+
+    text.match_indices(&query)
+        .map(|(start, _)| {
+            let start = visible_range.start + start;
+            start..start + query.len()
+        })
+        .filter(|occurrence| {
+            if *occurrence == range {
+                return false;
+            }
+            !whole_word
+                || !(snapshot
+                    .reversed_chars_at(occurrence.start)
+                    .next()
+                    .map_or(false, is_word_char)
+                    || snapshot
+                        .chars_at(occurrence.end)
+                        .next()
+                        .map_or(false, is_word_char))
+        })
+        .map(|occurrence| occurrence.to_anchors(snapshot))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_occurrence_highlights(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.highlight_occurrences = Some(true);
+                });
+            });
+        });
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("let foo = 1;\nlet foobar = fˇoo + 2;\n");
+        cx.assert_editor_background_highlights::<OccurrenceHighlight>(
+            "let foo = 1;\nlet foobar = foo + 2;\n",
+        );
+        cx.executor()
+            .advance_clock(OCCURRENCE_HIGHLIGHTS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+        // `foobar` contains the word but is a different one.
+        cx.assert_editor_background_highlights::<OccurrenceHighlight>(
+            "let «foo» = 1;\nlet foobar = foo + 2;\n",
+        );
+
+        // Selected text matches inside words too.
+        cx.set_selections_state("let «fooˇ» = 1;\nlet foobar = foo + 2;\n");
+        cx.executor()
+            .advance_clock(OCCURRENCE_HIGHLIGHTS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+        cx.assert_editor_background_highlights::<OccurrenceHighlight>(
+            "let foo = 1;\nlet «foo»bar = «foo» + 2;\n",
+        );
+
+        // Moving off the word keeps the highlights until the debounce has elapsed.
+        cx.set_selections_state("let foo = 1;ˇ\nlet foobar = foo + 2;\n");
+        cx.assert_editor_background_highlights::<OccurrenceHighlight>(
+            "let foo = 1;\nlet «foo»bar = «foo» + 2;\n",
+        );
+        cx.executor()
+            .advance_clock(OCCURRENCE_HIGHLIGHTS_DEBOUNCE_TIMEOUT);
+        cx.run_until_parked();
+        cx.assert_editor_background_highlights::<OccurrenceHighlight>(
+            "let foo = 1;\nlet foobar = foo + 2;\n",
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{scroll::Autoscroll, DecrementNumber, Editor, IncrementNumber, ToOffset};
+use gpui::ViewContext;
+use language::{Point, Selection, SelectionGoal};
+use std::ops::Range;
+
+#[derive(Debug, PartialEq)]
+enum Number<'a> {
+    Decimal(i64),
+    /// The digits of a hexadecimal literal, after its `0x` prefix.
+    Hex(&'a str),
+}
+
+impl Number<'_> {
+    fn add(&self, delta: i64) -> Option<String> {
+        match self {
+            Number::Decimal(value) => Some(value.checked_add(delta)?.to_string()),
+            Number::Hex(digits) => {
+                let value = u64::from_str_radix(digits, 16)
+                    .ok()?
+                    .checked_add_signed(delta)?;
+                // Keep the literal's width and case, so `0x0f` becomes `0x10` and `0xFF` `0x100`.
+                let width = digits.len();
+                Some(if digits.chars().any(|c| c.is_ascii_uppercase()) {
+                    format!("{value:0width$X}")
+                } else {
+                    format!("{value:0width$x}")
+                })
+            }
+        }
+    }
+}
+
+/// Finds the first number on the line that ends at or after `column`, so that a cursor before,
+/// inside or right after a number targets that number, and returns the byte range of its digits.
+fn find_number(line: &str, column: usize) -> Option<(Range<usize>, Number)> {
+    let bytes = line.as_bytes();
+    let is_word = |ix: usize| bytes[ix].is_ascii_alphanumeric() || bytes[ix] == b'_';
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'0'
+            && matches!(bytes.get(ix + 1), Some(b'x' | b'X'))
+            && bytes.get(ix + 2).is_some_and(u8::is_ascii_hexdigit)
+        {
+            let digits_start = ix + 2;
+            let mut end = digits_start;
+            while end < bytes.len() && bytes[end].is_ascii_hexdigit() {
+                end += 1;
+            }
+            if end >= column {
+                return Some((digits_start..end, Number::Hex(&line[digits_start..end])));
+            }
+            ix = end;
+            continue;
+        }
+
+        let negative = bytes[ix] == b'-'
+            && bytes.get(ix + 1).is_some_and(u8::is_ascii_digit)
+            && (ix == 0 || !is_word(ix - 1));
+        if negative || bytes[ix].is_ascii_digit() {
+            let mut end = ix + 1;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+            if end >= column {
+                let value = line[ix..end].parse().ok()?;
+                return Some((ix..end, Number::Decimal(value)));
+            }
+            ix = end;
+            continue;
+        }
+
+        ix += 1;
+    }
+    None
+}
+
+impl Editor {
+    pub fn increment_number(&mut self, _: &IncrementNumber, cx: &mut ViewContext<Self>) {
+        self.add_to_numbers(1, cx);
+    }
+
+    pub fn decrement_number(&mut self, _: &DecrementNumber, cx: &mut ViewContext<Self>) {
+        self.add_to_numbers(-1, cx);
+    }
+
+    /// Adds `delta` to the number under or after each cursor, on the cursor's line.
+    fn add_to_numbers(&mut self, delta: i64, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        // How far the edits made so far have shifted the offsets after them.
+        let mut adjustment = 0isize;
+        let mut last_edit = None::<(Range<usize>, usize)>;
+        for selection in self.selections.all::<usize>(cx) {
+            let head = buffer.offset_to_point(selection.head());
+            let line_start = Point::new(head.row, 0);
+            let line = buffer
+                .text_for_range(line_start..Point::new(head.row, buffer.line_len(head.row)))
+                .collect::<String>();
+            let line_offset = line_start.to_offset(&buffer);
+
+            let target = find_number(&line, head.column as usize).and_then(|(range, number)| {
+                let range = line_offset + range.start..line_offset + range.end;
+                Some((range, number.add(delta)?))
+            });
+            let Some((range, new_text)) = target else {
+                new_selections
+                    .push(selection.map(|offset| (offset as isize + adjustment) as usize));
+                continue;
+            };
+
+            // Several cursors can target the same number.
+            let cursor = match &last_edit {
+                Some((last_range, cursor)) if *last_range == range => *cursor,
+                _ => {
+                    let start = (range.start as isize + adjustment) as usize;
+                    adjustment += new_text.len() as isize - range.len() as isize;
+                    edits.push((range.clone(), new_text.clone()));
+                    // Leave the cursor after the new number.
+                    start + new_text.len()
+                }
+            };
+            last_edit = Some((range, cursor));
+            new_selections.push(Selection {
+                id: selection.id,
+                start: cursor,
+                end: cursor,
+                reversed: false,
+                goal: SelectionGoal::None,
+            });
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_number() {
+        assert_eq!(
+            find_number("let x = 41;", 0),
+            Some((8..10, Number::Decimal(41)))
+        );
+        assert_eq!(
+            find_number("a = -3 + 4", 5),
+            Some((4..6, Number::Decimal(-3)))
+        );
+        assert_eq!(find_number("a-3", 0), Some((2..3, Number::Decimal(3))));
+        assert_eq!(find_number("0x0f", 0), Some((2..4, Number::Hex("0f"))));
+        assert_eq!(find_number("12 ab", 3), None);
+    }
+}
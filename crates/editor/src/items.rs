@@ -5,6 +5,7 @@ use crate::{
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
+use file_icons::FileIcons;
 use futures::future::try_join_all;
 use git::repository::GitFileStatus;
 use gpui::{
@@ -597,6 +598,17 @@ impl Item for Editor {
         Some(path.to_string_lossy().to_string().into())
     }
 
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        let path = self
+            .buffer()
+            .read(cx)
+            .as_singleton()?
+            .read(cx)
+            .file()?
+            .path();
+        FileIcons::get_icon(path, cx).map(Icon::from_path)
+    }
+
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         let label_color = if ItemSettings::get_global(cx).git_status {
             self.buffer()
@@ -801,6 +813,13 @@ impl Item for Editor {
         self.pixel_position_of_newest_cursor
     }
 
+    fn set_font_size_adjustment(&mut self, adjustment: Pixels, cx: &mut ViewContext<Self>) {
+        if self.font_size_adjustment != adjustment {
+            self.font_size_adjustment = adjustment;
+            cx.notify();
+        }
+    }
+
     fn breadcrumb_location(&self) -> ToolbarItemLocation {
         if self.show_breadcrumbs {
             ToolbarItemLocation::PrimaryLeft
@@ -979,6 +998,27 @@ impl ProjectItem for Editor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
+        let abs_path = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx));
+        if let Some(abs_path) = abs_path {
+            // Files we are not permitted to write start out read-only.
+            let fs = project.read(cx).fs().clone();
+            cx.spawn(|editor, mut cx| async move {
+                let metadata = fs.metadata(&abs_path).await.log_err().flatten();
+                if metadata.map_or(false, |metadata| metadata.is_read_only) {
+                    editor
+                        .update(&mut cx, |editor, cx| {
+                            editor.set_read_only(true);
+                            cx.notify();
+                        })
+                        .ok();
+                }
+            })
+            .detach();
+        }
         Self::for_buffer(buffer, Some(project), cx)
     }
 }
@@ -1039,7 +1079,8 @@ impl SearchableItem for Editor {
     ) {
         self.unfold_ranges([matches[index].clone()], false, true, cx);
         let range = self.range_for_match(&matches[index]);
-        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        let autoscroll = Autoscroll::navigation(Autoscroll::fit(), cx);
+        self.change_selections(Some(autoscroll), cx, |s| {
             s.select_ranges([range]);
         })
     }
@@ -0,0 +1,176 @@
+//! Keyboard macros record the actions dispatched by keystrokes and the text input handled by
+//! editors, so that replaying them repeats exactly what was done instead of re-interpreting keys.
+//!
+//! The same observer remembers the last action that edited a buffer, for [`RepeatLastAction`].
+use crate::{
+    Editor, EditorEvent, Redo, RepeatLastAction, ReplayMacro, ToggleMacroRecording, Undo,
+    UndoSelection,
+};
+use gpui::{
+    Action, AppContext, AsyncAppContext, Global, KeystrokeEvent, ViewContext, WindowContext,
+};
+use std::{ops::Range, sync::Arc};
+
+enum MacroStep {
+    Action(Box<dyn Action>),
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl Clone for MacroStep {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action(action) => Self::Action(action.boxed_clone()),
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Self::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct KeyboardMacro {
+    /// The steps recorded so far, if a recording is in progress.
+    recording: Option<Vec<MacroStep>>,
+    /// The steps of the last finished recording.
+    steps: Arc<Vec<MacroStep>>,
+    replaying: bool,
+    /// The action dispatched by the current keystroke, until we know whether it edited.
+    pending_action: Option<Box<dyn Action>>,
+    /// The last action that edited a buffer.
+    last_repeatable_action: Option<Box<dyn Action>>,
+}
+
+impl Global for KeyboardMacro {}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.set_global(KeyboardMacro::default());
+    cx.observe_keystrokes(record_action).detach();
+    cx.observe_new_views(|_, cx: &mut ViewContext<Editor>| {
+        let editor = cx.view().clone();
+        cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| match event {
+            EditorEvent::InputHandled {
+                utf16_range_to_replace,
+                text,
+            } => record(
+                MacroStep::Insertion {
+                    text: text.clone(),
+                    utf16_range_to_replace: utf16_range_to_replace.clone(),
+                },
+                cx,
+            ),
+            EditorEvent::Edited => {
+                let keyboard_macro = cx.global_mut::<KeyboardMacro>();
+                if let Some(action) = keyboard_macro.pending_action.take() {
+                    keyboard_macro.last_repeatable_action = Some(action);
+                }
+            }
+            _ => {}
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn record_action(event: &KeystrokeEvent, cx: &mut WindowContext) {
+    let Some(action) = event.action.as_ref() else {
+        return;
+    };
+    let any = action.as_any();
+    if any.is::<ToggleMacroRecording>() || any.is::<ReplayMacro>() || any.is::<RepeatLastAction>() {
+        return;
+    }
+    record(MacroStep::Action(action.boxed_clone()), cx);
+
+    if !(any.is::<Undo>() || any.is::<Redo>() || any.is::<UndoSelection>()) {
+        // The action's edits are reported before this deferred callback runs, so an edit
+        // reported afterwards wasn't caused by it and shouldn't make it repeatable.
+        cx.global_mut::<KeyboardMacro>().pending_action = Some(action.boxed_clone());
+        cx.defer(|cx| cx.global_mut::<KeyboardMacro>().pending_action = None);
+    }
+}
+
+fn record(step: MacroStep, cx: &mut AppContext) {
+    let keyboard_macro = cx.global_mut::<KeyboardMacro>();
+    if keyboard_macro.replaying {
+        return;
+    }
+    if let Some(recording) = keyboard_macro.recording.as_mut() {
+        recording.push(step);
+    }
+}
+
+impl Editor {
+    pub fn is_recording_macro(cx: &AppContext) -> bool {
+        cx.global::<KeyboardMacro>().recording.is_some()
+    }
+
+    pub fn toggle_macro_recording(&mut self, _: &ToggleMacroRecording, cx: &mut ViewContext<Self>) {
+        let keyboard_macro = cx.global_mut::<KeyboardMacro>();
+        if keyboard_macro.replaying {
+            return;
+        }
+        match keyboard_macro.recording.take() {
+            Some(steps) => keyboard_macro.steps = Arc::new(steps),
+            None => keyboard_macro.recording = Some(Vec::new()),
+        }
+    }
+
+    pub fn repeat_last_action(&mut self, _: &RepeatLastAction, cx: &mut ViewContext<Self>) {
+        if let Some(action) = cx
+            .global::<KeyboardMacro>()
+            .last_repeatable_action
+            .as_ref()
+            .map(|action| action.boxed_clone())
+        {
+            cx.dispatch_action(action);
+        }
+    }
+
+    pub fn replay_macro(&mut self, action: &ReplayMacro, cx: &mut ViewContext<Self>) {
+        let keyboard_macro = cx.global_mut::<KeyboardMacro>();
+        if keyboard_macro.recording.is_some()
+            || keyboard_macro.replaying
+            || keyboard_macro.steps.is_empty()
+        {
+            return;
+        }
+        keyboard_macro.replaying = true;
+        let steps = keyboard_macro.steps.clone();
+        let times = action.times.max(1);
+
+        cx.spawn(|editor, mut cx| async move {
+            let result = async {
+                for _ in 0..times {
+                    for step in steps.iter().cloned() {
+                        match step {
+                            MacroStep::Action(action) => {
+                                cx.update(|cx| cx.dispatch_action(action))?
+                            }
+                            MacroStep::Insertion {
+                                text,
+                                utf16_range_to_replace,
+                            } => editor.update(&mut cx, |editor, cx| {
+                                editor.replay_insert_event(&text, utf16_range_to_replace, cx)
+                            })?,
+                        }
+                    }
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            AsyncAppContext::update(&cx, |cx| {
+                cx.global_mut::<KeyboardMacro>().replaying = false;
+            })
+            .ok();
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+}
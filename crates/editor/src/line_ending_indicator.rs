@@ -0,0 +1,109 @@
+use crate::{ConvertLineEndingsToCrlf, ConvertLineEndingsToLf, Editor};
+use gpui::{Subscription, View, WeakView};
+use language::LineEnding;
+use ui::{prelude::*, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+/// A status bar item showing the line endings of the active editor's buffer, and converting
+/// them to the other kind on click.
+pub struct LineEndingIndicator {
+    active_editor: Option<WeakView<Editor>>,
+    /// The buffer's line ending, and whether it was loaded with mixed ones.
+    line_ending: Option<(LineEnding, bool)>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl LineEndingIndicator {
+    pub fn new() -> Self {
+        Self {
+            active_editor: None,
+            line_ending: None,
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update_line_ending(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.line_ending = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .map(|buffer| {
+                let buffer = buffer.read(cx);
+                (buffer.line_ending(), buffer.has_mixed_line_endings())
+            });
+        cx.notify();
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn label(&self) -> Option<&'static str> {
+        self.line_ending
+            .map(|(line_ending, mixed)| label(line_ending, mixed))
+    }
+}
+
+fn label(line_ending: LineEnding, mixed: bool) -> &'static str {
+    if mixed {
+        "Mixed"
+    } else {
+        line_ending.label()
+    }
+}
+
+impl Default for LineEndingIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for LineEndingIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when_some(self.line_ending, |el, (line_ending, mixed)| {
+            let convert_to_lf = mixed || line_ending == LineEnding::Windows;
+            el.child(
+                Button::new("line-ending", label(line_ending, mixed))
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if let Some(editor) = this.active_editor.as_ref().and_then(|e| e.upgrade())
+                        {
+                            editor.update(cx, |editor, cx| {
+                                if convert_to_lf {
+                                    editor.convert_line_endings_to_lf(&ConvertLineEndingsToLf, cx)
+                                } else {
+                                    editor
+                                        .convert_line_endings_to_crlf(&ConvertLineEndingsToCrlf, cx)
+                                }
+                            });
+                        }
+                    }))
+                    .tooltip(move |cx| {
+                        if convert_to_lf {
+                            Tooltip::for_action("Convert to LF", &ConvertLineEndingsToLf, cx)
+                        } else {
+                            Tooltip::for_action("Convert to CRLF", &ConvertLineEndingsToCrlf, cx)
+                        }
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for LineEndingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_line_ending));
+            self.active_editor = Some(editor.downgrade());
+            self.update_line_ending(editor, cx);
+        } else {
+            self.active_editor = None;
+            self.line_ending = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{Editor, ToggleReadOnly};
+use gpui::{Subscription, View, WeakView};
+use ui::{prelude::*, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+/// A status bar item showing whether the active editor is read-only, and toggling it on click.
+pub struct ReadOnlyIndicator {
+    active_editor: Option<WeakView<Editor>>,
+    read_only: bool,
+    can_toggle: bool,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl ReadOnlyIndicator {
+    pub fn new() -> Self {
+        Self {
+            active_editor: None,
+            read_only: false,
+            can_toggle: true,
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update_read_only(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        let editor = editor.read(cx);
+        self.read_only = editor.read_only(cx);
+        self.can_toggle = editor.can_toggle_read_only(cx);
+        cx.notify();
+    }
+}
+
+impl Default for ReadOnlyIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for ReadOnlyIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when(self.active_editor.is_some(), |el| {
+            let (icon, tooltip) = if self.read_only {
+                (IconName::FileLock, "Make Editable")
+            } else {
+                (IconName::Pencil, "Make Read-Only")
+            };
+
+            let button = IconButton::new("toggle-read-only", icon)
+                .icon_size(IconSize::Small)
+                .selected(self.read_only);
+            // The buffer itself can't be written to, so there's nothing to toggle.
+            if !self.can_toggle {
+                return el.child(
+                    button
+                        .disabled(true)
+                        .tooltip(|cx| Tooltip::text("This buffer is read-only", cx)),
+                );
+            }
+
+            el.child(
+                button
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = this.active_editor.as_ref().and_then(|e| e.upgrade())
+                        {
+                            editor.update(cx, |editor, cx| {
+                                editor.toggle_read_only(&ToggleReadOnly, cx)
+                            });
+                        }
+                    }))
+                    .tooltip(move |cx| Tooltip::for_action(tooltip, &ToggleReadOnly, cx)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ReadOnlyIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_read_only));
+            self.active_editor = Some(editor.downgrade());
+            self.update_read_only(editor, cx);
+        } else {
+            self.active_editor = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
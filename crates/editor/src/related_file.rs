@@ -0,0 +1,145 @@
+//! Switching between a file and its counterpart, such as a source file and its header, following
+//! the `related_files` naming conventions of the file's language.
+use crate::{Editor, SwitchToRelatedFile};
+use gpui::ViewContext;
+use language::language_settings::language_settings;
+use project::ProjectPath;
+use std::path::Path;
+use workspace::notifications::NotificationId;
+use workspace::Toast;
+
+impl Editor {
+    /// Opens the file related to this one by the naming conventions of its language, preferring
+    /// one in the same directory, or tells the user there isn't one.
+    pub fn switch_to_related_file(&mut self, _: &SwitchToRelatedFile, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = project::File::from_dyn(buffer.file()) else {
+            return;
+        };
+        let path = file.path.clone();
+        let worktree_id = file.worktree_id(cx);
+        let Some(file_name) = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            return;
+        };
+        let settings = language_settings(buffer.language(), buffer.file(), cx);
+        let candidates = related_file_names(&file_name, &settings.related_files);
+
+        let Some(worktree) = project.read(cx).worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let snapshot = worktree.read(cx).snapshot();
+        let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let sibling = candidates.iter().find_map(|name| {
+            snapshot
+                .entry_for_path(directory.join(name))
+                .filter(|entry| entry.is_file())
+                .map(|entry| entry.path.clone())
+        });
+
+        // Opening the file deactivates this editor, so it can't be done from within it.
+        cx.spawn(|_, mut cx| async move {
+            let related_path = match sibling {
+                Some(path) => Some(path),
+                // Only fall back to searching the whole worktree, which can be large, in the
+                // background.
+                None => {
+                    cx.background_executor()
+                        .spawn(async move {
+                            snapshot
+                                .files(false, 0)
+                                .filter_map(|entry| {
+                                    let name = entry.path.file_name()?.to_str()?;
+                                    let rank = candidates
+                                        .iter()
+                                        .position(|candidate| candidate == name)?;
+                                    Some((
+                                        (rank, entry.path.components().count()),
+                                        entry.path.clone(),
+                                    ))
+                                })
+                                .min()
+                                .map(|(_, path)| path)
+                        })
+                        .await
+                }
+            };
+
+            let Some(related_path) = related_path else {
+                workspace.update(&mut cx, |workspace, cx| {
+                    struct NoRelatedFile;
+
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<NoRelatedFile>(),
+                            format!("No file related to {file_name} was found"),
+                        ),
+                        cx,
+                    )
+                })?;
+                return anyhow::Ok(());
+            };
+
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_path(
+                        ProjectPath {
+                            worktree_id,
+                            path: related_path,
+                        },
+                        None,
+                        true,
+                        cx,
+                    )
+                })?
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// Returns the names of the files related to `file_name` by `conventions`, most specific first.
+///
+/// Each convention is a pair of patterns where `*` stands for the part of the name the related
+/// files share, like `["*.c", "*.h"]`.
+fn related_file_names(file_name: &str, conventions: &[(String, String)]) -> Vec<String> {
+    let mut matches = Vec::new();
+    for (a, b) in conventions {
+        for (pattern, counterpart) in [(a, b), (b, a)] {
+            if let Some(stem) = match_pattern(pattern, file_name) {
+                let name = counterpart.replacen('*', stem, 1);
+                if name != file_name {
+                    matches.push((pattern.len(), name));
+                }
+            }
+        }
+    }
+    // A name matching `*_tests.rs` also matches `*.rs`, but its related file is the one the
+    // longer pattern gives.
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let mut names = Vec::<String>::new();
+    for (_, name) in matches {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+fn match_pattern<'a>(pattern: &str, file_name: &'a str) -> Option<&'a str> {
+    let (prefix, suffix) = pattern.split_once('*')?;
+    let stem = file_name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    (!stem.is_empty()).then_some(stem)
+}
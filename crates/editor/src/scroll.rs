@@ -4,6 +4,7 @@ pub(crate) mod scroll_amount;
 
 use crate::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
+    highlight_occurrences::refresh_occurrence_highlights,
     hover_popover::hide_hover,
     persistence::DB,
     Anchor, DisplayPoint, Editor, EditorEvent, EditorMode, EditorSettings, InlayHintRefreshReason,
@@ -23,6 +24,9 @@ use workspace::{ItemId, WorkspaceId};
 
 pub const SCROLL_EVENT_SEPARATION: Duration = Duration::from_millis(28);
 const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of frames a smooth scroll is spread over.
+pub const SMOOTH_SCROLL_FRAMES: u32 = 8;
+pub const SMOOTH_SCROLL_FRAME_DURATION: Duration = Duration::from_millis(16);
 
 #[derive(Default)]
 pub struct ScrollbarAutoHide(pub bool);
@@ -127,9 +131,15 @@ impl OngoingScroll {
     }
 }
 
+struct ScrollAnimation {
+    target: gpui::Point<f32>,
+    _task: Task<()>,
+}
+
 pub struct ScrollManager {
     pub(crate) vertical_scroll_margin: f32,
     anchor: ScrollAnchor,
+    animation: Option<ScrollAnimation>,
     ongoing: OngoingScroll,
     autoscroll_request: Option<(Autoscroll, bool)>,
     last_autoscroll: Option<(gpui::Point<f32>, f32, f32, AutoscrollStrategy)>,
@@ -145,6 +155,7 @@ impl ScrollManager {
         ScrollManager {
             vertical_scroll_margin: EditorSettings::get_global(cx).vertical_scroll_margin,
             anchor: ScrollAnchor::new(),
+            animation: None,
             ongoing: OngoingScroll::new(),
             autoscroll_request: None,
             show_scrollbars: true,
@@ -178,6 +189,19 @@ impl ScrollManager {
         self.anchor.scroll_position(snapshot)
     }
 
+    /// Returns the position being scrolled to, which is ahead of the current
+    /// one while a smooth scroll is in progress.
+    pub fn scroll_target(&self, snapshot: &DisplaySnapshot) -> gpui::Point<f32> {
+        match &self.animation {
+            Some(animation) => animation.target,
+            None => self.scroll_position(snapshot),
+        }
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
     fn set_scroll_position(
         &mut self,
         scroll_position: gpui::Point<f32>,
@@ -337,7 +361,8 @@ impl Editor {
             cx.spawn(|editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                        refresh_occurrence_highlights(editor, cx);
                     })
                     .ok()
             })
@@ -354,7 +379,7 @@ impl Editor {
             return;
         }
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
-        let position = self.scroll_manager.anchor.scroll_position(&display_map) + scroll_delta;
+        let position = self.scroll_manager.scroll_target(&display_map) + scroll_delta;
         self.set_scroll_position_taking_display_map(position, true, false, display_map, cx);
     }
 
@@ -387,6 +412,84 @@ impl Editor {
         autoscroll: bool,
         display_map: DisplaySnapshot,
         cx: &mut ViewContext<Self>,
+    ) {
+        if local && self.should_scroll_smoothly(cx) {
+            self.animate_scroll_position(scroll_position, autoscroll, display_map, cx);
+        } else {
+            self.scroll_manager.animation = None;
+            self.set_scroll_position_immediately(
+                scroll_position,
+                local,
+                autoscroll,
+                display_map,
+                cx,
+            );
+        }
+    }
+
+    fn should_scroll_smoothly(&self, cx: &ViewContext<Self>) -> bool {
+        self.mode == EditorMode::Full
+            && EditorSettings::get_global(cx).smooth_scroll
+            && !cx.should_reduce_motion()
+    }
+
+    /// Moves towards the given scroll position over the next few frames, easing out as it
+    /// approaches it. Starting another smooth scroll retargets the one in progress.
+    fn animate_scroll_position(
+        &mut self,
+        target: gpui::Point<f32>,
+        autoscroll: bool,
+        display_map: DisplaySnapshot,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let start = self.scroll_manager.scroll_position(&display_map);
+        if start == target {
+            self.scroll_manager.animation = None;
+            return;
+        }
+
+        let task = cx.spawn(|editor, mut cx| async move {
+            for frame in 1..=SMOOTH_SCROLL_FRAMES {
+                cx.background_executor()
+                    .timer(SMOOTH_SCROLL_FRAME_DURATION)
+                    .await;
+                let t = frame as f32 / SMOOTH_SCROLL_FRAMES as f32;
+                let progress = 1. - (1. - t).powi(3);
+                let position = point(
+                    start.x + (target.x - start.x) * progress,
+                    start.y + (target.y - start.y) * progress,
+                );
+                let updated = editor.update(&mut cx, |editor, cx| {
+                    let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                    editor.set_scroll_position_immediately(
+                        position,
+                        true,
+                        autoscroll,
+                        display_map,
+                        cx,
+                    );
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            editor
+                .update(&mut cx, |editor, _| editor.scroll_manager.animation = None)
+                .ok();
+        });
+        self.scroll_manager.animation = Some(ScrollAnimation {
+            target,
+            _task: task,
+        });
+    }
+
+    fn set_scroll_position_immediately(
+        &mut self,
+        scroll_position: gpui::Point<f32>,
+        local: bool,
+        autoscroll: bool,
+        display_map: DisplaySnapshot,
+        cx: &mut ViewContext<Self>,
     ) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().map(|workspace| workspace.1);
@@ -400,6 +503,7 @@ impl Editor {
         );
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        refresh_occurrence_highlights(self, cx);
     }
 
     pub fn scroll_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
@@ -408,6 +512,21 @@ impl Editor {
     }
 
     pub fn set_scroll_anchor(&mut self, scroll_anchor: ScrollAnchor, cx: &mut ViewContext<Self>) {
+        if self.should_scroll_smoothly(cx) {
+            let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+            let scroll_position = scroll_anchor.scroll_position(&display_map);
+            self.animate_scroll_position(scroll_position, false, display_map, cx);
+        } else {
+            self.scroll_manager.animation = None;
+            self.set_scroll_anchor_immediately(scroll_anchor, cx);
+        }
+    }
+
+    fn set_scroll_anchor_immediately(
+        &mut self,
+        scroll_anchor: ScrollAnchor,
+        cx: &mut ViewContext<Self>,
+    ) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().map(|workspace| workspace.1);
         let top_row = scroll_anchor
@@ -445,7 +564,8 @@ impl Editor {
             return;
         }
 
-        let cur_position = self.scroll_position(cx);
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let cur_position = self.scroll_manager.scroll_target(&display_map);
         let new_pos = cur_position + point(0., amount.lines(self));
         self.set_scroll_position(new_pos, cx);
     }
@@ -497,7 +617,8 @@ impl Editor {
                 offset: gpui::Point::new(x, y),
                 anchor: top_anchor,
             };
-            self.set_scroll_anchor(scroll_anchor, cx);
+            // Restoring where the editor was scrolled to isn't animated, as it happens on open.
+            self.set_scroll_anchor_immediately(scroll_anchor, cx);
         }
     }
 }
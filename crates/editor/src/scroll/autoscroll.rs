@@ -1,11 +1,13 @@
 use std::{any::TypeId, cmp, f32};
 
 use collections::HashSet;
-use gpui::{px, Bounds, Pixels, ViewContext};
+use gpui::{px, AppContext, Bounds, Pixels, ViewContext};
 use language::Point;
+use settings::Settings;
 
 use crate::{
-    display_map::ToDisplayPoint, DiffRowHighlight, Editor, EditorMode, LineWithInvisibles,
+    display_map::ToDisplayPoint, editor_settings::NavigationReveal, DiffRowHighlight, Editor,
+    EditorMode, EditorSettings, LineWithInvisibles,
 };
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -39,6 +41,17 @@ impl Autoscroll {
     pub fn top_relative(n: usize) -> Self {
         Self::Strategy(AutoscrollStrategy::TopRelative(n))
     }
+
+    /// Scrolls to a search result, diagnostic or definition that's been navigated to, as
+    /// chosen by the `navigation_reveal` setting, or with `default` if it's `auto`.
+    pub fn navigation(default: Self, cx: &AppContext) -> Self {
+        match EditorSettings::get_global(cx).navigation_reveal {
+            NavigationReveal::Auto => default,
+            NavigationReveal::Center => Self::center(),
+            NavigationReveal::Top => Self::focused(),
+            NavigationReveal::Minimal => Self::fit(),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Default, Clone, Copy)]
@@ -174,9 +187,11 @@ impl Editor {
 
         match strategy {
             AutoscrollStrategy::Fit | AutoscrollStrategy::Newest => {
+                // Like the top of the buffer, its last line doesn't need a margin below it.
                 let margin = margin.min(self.scroll_manager.vertical_scroll_margin);
                 let target_top = (target_top - margin).max(0.0);
-                let target_bottom = target_bottom + margin;
+                let target_bottom =
+                    (target_bottom + margin).min(display_map.max_point().row() as f32 + 1.);
                 let start_row = scroll_position.y;
                 let end_row = start_row + visible_lines;
 
@@ -0,0 +1,123 @@
+//! Dragging selected text with the mouse to move it, or to copy it when the drop is made with
+//! the alt modifier held.
+use crate::{scroll::Autoscroll, DisplayPoint, Editor};
+use gpui::ViewContext;
+use language::Bias;
+use std::ops::Range;
+
+pub(crate) struct SelectionDrag {
+    /// The selected text being dragged.
+    range: Range<usize>,
+    /// Where the mouse was pressed, which becomes the cursor if the text isn't moved.
+    origin: DisplayPoint,
+    /// Where the text would be dropped, once the mouse has moved.
+    drop_position: Option<DisplayPoint>,
+}
+
+impl Editor {
+    /// Starts dragging the selection under `position`, if there's a non-empty one. Returns
+    /// whether a drag started.
+    pub(crate) fn begin_selection_drag(
+        &mut self,
+        position: DisplayPoint,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let offset = position.to_offset(&display_map, Bias::Left);
+        let Some(selection) = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .find(|selection| !selection.is_empty() && selection.range().contains(&offset))
+        else {
+            return false;
+        };
+
+        self.selection_drag = Some(SelectionDrag {
+            range: selection.range(),
+            origin: position,
+            drop_position: None,
+        });
+        true
+    }
+
+    pub(crate) fn has_selection_drag(&self) -> bool {
+        self.selection_drag.is_some()
+    }
+
+    /// Where the dragged text would be dropped, to draw a cursor there.
+    pub(crate) fn selection_drag_drop_position(&self) -> Option<DisplayPoint> {
+        self.selection_drag.as_ref()?.drop_position
+    }
+
+    pub(crate) fn update_selection_drag(
+        &mut self,
+        position: DisplayPoint,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(drag) = self.selection_drag.as_mut() {
+            if drag.drop_position != Some(position) {
+                drag.drop_position = Some(position);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Drops the dragged text at `position`, moving it there in a single transaction, or copying
+    /// it when `copy` is set. Releasing the mouse without dragging, or inside the dragged text,
+    /// places the cursor instead.
+    pub(crate) fn end_selection_drag(
+        &mut self,
+        position: DisplayPoint,
+        copy: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(drag) = self.selection_drag.take() else {
+            return;
+        };
+        cx.notify();
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let drop = position.to_offset(&display_map, Bias::Left);
+        if drag.drop_position.is_none()
+            || (drag.range.start < drop && drop < drag.range.end)
+            || (!copy && (drop == drag.range.start || drop == drag.range.end))
+        {
+            let cursor = if drag.drop_position.is_none() {
+                drag.origin
+            } else {
+                position
+            };
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_display_ranges([cursor..cursor]);
+            });
+            return;
+        }
+
+        let text = display_map
+            .buffer_snapshot
+            .text_for_range(drag.range.clone())
+            .collect::<String>();
+        let mut edits = vec![(drop..drop, text.clone())];
+        let mut new_start = drop;
+        if !copy {
+            edits.push((drag.range.clone(), String::new()));
+            edits.sort_by_key(|(range, _)| range.start);
+            if drop > drag.range.start {
+                new_start -= drag.range.len();
+            }
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([new_start..new_start + text.len()]);
+            });
+        });
+    }
+}
@@ -0,0 +1,394 @@
+//! Actions for wrapping selections in a pair of delimiters, and for changing or deleting the pair
+//! surrounding them.
+//!
+//! A pair is described by a string: a single bracket or quote character stands for its pair, an
+//! opening tag such as `<div class="a">` stands for that tag and its closing tag, and anything
+//! else is used as both delimiters.
+use crate::{
+    display_map::ToDisplayPoint, movement, scroll::Autoscroll, Bias, ChangeSurround,
+    DeleteSurround, Editor, MultiBufferSnapshot, SurroundSelections,
+};
+use gpui::ViewContext;
+use language::Point;
+use std::ops::Range;
+
+const BRACKET_PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
+
+/// How far from a selection the text is first scanned for a pair, doubling until one is found
+/// or the whole buffer has been scanned.
+const INITIAL_SCAN_LEN: usize = 4096;
+
+#[derive(Debug, PartialEq)]
+struct SurroundPair {
+    open: String,
+    close: String,
+    /// The tag name, if the pair is an HTML or JSX tag.
+    tag: Option<String>,
+}
+
+impl SurroundPair {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        if let Some(inner) = text
+            .strip_prefix('<')
+            .and_then(|text| text.strip_suffix('>'))
+            .filter(|inner| !inner.is_empty())
+        {
+            let name = inner
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default();
+            if name.is_empty() {
+                return None;
+            }
+            return Some(Self {
+                open: format!("<{}>", inner.trim_end_matches('/').trim_end()),
+                close: format!("</{name}>"),
+                tag: Some(name.to_string()),
+            });
+        }
+
+        let mut chars = text.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            if let Some((open, close)) = BRACKET_PAIRS
+                .iter()
+                .find(|(open, close)| *open == ch || *close == ch)
+            {
+                return Some(Self {
+                    open: open.to_string(),
+                    close: close.to_string(),
+                    tag: None,
+                });
+            }
+        }
+
+        Some(Self {
+            open: text.to_string(),
+            close: text.to_string(),
+            tag: None,
+        })
+    }
+
+    /// Finds the innermost occurrence of this pair around `range`, returning the ranges of the
+    /// open and close delimiters.
+    ///
+    /// The language's bracket queries are used when they know about the pair, falling back to
+    /// scanning the text around the range otherwise.
+    fn find_around(
+        &self,
+        buffer: &MultiBufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        if self.tag.is_none() {
+            let filter = |open: Range<usize>, close: Range<usize>| {
+                open.len() == self.open.len()
+                    && close.len() == self.close.len()
+                    && buffer.contains_str_at(open.start, &self.open)
+                    && buffer.contains_str_at(close.start, &self.close)
+            };
+            if let Some(ranges) =
+                buffer.innermost_enclosing_bracket_ranges(range.clone(), Some(&filter))
+            {
+                return Some(ranges);
+            }
+        }
+        if self.open == self.close {
+            return self.find_same_delimiters_around(buffer, range);
+        }
+
+        let mut scan_len = INITIAL_SCAN_LEN;
+        let open = loop {
+            let start = buffer.clip_offset(range.start.saturating_sub(scan_len), Bias::Left);
+            let before = buffer
+                .text_for_range(start..range.start)
+                .collect::<String>();
+            if let Some(open) = self.find_open(&before) {
+                break open.start + start..open.end + start;
+            }
+            if start == 0 {
+                return None;
+            }
+            scan_len *= 2;
+        };
+
+        let mut scan_len = INITIAL_SCAN_LEN;
+        let close = loop {
+            let end = buffer.clip_offset((range.end + scan_len).min(buffer.len()), Bias::Right);
+            let after = buffer.text_for_range(range.end..end).collect::<String>();
+            if let Some(close) = self.find_close(&after) {
+                break close.start + range.end..close.end + range.end;
+            }
+            if end == buffer.len() {
+                return None;
+            }
+            scan_len *= 2;
+        };
+        Some((open, close))
+    }
+
+    /// Quotes and other pairs whose delimiters are the same can't be told apart by looking at
+    /// them, so they're paired up from the start of the line: the range is inside a pair when an
+    /// odd number of unescaped delimiters come before it on its line.
+    fn find_same_delimiters_around(
+        &self,
+        buffer: &MultiBufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        let line_start =
+            buffer.point_to_offset(Point::new(buffer.offset_to_point(range.start).row, 0));
+        let end_row = buffer.offset_to_point(range.end).row;
+        let line_end = buffer.point_to_offset(Point::new(end_row, buffer.line_len(end_row)));
+        let before = buffer
+            .text_for_range(line_start..range.start)
+            .collect::<String>();
+        let after = buffer
+            .text_for_range(range.end..line_end)
+            .collect::<String>();
+
+        let opens = self.unescaped_occurrences(&before);
+        let mut closes = self.unescaped_occurrences(&after).into_iter();
+        let (open, close) = if opens.len() % 2 == 1 {
+            let open = opens.last()?.clone();
+            (
+                open.start + line_start..open.end + line_start,
+                closes.next()?,
+            )
+        } else {
+            // A cursor on an opening delimiter is inside the pair it opens.
+            let open = closes
+                .next()
+                .filter(|open| range.is_empty() && open.start == 0)?;
+            (open.start + range.end..open.end + range.end, closes.next()?)
+        };
+        Some((open, close.start + range.end..close.end + range.end))
+    }
+
+    fn unescaped_occurrences(&self, text: &str) -> Vec<Range<usize>> {
+        text.match_indices(self.open.as_str())
+            .filter(|(ix, _)| {
+                let backslashes = text[..*ix].chars().rev().take_while(|c| *c == '\\').count();
+                backslashes % 2 == 0
+            })
+            .map(|(ix, open)| ix..ix + open.len())
+            .collect()
+    }
+
+    fn find_open(&self, before: &str) -> Option<Range<usize>> {
+        let opens = self.open_occurrences(before);
+        let closes = before
+            .match_indices(self.close.as_str())
+            .map(|(ix, close)| ix..ix + close.len());
+        let mut delimiters = opens
+            .into_iter()
+            .map(|range| (range, true))
+            .chain(closes.map(|range| (range, false)))
+            .collect::<Vec<_>>();
+        delimiters.sort_by_key(|(range, _)| range.start);
+
+        let mut depth = 0;
+        for (range, is_open) in delimiters.into_iter().rev() {
+            if !is_open {
+                depth += 1;
+            } else if depth == 0 {
+                return Some(range);
+            } else {
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    fn find_close(&self, after: &str) -> Option<Range<usize>> {
+        let closes = after
+            .match_indices(self.close.as_str())
+            .map(|(ix, close)| ix..ix + close.len());
+        let mut delimiters = self
+            .open_occurrences(after)
+            .into_iter()
+            .map(|range| (range, true))
+            .chain(closes.map(|range| (range, false)))
+            .collect::<Vec<_>>();
+        delimiters.sort_by_key(|(range, _)| range.start);
+
+        let mut depth = 0;
+        for (range, is_open) in delimiters {
+            if is_open {
+                depth += 1;
+            } else if depth == 0 {
+                return Some(range);
+            } else {
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    /// Opening tags can carry attributes, so they're matched by name up to their closing `>`.
+    fn open_occurrences(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(name) = self.tag.as_ref() else {
+            return text
+                .match_indices(self.open.as_str())
+                .map(|(ix, open)| ix..ix + open.len())
+                .collect();
+        };
+
+        let prefix = format!("<{name}");
+        text.match_indices(prefix.as_str())
+            .filter_map(|(ix, _)| {
+                let rest = &text[ix + prefix.len()..];
+                if !rest.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+                    return None;
+                }
+                let end = rest.find('>')?;
+                // Self-closing tags don't surround anything.
+                if rest[..end].ends_with('/') {
+                    return None;
+                }
+                Some(ix..ix + prefix.len() + end + 1)
+            })
+            .collect()
+    }
+}
+
+impl Editor {
+    pub fn surround_selections(&mut self, action: &SurroundSelections, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            self.notify_read_only(cx);
+            return;
+        }
+        let Some(pair) = SurroundPair::parse(&action.pair) else {
+            return;
+        };
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let (start, end) = if selection.is_empty() {
+                let word_range = movement::surrounding_word(
+                    &display_map,
+                    selection.start.to_display_point(&display_map),
+                );
+                (
+                    word_range.start.to_offset(&display_map, Bias::Left),
+                    word_range.end.to_offset(&display_map, Bias::Left),
+                )
+            } else {
+                (selection.start, selection.end)
+            };
+
+            edits.push((start..start, pair.open.clone()));
+            edits.push((end..end, pair.close.clone()));
+            // Keep the wrapped text selected.
+            new_selections.push(buffer.anchor_after(start)..buffer.anchor_before(end));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges(new_selections);
+            });
+        });
+    }
+
+    pub fn change_surround(&mut self, action: &ChangeSurround, cx: &mut ViewContext<Self>) {
+        let (Some(from), Some(to)) = (
+            SurroundPair::parse(&action.from),
+            SurroundPair::parse(&action.to),
+        ) else {
+            return;
+        };
+        self.edit_surrounding_pairs(&from, &to.open, &to.close, cx);
+    }
+
+    pub fn delete_surround(&mut self, action: &DeleteSurround, cx: &mut ViewContext<Self>) {
+        let Some(pair) = SurroundPair::parse(&action.pair) else {
+            return;
+        };
+        self.edit_surrounding_pairs(&pair, "", "", cx);
+    }
+
+    fn edit_surrounding_pairs(
+        &mut self,
+        pair: &SurroundPair,
+        new_open: &str,
+        new_close: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            self.notify_read_only(cx);
+            return;
+        }
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let mut edits = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            if let Some((open, close)) = pair.find_around(&buffer, selection.range()) {
+                edits.push((open, new_open.to_string()));
+                edits.push((close, new_close.to_string()));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        // Selections inside the same pair find it more than once.
+        edits.sort_by_key(|(range, _)| range.start);
+        edits.dedup_by_key(|(range, _)| range.start);
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.request_autoscroll(Autoscroll::fit(), cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_surround_pair() {
+        assert_eq!(
+            SurroundPair::parse(")"),
+            Some(SurroundPair {
+                open: "(".into(),
+                close: ")".into(),
+                tag: None,
+            })
+        );
+        assert_eq!(
+            SurroundPair::parse("<div class=\"a\">"),
+            Some(SurroundPair {
+                open: "<div class=\"a\">".into(),
+                close: "</div>".into(),
+                tag: Some("div".into()),
+            })
+        );
+        assert_eq!(
+            SurroundPair::parse("**"),
+            Some(SurroundPair {
+                open: "**".into(),
+                close: "**".into(),
+                tag: None,
+            })
+        );
+        assert_eq!(SurroundPair::parse(""), None);
+    }
+}
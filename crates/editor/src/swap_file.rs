@@ -0,0 +1,167 @@
+//! Swap files keep the contents of dirty buffers on disk, so that unsaved edits survive a crash.
+//!
+//! While a buffer is dirty, its editors periodically write the buffer's text into [`SWAP_DIR`].
+//! The swap file is removed once the buffer is saved, becomes clean again, or its last editor is
+//! closed.
+//! Whatever is left in the directory when a workspace opens was abandoned by a crash, and is offered
+//! for restoration.
+
+use crate::Editor;
+use anyhow::Result;
+use futures::StreamExt;
+use gpui::{PromptLevel, Task, ViewContext};
+use project::{Fs, RemoveOptions};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::{paths::SWAP_DIR, ResultExt};
+use workspace::Workspace;
+
+/// How long a buffer has to stay untouched after an edit before its swap file is written.
+pub const SWAP_FILE_DEBOUNCE: Duration = Duration::from_secs(2);
+
+const SWAP_FILE_EXTENSION: &str = "swp";
+
+/// The contents of a single swap file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SwapFile {
+    /// The path of the file whose unsaved contents are stored.
+    pub abs_path: PathBuf,
+    /// The buffer text at the time the swap file was written.
+    pub text: String,
+}
+
+/// Returns the location of the swap file for the file at `abs_path`.
+///
+/// Like Vim, the original path is encoded into the file name by replacing its separators with `%`.
+pub fn swap_file_path(abs_path: &Path) -> PathBuf {
+    let mut file_name = abs_path.to_string_lossy().replace(['/', '\\', ':'], "%");
+    file_name.push('.');
+    file_name.push_str(SWAP_FILE_EXTENSION);
+    SWAP_DIR.join(file_name)
+}
+
+pub(crate) async fn write_swap_file(fs: &dyn Fs, abs_path: PathBuf, text: String) -> Result<()> {
+    let swap_file_path = swap_file_path(&abs_path);
+    let contents = serde_json::to_string(&SwapFile { abs_path, text })?;
+    fs.create_dir(&SWAP_DIR).await?;
+    fs.atomic_write(swap_file_path, contents).await
+}
+
+pub(crate) async fn remove_swap_file(fs: &dyn Fs, abs_path: &Path) -> Result<()> {
+    fs.remove_file(
+        &swap_file_path(abs_path),
+        RemoveOptions {
+            recursive: false,
+            ignore_if_not_exists: true,
+        },
+    )
+    .await
+}
+
+/// Collects the swap files left behind for files within the given roots.
+///
+/// Swap files whose contents match the file on disk carry no unsaved changes and are removed.
+pub async fn find_swap_files(fs: &dyn Fs, roots: &[Arc<Path>]) -> Result<Vec<SwapFile>> {
+    let mut swap_files = Vec::new();
+    let Ok(mut entries) = fs.read_dir(&SWAP_DIR).await else {
+        return Ok(swap_files);
+    };
+    while let Some(entry) = entries.next().await {
+        let path = entry?;
+        if path.extension().and_then(|extension| extension.to_str()) != Some(SWAP_FILE_EXTENSION) {
+            continue;
+        }
+        let Some(swap_file) = fs
+            .load(&path)
+            .await
+            .ok()
+            .and_then(|contents| serde_json::from_str::<SwapFile>(&contents).log_err())
+        else {
+            continue;
+        };
+        if !roots
+            .iter()
+            .any(|root| swap_file.abs_path.starts_with(root))
+        {
+            continue;
+        }
+
+        if fs.load(&swap_file.abs_path).await.ok().as_ref() == Some(&swap_file.text) {
+            remove_swap_file(fs, &swap_file.abs_path).await.log_err();
+        } else {
+            swap_files.push(swap_file);
+        }
+    }
+    swap_files.sort_by(|a, b| a.abs_path.cmp(&b.abs_path));
+    Ok(swap_files)
+}
+
+/// Looks for swap files abandoned within the workspace's worktrees, and prompts to restore them.
+pub fn recover_swap_files(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return Task::ready(Ok(()));
+    }
+    let fs = project.fs().clone();
+    let roots = project
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path())
+        .collect::<Vec<_>>();
+
+    cx.spawn(|workspace, mut cx| async move {
+        let swap_files = find_swap_files(fs.as_ref(), &roots).await?;
+        if swap_files.is_empty() {
+            return Ok(());
+        }
+
+        let message = if swap_files.len() == 1 {
+            "Unsaved changes to 1 file were recovered after Zed quit unexpectedly.".to_string()
+        } else {
+            format!(
+                "Unsaved changes to {} files were recovered after Zed quit unexpectedly.",
+                swap_files.len()
+            )
+        };
+        let detail = swap_files
+            .iter()
+            .map(|swap_file| swap_file.abs_path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let answer = workspace.update(&mut cx, |_, cx| {
+            cx.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some(&detail),
+                &["Restore", "Discard"],
+            )
+        })?;
+
+        if answer.await != Ok(0) {
+            for swap_file in swap_files {
+                remove_swap_file(fs.as_ref(), &swap_file.abs_path)
+                    .await
+                    .log_err();
+            }
+            return Ok(());
+        }
+
+        for swap_file in swap_files {
+            let item = workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_abs_path(swap_file.abs_path.clone(), true, cx)
+                })?
+                .await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| editor.set_text(swap_file.text, cx))?;
+            }
+        }
+        Ok(())
+    })
+}
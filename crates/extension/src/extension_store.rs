@@ -15,6 +15,7 @@ use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
 use collections::{btree_map, BTreeMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+use file_icons::FileIcons;
 use fs::{Fs, RemoveOptions};
 use futures::{
     channel::{
@@ -29,8 +30,8 @@ use gpui::{
     WeakModel,
 };
 use language::{
-    ContextProviderWithTasks, LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry,
-    QUERY_FILENAME_PREFIXES,
+    load_plugin_queries, ContextProviderWithTasks, LanguageConfig, LanguageMatcher,
+    LanguageRegistry,
 };
 use node_runtime::NodeRuntime;
 use semantic_version::SemanticVersion;
@@ -138,6 +139,8 @@ impl Global for GlobalExtensionStore {}
 pub struct ExtensionIndex {
     pub extensions: BTreeMap<Arc<str>, ExtensionIndexEntry>,
     pub themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
+    #[serde(default)]
+    pub icon_themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
     pub languages: BTreeMap<Arc<str>, ExtensionIndexLanguageEntry>,
 }
 
@@ -993,6 +996,17 @@ impl ExtensionStore {
                 }
             })
             .collect::<Vec<_>>();
+        let icon_themes_to_remove = old_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, entry)| {
+                if extensions_to_unload.contains(&entry.extension) {
+                    Some(name.clone().into())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
         let languages_to_remove = old_index
             .languages
             .iter()
@@ -1021,6 +1035,11 @@ impl ExtensionStore {
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.theme_registry.remove_user_themes(&themes_to_remove);
+        if cx.has_global::<FileIcons>() {
+            cx.update_global(|file_icons: &mut FileIcons, _| {
+                file_icons.remove_icon_themes(&icon_themes_to_remove)
+            });
+        }
         self.language_registry
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -1031,6 +1050,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
+        let mut icon_themes_to_add = Vec::new();
         for extension_id in &extensions_to_load {
             let Some(extension) = new_index.extensions.get(extension_id) else {
                 continue;
@@ -1048,6 +1068,13 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+            icon_themes_to_add.extend(extension.manifest.icon_themes.iter().map(
+                |icon_theme_path| {
+                    let mut path = self.installed_dir.clone();
+                    path.extend([Path::new(extension_id.as_ref()), icon_theme_path.as_path()]);
+                    path
+                },
+            ));
         }
 
         self.language_registry
@@ -1106,6 +1133,11 @@ impl ExtensionStore {
                     }
                 })
                 .await;
+            for icon_theme_path in &icon_themes_to_add {
+                FileIcons::load_user_icon_theme(icon_theme_path, fs.clone(), &mut cx)
+                    .await
+                    .log_err();
+            }
 
             let mut wasm_extensions = Vec::new();
             for extension in extension_entries {
@@ -1300,6 +1332,36 @@ impl ExtensionStore {
             }
         }
 
+        if let Ok(mut icon_theme_paths) = fs.read_dir(&extension_dir.join("icon_themes")).await {
+            while let Some(icon_theme_path) = icon_theme_paths.next().await {
+                let icon_theme_path = icon_theme_path?;
+                let Ok(relative_path) = icon_theme_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+
+                let Some(icon_theme) =
+                    FileIcons::read_user_icon_theme(&icon_theme_path, fs.clone())
+                        .await
+                        .log_err()
+                else {
+                    continue;
+                };
+
+                let relative_path = relative_path.to_path_buf();
+                if !extension_manifest.icon_themes.contains(&relative_path) {
+                    extension_manifest.icon_themes.push(relative_path.clone());
+                }
+
+                index.icon_themes.insert(
+                    icon_theme.name.into(),
+                    ExtensionIndexThemeEntry {
+                        extension: extension_id.clone(),
+                        path: relative_path,
+                    },
+                );
+            }
+        }
+
         let extension_wasm_path = extension_dir.join("extension.wasm");
         if fs.is_file(&extension_wasm_path).await {
             extension_manifest
@@ -1319,32 +1381,3 @@ impl ExtensionStore {
         Ok(())
     }
 }
-
-fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
-    let mut result = LanguageQueries::default();
-    if let Some(entries) = std::fs::read_dir(root_path).log_err() {
-        for entry in entries {
-            let Some(entry) = entry.log_err() else {
-                continue;
-            };
-            let path = entry.path();
-            if let Some(remainder) = path.strip_prefix(root_path).ok().and_then(|p| p.to_str()) {
-                if !remainder.ends_with(".scm") {
-                    continue;
-                }
-                for (name, query) in QUERY_FILENAME_PREFIXES {
-                    if remainder.starts_with(name) {
-                        if let Some(contents) = std::fs::read_to_string(&path).log_err() {
-                            match query(&mut result) {
-                                None => *query(&mut result) = Some(contents.into()),
-                                Some(r) => r.to_mut().push_str(contents.as_ref()),
-                            }
-                        }
-                        break;
-                    }
-                }
-            }
-        }
-    }
-    result
-}
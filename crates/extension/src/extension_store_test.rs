@@ -7,6 +7,7 @@ use crate::{
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::BTreeMap;
+use file_icons::FileIcons;
 use fs::{FakeFs, Fs, RealFs};
 use futures::{io::BufReader, AsyncReadExt, StreamExt};
 use gpui::{Context, TestAppContext};
@@ -149,6 +150,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         authors: Vec::new(),
                         repository: None,
                         themes: Default::default(),
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: vec!["languages/erb".into(), "languages/ruby".into()],
                         grammars: [
@@ -177,6 +179,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                             "themes/monokai-pro.json".into(),
                             "themes/monokai.json".into(),
                         ],
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: Default::default(),
                         grammars: BTreeMap::default(),
@@ -248,6 +251,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ]
         .into_iter()
         .collect(),
+        icon_themes: BTreeMap::default(),
     };
 
     let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
@@ -302,6 +306,13 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                     "Gruvbox": "themes/gruvbox.json"
                 }
             }"#,
+            "icon_themes": {
+                "gruvbox-icons.json": r#"{
+                    "name": "Gruvbox Icons",
+                    "suffixes": { "rs": "rust" },
+                    "types": { "rust": { "icon": "icons/file_icons/rust.svg" } }
+                }"#,
+            },
             "themes": {
                 "gruvbox.json": r#"{
                     "name": "Gruvbox",
@@ -331,6 +342,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 authors: vec![],
                 repository: None,
                 themes: vec!["themes/gruvbox.json".into()],
+                icon_themes: vec!["icon_themes/gruvbox-icons.json".into()],
                 lib: Default::default(),
                 languages: Default::default(),
                 grammars: BTreeMap::default(),
@@ -346,15 +358,27 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             path: "themes/gruvbox.json".into(),
         },
     );
+    expected_index.icon_themes.insert(
+        "Gruvbox Icons".into(),
+        ExtensionIndexThemeEntry {
+            extension: "zed-gruvbox".into(),
+            path: "icon_themes/gruvbox-icons.json".into(),
+        },
+    );
 
     let _ = store.update(cx, |store, cx| store.reload(None, cx));
 
     cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
-    store.read_with(cx, |store, _| {
+    store.read_with(cx, |store, cx| {
         let index = &store.extension_index;
         assert_eq!(index.extensions, expected_index.extensions);
         assert_eq!(index.languages, expected_index.languages);
         assert_eq!(index.themes, expected_index.themes);
+        assert_eq!(index.icon_themes, expected_index.icon_themes);
+        assert_eq!(
+            FileIcons::get(cx).list_icon_theme_names(),
+            ["Gruvbox Icons", file_icons::DEFAULT_ICON_THEME]
+        );
 
         assert_eq!(
             theme_registry.list_names(false),
@@ -717,6 +741,7 @@ fn init_test(cx: &mut TestAppContext) {
         let store = SettingsStore::test(cx);
         cx.set_global(store);
         theme::init(theme::LoadThemes::JustBase, cx);
+        file_icons::init((), cx);
         Project::init_settings(cx);
         ExtensionSettings::register(cx);
         language::init(cx);
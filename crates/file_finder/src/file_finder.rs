@@ -170,6 +170,8 @@ pub struct FileFinderDelegate {
 /// In the file finder, we would prefer to have the max element with the highest score and the earliest alphanumerical path, e.g:
 /// `[{ score: 0.5, path = "/a/b" }, {score: 0.5, path = "c/d" }]`
 /// as the files are shown in the project panel lists.
+///
+/// As in the regular ordering, shorter paths win ties before the alphanumerical order is used.
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ProjectPanelOrdMatch(PathMatch);
 
@@ -186,6 +188,14 @@ impl Ord for ProjectPanelOrdMatch {
                     .distance_to_relative_ancestor
                     .cmp(&self.0.distance_to_relative_ancestor)
             })
+            .then_with(|| {
+                other
+                    .0
+                    .path
+                    .as_os_str()
+                    .len()
+                    .cmp(&self.0.path.as_os_str().len())
+            })
             .then_with(|| self.0.path.cmp(&other.0.path).reverse())
     }
 }
@@ -564,6 +564,46 @@ async fn test_path_distance_ordering(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_shorter_paths_win_ties(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "crates": {
+                    "b": { "src": { "main.rs": "" } },
+                    "a": { "src": { "main.rs": "" } },
+                },
+                "src": { "main.rs": "" },
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+    let finder = open_file_picker(&workspace, cx);
+    finder
+        .update(cx, |f, cx| {
+            f.delegate.spawn_search(test_path_like("main.rs"), cx)
+        })
+        .await;
+
+    finder.update(cx, |f, _| {
+        assert_eq!(
+            collect_search_matches(f).search_only(),
+            vec![
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("crates/a/src/main.rs"),
+                PathBuf::from("crates/b/src/main.rs"),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_search_worktree_without_files(cx: &mut TestAppContext) {
     let app_state = init_test(cx);
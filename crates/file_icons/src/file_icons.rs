@@ -1,32 +1,103 @@
 use std::{path::Path, str, sync::Arc};
 
+use anyhow::{Context as _, Result};
 use collections::HashMap;
-
-use gpui::{AppContext, AssetSource, Global};
-use serde_derive::Deserialize;
-use util::{maybe, paths::PathExt};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{AppContext, AssetSource, AsyncAppContext, Global, SharedString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use util::{paths::PathExt, ResultExt as _};
 
 #[derive(Deserialize, Debug)]
 struct TypeConfig {
     icon: Arc<str>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct FileIcons {
+/// A set of icons for files and folders, chosen by the file's name or extension.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct IconTheme {
     stems: HashMap<String, String>,
     suffixes: HashMap<String, String>,
     types: HashMap<String, TypeConfig>,
 }
 
+impl IconTheme {
+    /// Loads an icon theme from a JSON asset in the format of [`FILE_TYPES_ASSET`].
+    pub fn load(assets: &impl AssetSource, path: &str) -> Result<Self> {
+        let file = assets.load(path)?;
+        Ok(serde_json::from_str(str::from_utf8(&file)?)?)
+    }
+
+    fn icon_for_path(&self, path: &Path) -> Option<Arc<str>> {
+        let suffix = path.icon_stem_or_suffix()?;
+        let type_str = self
+            .stems
+            .get(suffix)
+            .or_else(|| self.suffixes.get(suffix))?;
+        self.get_type_icon(type_str)
+    }
+
+    pub fn get_type_icon(&self, typ: &str) -> Option<Arc<str>> {
+        self.types
+            .get(typ)
+            .map(|type_config| type_config.icon.clone())
+    }
+}
+
+/// An icon theme loaded from the user's icon themes directory or an extension: a named
+/// [`IconTheme`] whose icons are paths of the icons bundled with Zed.
+#[derive(Deserialize, Debug)]
+pub struct IconThemeContent {
+    pub name: String,
+    #[serde(flatten)]
+    pub theme: IconTheme,
+}
+
+/// The registered icon themes.
+pub struct FileIcons {
+    themes: HashMap<SharedString, Arc<IconTheme>>,
+}
+
 impl Global for FileIcons {}
 
+#[derive(Deserialize)]
+pub struct IconThemeSettings {
+    pub icon_theme: SharedString,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IconThemeSettingsContent {
+    /// The name of the icon theme used for files and folders.
+    ///
+    /// Default: Zed (Default)
+    pub icon_theme: Option<String>,
+}
+
+impl Settings for IconThemeSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = IconThemeSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
 const COLLAPSED_DIRECTORY_TYPE: &str = "collapsed_folder";
 const EXPANDED_DIRECTORY_TYPE: &str = "expanded_folder";
 const COLLAPSED_CHEVRON_TYPE: &str = "collapsed_chevron";
 const EXPANDED_CHEVRON_TYPE: &str = "expanded_chevron";
 pub const FILE_TYPES_ASSET: &str = "icons/file_icons/file_types.json";
+/// The icon of files that no icon theme knows about.
+pub const GENERIC_FILE_ICON: &str = "icons/file_icons/file.svg";
+/// The name of the icon theme built from [`FILE_TYPES_ASSET`].
+pub const DEFAULT_ICON_THEME: &str = "Zed (Default)";
 
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
+    IconThemeSettings::register(cx);
     cx.set_global(FileIcons::new(assets))
 }
 
@@ -36,65 +107,253 @@ impl FileIcons {
     }
 
     pub fn new(assets: impl AssetSource) -> Self {
-        assets
-            .load("icons/file_icons/file_types.json")
-            .and_then(|file| {
-                serde_json::from_str::<FileIcons>(str::from_utf8(&file).unwrap())
-                    .map_err(Into::into)
-            })
-            .unwrap_or_else(|_| FileIcons {
-                stems: HashMap::default(),
-                suffixes: HashMap::default(),
-                types: HashMap::default(),
-            })
+        let mut this = FileIcons {
+            themes: HashMap::default(),
+        };
+        this.insert_icon_theme(
+            DEFAULT_ICON_THEME,
+            IconTheme::load(&assets, FILE_TYPES_ASSET).unwrap_or_default(),
+        );
+        this
     }
 
-    pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
-        let this = cx.try_global::<Self>()?;
+    /// Registers an icon theme, replacing any theme with the same name.
+    pub fn insert_icon_theme(&mut self, name: impl Into<SharedString>, theme: IconTheme) {
+        self.themes.insert(name.into(), Arc::new(theme));
+    }
 
-        // FIXME: Associate a type with the languages and have the file's language
-        //        override these associations
-        maybe!({
-            let suffix = path.icon_stem_or_suffix()?;
+    pub fn remove_icon_themes(&mut self, names: &[SharedString]) {
+        self.themes.retain(|name, _| !names.contains(name));
+    }
 
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
-            }
+    pub async fn read_user_icon_theme(
+        icon_theme_path: &Path,
+        fs: Arc<dyn Fs>,
+    ) -> Result<IconThemeContent> {
+        let reader = fs.open_sync(icon_theme_path).await?;
+        let icon_theme = serde_json_lenient::from_reader(reader)
+            .with_context(|| format!("parsing icon theme {icon_theme_path:?}"))?;
+
+        Ok(icon_theme)
+    }
 
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
+    /// Registers the icon theme in the given file, and re-renders the windows in case it's the
+    /// selected one.
+    pub async fn load_user_icon_theme(
+        icon_theme_path: &Path,
+        fs: Arc<dyn Fs>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let icon_theme = Self::read_user_icon_theme(icon_theme_path, fs).await?;
+        cx.update(|cx| {
+            if cx.has_global::<Self>() {
+                cx.update_global(|this: &mut Self, _| {
+                    this.insert_icon_theme(icon_theme.name, icon_theme.theme)
+                });
+                cx.refresh();
+            }
         })
-        .or_else(|| this.get_type_icon("default"))
     }
 
-    pub fn get_type_icon(&self, typ: &str) -> Option<Arc<str>> {
-        self.types
-            .get(typ)
-            .map(|type_config| type_config.icon.clone())
+    pub async fn load_user_icon_themes(
+        icon_themes_path: &Path,
+        fs: Arc<dyn Fs>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let mut icon_theme_paths = fs
+            .read_dir(icon_themes_path)
+            .await
+            .with_context(|| format!("reading icon themes from {icon_themes_path:?}"))?;
+
+        while let Some(icon_theme_path) = icon_theme_paths.next().await {
+            let Some(icon_theme_path) = icon_theme_path.log_err() else {
+                continue;
+            };
+
+            Self::load_user_icon_theme(&icon_theme_path, fs.clone(), cx)
+                .await
+                .log_err();
+        }
+
+        Ok(())
     }
 
-    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
+    pub fn list_icon_theme_names(&self) -> Vec<SharedString> {
+        let mut names = self.themes.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Returns the icon theme selected in the settings, or the default one if that isn't
+    /// registered.
+    pub fn active_icon_theme(cx: &AppContext) -> Option<Arc<IconTheme>> {
         let this = cx.try_global::<Self>()?;
+        let name = &IconThemeSettings::get_global(cx).icon_theme;
+        this.themes
+            .get(name)
+            .or_else(|| this.themes.get(DEFAULT_ICON_THEME))
+            .cloned()
+    }
+
+    pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
+        let theme = Self::active_icon_theme(cx)?;
+
+        // FIXME: Associate a type with the languages and have the file's language
+        //        override these associations
+        theme
+            .icon_for_path(path)
+            .or_else(|| theme.get_type_icon("default"))
+            .or_else(|| Some(GENERIC_FILE_ICON.into()))
+    }
+
+    pub fn get_type_icon(typ: &str, cx: &AppContext) -> Option<Arc<str>> {
+        Self::active_icon_theme(cx)?.get_type_icon(typ)
+    }
 
+    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
         let key = if expanded {
             EXPANDED_DIRECTORY_TYPE
         } else {
             COLLAPSED_DIRECTORY_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
-        let this = cx.try_global::<Self>()?;
-
         let key = if expanded {
             EXPANDED_CHEVRON_TYPE
         } else {
             COLLAPSED_CHEVRON_TYPE
         };
 
-        this.get_type_icon(key)
+        Self::get_type_icon(key, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+
+    fn icon_theme(json: serde_json::Value) -> IconTheme {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[gpui::test]
+    fn test_file_icons(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(SettingsStore::test(cx));
+            IconThemeSettings::register(cx);
+
+            let mut file_icons = FileIcons::new(());
+            file_icons.insert_icon_theme(
+                DEFAULT_ICON_THEME,
+                icon_theme(serde_json::json!({
+                    "stems": { "Dockerfile": "docker" },
+                    "suffixes": { "rs": "rust" },
+                    "types": {
+                        "docker": { "icon": "icons/docker.svg" },
+                        "rust": { "icon": "icons/rust.svg" },
+                    },
+                })),
+            );
+            file_icons.insert_icon_theme(
+                "Other",
+                icon_theme(serde_json::json!({
+                    "stems": {},
+                    "suffixes": { "rs": "rust" },
+                    "types": { "rust": { "icon": "icons/other/rust.svg" } },
+                })),
+            );
+            cx.set_global(file_icons);
+
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/rust.svg")
+            );
+            assert_eq!(
+                FileIcons::get_icon(Path::new("Dockerfile"), cx).as_deref(),
+                Some("icons/docker.svg")
+            );
+            // Unknown files get the generic icon.
+            assert_eq!(
+                FileIcons::get_icon(Path::new("notes.unknown"), cx).as_deref(),
+                Some(GENERIC_FILE_ICON)
+            );
+
+            // Switching themes changes the icons.
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<IconThemeSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Other".into());
+                });
+            });
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/other/rust.svg")
+            );
+
+            // An unknown theme falls back to the default one.
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<IconThemeSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Missing".into());
+                });
+            });
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/rust.svg")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_user_icon_themes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(SettingsStore::test(cx));
+            init((), cx);
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<IconThemeSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Mine".into());
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/icon_themes",
+            serde_json::json!({
+                "mine.json": r#"{
+                    // Comments are allowed, as in the settings.
+                    "name": "Mine",
+                    "suffixes": { "rs": "rust" },
+                    "types": { "rust": { "icon": "icons/mine/rust.svg" } }
+                }"#,
+            }),
+        )
+        .await;
+        FileIcons::load_user_icon_themes(Path::new("/icon_themes"), fs, &mut cx.to_async())
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            assert_eq!(
+                FileIcons::get(cx).list_icon_theme_names(),
+                ["Mine", DEFAULT_ICON_THEME]
+            );
+            assert_eq!(
+                FileIcons::get_icon(Path::new("src/main.rs"), cx).as_deref(),
+                Some("icons/mine/rust.svg")
+            );
+
+            cx.update_global(|file_icons: &mut FileIcons, _| {
+                file_icons.remove_icon_themes(&["Mine".into()])
+            });
+            assert_eq!(
+                FileIcons::get(cx).list_icon_theme_names(),
+                [DEFAULT_ICON_THEME]
+            );
+        });
     }
 }
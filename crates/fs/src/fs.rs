@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
@@ -25,7 +25,7 @@ use text::LineEnding;
 use util::{paths, ResultExt};
 
 #[cfg(any(test, feature = "test-support"))]
-use collections::{btree_map, BTreeMap};
+use collections::{btree_map, BTreeMap, HashMap, HashSet};
 #[cfg(any(test, feature = "test-support"))]
 use git::repository::{FakeGitRepositoryState, GitFileStatus};
 #[cfg(any(test, feature = "test-support"))]
@@ -60,6 +60,66 @@ pub trait Fs: Send + Sync {
     async fn load(&self, path: &Path) -> Result<String>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+
+    /// Saves every file or none of them, replacing each one atomically. When a write fails, the
+    /// files that were already written are restored.
+    async fn save_all(&self, writes: Vec<(PathBuf, Rope, LineEnding)>) -> SaveAllOutcome {
+        let mut written = Vec::<(PathBuf, Option<String>)>::new();
+        let mut failure = None;
+        let mut writes = writes.into_iter();
+        for (path, text, line_ending) in writes.by_ref() {
+            let backup = if self.is_file(&path).await {
+                match self.load(&path).await {
+                    Ok(content) => Some(content),
+                    Err(error) => {
+                        failure = Some((path, error));
+                        break;
+                    }
+                }
+            } else {
+                None
+            };
+            let content = chunks(&text, line_ending).collect::<String>();
+            match self.atomic_write(path.clone(), content).await {
+                Ok(()) => written.push((path, backup)),
+                Err(error) => {
+                    failure = Some((path, error));
+                    break;
+                }
+            }
+        }
+
+        let Some((failed_path, error)) = failure else {
+            return SaveAllOutcome {
+                saved: written.into_iter().map(|(path, _)| path).collect(),
+                failed: Vec::new(),
+            };
+        };
+        let mut failed = Vec::new();
+        for (path, backup) in written.into_iter().rev() {
+            let restored = match backup {
+                Some(content) => self.atomic_write(path.clone(), content).await,
+                None => self.remove_file(&path, RemoveOptions::default()).await,
+            };
+            restored
+                .with_context(|| format!("restoring {path:?}"))
+                .log_err();
+            failed.push(path);
+        }
+        failed.extend(writes.map(|(path, _, _)| path));
+        let failed = failed
+            .into_iter()
+            .map(|path| {
+                let error = anyhow!("not saved, because saving {failed_path:?} failed");
+                (path, error)
+            })
+            .collect::<Vec<_>>();
+        SaveAllOutcome {
+            saved: Vec::new(),
+            failed: [(failed_path, error)].into_iter().chain(failed).collect(),
+        }
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -101,6 +161,14 @@ pub struct RenameOptions {
     pub ignore_if_exists: bool,
 }
 
+/// The outcome of [`Fs::save_all`].
+#[derive(Debug, Default)]
+pub struct SaveAllOutcome {
+    pub saved: Vec<PathBuf>,
+    /// The files that weren't saved, starting with the one whose write failed.
+    pub failed: Vec<(PathBuf, anyhow::Error)>,
+}
+
 #[derive(Copy, Clone, Default)]
 pub struct RemoveOptions {
     pub recursive: bool,
@@ -113,6 +181,8 @@ pub struct Metadata {
     pub mtime: SystemTime,
     pub is_symlink: bool,
     pub is_dir: bool,
+    /// Whether the file lacks write permissions.
+    pub is_read_only: bool,
 }
 
 #[derive(Default)]
@@ -282,6 +352,17 @@ impl Fs for RealFs {
 
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
+            // Replace the file a symlink points to rather than the link itself, and keep the
+            // permissions of the file being replaced, as the temp file is only readable by us.
+            let (path, permissions) = match std::fs::canonicalize(&path) {
+                Ok(resolved_path) => {
+                    let permissions = std::fs::metadata(&resolved_path)
+                        .ok()
+                        .map(|metadata| metadata.permissions());
+                    (resolved_path, permissions)
+                }
+                Err(_) => (path, None),
+            };
             let mut tmp_file = if cfg!(target_os = "linux") {
                 // Use the directory of the destination as temp dir to avoid
                 // invalid cross-device link error, and XDG_CACHE_DIR for fallback.
@@ -291,6 +372,9 @@ impl Fs for RealFs {
                 NamedTempFile::new()
             }?;
             tmp_file.write_all(data.as_bytes())?;
+            if let Some(permissions) = permissions {
+                tmp_file.as_file().set_permissions(permissions)?;
+            }
             tmp_file.persist(path)?;
             Ok::<(), anyhow::Error>(())
         })
@@ -359,6 +443,7 @@ impl Fs for RealFs {
             mtime: metadata.modified().unwrap(),
             is_symlink,
             is_dir: metadata.file_type().is_dir(),
+            is_read_only: metadata.permissions().readonly(),
         }))
     }
 
@@ -405,7 +490,7 @@ impl Fs for RealFs {
     async fn watch(
         &self,
         path: &Path,
-        _latency: Duration,
+        latency: Duration,
     ) -> Pin<Box<dyn Send + Stream<Item = Vec<PathBuf>>>> {
         use notify::{event::EventKind, Watcher};
         // todo(linux): This spawns two threads, while the macOS impl
@@ -461,7 +546,11 @@ impl Fs for RealFs {
             )
             .expect("Could not start watcher on parent directory");
 
-        Box::pin(rx.chain(futures::stream::once(async move {
+        // Unlike FSEvents, notify reports every change as it happens.
+        let events = debounce_events(rx, move || async move {
+            smol::Timer::after(latency).await;
+        });
+        Box::pin(events.chain(futures::stream::once(async move {
             drop(parent_watcher);
             vec![]
         })))
@@ -540,6 +629,9 @@ struct FakeFsState {
     event_txs: Vec<smol::channel::Sender<Vec<PathBuf>>>,
     events_paused: bool,
     buffered_events: Vec<PathBuf>,
+    failing_writes: HashSet<PathBuf>,
+    /// Directories whose reads wait until they're resumed, with the reads waiting so far.
+    paused_dir_reads: HashMap<PathBuf, Vec<futures::channel::oneshot::Sender<()>>>,
     metadata_call_count: usize,
     read_dir_call_count: usize,
 }
@@ -690,6 +782,8 @@ impl FakeFs {
                 next_inode: 1,
                 event_txs: Default::default(),
                 buffered_events: Vec::new(),
+                failing_writes: Default::default(),
+                paused_dir_reads: Default::default(),
                 events_paused: false,
                 read_dir_call_count: 0,
                 metadata_call_count: 0,
@@ -778,19 +872,54 @@ impl FakeFs {
         self.state.lock().flush_events(count);
     }
 
+    /// Makes saving the file at `path` fail, to simulate errors like a full disk.
+    pub fn fail_writes_to(&self, path: impl AsRef<Path>) {
+        self.state
+            .lock()
+            .failing_writes
+            .insert(normalize_path(path.as_ref()));
+    }
+
+    /// Makes reading the directory at `path` wait until [`Self::resume_dir_reads`] is called, so
+    /// that tests can look at a scan that's still in progress.
+    pub fn pause_dir_reads(&self, path: impl AsRef<Path>) {
+        self.state
+            .lock()
+            .paused_dir_reads
+            .entry(normalize_path(path.as_ref()))
+            .or_default();
+    }
+
+    pub fn resume_dir_reads(&self, path: impl AsRef<Path>) {
+        let waiting = self
+            .state
+            .lock()
+            .paused_dir_reads
+            .remove(&normalize_path(path.as_ref()));
+        for tx in waiting.into_iter().flatten() {
+            tx.send(()).ok();
+        }
+    }
+
     #[must_use]
+    /// Inserts a tree of directories and files in the shape `util::test::temp_tree` accepts,
+    /// including symlinks written as `{ "link": "../target" }`.
     pub fn insert_tree<'a>(
         &'a self,
         path: impl 'a + AsRef<Path> + Send,
         tree: serde_json::Value,
     ) -> futures::future::BoxFuture<'a, ()> {
         use futures::FutureExt as _;
-        use serde_json::Value::*;
+        use serde_json::Value::{self, *};
 
         async move {
             let path = path.as_ref();
 
             match tree {
+                Object(map) if map.len() == 1 && map.get("link").is_some_and(Value::is_string) => {
+                    let target = map["link"].as_str().unwrap();
+                    self.insert_symlink(path, target.into()).await;
+                }
                 Object(map) => {
                     self.create_dir(path).await.unwrap();
                     for (name, contents) in map {
@@ -1316,6 +1445,9 @@ impl Fs for FakeFs {
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
+        if self.state.lock().failing_writes.contains(&path) {
+            return Err(anyhow!("simulated write failure: {}", path.display()));
+        }
         self.write_file_internal(path, data.into_bytes())?;
         Ok(())
     }
@@ -1323,6 +1455,9 @@ impl Fs for FakeFs {
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
+        if self.state.lock().failing_writes.contains(&path) {
+            return Err(anyhow!("simulated write failure: {}", path.display()));
+        }
         let content = chunks(text, line_ending).collect::<String>();
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
@@ -1381,12 +1516,14 @@ impl Fs for FakeFs {
                     mtime: *mtime,
                     is_dir: false,
                     is_symlink,
+                    is_read_only: false,
                 },
                 FakeFsEntry::Dir { inode, mtime, .. } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
                     is_dir: true,
                     is_symlink,
+                    is_read_only: false,
                 },
                 FakeFsEntry::Symlink { .. } => unreachable!(),
             }))
@@ -1417,6 +1554,19 @@ impl Fs for FakeFs {
     ) -> Result<Pin<Box<dyn Send + Stream<Item = Result<PathBuf>>>>> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
+        let resumed = self
+            .state
+            .lock()
+            .paused_dir_reads
+            .get_mut(&path)
+            .map(|waiting| {
+                let (tx, rx) = futures::channel::oneshot::channel();
+                waiting.push(tx);
+                rx
+            });
+        if let Some(resumed) = resumed {
+            resumed.await.ok();
+        }
         let mut state = self.state.lock();
         state.read_dir_call_count += 1;
         let entry = state.read_path(&path)?;
@@ -1439,6 +1589,13 @@ impl Fs for FakeFs {
         self.state.lock().event_txs.push(tx);
         let path = path.to_path_buf();
         let executor = self.executor.clone();
+        let rx = debounce_events(rx, {
+            let executor = executor.clone();
+            move || {
+                let executor = executor.clone();
+                async move { executor.simulate_random_delay().await }
+            }
+        });
         Box::pin(futures::StreamExt::filter(rx, move |events| {
             let result = events.iter().any(|evt_path| evt_path.starts_with(&path));
             let executor = executor.clone();
@@ -1601,12 +1758,155 @@ async fn file_id(path: impl AsRef<Path>) -> Result<u64> {
     .await
 }
 
+/// Merges the batches of changed paths that arrive before `window` elapses after a first one
+/// into a single batch, so that a burst of changes, like a formatter rewriting every file, is
+/// reported once.
+///
+/// Each path is listed once. Consumers read the current state of the paths they're given, so a
+/// path that was modified and then removed within the window is seen as removed.
+#[cfg(any(test, feature = "test-support", not(target_os = "macos")))]
+fn debounce_events<S, W>(
+    events: S,
+    window: impl 'static + Send + Fn() -> W,
+) -> impl Send + Stream<Item = Vec<PathBuf>>
+where
+    S: 'static + Send + Stream<Item = Vec<PathBuf>>,
+    W: Send + futures::Future<Output = ()>,
+{
+    use futures::FutureExt as _;
+
+    futures::stream::unfold(
+        (Box::pin(events), window),
+        |(mut events, window)| async move {
+            let mut batch = events.next().await?;
+            window().await;
+            while let Some(Some(paths)) = events.next().now_or_never() {
+                batch.extend(paths);
+            }
+            batch.sort();
+            batch.dedup();
+            Some((batch, (events, window)))
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::FutureExt as _;
     use gpui::BackgroundExecutor;
     use serde_json::json;
 
+    #[gpui::test]
+    async fn test_save_all(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_tree("/root", json!({ "a.txt": "old a" })).await;
+        let writes = || {
+            ["a.txt", "b.txt", "c.txt", "d.txt"]
+                .map(|name| {
+                    let path = PathBuf::from("/root").join(name);
+                    (
+                        path,
+                        Rope::from(format!("new {name}").as_str()),
+                        LineEnding::Unix,
+                    )
+                })
+                .to_vec()
+        };
+
+        // A failed write restores the files that were already written.
+        fs.fail_writes_to("/root/c.txt");
+        let outcome = fs.save_all(writes()).await;
+        assert!(outcome.saved.is_empty());
+        assert_eq!(
+            outcome
+                .failed
+                .iter()
+                .map(|(path, _)| path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            ["/root/c.txt", "/root/b.txt", "/root/a.txt", "/root/d.txt"]
+        );
+        assert_eq!(fs.load("/root/a.txt".as_ref()).await.unwrap(), "old a");
+        assert_eq!(fs.files(), [PathBuf::from("/root/a.txt")]);
+
+        fs.state.lock().failing_writes.clear();
+        let outcome = fs.save_all(writes()).await;
+        assert!(outcome.failed.is_empty());
+        assert_eq!(outcome.saved.len(), 4);
+        assert_eq!(fs.load("/root/d.txt".as_ref()).await.unwrap(), "new d.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_real_fs_save_all_keeps_permissions_and_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt as _};
+
+        let dir = TempDir::new().unwrap();
+        let script_path = dir.path().join("script.sh");
+        std::fs::write(&script_path, "old script").unwrap();
+        std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let target_path = dir.path().join("target.txt");
+        std::fs::write(&target_path, "old target").unwrap();
+        let link_path = dir.path().join("link.txt");
+        symlink(&target_path, &link_path).unwrap();
+
+        let fs = RealFs::default();
+        let outcome = futures::executor::block_on(fs.save_all(vec![
+            (
+                script_path.clone(),
+                Rope::from("new script"),
+                LineEnding::Unix,
+            ),
+            (
+                link_path.clone(),
+                Rope::from("new target"),
+                LineEnding::Unix,
+            ),
+        ]));
+        assert!(outcome.failed.is_empty());
+
+        assert_eq!(std::fs::read_to_string(&script_path).unwrap(), "new script");
+        assert_eq!(
+            std::fs::metadata(&script_path)
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777,
+            0o755
+        );
+        assert!(std::fs::symlink_metadata(&link_path)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&target_path).unwrap(), "new target");
+    }
+
+    #[gpui::test]
+    async fn test_debounced_watch_events(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_tree("/root", json!({ "a.rs": "", "b.rs": "" }))
+            .await;
+        let mut events = fs.watch("/root".as_ref(), Duration::ZERO).await;
+
+        for i in 0..5 {
+            fs.insert_file("/root/a.rs", i.to_string().into_bytes())
+                .await;
+        }
+        fs.insert_file("/root/b.rs", b"modified".to_vec()).await;
+        fs.remove_file("/root/b.rs".as_ref(), Default::default())
+            .await
+            .unwrap();
+
+        // The burst is reported as one batch, listing each path once.
+        assert_eq!(
+            events.next().await.unwrap(),
+            [PathBuf::from("/root/a.rs"), PathBuf::from("/root/b.rs")]
+        );
+        executor.run_until_parked();
+        assert!(events.next().now_or_never().is_none());
+        assert!(!fs.is_file("/root/b.rs".as_ref()).await);
+    }
+
     #[gpui::test]
     async fn test_fake_fs(executor: BackgroundExecutor) {
         let fs = FakeFs::new(executor.clone());
@@ -1658,4 +1958,49 @@ mod tests {
             "D",
         );
     }
+
+    #[gpui::test]
+    async fn test_fake_fs_tree_with_symlinks(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "dir1": {
+                    "a": "A",
+                    "link": "a file named link",
+                },
+                "dir2": {
+                    "link-to-a": { "link": "../dir1/a" },
+                    "link-to-dir1": { "link": "../dir1" },
+                    "dangling": { "link": "../missing" },
+                },
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            fs.load("/root/dir1/link".as_ref()).await.unwrap(),
+            "a file named link"
+        );
+        assert_eq!(fs.load("/root/dir2/link-to-a".as_ref()).await.unwrap(), "A");
+        assert_eq!(
+            fs.canonicalize("/root/dir2/link-to-dir1/a".as_ref())
+                .await
+                .unwrap(),
+            PathBuf::from("/root/dir1/a"),
+        );
+        assert_eq!(
+            fs.read_link("/root/dir2/dangling".as_ref()).await.unwrap(),
+            PathBuf::from("../missing"),
+        );
+        assert!(fs
+            .metadata("/root/dir2/dangling".as_ref())
+            .await
+            .unwrap()
+            .is_none());
+
+        // External edits show through the links.
+        fs.insert_file("/root/dir1/a", b"B".to_vec()).await;
+        assert_eq!(fs.load("/root/dir2/link-to-a".as_ref()).await.unwrap(), "B");
+    }
 }
@@ -74,6 +74,8 @@ impl PartialOrd for PathMatch {
     }
 }
 
+/// Better matches are greater. Among matches with the same score that are as close to the
+/// relative path, the shorter path is the more specific one, so it's greater.
 impl Ord for PathMatch {
     fn cmp(&self, other: &Self) -> Ordering {
         self.score
@@ -85,6 +87,13 @@ impl Ord for PathMatch {
                     .distance_to_relative_ancestor
                     .cmp(&self.distance_to_relative_ancestor)
             })
+            .then_with(|| {
+                other
+                    .path
+                    .as_os_str()
+                    .len()
+                    .cmp(&self.path.as_os_str().len())
+            })
             .then_with(|| self.path.cmp(&other.path))
     }
 }
@@ -248,10 +257,40 @@ fn distance_between_paths(path: &Path, relative_to: &Path) -> usize {
 mod tests {
     use std::path::Path;
 
-    use super::distance_between_paths;
+    use super::{distance_between_paths, match_fixed_path_set, PathMatchCandidate};
+    use crate::CharBag;
 
     #[test]
     fn test_distance_between_paths_empty() {
         distance_between_paths(Path::new(""), Path::new(""));
     }
+
+    #[test]
+    fn test_ties_prefer_shorter_paths() {
+        let paths = [
+            Path::new("crates/b/src/main.rs"),
+            Path::new("src/main.rs"),
+            Path::new("crates/a/src/main.rs"),
+        ];
+        let candidates = paths
+            .iter()
+            .map(|path| PathMatchCandidate {
+                path,
+                char_bag: CharBag::from(path.to_str().unwrap()),
+            })
+            .collect();
+
+        let matches = match_fixed_path_set(candidates, 0, "main.rs", false, 10);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|path_match| path_match.path.as_ref())
+                .collect::<Vec<_>>(),
+            [
+                Path::new("src/main.rs"),
+                Path::new("crates/b/src/main.rs"),
+                Path::new("crates/a/src/main.rs"),
+            ]
+        );
+    }
 }
@@ -65,6 +65,18 @@ impl CursorPosition {
         cx.notify();
     }
 
+    /// The text shown for the cursor, like `12:4 (2 selections, 3 lines, 18 characters)`.
+    fn label(&self, cx: &AppContext) -> Option<String> {
+        let position = self.position?;
+        let mut text = format!(
+            "{}{FILE_ROW_COLUMN_DELIMITER}{}",
+            position.row + 1,
+            position.column + 1
+        );
+        self.write_position(&mut text, cx);
+        Some(text)
+    }
+
     fn write_position(&self, text: &mut String, cx: &AppContext) {
         if self.selected_count
             <= (SelectionStats {
@@ -110,14 +122,7 @@ impl CursorPosition {
 
 impl Render for CursorPosition {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        div().when_some(self.position, |el, position| {
-            let mut text = format!(
-                "{}{FILE_ROW_COLUMN_DELIMITER}{}",
-                position.row + 1,
-                position.column + 1
-            );
-            self.write_position(&mut text, cx);
-
+        div().when_some(self.label(cx), |el, text| {
             el.child(
                 Button::new("go-to-line-column", text)
                     .label_size(LabelSize::Small)
@@ -166,10 +171,10 @@ pub(crate) enum LineIndicatorFormat {
     Long,
 }
 
-/// Whether or not to automatically check for updates.
+/// How the selections are summarized next to the cursor position.
 ///
 /// Values: short, long
-/// Default: short
+/// Default: long
 #[derive(Clone, Copy, Default, JsonSchema, Deserialize, Serialize)]
 #[serde(transparent)]
 pub(crate) struct LineIndicatorFormatContent(LineIndicatorFormat);
@@ -191,3 +196,69 @@ impl Settings for LineIndicatorFormat {
         Ok(format.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_cursor_position_label(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\n" }))
+            .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let cursor_position = workspace.update(cx, |workspace, cx| {
+            let cursor_position = cx.new_view(|_| CursorPosition::new(workspace));
+            cursor_position.update(cx, |cursor_position, cx| {
+                cursor_position.set_active_pane_item(Some(&editor as &dyn ItemHandle), cx)
+            });
+            cursor_position
+        });
+        let label = |cx: &mut gpui::VisualTestContext| {
+            cursor_position.update(cx, |cursor_position, cx| cursor_position.label(cx))
+        };
+        assert_eq!(label(cx).as_deref(), Some("1:1"));
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([Point::new(0, 0)..Point::new(2, 3)])
+            })
+        });
+        assert_eq!(label(cx).as_deref(), Some("3:4 (3 lines, 11 characters)"));
+
+        // With several cursors, the newest one's position is shown.
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges([
+                    Point::new(0, 1)..Point::new(0, 1),
+                    Point::new(1, 2)..Point::new(1, 2),
+                ])
+            })
+        });
+        assert_eq!(label(cx).as_deref(), Some("2:3 (2 selections)"));
+    }
+}
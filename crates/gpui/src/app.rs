@@ -641,6 +641,11 @@ impl AppContext {
         self.platform.should_auto_hide_scrollbars()
     }
 
+    /// Returns whether the user has asked the platform to reduce motion in animations.
+    pub fn should_reduce_motion(&self) -> bool {
+        self.platform.should_reduce_motion()
+    }
+
     /// Restart the application.
     pub fn restart(&self) {
         self.platform.restart()
@@ -272,6 +272,11 @@ impl TestAppContext {
         self.test_platform.has_pending_prompt()
     }
 
+    /// Simulates the user toggling the platform's reduced motion accessibility setting.
+    pub fn simulate_reduced_motion(&self, reduce_motion: bool) {
+        self.test_platform.reduce_motion.set(reduce_motion);
+    }
+
     /// All the urls that have been opened with cx.open_url() during this test.
     pub fn opened_url(&self) -> Option<String> {
         self.test_platform.opened_url.borrow().clone()
@@ -426,22 +431,28 @@ impl TestAppContext {
         rx
     }
 
-    /// Retuens a stream of events emitted by the given Model.
+    /// Returns a stream of the events emitted by the given entity, in the order they were
+    /// emitted. Unlike [`Self::notifications`], it carries the events themselves, and it ends
+    /// when the entity is released.
     pub fn events<Evt, T: 'static + EventEmitter<Evt>>(
         &mut self,
-        entity: &Model<T>,
+        entity: &impl Entity<T>,
     ) -> futures::channel::mpsc::UnboundedReceiver<Evt>
     where
         Evt: 'static + Clone,
     {
         let (tx, rx) = futures::channel::mpsc::unbounded();
-        entity
-            .update(self, |_, cx: &mut ModelContext<T>| {
-                cx.subscribe(entity, move |_model, _handle, event, _cx| {
+        self.update(|cx| {
+            cx.subscribe(entity, {
+                let tx = tx.clone();
+                move |_, event, _| {
                     let _ = tx.unbounded_send(event.clone());
-                })
+                }
             })
             .detach();
+            cx.observe_release(entity, move |_, _| tx.close_channel())
+                .detach()
+        });
         rx
     }
 
@@ -972,3 +983,40 @@ impl AnyWindowHandle {
         self.update(cx, |_, cx| cx.new_view(build_view)).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{self as gpui, Context, EventEmitter, TestAppContext};
+    use futures::StreamExt;
+
+    struct Counter(usize);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Incremented(usize);
+
+    impl EventEmitter<Incremented> for Counter {}
+
+    #[gpui::test]
+    async fn test_events(cx: &mut TestAppContext) {
+        let counter = cx.new_model(|_| Counter(0));
+        let mut events = cx.events(&counter);
+        let mut notifications = cx.notifications(&counter);
+
+        for _ in 0..3 {
+            counter.update(cx, |counter, cx| {
+                counter.0 += 1;
+                cx.emit(Incremented(counter.0));
+                cx.notify();
+            });
+        }
+        // Releasing the model ends both streams.
+        drop(counter);
+        cx.update(|_| {});
+
+        assert_eq!(
+            events.by_ref().collect::<Vec<_>>().await,
+            [Incremented(1), Incremented(2), Incremented(3)]
+        );
+        assert_eq!(notifications.by_ref().count().await, 3);
+    }
+}
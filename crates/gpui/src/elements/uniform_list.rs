@@ -95,6 +95,12 @@ impl UniformListScrollHandle {
     pub fn scroll_to_item(&mut self, ix: usize) {
         self.deferred_scroll_to_item.replace(Some(ix));
     }
+
+    /// The item the list will scroll to when it's next laid out, if any.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn scroll_to_item_pending(&self) -> Option<usize> {
+        *self.deferred_scroll_to_item.borrow()
+    }
 }
 
 impl Styled for UniformList {
@@ -373,6 +373,11 @@ impl<P: LinuxClient + 'static> Platform for P {
         false
     }
 
+    // todo(linux)
+    fn should_reduce_motion(&self) -> bool {
+        false
+    }
+
     fn write_credentials(&self, url: &str, username: &str, password: &[u8]) -> Task<Result<()>> {
         let url = url.to_string();
         let username = username.to_string();
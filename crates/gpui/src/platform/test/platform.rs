@@ -8,7 +8,7 @@ use collections::VecDeque;
 use futures::channel::oneshot;
 use parking_lot::Mutex;
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     path::{Path, PathBuf},
     rc::{Rc, Weak},
     sync::Arc,
@@ -26,6 +26,7 @@ pub(crate) struct TestPlatform {
     current_primary_item: Mutex<Option<ClipboardItem>>,
     pub(crate) prompts: RefCell<TestPrompts>,
     pub opened_url: RefCell<Option<String>>,
+    pub(crate) reduce_motion: Cell<bool>,
     weak: Weak<Self>,
 }
 
@@ -48,6 +49,7 @@ impl TestPlatform {
             current_primary_item: Mutex::new(None),
             weak: weak.clone(),
             opened_url: Default::default(),
+            reduce_motion: Cell::new(false),
         })
     }
 
@@ -272,6 +274,10 @@ impl Platform for TestPlatform {
         false
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        self.reduce_motion.get()
+    }
+
     fn write_to_primary(&self, item: ClipboardItem) {
         *self.current_primary_item.lock() = Some(item);
     }
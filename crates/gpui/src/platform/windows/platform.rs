@@ -678,6 +678,11 @@ impl Platform for WindowsPlatform {
         false
     }
 
+    // todo(windows)
+    fn should_reduce_motion(&self) -> bool {
+        false
+    }
+
     fn write_to_primary(&self, _item: ClipboardItem) {}
 
     fn write_to_clipboard(&self, item: ClipboardItem) {
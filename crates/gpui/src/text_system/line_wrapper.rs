@@ -34,6 +34,17 @@ impl LineWrapper {
         &'a mut self,
         line: &'a str,
         wrap_width: Pixels,
+    ) -> impl Iterator<Item = Boundary> + 'a {
+        self.wrap_line_with_indent(line, wrap_width, Some(0))
+    }
+
+    /// Wrap a line of text like [`Self::wrap_line`], indenting the lines it continues on by the
+    /// line's own indentation plus `extra_indent`, or not at all if it's `None`.
+    pub fn wrap_line_with_indent<'a>(
+        &'a mut self,
+        line: &'a str,
+        wrap_width: Pixels,
+        extra_indent: Option<u32>,
     ) -> impl Iterator<Item = Boundary> + 'a {
         let mut width = px(0.);
         let mut first_non_whitespace_ix = None;
@@ -63,9 +74,10 @@ impl LineWrapper {
                 if width > wrap_width && ix > last_wrap_ix {
                     if let (None, Some(first_non_whitespace_ix)) = (indent, first_non_whitespace_ix)
                     {
-                        indent = Some(
-                            Self::MAX_INDENT.min((first_non_whitespace_ix - last_wrap_ix) as u32),
-                        );
+                        indent = Some(extra_indent.map_or(0, |extra_indent| {
+                            Self::MAX_INDENT
+                                .min((first_non_whitespace_ix - last_wrap_ix) as u32 + extra_indent)
+                        }));
                     }
 
                     if last_candidate_ix > 0 {
@@ -209,6 +221,18 @@ mod tests {
                     Boundary::new(22, 3),
                 ]
             );
+            assert_eq!(
+                wrapper
+                    .wrap_line_with_indent("  aaa bbb ccc", px(72.), Some(1))
+                    .collect::<Vec<_>>(),
+                &[Boundary::new(6, 3), Boundary::new(10, 3)]
+            );
+            assert_eq!(
+                wrapper
+                    .wrap_line_with_indent("  aaa bbb ccc", px(72.), None)
+                    .collect::<Vec<_>>(),
+                &[Boundary::new(6, 0)]
+            );
         });
     }
 
@@ -22,6 +22,8 @@ use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLab
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
 use smallvec::SmallVec;
 use smol::future::yield_now;
@@ -150,7 +152,8 @@ pub enum IndentKind {
 }
 
 /// The shape of a selection cursor.
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum CursorShape {
     /// A vertical bar
     #[default]
@@ -158,6 +161,7 @@ pub enum CursorShape {
     /// A block that surrounds the following character
     Block,
     /// An underline that runs along the following character
+    #[serde(rename = "underline")]
     Underscore,
     /// A box drawn around the following character
     Hollow,
@@ -794,6 +798,7 @@ impl Buffer {
             };
 
             let new_text = new_text.await?;
+            let mixed_line_endings = LineEnding::is_mixed(&new_text);
             let diff = this
                 .update(&mut cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -804,6 +809,8 @@ impl Buffer {
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
+                    this.text.set_mixed_line_endings(mixed_line_endings);
+                    cx.notify();
                 } else {
                     if !diff.edits.is_empty()
                         || this
@@ -886,6 +893,12 @@ impl Buffer {
         }
     }
 
+    /// Sets the line ending the buffer's text is saved with.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut ModelContext<Self>) {
+        self.text.set_line_ending(line_ending);
+        cx.notify();
+    }
+
     /// Returns the current diff base, see [Buffer::set_diff_base].
     pub fn diff_base(&self) -> Option<&Rope> {
         self.diff_base.as_ref()
@@ -1472,6 +1485,26 @@ impl Buffer {
         self.edit([(offset..len, "\n")], None, cx);
     }
 
+    /// Removes all the newlines, and any other whitespace, at the end of the
+    /// buffer.
+    pub fn trim_final_newlines(&mut self, cx: &mut ModelContext<Self>) {
+        let len = self.len();
+        let mut offset = len;
+        for chunk in self.as_rope().reversed_chunks_in_range(0..len) {
+            let non_whitespace_len = chunk
+                .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+            offset -= chunk.len();
+            offset += non_whitespace_len;
+            if non_whitespace_len != 0 {
+                break;
+            }
+        }
+        if offset < len {
+            self.edit([(offset..len, "")], None, cx);
+        }
+    }
+
     /// Applies a diff to the buffer. If the buffer has changed since the given diff was
     /// calculated, then adjust the diff to account for those changes, and discard any
     /// parts of the diff that conflict with those changes.
@@ -177,6 +177,103 @@ async fn test_first_line_pattern(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_language_for_content(cx: &mut TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+    for (name, suffix, pattern) in [
+        ("Shell Script", "sh", Some(r"^#!.*\bsh\b")),
+        ("Python", "py", Some(r"^#!.*\bpython[0-9.]*\b")),
+        ("Perl", "pl", Some(r"^#!.*\bperl\b")),
+        ("Prolog", "pl", None),
+    ] {
+        languages.register_test_language(LanguageConfig {
+            name: name.into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec![suffix.into()],
+                first_line_pattern: pattern.map(|pattern| Regex::new(pattern).unwrap()),
+            },
+            ..Default::default()
+        });
+    }
+
+    let language_for_content = |first_line: &'static str, path: &'static str| {
+        let languages = languages.clone();
+        async move {
+            languages
+                .language_for_content(first_line, Path::new(path))
+                .await
+                .ok()
+                .map(|language| language.name().to_string())
+        }
+    };
+
+    // Without an extension, the shebang decides.
+    assert_eq!(
+        language_for_content("#!/usr/bin/env python3", "bin/script").await,
+        Some("Python".into())
+    );
+    // Interpreter names are matched case-sensitively.
+    assert_eq!(
+        language_for_content("#!/usr/bin/env Python3", "bin/script").await,
+        None
+    );
+    // An extension claimed by a single language wins over the shebang.
+    assert_eq!(
+        language_for_content("#!/usr/bin/env python3", "bin/run.sh").await,
+        Some("Shell Script".into())
+    );
+    // The shebang picks between the languages claiming an extension.
+    assert_eq!(
+        language_for_content("#!/usr/bin/perl", "lib/tool.pl").await,
+        Some("Perl".into())
+    );
+}
+
+#[gpui::test]
+async fn test_register_languages_from_dir(cx: &mut TestAppContext) {
+    let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+    let dir = tempfile::tempdir().unwrap();
+    let write_bundle = |bundle: &str, config: &str| {
+        std::fs::create_dir_all(dir.path().join(bundle)).unwrap();
+        std::fs::write(dir.path().join(bundle).join("config.toml"), config).unwrap();
+    };
+    write_bundle("scheme", "name = \"Scheme\"\npath_suffixes = [\"scm\"]");
+    write_bundle("broken", "name = ");
+    std::fs::write(
+        dir.path().join("scheme/highlights.scm"),
+        "(comment) @comment",
+    )
+    .unwrap();
+
+    let mut results = languages.register_from_dir(dir.path()).unwrap();
+    results.sort_by_key(|result| result.is_ok());
+    assert!(results[0].is_err());
+    assert_eq!(results[1].as_ref().unwrap().as_ref(), "Scheme");
+
+    let language = languages
+        .language_for_file_path(Path::new("src/lib.scm"))
+        .await
+        .unwrap();
+    assert_eq!(language.name().as_ref(), "Scheme");
+
+    // Registering a bundle again replaces the loaded language, and tells observers to reload.
+    let reload_count = languages.reload_count();
+    write_bundle("scheme", "name = \"Scheme\"\npath_suffixes = [\"ss\"]");
+    languages.register_from_dir(dir.path()).unwrap();
+    assert_eq!(languages.reload_count(), reload_count + 1);
+    languages
+        .language_for_file_path(Path::new("src/lib.scm"))
+        .await
+        .unwrap_err();
+    let reloaded_language = languages
+        .language_for_file_path(Path::new("src/lib.ss"))
+        .await
+        .unwrap();
+    assert!(!Arc::ptr_eq(&reloaded_language, &language));
+}
+
 #[gpui::test]
 async fn test_language_for_file_with_custom_file_types(cx: &mut TestAppContext) {
     cx.update(|cx| {
@@ -2218,7 +2315,7 @@ fn test_random_collaboration(cx: &mut AppContext, mut rng: StdRng) {
                     replica_ids.push(new_replica_id);
                 } else {
                     let new_buffer = new_buffer.take().unwrap();
-                    while network.lock().has_unreceived(new_replica_id) {
+                    while network.lock().has_deliverable(new_replica_id) {
                         let ops = network
                             .lock()
                             .receive(new_replica_id)
@@ -2247,7 +2344,7 @@ fn test_random_collaboration(cx: &mut AppContext, mut rng: StdRng) {
                 });
                 mutation_count -= 1;
             }
-            _ if network.lock().has_unreceived(replica_id) => {
+            _ if network.lock().has_deliverable(replica_id) => {
                 let ops = network
                     .lock()
                     .receive(replica_id)
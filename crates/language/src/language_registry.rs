@@ -333,6 +333,14 @@ impl LanguageRegistry {
                 existing_language.grammar = grammar_name;
                 existing_language.matcher = matcher;
                 existing_language.load = load;
+                // Drop the previous definition, so that buffers using it load the new one.
+                if existing_language.loaded {
+                    existing_language.loaded = false;
+                    state.languages.retain(|language| language.name() != name);
+                }
+                state.version += 1;
+                state.reload_count += 1;
+                *state.subscription.0.borrow_mut() = ();
                 return;
             }
         }
@@ -350,6 +358,54 @@ impl LanguageRegistry {
         *state.subscription.0.borrow_mut() = ();
     }
 
+    /// Registers a language for each directory in `dir` holding a language bundle: a
+    /// `config.toml`, the queries in `.scm` files, and optionally the WASM grammar the config
+    /// names, as `<grammar>.wasm`.
+    ///
+    /// Returns the outcome for each bundle, as one that fails to load doesn't stop the others
+    /// from being registered. Languages that were already registered are replaced.
+    pub fn register_from_dir(&self, dir: &Path) -> Result<Vec<Result<Arc<str>>>> {
+        let mut results = Vec::new();
+        for entry in std::fs::read_dir(dir)
+            .with_context(|| format!("reading language bundles in {}", dir.display()))?
+        {
+            let bundle_path = match entry {
+                Ok(entry) if entry.path().is_dir() => entry.path(),
+                Ok(_) => continue,
+                Err(error) => {
+                    results.push(Err(error.into()));
+                    continue;
+                }
+            };
+            results.push(
+                self.register_bundle(bundle_path.clone())
+                    .with_context(|| format!("loading language bundle {}", bundle_path.display())),
+            );
+        }
+        Ok(results)
+    }
+
+    fn register_bundle(&self, bundle_path: PathBuf) -> Result<Arc<str>> {
+        let config = load_bundle_config(&bundle_path)?;
+        let name = config.name.clone();
+        if let Some(grammar) = &config.grammar {
+            let grammar_path = bundle_path.join(grammar.as_ref()).with_extension("wasm");
+            if grammar_path.is_file() {
+                self.register_wasm_grammars([(grammar.clone(), grammar_path)]);
+            }
+        }
+        self.register_language(
+            name.clone(),
+            config.grammar.clone(),
+            config.matcher.clone(),
+            move || {
+                let config = load_bundle_config(&bundle_path)?;
+                Ok((config, load_plugin_queries(&bundle_path), None))
+            },
+        );
+        Ok(name)
+    }
+
     /// Adds grammars to the registry. Language configurations reference a grammar by name. The
     /// grammar controls how the source code is parsed.
     pub fn register_native_grammars(
@@ -500,6 +556,20 @@ impl LanguageRegistry {
             .map_err(|error| error.context(format!("language for file path {}", path.display())))
     }
 
+    /// Returns the language for a file at `path` whose first line is `first_line`.
+    ///
+    /// The file's extension or name decides the language, unless it has none or several languages
+    /// claim it. Then a `first_line_pattern`, such as one matching a shebang's interpreter, picks
+    /// between them.
+    pub fn language_for_content<'a>(
+        self: &Arc<Self>,
+        first_line: &str,
+        path: &'a Path,
+    ) -> impl Future<Output = Result<Arc<Language>>> + 'a {
+        self.language_for_file_internal(path, Some(&Rope::from(first_line)), None)
+            .map_err(|error| error.context(format!("language for file path {}", path.display())))
+    }
+
     fn language_for_file_internal(
         self: &Arc<Self>,
         path: &Path,
@@ -530,9 +600,15 @@ impl LanguageRegistry {
                     pattern.is_match(&text)
                 },
             );
+            // A language claiming the path wins over any that only matches the content, which
+            // breaks ties between the languages claiming the same path.
             if path_matches_custom_suffix {
+                4
+            } else if path_matches_default_suffix && content_matches {
+                3
+            } else if path_matches_default_suffix {
                 2
-            } else if path_matches_default_suffix || content_matches {
+            } else if content_matches {
                 1
             } else {
                 0
@@ -956,6 +1032,41 @@ impl LanguageRegistryState {
     }
 }
 
+fn load_bundle_config(bundle_path: &Path) -> Result<LanguageConfig> {
+    let config = std::fs::read_to_string(bundle_path.join("config.toml"))?;
+    Ok(::toml::from_str(&config)?)
+}
+
+/// Loads the queries in the `.scm` files of a language's directory, by their file name prefixes.
+pub fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
+    let mut result = LanguageQueries::default();
+    if let Some(entries) = std::fs::read_dir(root_path).log_err() {
+        for entry in entries {
+            let Some(entry) = entry.log_err() else {
+                continue;
+            };
+            let path = entry.path();
+            if let Some(remainder) = path.strip_prefix(root_path).ok().and_then(|p| p.to_str()) {
+                if !remainder.ends_with(".scm") {
+                    continue;
+                }
+                for (name, query) in QUERY_FILENAME_PREFIXES {
+                    if remainder.starts_with(name) {
+                        if let Some(contents) = std::fs::read_to_string(&path).log_err() {
+                            match query(&mut result) {
+                                None => *query(&mut result) = Some(contents.into()),
+                                Some(r) => r.to_mut().push_str(contents.as_ref()),
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
 impl LspBinaryStatusSender {
     fn subscribe(
         &self,
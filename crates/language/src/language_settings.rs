@@ -68,6 +68,11 @@ pub struct LanguageSettings {
     pub hard_tabs: bool,
     /// How to soft-wrap long lines of text.
     pub soft_wrap: SoftWrap,
+    /// Whether to indent soft-wrapped lines to match the line they continue.
+    pub soft_wrap_indent: bool,
+    /// How many more columns than the line they continue to indent soft-wrapped
+    /// lines by, when `soft_wrap_indent` is enabled.
+    pub soft_wrap_extra_indent: u32,
     /// The column at which to soft-wrap lines, for buffers where soft-wrap
     /// is enabled.
     pub preferred_line_length: u32,
@@ -83,9 +88,8 @@ pub struct LanguageSettings {
     /// Whether or not to remove any trailing whitespace from lines of a buffer
     /// before saving it.
     pub remove_trailing_whitespace_on_save: bool,
-    /// Whether or not to ensure there's a single newline at the end of a buffer
-    /// when saving it.
-    pub ensure_final_newline_on_save: bool,
+    /// How to handle the newlines at the end of a buffer when saving it.
+    pub final_newline_on_save: FinalNewlineOnSave,
     /// How to perform a buffer format.
     pub formatter: Formatter,
     /// Zed's Prettier integration settings.
@@ -116,6 +120,9 @@ pub struct LanguageSettings {
     pub always_treat_brackets_as_autoclosed: bool,
     /// Which code actions to run on save
     pub code_actions_on_format: HashMap<String, bool>,
+    /// The naming conventions relating files to each other, as pairs of
+    /// patterns where `*` stands for the part of the name they share.
+    pub related_files: Vec<(String, String)>,
 }
 
 impl LanguageSettings {
@@ -225,6 +232,17 @@ pub struct LanguageSettingsContent {
     /// Default: none
     #[serde(default)]
     pub soft_wrap: Option<SoftWrap>,
+    /// Whether to indent soft-wrapped lines to match the line they continue.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub soft_wrap_indent: Option<bool>,
+    /// How many more columns than the line they continue to indent soft-wrapped
+    /// lines by, when `soft_wrap_indent` is enabled.
+    ///
+    /// Default: 0
+    #[serde(default)]
+    pub soft_wrap_extra_indent: Option<u32>,
     /// The column at which to soft-wrap lines, for buffers where soft-wrap
     /// is enabled.
     ///
@@ -259,8 +277,16 @@ pub struct LanguageSettingsContent {
     /// when saving it.
     ///
     /// Default: true
+    ///
+    /// Deprecated: use `final_newline_on_save` instead, which takes precedence
+    /// over this setting.
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// How to handle the newlines at the end of a buffer when saving it.
+    ///
+    /// Default: ensure_single
+    #[serde(default)]
+    pub final_newline_on_save: Option<FinalNewlineOnSave>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -323,6 +349,12 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
     pub code_actions_on_format: Option<HashMap<String, bool>>,
+    /// The naming conventions `editor::SwitchToRelatedFile` follows, as pairs
+    /// of file name patterns where `*` stands for the part of the name the
+    /// related files share, like `["*.c", "*.h"]`.
+    ///
+    /// Default: [] (or [["*.c", "*.h"], ["*.cpp", "*.h"], ...] for C and C++).
+    pub related_files: Option<Vec<(String, String)>>,
 }
 
 /// The contents of the inline completion settings.
@@ -378,6 +410,18 @@ pub enum FormatOnSave {
     CodeActions(HashMap<String, bool>),
 }
 
+/// Controls how the newlines at the end of a buffer are handled when it's saved.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalNewlineOnSave {
+    /// The buffer should end with exactly one newline, and no other whitespace.
+    EnsureSingle,
+    /// The buffer should end without any newlines or other whitespace.
+    Trim,
+    /// The end of the buffer should be saved as it is.
+    LeaveUnchanged,
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -388,6 +432,8 @@ pub enum ShowWhitespaceSetting {
     None,
     /// Draw all invisible symbols.
     All,
+    /// Draw only the whitespace at the start and end of lines.
+    Boundary,
 }
 
 /// Controls which formatter should be used when formatting code.
@@ -707,6 +753,11 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
     merge(&mut settings.tab_size, src.tab_size);
     merge(&mut settings.hard_tabs, src.hard_tabs);
     merge(&mut settings.soft_wrap, src.soft_wrap);
+    merge(&mut settings.soft_wrap_indent, src.soft_wrap_indent);
+    merge(
+        &mut settings.soft_wrap_extra_indent,
+        src.soft_wrap_extra_indent,
+    );
     merge(&mut settings.use_autoclose, src.use_autoclose);
     merge(
         &mut settings.always_treat_brackets_as_autoclosed,
@@ -718,6 +769,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.code_actions_on_format,
         src.code_actions_on_format.clone(),
     );
+    merge(&mut settings.related_files, src.related_files.clone());
 
     merge(
         &mut settings.preferred_line_length,
@@ -731,8 +783,18 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.remove_trailing_whitespace_on_save,
     );
     merge(
-        &mut settings.ensure_final_newline_on_save,
-        src.ensure_final_newline_on_save,
+        &mut settings.final_newline_on_save,
+        src.ensure_final_newline_on_save.map(|ensure| {
+            if ensure {
+                FinalNewlineOnSave::EnsureSingle
+            } else {
+                FinalNewlineOnSave::LeaveUnchanged
+            }
+        }),
+    );
+    merge(
+        &mut settings.final_newline_on_save,
+        src.final_newline_on_save,
     );
     merge(
         &mut settings.enable_language_server,
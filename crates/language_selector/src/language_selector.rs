@@ -143,7 +143,7 @@ impl PickerDelegate for LanguageSelectorDelegate {
                     .upgrade()
                     .ok_or_else(|| anyhow!("buffer was dropped"))?;
                 project.update(&mut cx, |project, cx| {
-                    project.set_language_for_buffer(&buffer, language, cx);
+                    project.select_language_for_buffer(&buffer, language, cx);
                 })
             })
             .detach_and_log_err(cx);
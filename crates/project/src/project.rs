@@ -40,7 +40,9 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
+    language_settings::{
+        language_settings, FinalNewlineOnSave, FormatOnSave, Formatter, InlayHintKind,
+    },
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
         deserialize_anchor, deserialize_line_ending, deserialize_version, serialize_anchor,
@@ -64,7 +66,7 @@ use node_runtime::NodeRuntime;
 use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
-use project_settings::{LspSettings, ProjectSettings};
+use project_settings::{EnvironmentSettings, LspSettings, ProjectSettings};
 use rand::prelude::*;
 use search_history::SearchHistory;
 use worktree::LocalSnapshot;
@@ -124,6 +126,8 @@ const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
 const SERVER_REINSTALL_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
+/// How long a language server is given to update references before a file is renamed anyway.
+const WILL_RENAME_FILES_TIMEOUT: Duration = Duration::from_secs(5);
 
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
 
@@ -179,6 +183,8 @@ pub struct Project {
     _subscriptions: Vec<gpui::Subscription>,
     loading_buffers: HashMap<BufferId, Vec<oneshot::Sender<Result<Model<Buffer>, anyhow::Error>>>>,
     incomplete_remote_buffers: HashMap<BufferId, Model<Buffer>>,
+    /// Languages the user chose for buffers, which detection mustn't replace.
+    selected_buffer_languages: HashMap<BufferId, SelectedLanguage>,
     shared_buffers: HashMap<proto::PeerId, HashSet<BufferId>>,
     #[allow(clippy::type_complexity)]
     loading_buffers_by_path: HashMap<
@@ -220,6 +226,14 @@ struct LspBufferSnapshot {
     snapshot: TextBufferSnapshot,
 }
 
+/// A language the user chose for a buffer, by name so that it can be looked up again after the
+/// language is reloaded.
+struct SelectedLanguage {
+    name: Arc<str>,
+    /// The path of the buffer's file when the language was chosen.
+    path: Option<PathBuf>,
+}
+
 /// Message ordered with respect to buffer operations
 #[derive(Debug)]
 enum BufferOrderedMessage {
@@ -701,6 +715,7 @@ impl Project {
                 client_state: ProjectClientState::Local,
                 loading_buffers: HashMap::default(),
                 incomplete_remote_buffers: HashMap::default(),
+                selected_buffer_languages: HashMap::default(),
                 client_subscriptions: Vec::new(),
                 _subscriptions: vec![
                     cx.observe_global::<SettingsStore>(Self::on_settings_changed),
@@ -825,6 +840,7 @@ impl Project {
                 loading_buffers: Default::default(),
                 shared_buffers: Default::default(),
                 incomplete_remote_buffers: Default::default(),
+                selected_buffer_languages: Default::default(),
                 loading_local_worktrees: Default::default(),
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
@@ -1454,11 +1470,104 @@ impl Project {
         };
         let new_path = new_path.into();
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .rename_entry(entry_id, new_path, cx)
+            let local_worktree = worktree.read(cx).as_local().unwrap();
+            let Some(old_entry) = local_worktree.entry_for_id(entry_id) else {
+                return Task::ready(Ok(None));
+            };
+            let is_dir = old_entry.is_dir();
+            let (Ok(old_abs_path), Ok(new_abs_path)) = (
+                local_worktree.absolutize(&old_entry.path),
+                local_worktree.absolutize(&new_path),
+            ) else {
+                return Task::ready(Ok(None));
+            };
+            // Language servers can ask to hear about renames, and update the references to the
+            // renamed file before it moves.
+            let language_servers = self
+                .language_servers_for_worktree(worktree.read(cx).id())
+                .map(|(adapter, _, server)| (adapter.clone(), server.clone()))
+                .collect::<Vec<_>>();
+            let rename_params = lsp::RenameFilesParams {
+                files: vec![lsp::FileRename {
+                    old_uri: lsp::Url::from_file_path(&old_abs_path)
+                        .map(String::from)
+                        .unwrap_or_default(),
+                    new_uri: lsp::Url::from_file_path(&new_abs_path)
+                        .map(String::from)
+                        .unwrap_or_default(),
+                }],
+            };
+
+            cx.spawn(move |this, mut cx| async move {
+                for (adapter, server) in &language_servers {
+                    let filters = server
+                        .capabilities()
+                        .workspace
+                        .as_ref()
+                        .and_then(|workspace| workspace.file_operations.as_ref())
+                        .and_then(|operations| operations.will_rename.as_ref());
+                    if !filters.map_or(false, |options| {
+                        file_operation_filters_match(&options.filters, &old_abs_path, is_dir)
+                    }) {
+                        continue;
+                    }
+                    let request = server
+                        .request::<lsp::request::WillRenameFiles>(rename_params.clone())
+                        .fuse();
+                    futures::pin_mut!(request);
+                    let mut timeout = cx
+                        .background_executor()
+                        .timer(WILL_RENAME_FILES_TIMEOUT)
+                        .fuse();
+                    let edit = select! {
+                        edit = request => edit.log_err().flatten(),
+                        _ = timeout => {
+                            log::warn!(
+                                "timeout waiting for language server {} to handle a file rename",
+                                server.name()
+                            );
+                            None
+                        },
+                    };
+                    if let Some((edit, this)) = edit.zip(this.upgrade()) {
+                        Self::deserialize_workspace_edit(
+                            this,
+                            edit,
+                            true,
+                            adapter.clone(),
+                            server.clone(),
+                            &mut cx,
+                        )
+                        .await
+                        .log_err();
+                    }
+                }
+
+                let entry = worktree
+                    .update(&mut cx, |worktree, cx| {
+                        worktree
+                            .as_local_mut()
+                            .unwrap()
+                            .rename_entry(entry_id, new_path, cx)
+                    })?
+                    .await?;
+
+                for (_, server) in &language_servers {
+                    let filters = server
+                        .capabilities()
+                        .workspace
+                        .as_ref()
+                        .and_then(|workspace| workspace.file_operations.as_ref())
+                        .and_then(|operations| operations.did_rename.as_ref());
+                    if filters.map_or(false, |options| {
+                        file_operation_filters_match(&options.filters, &old_abs_path, is_dir)
+                    }) {
+                        server
+                            .notify::<lsp::notification::DidRenameFiles>(rename_params.clone())
+                            .log_err();
+                    }
+                }
+                Ok(entry)
             })
         } else {
             let client = self.client.clone();
@@ -2331,6 +2440,7 @@ impl Project {
         self.register_buffer_with_language_servers(buffer, cx);
         // self.register_buffer_with_copilot(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.selected_buffer_languages.remove(&buffer.remote_id());
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -3030,6 +3140,40 @@ impl Project {
     ) {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let path = buffer.file().map(|file| file.full_path(cx));
+        if let Some(selected) = self.selected_buffer_languages.get(&buffer_id) {
+            // A choice made for one file doesn't carry over to the buffer being saved as another.
+            if selected.path != path {
+                self.selected_buffer_languages.remove(&buffer_id);
+            } else {
+                // Look the language up again, in case it was reloaded since it was chosen.
+                let mut language = self.languages.language_for_name(&selected.name).boxed();
+                match (&mut language).now_or_never() {
+                    Some(Ok(language)) => {
+                        self.set_language_for_buffer(buffer_handle, language, cx);
+                        return;
+                    }
+                    Some(Err(_)) => {
+                        self.selected_buffer_languages.remove(&buffer_id);
+                    }
+                    None => {
+                        let buffer = buffer_handle.downgrade();
+                        cx.spawn(|this, mut cx| async move {
+                            let language = language.await?;
+                            this.update(&mut cx, |this, cx| {
+                                if let Some(buffer) = buffer.upgrade() {
+                                    this.set_language_for_buffer(&buffer, language, cx);
+                                }
+                            })
+                        })
+                        .detach_and_log_err(cx);
+                        return;
+                    }
+                }
+            }
+        }
+        let buffer = buffer_handle.read(cx);
         let Some(file) = buffer.file() else {
             return;
         };
@@ -3054,6 +3198,24 @@ impl Project {
         };
     }
 
+    /// Sets the language of the buffer to one the user chose, keeping it for as long as the
+    /// buffer is open at the same path instead of detecting the language again.
+    pub fn select_language_for_buffer(
+        &mut self,
+        buffer: &Model<Buffer>,
+        language: Arc<Language>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_snapshot = buffer.read(cx);
+        let selected = SelectedLanguage {
+            name: language.name(),
+            path: buffer_snapshot.file().map(|file| file.full_path(cx)),
+        };
+        self.selected_buffer_languages
+            .insert(buffer_snapshot.remote_id(), selected);
+        self.set_language_for_buffer(buffer, language, cx);
+    }
+
     pub fn set_language_for_buffer(
         &mut self,
         buffer: &Model<Buffer>,
@@ -4739,7 +4901,7 @@ impl Project {
             })?;
 
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
-            let ensure_final_newline = settings.ensure_final_newline_on_save;
+            let final_newline = settings.final_newline_on_save;
             let tab_size = settings.tab_size;
 
             // First, format buffer's whitespace according to the settings.
@@ -4758,8 +4920,10 @@ impl Project {
                 if let Some(diff) = trailing_whitespace_diff {
                     buffer.apply_diff(diff, cx);
                 }
-                if ensure_final_newline {
-                    buffer.ensure_final_newline(cx);
+                match final_newline {
+                    FinalNewlineOnSave::EnsureSingle => buffer.ensure_final_newline(cx),
+                    FinalNewlineOnSave::Trim => buffer.trim_final_newlines(cx),
+                    FinalNewlineOnSave::LeaveUnchanged => {}
                 }
                 buffer.end_transaction(cx)
             })?;
@@ -10586,30 +10750,43 @@ struct ProjectLspAdapterDelegate {
     fs: Arc<dyn Fs>,
     http_client: Arc<dyn HttpClient>,
     language_registry: Arc<LanguageRegistry>,
+    environment: EnvironmentSettings,
     shell_env: Mutex<Option<HashMap<String, String>>>,
 }
 
 impl ProjectLspAdapterDelegate {
     fn new(project: &Project, worktree: &Model<Worktree>, cx: &ModelContext<Project>) -> Arc<Self> {
+        let worktree = worktree.read(cx);
+        let environment = ProjectSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree.id().to_usize(),
+                path: Path::new(""),
+            }),
+            cx,
+        )
+        .environment
+        .clone();
         Arc::new(Self {
             project: cx.weak_model(),
-            worktree: worktree.read(cx).snapshot(),
+            worktree: worktree.snapshot(),
             fs: project.fs.clone(),
             http_client: project.client.http_client(),
             language_registry: project.languages.clone(),
+            environment,
             shell_env: Default::default(),
         })
     }
 
     async fn load_shell_env(&self) {
         let worktree_abs_path = self.worktree.abs_path();
-        let shell_env = load_shell_environment(&worktree_abs_path)
+        let mut shell_env = load_shell_environment(&worktree_abs_path)
             .await
             .with_context(|| {
                 format!("failed to determine load login shell environment in {worktree_abs_path:?}")
             })
             .log_err()
             .unwrap_or_default();
+        self.environment.apply(&mut shell_env);
         *self.shell_env.lock() = Some(shell_env);
     }
 }
@@ -10794,6 +10971,39 @@ async fn wait_for_loading_buffer(
     }
 }
 
+/// Whether a file operation on `path` is one that a language server registered for.
+fn file_operation_filters_match(
+    filters: &[lsp::FileOperationFilter],
+    path: &Path,
+    is_dir: bool,
+) -> bool {
+    filters.iter().any(|filter| {
+        if filter
+            .scheme
+            .as_deref()
+            .map_or(false, |scheme| scheme != "file")
+        {
+            return false;
+        }
+        let pattern = &filter.pattern;
+        match pattern.matches {
+            Some(lsp::FileOperationPatternKind::File) if is_dir => return false,
+            Some(lsp::FileOperationPatternKind::Folder) if !is_dir => return false,
+            _ => {}
+        }
+        let ignore_case = pattern
+            .options
+            .as_ref()
+            .and_then(|options| options.ignore_case)
+            .unwrap_or(false);
+        globset::GlobBuilder::new(&pattern.glob)
+            .case_insensitive(ignore_case)
+            .literal_separator(true)
+            .build()
+            .map_or(false, |glob| glob.compile_matcher().is_match(path))
+    })
+}
+
 fn include_text(server: &lsp::LanguageServer) -> bool {
     server
         .capabilities()
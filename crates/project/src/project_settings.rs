@@ -3,7 +3,8 @@ use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
-use std::{sync::Arc, time::Duration};
+use std::{ffi::OsString, path::PathBuf, sync::Arc, time::Duration};
+use util::ResultExt;
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSettings {
@@ -20,6 +21,53 @@ pub struct ProjectSettings {
     /// Configuration for Git-related features
     #[serde(default)]
     pub git: GitSettings,
+
+    /// Environment to set for processes spawned within the project,
+    /// such as terminals, tasks and language servers.
+    #[serde(default)]
+    pub environment: EnvironmentSettings,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EnvironmentSettings {
+    /// Environment variables to set, overriding inherited variables of the same name.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Directories to prepend to the inherited `PATH`.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub path: Vec<PathBuf>,
+}
+
+impl EnvironmentSettings {
+    /// Layers the configured environment over `env`. When `env` doesn't set a `PATH`,
+    /// the additional directories are prepended to the one Zed was started with.
+    pub fn apply(&self, env: &mut HashMap<String, String>) {
+        env.extend(
+            self.variables
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        if self.path.is_empty() {
+            return;
+        }
+
+        let inherited_path = env
+            .get("PATH")
+            .map(OsString::from)
+            .or_else(|| std::env::var_os("PATH"));
+        let paths = self
+            .path
+            .iter()
+            .cloned()
+            .chain(inherited_path.iter().flat_map(std::env::split_paths));
+        if let Some(path) = std::env::join_paths(paths).log_err() {
+            env.insert("PATH".to_string(), path.to_string_lossy().to_string());
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -331,6 +331,64 @@ impl TaskSource for TestTaskSource {
     }
 }
 
+#[gpui::test]
+async fn test_project_specific_environment(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "environment": {
+                        "variables": { "RUST_LOG": "debug" },
+                        "path": ["/the-root/bin"]
+                    }
+                }"#,
+            },
+            "a": {
+                "a.rs": "fn a() {}"
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    // The environment a task would otherwise inherit.
+    let inherited_env = HashMap::from_iter([
+        ("RUST_LOG".to_string(), "info".to_string()),
+        ("CARGO_TERM_COLOR".to_string(), "always".to_string()),
+        ("PATH".to_string(), "/usr/bin".to_string()),
+    ]);
+
+    project.update(cx, |project, cx| {
+        let mut env = inherited_env.clone();
+        project.apply_environment_settings(Some(Path::new("/the-root/a")), &mut env, cx);
+        assert_eq!(env.get("RUST_LOG").map(String::as_str), Some("debug"));
+        assert_eq!(
+            env.get("CARGO_TERM_COLOR").map(String::as_str),
+            Some("always")
+        );
+        assert_eq!(
+            env.get("PATH").map(std::ffi::OsString::from),
+            std::env::join_paths(["/the-root/bin", "/usr/bin"]).ok()
+        );
+
+        // Tasks without a working directory run in the project root.
+        let mut env = inherited_env.clone();
+        project.apply_environment_settings(None, &mut env, cx);
+        assert_eq!(env.get("RUST_LOG").map(String::as_str), Some("debug"));
+
+        // Processes started outside of the project are left alone.
+        let mut env = inherited_env.clone();
+        project.apply_environment_settings(Some(Path::new("/elsewhere")), &mut env, cx);
+        assert_eq!(env, inherited_env);
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2971,6 +3029,88 @@ async fn test_save_as(cx: &mut gpui::TestAppContext) {
     assert_eq!(opened_buffer, buffer);
 }
 
+#[gpui::test]
+async fn test_selected_language_survives_detection(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "notes.txt": "fn main() {}" }))
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let languages = project.update(cx, |project, _| project.languages().clone());
+    // Registered rather than added, so that they can be loaded again after a reload.
+    languages.register_native_grammars([("rust", tree_sitter_rust::language())]);
+    languages.register_test_language(LanguageConfig {
+        name: "Rust".into(),
+        grammar: Some("rust".into()),
+        matcher: LanguageMatcher {
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    languages.register_test_language(LanguageConfig {
+        name: "JavaScript".into(),
+        matcher: LanguageMatcher {
+            path_suffixes: vec!["js".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/notes.txt", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.language().unwrap().name().as_ref(), "Plain Text");
+        assert_eq!(buffer.snapshot().syntax_layers().count(), 0);
+    });
+
+    // Choosing a language reparses the buffer with its grammar.
+    let rust = languages.language_for_name("Rust").await.unwrap();
+    project.update(cx, |project, cx| {
+        project.select_language_for_buffer(&buffer, rust.clone(), cx)
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        let layers = snapshot.syntax_layers().collect::<Vec<_>>();
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].language.name().as_ref(), "Rust");
+    });
+
+    // Reloading the languages keeps the chosen one, as it's loaded again.
+    languages.reload();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let language = buffer.language().unwrap();
+        assert_eq!(language.name().as_ref(), "Rust");
+        assert!(!Arc::ptr_eq(language, &rust));
+    });
+
+    // Saving under another name detects the language of the new file instead.
+    project
+        .update(cx, |project, cx| {
+            let worktree_id = project.worktrees().next().unwrap().read(cx).id();
+            let path = ProjectPath {
+                worktree_id,
+                path: Arc::from(Path::new("notes.js")),
+            };
+            project.save_buffer_as(buffer.clone(), path, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.language().unwrap().name().as_ref(), "JavaScript");
+    });
+}
+
 #[gpui::test(retries = 5)]
 async fn test_rescan_and_remote_updates(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3876,6 +4016,159 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_rename_file_updates_references(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let rename_options = Some(lsp::FileOperationRegistrationOptions {
+        filters: vec![lsp::FileOperationFilter {
+            scheme: Some("file".into()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".into(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    });
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        will_rename: rename_options.clone(),
+                        did_rename: rename_options,
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let one = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let two = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/two.rs", cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+
+    let mut will_rename =
+        fake_server.handle_request::<lsp::request::WillRenameFiles, _, _>(|params, _| async move {
+            assert_eq!(
+                params.files,
+                [lsp::FileRename {
+                    old_uri: "file:///dir/one.rs".into(),
+                    new_uri: "file:///dir/three.rs".into(),
+                }]
+            );
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [(
+                        lsp::Url::from_file_path("/dir/two.rs").unwrap(),
+                        vec![
+                            lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 19),
+                                    lsp::Position::new(0, 22),
+                                ),
+                                "three".to_string(),
+                            ),
+                            lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 30),
+                                    lsp::Position::new(0, 33),
+                                ),
+                                "three".to_string(),
+                            ),
+                        ],
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        });
+
+    let entry_id = project.update(cx, |project, cx| {
+        let worktree_id = project.worktrees().next().unwrap().read(cx).id();
+        project
+            .entry_for_path(&(worktree_id, "one.rs").into(), cx)
+            .unwrap()
+            .id
+    });
+    let rename = project.update(cx, |project, cx| {
+        project.rename_entry(entry_id, Path::new("three.rs"), cx)
+    });
+    will_rename.next().await.unwrap();
+    rename.await.unwrap().unwrap();
+    cx.executor().run_until_parked();
+
+    // The file is moved, and the server's edits are applied before it hears about the move.
+    assert_eq!(
+        fs.paths(true),
+        vec![
+            PathBuf::from("/"),
+            PathBuf::from("/dir"),
+            PathBuf::from("/dir/three.rs"),
+            PathBuf::from("/dir/two.rs"),
+        ]
+    );
+    one.read_with(cx, |buffer, _| {
+        assert_eq!(
+            buffer.file().unwrap().path().as_ref(),
+            Path::new("three.rs")
+        );
+    });
+    two.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "const TWO: usize = three::ONE + three::ONE;");
+    });
+    let did_rename = fake_server
+        .receive_notification::<lsp::notification::DidRenameFiles>()
+        .await;
+    assert_eq!(did_rename.files[0].new_uri, "file:///dir/three.rs");
+
+    // A server that doesn't respond doesn't hold the rename up for long.
+    let mut will_rename =
+        fake_server.handle_request::<lsp::request::WillRenameFiles, _, _>(|_, _| async move {
+            futures::future::pending::<()>().await;
+            Ok(None)
+        });
+    let rename = project.update(cx, |project, cx| {
+        project.rename_entry(entry_id, Path::new("four.rs"), cx)
+    });
+    will_rename.next().await.unwrap();
+    cx.executor().advance_clock(WILL_RENAME_FILES_TIMEOUT);
+    rename.await.unwrap().unwrap();
+    cx.executor().run_until_parked();
+    one.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.file().unwrap().path().as_ref(), Path::new("four.rs"));
+    });
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1,9 +1,12 @@
-use crate::Project;
+use crate::{project_settings::ProjectSettings, Project};
 use collections::HashMap;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
-use settings::Settings;
+use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, WeakModel};
+use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
-use std::path::{Path, PathBuf};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 use task::SpawnInTerminal;
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
@@ -37,6 +40,7 @@ impl Project {
         let (completion_tx, completion_rx) = bounded(1);
 
         let mut env = settings.env.clone();
+        self.apply_environment_settings(working_directory.as_deref(), &mut env, cx);
         // Alacritty uses parent project's working directory when no working directory is provided
         // https://github.com/alacritty/alacritty/blob/fd1a3cc79192d1d03839f0fd8c72e1f8d0fce42e/extra/man/alacritty.5.scd?plain=1#L47-L52
 
@@ -122,6 +126,34 @@ impl Project {
         terminal
     }
 
+    /// Layers the environment configured for the project over `env`, for a process
+    /// started in `working_directory`.
+    pub fn apply_environment_settings(
+        &self,
+        working_directory: Option<&Path>,
+        env: &mut HashMap<String, String>,
+        cx: &AppContext,
+    ) {
+        let location = match working_directory {
+            Some(directory) => self.find_local_worktree(directory, cx),
+            None => self
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| (worktree, PathBuf::new())),
+        };
+        let settings = match &location {
+            Some((worktree, path)) => ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.read(cx).id().to_usize(),
+                    path,
+                }),
+                cx,
+            ),
+            None => ProjectSettings::get_global(cx),
+        };
+        settings.environment.apply(env);
+    }
+
     pub fn find_activate_script_path(
         &mut self,
         settings: &VenvSettingsContent,
@@ -169,7 +201,11 @@ impl Project {
 
             let path_bin = path.join("bin");
             // We need to set the PATH to include the virtual environment's bin directory
-            if let Some(paths) = std::env::var_os("PATH") {
+            let paths = env
+                .get("PATH")
+                .map(OsString::from)
+                .or_else(|| std::env::var_os("PATH"));
+            if let Some(paths) = paths {
                 let paths = std::iter::once(path_bin).chain(std::env::split_paths(&paths));
                 if let Some(new_path) = std::env::join_paths(paths).log_err() {
                     env.insert("PATH".to_string(), new_path.to_string_lossy().to_string());
@@ -13,16 +13,19 @@ use gpui::{
     actions, anchored, deferred, div, impl_actions, px, uniform_list, Action, AppContext,
     AssetSource, AsyncWindowContext, ClipboardItem, DismissEvent, Div, EventEmitter, FocusHandle,
     FocusableView, InteractiveElement, KeyContext, Model, MouseButton, MouseDownEvent,
-    ParentElement, Pixels, Point, PromptLevel, Render, Stateful, Styled, Subscription, Task,
-    UniformListScrollHandle, View, ViewContext, VisualContext as _, WeakView, WindowContext,
+    MouseMoveEvent, ParentElement, Pixels, Point, PromptLevel, Render, Stateful, Styled,
+    Subscription, Task, UniformListScrollHandle, View, ViewContext, VisualContext as _, WeakView,
+    WindowContext,
 };
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
 use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
-use project_panel_settings::{ProjectPanelDockPosition, ProjectPanelSettings};
+use project_panel_settings::{
+    ProjectPanelDockPosition, ProjectPanelSettings, ProjectPanelSortOrder,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
     ffi::OsStr,
     ops::Range,
     path::{Path, PathBuf},
@@ -34,13 +37,15 @@ use unicase::UniCase;
 use util::{maybe, NumericPrefixWithSuffix, ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
-    notifications::DetachAndPromptErr,
-    OpenInTerminal, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId},
+    OpenInTerminal, Toast, Workspace,
 };
 
 const PROJECT_PANEL_KEY: &str = "ProjectPanel";
 const NEW_ENTRY_ID: ProjectEntryId = ProjectEntryId::MAX;
 
+struct EntryAlreadyExists;
+
 pub struct ProjectPanel {
     project: Model<Project>,
     fs: Arc<dyn Fs>,
@@ -51,6 +56,13 @@ pub struct ProjectPanel {
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
     unfolded_dir_ids: HashSet<ProjectEntryId>,
     selection: Option<Selection>,
+    /// Entries marked with alt- or shift-clicks, or by dragging over them, which file operations
+    /// act on together with the selection.
+    marked_entries: BTreeSet<Selection>,
+    /// Whether the mouse button was last pressed on an entry rather than on the empty space.
+    entry_pressed: bool,
+    /// While dragging from the empty space below the entries, the first entry that was crossed.
+    marking_drag: Option<Option<Selection>>,
     context_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
     edit_state: Option<EditState>,
     filename_editor: View<Editor>,
@@ -61,8 +73,8 @@ pub struct ProjectPanel {
     pending_serialization: Task<Option<()>>,
 }
 
-#[derive(Copy, Clone, Debug)]
-struct Selection {
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Selection {
     worktree_id: WorktreeId,
     entry_id: ProjectEntryId,
 }
@@ -76,16 +88,10 @@ struct EditState {
     processing_filename: Option<String>,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub enum ClipboardEntry {
-    Copied {
-        worktree_id: WorktreeId,
-        entry_id: ProjectEntryId,
-    },
-    Cut {
-        worktree_id: WorktreeId,
-        entry_id: ProjectEntryId,
-    },
+    Copied(BTreeSet<Selection>),
+    Cut(BTreeSet<Selection>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -98,6 +104,7 @@ pub struct EntryDetails {
     is_ignored: bool,
     is_expanded: bool,
     is_selected: bool,
+    is_marked: bool,
     is_editing: bool,
     is_processing: bool,
     is_cut: bool,
@@ -125,14 +132,17 @@ actions!(
         ExpandSelectedEntry,
         CollapseSelectedEntry,
         CollapseAllEntries,
+        RevealActiveFile,
         NewDirectory,
         NewFile,
         Copy,
+        CompareMarkedFiles,
         CopyPath,
         CopyRelativePath,
         RevealInFinder,
         Cut,
         Paste,
+        Duplicate,
         Rename,
         Open,
         OpenPermanent,
@@ -244,10 +254,15 @@ impl ProjectPanel {
             .detach();
 
             let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
-            cx.observe_global::<SettingsStore>(move |_, cx| {
+            cx.observe_global::<SettingsStore>(move |this, cx| {
                 let new_settings = *ProjectPanelSettings::get_global(cx);
                 if project_panel_settings != new_settings {
+                    let resort = project_panel_settings.sort_order != new_settings.sort_order
+                        || project_panel_settings.folders_first != new_settings.folders_first;
                     project_panel_settings = new_settings;
+                    if resort {
+                        this.update_visible_entries(None, cx);
+                    }
                     cx.notify();
                 }
             })
@@ -263,6 +278,9 @@ impl ProjectPanel {
                 expanded_dir_ids: Default::default(),
                 unfolded_dir_ids: Default::default(),
                 selection: None,
+                marked_entries: Default::default(),
+                entry_pressed: false,
+                marking_drag: None,
                 edit_state: None,
                 context_menu: None,
                 filename_editor,
@@ -416,10 +434,14 @@ impl ProjectPanel {
             return;
         };
 
-        self.selection = Some(Selection {
+        let selection = Selection {
             worktree_id,
             entry_id,
-        });
+        };
+        if !self.marked_entries.contains(&selection) {
+            self.marked_entries.clear();
+        }
+        self.selection = Some(selection);
 
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let auto_fold_dirs = ProjectPanelSettings::get_global(cx).auto_fold_dirs;
@@ -431,6 +453,13 @@ impl ProjectPanel {
             let is_local = project.is_local();
             let is_read_only = project.is_read_only();
             let is_remote = project.is_remote();
+            let can_compare = self.marked_file_pair(cx).is_some();
+            let can_paste = self.clipboard_entry.as_ref().map_or(false, |entry| {
+                entry
+                    .items()
+                    .iter()
+                    .any(|item| item.worktree_id == worktree_id)
+            });
 
             let context_menu = ContextMenu::build(cx, |menu, cx| {
                 menu.context(self.focus_handle.clone()).when_else(
@@ -461,10 +490,12 @@ impl ProjectPanel {
                             .action("Cut", Box::new(Cut))
                             .action("Copy", Box::new(Copy))
                             // TODO: Paste should always be visible, but disabled when clipboard is empty
-                            .when_some(self.clipboard_entry, |menu, entry| {
-                                menu.when(entry.worktree_id() == worktree_id, |menu| {
-                                    menu.action("Paste", Box::new(Paste))
-                                })
+                            .when(can_paste, |menu| menu.action("Paste", Box::new(Paste)))
+                            .when(!is_root, |menu| {
+                                menu.action("Duplicate", Box::new(Duplicate))
+                            })
+                            .when(can_compare, |menu| {
+                                menu.action("Compare Marked Files", Box::new(CompareMarkedFiles))
                             })
                             .separator()
                             .action("Copy Path", Box::new(CopyPath))
@@ -495,6 +526,7 @@ impl ProjectPanel {
                             })
                             .when(is_local & is_root, |menu| {
                                 menu.separator()
+                                    .action("Reveal Active File", Box::new(RevealActiveFile))
                                     .action("Collapse All", Box::new(CollapseAllEntries))
                             })
                     },
@@ -613,6 +645,14 @@ impl ProjectPanel {
         cx.notify();
     }
 
+    /// Expands the directories containing the file that's active in the workspace, then selects
+    /// and scrolls to it, regardless of the `auto_reveal_entries` setting.
+    fn reveal_active_file(&mut self, _: &RevealActiveFile, cx: &mut ViewContext<Self>) {
+        if let Some(entry_id) = self.project.read(cx).active_entry() {
+            self.reveal_entry(self.project.clone(), entry_id, false, cx);
+        }
+    }
+
     fn toggle_expanded(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
         if let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) {
             if let Some(expanded_dir_ids) = self.expanded_dir_ids.get_mut(&worktree_id) {
@@ -709,6 +749,7 @@ impl ProjectPanel {
             });
             let new_path = entry.path.join(&filename.trim_start_matches('/'));
             if path_already_exists(new_path.as_path()) {
+                self.show_entry_already_exists(&new_path, cx);
                 return None;
             }
 
@@ -722,7 +763,15 @@ impl ProjectPanel {
             } else {
                 filename.clone().into()
             };
+            // Confirming the name unchanged just leaves edit mode.
+            if new_path.as_path() == entry.path.as_ref() {
+                self.edit_state = None;
+                self.update_visible_entries(None, cx);
+                cx.notify();
+                return None;
+            }
             if path_already_exists(new_path.as_path()) {
+                self.show_entry_already_exists(&new_path, cx);
                 return None;
             }
 
@@ -762,6 +811,18 @@ impl ProjectPanel {
         }))
     }
 
+    fn show_entry_already_exists(&self, path: &Path, cx: &mut ViewContext<Self>) {
+        let message = format!("A file or folder named `{}` already exists", path.display());
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<EntryAlreadyExists>(), message),
+                    cx,
+                )
+            })
+            .ok();
+    }
+
     fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
         self.edit_state = None;
         self.update_visible_entries(None, cx);
@@ -900,32 +961,61 @@ impl ProjectPanel {
 
     fn remove(&mut self, trash: bool, skip_prompt: bool, cx: &mut ViewContext<'_, ProjectPanel>) {
         maybe!({
-            let Selection { entry_id, .. } = self.selection?;
-            let path = self.project.read(cx).path_for_entry(entry_id, cx)?.path;
-            let file_name = path.file_name()?;
-
+            let entries = self.disjoint_entries(&self.effective_entries(), cx);
             let operation = if trash { "Trash" } else { "Delete" };
+            let (message, detail) = match entries.as_slice() {
+                [] => return None,
+                [(_, path)] => {
+                    let file_name = path.file_name()?;
+                    (format!("{operation} {file_name:?}?"), None)
+                }
+                _ => {
+                    let file_names = entries
+                        .iter()
+                        .filter_map(|(_, path)| Some(path.file_name()?.to_string_lossy()))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    (
+                        format!("{operation} {} items?", entries.len()),
+                        Some(file_names),
+                    )
+                }
+            };
+
             let answer = (!skip_prompt).then(|| {
                 cx.prompt(
                     PromptLevel::Info,
-                    &format!("{operation} {file_name:?}?",),
-                    None,
+                    &message,
+                    detail.as_deref(),
                     &[operation, "Cancel"],
                 )
             });
 
+            let entry_ids = entries
+                .into_iter()
+                .map(|(entry, _)| entry.entry_id)
+                .collect::<Vec<_>>();
             cx.spawn(|this, mut cx| async move {
                 if let Some(answer) = answer {
                     if answer.await != Ok(0) {
                         return Ok(());
                     }
                 }
-                this.update(&mut cx, |this, cx| {
-                    this.project
-                        .update(cx, |project, cx| project.delete_entry(entry_id, trash, cx))
-                        .ok_or_else(|| anyhow!("no such entry"))
-                })??
-                .await
+                let tasks = this.update(&mut cx, |this, cx| {
+                    this.marked_entries.clear();
+                    entry_ids
+                        .into_iter()
+                        .map(|entry_id| {
+                            this.project
+                                .update(cx, |project, cx| project.delete_entry(entry_id, trash, cx))
+                                .ok_or_else(|| anyhow!("no such entry"))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })??;
+                for task in tasks {
+                    task.await?;
+                }
+                Ok(())
             })
             .detach_and_log_err(cx);
             Some(())
@@ -1076,21 +1166,17 @@ impl ProjectPanel {
     }
 
     fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
-        if let Some((worktree, entry)) = self.selected_entry(cx) {
-            self.clipboard_entry = Some(ClipboardEntry::Cut {
-                worktree_id: worktree.id(),
-                entry_id: entry.id,
-            });
+        let entries = self.effective_entries();
+        if !entries.is_empty() {
+            self.clipboard_entry = Some(ClipboardEntry::Cut(entries));
             cx.notify();
         }
     }
 
     fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
-        if let Some((worktree, entry)) = self.selected_entry(cx) {
-            self.clipboard_entry = Some(ClipboardEntry::Copied {
-                worktree_id: worktree.id(),
-                entry_id: entry.id,
-            });
+        let entries = self.effective_entries();
+        if !entries.is_empty() {
+            self.clipboard_entry = Some(ClipboardEntry::Copied(entries));
             cx.notify();
         }
     }
@@ -1098,64 +1184,147 @@ impl ProjectPanel {
     fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
         maybe!({
             let (worktree, entry) = self.selected_entry(cx)?;
-            let clipboard_entry = self.clipboard_entry?;
-            if clipboard_entry.worktree_id() != worktree.id() {
-                return None;
-            }
-
-            let clipboard_entry_file_name = self
-                .project
-                .read(cx)
-                .path_for_entry(clipboard_entry.entry_id(), cx)?
-                .path
-                .file_name()?
-                .to_os_string();
+            let clipboard_entry = self.clipboard_entry.as_ref()?;
+            let project = self.project.read(cx);
 
-            let mut new_path = entry.path.to_path_buf();
-            // If we're pasting into a file, or a directory into itself, go up one level.
-            if entry.is_file() || (entry.is_dir() && entry.id == clipboard_entry.entry_id()) {
-                new_path.pop();
-            }
-
-            new_path.push(&clipboard_entry_file_name);
-            let extension = new_path.extension().map(|e| e.to_os_string());
-            let file_name_without_extension = Path::new(&clipboard_entry_file_name).file_stem()?;
-            let mut ix = 0;
-            while worktree.entry_for_path(&new_path).is_some() {
-                new_path.pop();
-
-                let mut new_file_name = file_name_without_extension.to_os_string();
-                new_file_name.push(" copy");
-                if ix > 0 {
-                    new_file_name.push(format!(" {}", ix));
+            let mut paste_paths = Vec::new();
+            let mut taken_paths = HashSet::new();
+            for clipboard_item in clipboard_entry.items() {
+                if clipboard_item.worktree_id != worktree.id() {
+                    continue;
                 }
-                if let Some(extension) = extension.as_ref() {
-                    new_file_name.push(".");
-                    new_file_name.push(extension);
+                let Some(file_name) = project
+                    .path_for_entry(clipboard_item.entry_id, cx)
+                    .and_then(|path| Some(path.path.file_name()?.to_os_string()))
+                else {
+                    continue;
+                };
+
+                let mut new_path = entry.path.to_path_buf();
+                // If we're pasting into a file, or a directory into itself, go up one level.
+                if entry.is_file() || (entry.is_dir() && entry.id == clipboard_item.entry_id) {
+                    new_path.pop();
                 }
 
-                new_path.push(new_file_name);
-                ix += 1;
+                // Marked entries can share a file name, so names picked earlier in
+                // this paste count as taken too.
+                if let Some(new_path) =
+                    available_copy_path(worktree, &taken_paths, new_path, &file_name)
+                {
+                    taken_paths.insert(new_path.clone());
+                    paste_paths.push((clipboard_item.entry_id, new_path));
+                }
             }
 
-            if clipboard_entry.is_cut() {
-                self.project
-                    .update(cx, |project, cx| {
-                        project.rename_entry(clipboard_entry.entry_id(), new_path, cx)
-                    })
-                    .detach_and_log_err(cx)
-            } else {
-                self.project
-                    .update(cx, |project, cx| {
-                        project.copy_entry(clipboard_entry.entry_id(), new_path, cx)
-                    })
-                    .detach_and_log_err(cx)
+            let is_cut = clipboard_entry.is_cut();
+            for (entry_id, new_path) in paste_paths {
+                if is_cut {
+                    self.project
+                        .update(cx, |project, cx| {
+                            project.rename_entry(entry_id, new_path, cx)
+                        })
+                        .detach_and_log_err(cx)
+                } else {
+                    self.project
+                        .update(cx, |project, cx| project.copy_entry(entry_id, new_path, cx))
+                        .detach_and_log_err(cx)
+                }
             }
 
             Some(())
         });
     }
 
+    /// Copies the selected file or directory next to itself, naming the copy like `foo copy.rs`.
+    fn duplicate(&mut self, _: &Duplicate, cx: &mut ViewContext<Self>) {
+        maybe!({
+            let (worktree, entry) = self.selected_entry(cx)?;
+            let file_name = entry.path.file_name()?;
+            let parent = entry.path.parent()?.to_path_buf();
+            let new_path = available_copy_path(worktree, &HashSet::new(), parent, file_name)?;
+            let worktree_id = worktree.id();
+            let entry_id = entry.id;
+
+            let task = self
+                .project
+                .update(cx, |project, cx| project.copy_entry(entry_id, new_path, cx));
+            cx.spawn(|this, mut cx| async move {
+                let new_entry = task.await?;
+                this.update(&mut cx, |this, cx| {
+                    if let Some(new_entry) = new_entry {
+                        this.selection = Some(Selection {
+                            worktree_id,
+                            entry_id: new_entry.id,
+                        });
+                        this.update_visible_entries(None, cx);
+                        cx.notify();
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+            Some(())
+        });
+    }
+
+    /// Opens how the second of the two marked files differs from the first.
+    fn compare_marked_files(&mut self, _: &CompareMarkedFiles, cx: &mut ViewContext<Self>) {
+        let Some([old_path, new_path]) = self.marked_file_pair(cx) else {
+            return;
+        };
+        let title = format!(
+            "{} ↔ {}",
+            old_path
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+            new_path
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+        );
+        let (old_buffer, new_buffer) = self.project.update(cx, |project, cx| {
+            (
+                project.open_buffer(old_path, cx),
+                project.open_buffer(new_path, cx),
+            )
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            let old_buffer = old_buffer.await?;
+            let new_buffer = new_buffer.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let old_text = old_buffer.read(cx).text();
+                let new_buffer = new_buffer.read(cx);
+                let new_text = new_buffer.text();
+                let language = new_buffer.language().cloned();
+                Editor::open_diff_view(workspace, title, old_text, new_text, language, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The paths of the two entries to compare, in the order they're listed, when exactly two
+    /// files are marked.
+    fn marked_file_pair(&self, cx: &AppContext) -> Option<[ProjectPath; 2]> {
+        let mut entries = self.effective_entries().into_iter().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| self.index_for_selection(*entry).map(|(_, _, ix)| ix));
+        let [first, second] = entries.as_slice() else {
+            return None;
+        };
+
+        let project = self.project.read(cx);
+        let file_path = |entry: &Selection| {
+            let worktree = project.worktree_for_id(entry.worktree_id, cx)?;
+            if !worktree.read(cx).entry_for_id(entry.entry_id)?.is_file() {
+                return None;
+            }
+            project.path_for_entry(entry.entry_id, cx)
+        };
+        Some([file_path(first)?, file_path(second)?])
+    }
+
     fn copy_path(&mut self, _: &CopyPath, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             cx.write_to_clipboard(ClipboardItem::new(
@@ -1226,6 +1395,37 @@ impl ProjectPanel {
         }
     }
 
+    /// Moves the dragged entry into `destination`, along with the other marked entries when it's
+    /// one of them.
+    fn move_entries(
+        &mut self,
+        dragged_entry: ProjectEntryId,
+        destination: ProjectEntryId,
+        destination_is_file: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self
+            .marked_entries
+            .iter()
+            .any(|entry| entry.entry_id == dragged_entry)
+        {
+            self.move_entry(dragged_entry, destination, destination_is_file, cx);
+            return;
+        }
+
+        let Some(destination_worktree) =
+            self.project.read(cx).worktree_id_for_entry(destination, cx)
+        else {
+            return;
+        };
+        let marked_entries = std::mem::take(&mut self.marked_entries);
+        for (entry, _) in self.disjoint_entries(&marked_entries, cx) {
+            if entry.worktree_id == destination_worktree && entry.entry_id != destination {
+                self.move_entry(entry.entry_id, destination, destination_is_file, cx);
+            }
+        }
+    }
+
     fn move_entry(
         &mut self,
         entry_to_move: ProjectEntryId,
@@ -1299,6 +1499,128 @@ impl ProjectPanel {
         Some((worktree, entry))
     }
 
+    /// The entries that file operations act on: the marked entries when the selection is one of
+    /// them, and otherwise just the selection.
+    fn effective_entries(&self) -> BTreeSet<Selection> {
+        match self.selection {
+            Some(selection) if self.marked_entries.contains(&selection) => {
+                self.marked_entries.clone()
+            }
+            Some(selection) => BTreeSet::from([selection]),
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// Pairs `entries` with their paths, leaving out those inside another of the entries, as an
+    /// operation on the directory already covers them.
+    fn disjoint_entries(
+        &self,
+        entries: &BTreeSet<Selection>,
+        cx: &AppContext,
+    ) -> Vec<(Selection, Arc<Path>)> {
+        let project = self.project.read(cx);
+        let entries = entries
+            .iter()
+            .filter_map(|entry| Some((*entry, project.path_for_entry(entry.entry_id, cx)?.path)))
+            .collect::<Vec<_>>();
+        entries
+            .iter()
+            .filter(|(entry, path)| {
+                !entries.iter().any(|(other, other_path)| {
+                    other.worktree_id == entry.worktree_id
+                        && other_path != path
+                        && path.starts_with(other_path)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Marks or unmarks `entry_id`, and selects it. The first mark also marks the entry that was
+    /// selected until then.
+    fn toggle_marked(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
+        let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) else {
+            return;
+        };
+        if self.marked_entries.is_empty() {
+            self.marked_entries.extend(self.selection);
+        }
+        let entry = Selection {
+            worktree_id,
+            entry_id,
+        };
+        if !self.marked_entries.remove(&entry) {
+            self.marked_entries.insert(entry);
+        }
+        self.selection = Some(entry);
+        cx.notify();
+    }
+
+    /// Marks the visible entries from the selection to `entry_id`, and selects it.
+    fn mark_range_to(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
+        let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) else {
+            return;
+        };
+        let entry = Selection {
+            worktree_id,
+            entry_id,
+        };
+        let anchor = self.selection.unwrap_or(entry);
+        self.mark_range(anchor, entry, cx);
+    }
+
+    /// Extends the marks of a drag that started on the empty space to `entry_id`, once the pointer
+    /// moves over it.
+    fn drag_marking_over(
+        &mut self,
+        entry_id: ProjectEntryId,
+        event: &MouseMoveEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(anchor) = self.marking_drag else {
+            return;
+        };
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.marking_drag = None;
+            return;
+        }
+        let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) else {
+            return;
+        };
+        let entry = Selection {
+            worktree_id,
+            entry_id,
+        };
+        let anchor = anchor.unwrap_or(entry);
+        self.marking_drag = Some(Some(anchor));
+        self.mark_range(anchor, entry, cx);
+    }
+
+    /// Marks the visible entries from `anchor` to `entry`, and selects `entry`.
+    fn mark_range(&mut self, anchor: Selection, entry: Selection, cx: &mut ViewContext<Self>) {
+        let (Some((_, _, start)), Some((_, _, end))) = (
+            self.index_for_selection(anchor),
+            self.index_for_selection(entry),
+        ) else {
+            return;
+        };
+
+        self.marked_entries = self
+            .visible_entries
+            .iter()
+            .flat_map(|(worktree_id, entries)| {
+                entries.iter().map(|entry| Selection {
+                    worktree_id: *worktree_id,
+                    entry_id: entry.id,
+                })
+            })
+            .skip(start.min(end))
+            .take(start.abs_diff(end) + 1)
+            .collect();
+        self.selection = Some(entry);
+        cx.notify();
+    }
+
     fn expand_to_selection(&mut self, cx: &mut ViewContext<Self>) -> Option<()> {
         let (worktree, entry) = self.selected_entry(cx)?;
         let expanded_dir_ids = self.expanded_dir_ids.entry(worktree.id()).or_default();
@@ -1322,7 +1644,10 @@ impl ProjectPanel {
         new_selected_entry: Option<(WorktreeId, ProjectEntryId)>,
         cx: &mut ViewContext<Self>,
     ) {
-        let auto_collapse_dirs = ProjectPanelSettings::get_global(cx).auto_fold_dirs;
+        let settings = ProjectPanelSettings::get_global(cx);
+        let auto_collapse_dirs = settings.auto_fold_dirs;
+        let sort_order = settings.sort_order;
+        let folders_first = settings.folders_first;
         let project = self.project.read(cx);
         self.last_worktree_root_id = project
             .visible_worktrees(cx)
@@ -1409,49 +1734,13 @@ impl ProjectPanel {
             snapshot.propagate_git_statuses(&mut visible_worktree_entries);
 
             visible_worktree_entries.sort_by(|entry_a, entry_b| {
-                let mut components_a = entry_a.path.components().peekable();
-                let mut components_b = entry_b.path.components().peekable();
-                loop {
-                    match (components_a.next(), components_b.next()) {
-                        (Some(component_a), Some(component_b)) => {
-                            let a_is_file = components_a.peek().is_none() && entry_a.is_file();
-                            let b_is_file = components_b.peek().is_none() && entry_b.is_file();
-                            let ordering = a_is_file.cmp(&b_is_file).then_with(|| {
-                                let maybe_numeric_ordering = maybe!({
-                                    let num_and_remainder_a = Path::new(component_a.as_os_str())
-                                        .file_stem()
-                                        .and_then(|s| s.to_str())
-                                        .and_then(
-                                            NumericPrefixWithSuffix::from_numeric_prefixed_str,
-                                        )?;
-                                    let num_and_remainder_b = Path::new(component_b.as_os_str())
-                                        .file_stem()
-                                        .and_then(|s| s.to_str())
-                                        .and_then(
-                                            NumericPrefixWithSuffix::from_numeric_prefixed_str,
-                                        )?;
-
-                                    num_and_remainder_a.partial_cmp(&num_and_remainder_b)
-                                });
-
-                                maybe_numeric_ordering.unwrap_or_else(|| {
-                                    let name_a =
-                                        UniCase::new(component_a.as_os_str().to_string_lossy());
-                                    let name_b =
-                                        UniCase::new(component_b.as_os_str().to_string_lossy());
-
-                                    name_a.cmp(&name_b)
-                                })
-                            });
-                            if !ordering.is_eq() {
-                                return ordering;
-                            }
-                        }
-                        (Some(_), None) => break Ordering::Greater,
-                        (None, Some(_)) => break Ordering::Less,
-                        (None, None) => break Ordering::Equal,
-                    }
-                }
+                compare_entries(
+                    entry_a,
+                    entry_b,
+                    sort_order,
+                    folders_first,
+                    worktree.read(cx),
+                )
             });
             self.visible_entries
                 .push((worktree_id, visible_worktree_entries));
@@ -1575,6 +1864,10 @@ impl ProjectPanel {
                             .unwrap_or_else(|| root_name.to_string_lossy().to_string()),
                     };
 
+                    let selection = Selection {
+                        worktree_id: snapshot.id(),
+                        entry_id: entry.id,
+                    };
                     let mut details = EntryDetails {
                         filename,
                         icon,
@@ -1583,14 +1876,14 @@ impl ProjectPanel {
                         kind: entry.kind,
                         is_ignored: entry.is_ignored,
                         is_expanded,
-                        is_selected: self.selection.map_or(false, |e| {
-                            e.worktree_id == snapshot.id() && e.entry_id == entry.id
-                        }),
+                        is_selected: self.selection == Some(selection),
+                        is_marked: self.marked_entries.contains(&selection),
                         is_editing: false,
                         is_processing: false,
                         is_cut: self
                             .clipboard_entry
-                            .map_or(false, |e| e.is_cut() && e.entry_id() == entry.id),
+                            .as_ref()
+                            .map_or(false, |e| e.is_cut() && e.items().contains(&selection)),
                         git_status: status,
                         is_dotenv: entry.is_private,
                     };
@@ -1680,6 +1973,7 @@ impl ProjectPanel {
         let file_name = details.filename.clone();
         let icon = details.icon.clone();
         let depth = details.depth;
+        let is_marked = details.is_marked;
         div()
             .id(entry_id.to_proto() as usize)
             .on_drag(entry_id, move |entry_id, cx| {
@@ -1693,13 +1987,20 @@ impl ProjectPanel {
                 style.bg(cx.theme().colors().drop_target_background)
             })
             .on_drop(cx.listener(move |this, dragged_id: &ProjectEntryId, cx| {
-                this.move_entry(*dragged_id, entry_id, kind.is_file(), cx);
+                this.move_entries(*dragged_id, entry_id, kind.is_file(), cx);
+            }))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, _| this.entry_pressed = true),
+            )
+            .on_mouse_move(cx.listener(move |this, event: &MouseMoveEvent, cx| {
+                this.drag_marking_over(entry_id, event, cx)
             }))
             .child(
                 ListItem::new(entry_id.to_proto() as usize)
                     .indent_level(depth)
                     .indent_step_size(px(settings.indent_size))
-                    .selected(is_selected)
+                    .selected(is_selected || is_marked)
                     .child(if let Some(icon) = &icon {
                         h_flex().child(Icon::from_path(icon.to_string()).color(filename_text_color))
                     } else {
@@ -1725,10 +2026,18 @@ impl ProjectPanel {
                             return;
                         }
                         if !show_editor {
-                            if kind.is_dir() {
+                            // Cmd-click (ctrl-click on Linux) marks entries, as in file
+                            // managers, so opening a file in a split moves to alt-click.
+                            if event.down.modifiers.shift {
+                                this.mark_range_to(entry_id, cx);
+                            } else if event.down.modifiers.secondary() {
+                                this.toggle_marked(entry_id, cx);
+                            } else if kind.is_dir() {
+                                this.marked_entries.clear();
                                 this.toggle_expanded(entry_id, cx);
                             } else {
-                                if event.down.modifiers.secondary() {
+                                this.marked_entries.clear();
+                                if event.down.modifiers.alt {
                                     this.split_entry(entry_id, cx);
                                 } else {
                                     let click_count = event.up.click_count;
@@ -1794,6 +2103,127 @@ impl ProjectPanel {
     }
 }
 
+/// Orders entries depth-first, comparing siblings by `sort_order`, with directories ahead of
+/// files when `folders_first` is set.
+fn compare_entries(
+    entry_a: &Entry,
+    entry_b: &Entry,
+    sort_order: ProjectPanelSortOrder,
+    folders_first: bool,
+    worktree: &Worktree,
+) -> Ordering {
+    let mut components_a = entry_a.path.components().peekable();
+    let mut components_b = entry_b.path.components().peekable();
+    let mut depth = 0;
+    loop {
+        depth += 1;
+        match (components_a.next(), components_b.next()) {
+            (Some(component_a), Some(component_b)) => {
+                if component_a == component_b {
+                    continue;
+                }
+                let a_is_file = components_a.peek().is_none() && entry_a.is_file();
+                let b_is_file = components_b.peek().is_none() && entry_b.is_file();
+                let name_a = Path::new(component_a.as_os_str());
+                let name_b = Path::new(component_b.as_os_str());
+
+                let mut ordering = if folders_first {
+                    a_is_file.cmp(&b_is_file)
+                } else {
+                    Ordering::Equal
+                };
+                ordering = ordering.then_with(|| match sort_order {
+                    ProjectPanelSortOrder::Name => Ordering::Equal,
+                    ProjectPanelSortOrder::Type => {
+                        let extension = |name: &Path, is_file: bool| {
+                            is_file
+                                .then(|| name.extension())
+                                .flatten()
+                                .map(|extension| UniCase::new(extension.to_string_lossy()))
+                        };
+                        extension(name_a, a_is_file).cmp(&extension(name_b, b_is_file))
+                    }
+                    ProjectPanelSortOrder::ModifiedTime => {
+                        // Directories are compared by their own mtime, not their children's.
+                        let mtime = |entry: &Entry| {
+                            if entry.path.components().count() == depth {
+                                entry.mtime
+                            } else {
+                                let ancestor = entry.path.components().take(depth);
+                                worktree
+                                    .entry_for_path(ancestor.collect::<PathBuf>())?
+                                    .mtime
+                            }
+                        };
+                        // The most recently modified entries come first.
+                        mtime(entry_b).cmp(&mtime(entry_a))
+                    }
+                });
+                return ordering
+                    .then_with(|| compare_file_names(name_a, name_b))
+                    .then_with(|| component_a.cmp(&component_b));
+            }
+            (Some(_), None) => break Ordering::Greater,
+            (None, Some(_)) => break Ordering::Less,
+            (None, None) => break Ordering::Equal,
+        }
+    }
+}
+
+/// Compares names case-insensitively, ordering names that start with numbers by their value.
+fn compare_file_names(name_a: &Path, name_b: &Path) -> Ordering {
+    let maybe_numeric_ordering = maybe!({
+        let num_and_remainder_a = name_a
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(NumericPrefixWithSuffix::from_numeric_prefixed_str)?;
+        let num_and_remainder_b = name_b
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(NumericPrefixWithSuffix::from_numeric_prefixed_str)?;
+
+        num_and_remainder_a.partial_cmp(&num_and_remainder_b)
+    });
+
+    maybe_numeric_ordering.unwrap_or_else(|| {
+        let name_a = UniCase::new(name_a.as_os_str().to_string_lossy());
+        let name_b = UniCase::new(name_b.as_os_str().to_string_lossy());
+        name_a.cmp(&name_b)
+    })
+}
+
+/// Finds a path in `dir` for a copy of `file_name`, keeping the name if it's free, and otherwise
+/// trying `name copy.ext`, `name copy 1.ext` and so on.
+fn available_copy_path(
+    worktree: &Worktree,
+    taken_paths: &HashSet<PathBuf>,
+    dir: PathBuf,
+    file_name: &OsStr,
+) -> Option<PathBuf> {
+    let mut new_path = dir;
+    new_path.push(file_name);
+    let extension = new_path.extension().map(|e| e.to_os_string());
+    let file_name_without_extension = Path::new(file_name).file_stem()?;
+    let mut ix = 0;
+    while worktree.entry_for_path(&new_path).is_some() || taken_paths.contains(&new_path) {
+        new_path.pop();
+
+        let mut new_file_name = file_name_without_extension.to_os_string();
+        new_file_name.push(" copy");
+        if ix > 0 {
+            new_file_name.push(format!(" {}", ix));
+        }
+        if let Some(extension) = extension.as_ref() {
+            new_file_name.push(".");
+            new_file_name.push(extension);
+        }
+
+        new_path.push(new_file_name);
+        ix += 1;
+    }
+    Some(new_path)
+}
+
 impl Render for ProjectPanel {
     fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl IntoElement {
         let has_worktree = self.visible_entries.len() != 0;
@@ -1813,6 +2243,8 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::expand_selected_entry))
                 .on_action(cx.listener(Self::collapse_selected_entry))
                 .on_action(cx.listener(Self::collapse_all_entries))
+                .on_action(cx.listener(Self::reveal_active_file))
+                .on_action(cx.listener(Self::compare_marked_files))
                 .on_action(cx.listener(Self::open))
                 .on_action(cx.listener(Self::open_permanent))
                 .on_action(cx.listener(Self::confirm))
@@ -1831,6 +2263,7 @@ impl Render for ProjectPanel {
                         .on_action(cx.listener(Self::cut))
                         .on_action(cx.listener(Self::copy))
                         .on_action(cx.listener(Self::paste))
+                        .on_action(cx.listener(Self::duplicate))
                 })
                 .when(project.is_local(), |el| {
                     el.on_action(cx.listener(Self::reveal_in_finder))
@@ -1846,6 +2279,20 @@ impl Render for ProjectPanel {
                         }
                     }),
                 )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _, _| {
+                        // Entries handle the press first, so one that reached the panel without
+                        // them started on the empty space.
+                        if !std::mem::take(&mut this.entry_pressed) {
+                            this.marking_drag = Some(None);
+                        }
+                    }),
+                )
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _, _| this.marking_drag = None),
+                )
                 .track_focus(&self.focus_handle)
                 .child(
                     uniform_list(
@@ -1996,21 +2443,12 @@ impl FocusableView for ProjectPanel {
 
 impl ClipboardEntry {
     fn is_cut(&self) -> bool {
-        matches!(self, Self::Cut { .. })
-    }
-
-    fn entry_id(&self) -> ProjectEntryId {
-        match self {
-            ClipboardEntry::Copied { entry_id, .. } | ClipboardEntry::Cut { entry_id, .. } => {
-                *entry_id
-            }
-        }
+        matches!(self, Self::Cut(_))
     }
 
-    fn worktree_id(&self) -> WorktreeId {
+    fn items(&self) -> &BTreeSet<Selection> {
         match self {
-            ClipboardEntry::Copied { worktree_id, .. }
-            | ClipboardEntry::Cut { worktree_id, .. } => *worktree_id,
+            ClipboardEntry::Copied(entries) | ClipboardEntry::Cut(entries) => entries,
         }
     }
 }
@@ -2237,53 +2675,155 @@ mod tests {
     }
 
     #[gpui::test]
-    async fn test_auto_collapse_dir_paths(cx: &mut gpui::TestAppContext) {
+    async fn test_sort_order(cx: &mut gpui::TestAppContext) {
         init_test(cx);
 
         let fs = FakeFs::new(cx.executor().clone());
         fs.insert_tree(
-            "/root1",
-            json!({
-                "dir_1": {
-                    "nested_dir_1": {
-                        "nested_dir_2": {
-                            "nested_dir_3": {
-                                "file_a.java": "// File contents",
-                                "file_b.java": "// File contents",
-                                "file_c.java": "// File contents",
-                                "nested_dir_4": {
-                                    "nested_dir_5": {
-                                        "file_d.java": "// File contents",
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }),
-        )
-        .await;
-        fs.insert_tree(
-            "/root2",
+            "/root",
             json!({
-                "dir_2": {
-                    "file_1.java": "// File contents",
-                }
+                "a.txt": "",
+                "b_dir": { "inner.txt": "" },
+                "c.rs": "",
+                "d.txt": "",
             }),
         )
         .await;
+        // Touch the files so that they're modified in a different order than their names.
+        for path in ["/root/c.rs", "/root/a.txt", "/root/d.txt"] {
+            fs.insert_file(path, Vec::new()).await;
+        }
 
-        let project = Project::test(fs.clone(), ["/root1".as_ref(), "/root2".as_ref()], cx).await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
         let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
         let cx = &mut VisualTestContext::from_window(*workspace, cx);
-        cx.update(|cx| {
-            let settings = *ProjectPanelSettings::get_global(cx);
-            ProjectPanelSettings::override_global(
-                ProjectPanelSettings {
-                    auto_fold_dirs: true,
-                    ..settings
-                },
-                cx,
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root",
+                "    > b_dir",
+                "      a.txt",
+                "      c.rs",
+                "      d.txt"
+            ]
+        );
+
+        let update_settings = |sort_order, folders_first, cx: &mut VisualTestContext| {
+            cx.update(|cx| {
+                cx.update_global::<SettingsStore, _>(|store, cx| {
+                    store.update_user_settings::<ProjectPanelSettings>(cx, |settings| {
+                        settings.sort_order = Some(sort_order);
+                        settings.folders_first = Some(folders_first);
+                    });
+                });
+            });
+            cx.executor().run_until_parked();
+        };
+
+        update_settings(ProjectPanelSortOrder::ModifiedTime, true, cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root",
+                "    > b_dir",
+                "      d.txt",
+                "      a.txt",
+                "      c.rs"
+            ]
+        );
+
+        update_settings(ProjectPanelSortOrder::ModifiedTime, false, cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root",
+                "      d.txt",
+                "      a.txt",
+                "      c.rs",
+                "    > b_dir"
+            ]
+        );
+
+        update_settings(ProjectPanelSortOrder::Type, true, cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root",
+                "    > b_dir",
+                "      c.rs",
+                "      a.txt",
+                "      d.txt"
+            ]
+        );
+
+        // Without folders first, directories are sorted among the files at each level.
+        update_settings(ProjectPanelSortOrder::Name, false, cx);
+        toggle_expand_dir(&panel, "root/b_dir", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root",
+                "      a.txt",
+                "    v b_dir  <== selected",
+                "          inner.txt",
+                "      c.rs",
+                "      d.txt",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_auto_collapse_dir_paths(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "dir_1": {
+                    "nested_dir_1": {
+                        "nested_dir_2": {
+                            "nested_dir_3": {
+                                "file_a.java": "// File contents",
+                                "file_b.java": "// File contents",
+                                "file_c.java": "// File contents",
+                                "nested_dir_4": {
+                                    "nested_dir_5": {
+                                        "file_d.java": "// File contents",
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            "/root2",
+            json!({
+                "dir_2": {
+                    "file_1.java": "// File contents",
+                }
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref(), "/root2".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        cx.update(|cx| {
+            let settings = *ProjectPanelSettings::get_global(cx);
+            ProjectPanelSettings::override_global(
+                ProjectPanelSettings {
+                    auto_fold_dirs: true,
+                    ..settings
+                },
+                cx,
             );
         });
         let panel = workspace
@@ -2918,6 +3458,51 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_paste_marked_entries_with_same_name(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "a": { "mod.rs": "" },
+                "b": { "mod.rs": "" },
+                "c": {},
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        toggle_expand_dir(&panel, "root/a", cx);
+        toggle_expand_dir(&panel, "root/b", cx);
+        select_path(&panel, "root/a/mod.rs", cx);
+        let second = find_project_entry(&panel, "root/b/mod.rs", cx).unwrap();
+        panel.update(cx, |panel, cx| {
+            panel.toggle_marked(second, cx);
+            panel.copy(&Default::default(), cx);
+        });
+        select_path(&panel, "root/c", cx);
+        panel.update(cx, |panel, cx| panel.paste(&Default::default(), cx));
+        cx.executor().run_until_parked();
+
+        assert_eq!(
+            fs.files(),
+            &[
+                PathBuf::from("/root/a/mod.rs"),
+                PathBuf::from("/root/b/mod.rs"),
+                PathBuf::from("/root/c/mod copy.rs"),
+                PathBuf::from("/root/c/mod.rs"),
+            ]
+        );
+    }
+
     #[gpui::test]
     async fn test_copy_paste_directory(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -3013,6 +3598,225 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_duplicate(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "config": {
+                    "inner": {
+                        "deep.toml": "deep",
+                    },
+                    "settings.json": "{}",
+                },
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        toggle_expand_dir(&panel, "root/config", cx);
+        select_path(&panel, "root/config/settings.json", cx);
+        panel.update(cx, |panel, cx| panel.duplicate(&Duplicate, cx));
+        cx.executor().run_until_parked();
+        select_path(&panel, "root/config/settings.json", cx);
+        panel.update(cx, |panel, cx| panel.duplicate(&Duplicate, cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                //
+                "v root",
+                "    v config",
+                "        > inner",
+                "          settings copy 1.json  <== selected",
+                "          settings copy.json",
+                "          settings.json",
+            ]
+        );
+        assert_eq!(
+            fs.load("/root/config/settings copy.json".as_ref())
+                .await
+                .unwrap(),
+            "{}"
+        );
+
+        // Directories are copied along with their contents.
+        select_path(&panel, "root/config", cx);
+        panel.update(cx, |panel, cx| panel.duplicate(&Duplicate, cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            fs.load("/root/config copy/inner/deep.toml".as_ref())
+                .await
+                .unwrap(),
+            "deep"
+        );
+        assert_ne!(
+            find_project_entry(&panel, "root/config copy/inner/deep.toml", cx),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_multiple_marked_entries(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "src": {
+                    "first.rs": "",
+                    "fourth.rs": "",
+                    "second.rs": "",
+                    "third.rs": "",
+                },
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        toggle_expand_dir(&panel, "root/src", cx);
+        select_path(&panel, "root/src/fourth.rs", cx);
+        let second = find_project_entry(&panel, "root/src/second.rs", cx).unwrap();
+        panel.update(cx, |panel, cx| panel.mark_range_to(second, cx));
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                //
+                "v root",
+                "    v src",
+                "          first.rs",
+                "          fourth.rs  <== marked",
+                "          second.rs  <== selected",
+                "          third.rs",
+            ]
+        );
+
+        let fourth = find_project_entry(&panel, "root/src/fourth.rs", cx).unwrap();
+        let third = find_project_entry(&panel, "root/src/third.rs", cx).unwrap();
+        panel.update(cx, |panel, cx| {
+            panel.toggle_marked(fourth, cx);
+            panel.toggle_marked(third, cx);
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                //
+                "v root",
+                "    v src",
+                "          first.rs",
+                "          fourth.rs",
+                "          second.rs  <== marked",
+                "          third.rs  <== selected",
+            ]
+        );
+
+        submit_deletion(&panel, cx);
+        assert_eq!(
+            fs.files(),
+            &[
+                PathBuf::from("/root/src/first.rs"),
+                PathBuf::from("/root/src/fourth.rs"),
+            ]
+        );
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                //
+                "v root",
+                "    v src",
+                "          first.rs",
+                "          fourth.rs",
+            ]
+        );
+
+        // Dragging up from the empty space marks the entries the pointer passes over.
+        let first = find_project_entry(&panel, "root/src/first.rs", cx).unwrap();
+        let fourth = find_project_entry(&panel, "root/src/fourth.rs", cx).unwrap();
+        let drag = MouseMoveEvent {
+            pressed_button: Some(MouseButton::Left),
+            ..Default::default()
+        };
+        panel.update(cx, |panel, cx| {
+            panel.marked_entries.clear();
+            panel.marking_drag = Some(None);
+            panel.drag_marking_over(fourth, &drag, cx);
+            panel.drag_marking_over(first, &drag, cx);
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                //
+                "v root",
+                "    v src",
+                "          first.rs  <== selected",
+                "          fourth.rs  <== marked",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_compare_marked_files(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "new.txt": "one\n2\nthree\n",
+                "old.txt": "one\ntwo\nthree\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        select_path(&panel, "root/old.txt", cx);
+        let new_file = find_project_entry(&panel, "root/new.txt", cx).unwrap();
+        panel.update(cx, |panel, cx| {
+            assert!(panel.marked_file_pair(cx).is_none());
+            panel.toggle_marked(new_file, cx);
+            panel.compare_marked_files(&CompareMarkedFiles, cx);
+        });
+        cx.executor().run_until_parked();
+
+        let diff_editor = workspace
+            .update(cx, |workspace, cx| workspace.active_item_as::<Editor>(cx))
+            .unwrap()
+            .expect("comparing should open the diff in an editor");
+        diff_editor.update(cx, |diff_editor, cx| {
+            // The files are compared in the order they're listed in.
+            assert_eq!(diff_editor.title(cx), "new.txt ↔ old.txt");
+            assert_eq!(diff_editor.text(cx), "one\ntwo\nthree\n");
+            let buffer = diff_editor.buffer().read(cx).as_singleton().unwrap();
+            assert_eq!(
+                buffer.read(cx).diff_base().map(|text| text.to_string()),
+                Some("one\n2\nthree\n".to_string())
+            );
+            assert!(buffer.read(cx).has_git_diff());
+        });
+    }
+
     #[gpui::test]
     async fn test_remove_opened_file(cx: &mut gpui::TestAppContext) {
         init_test_with_editor(cx);
@@ -3273,6 +4077,102 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_new_file_in_directory(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/src",
+            json!({
+                "test": {
+                    "first.rs": "// First Rust file",
+                }
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/src".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| {
+                let panel = ProjectPanel::new(workspace, cx);
+                workspace.add_panel(panel.clone(), cx);
+                panel
+            })
+            .unwrap();
+
+        select_path(&panel, "src/test/", cx);
+        panel.update(cx, |panel, cx| panel.new_file(&NewFile, cx));
+        panel
+            .update(cx, |panel, cx| {
+                panel
+                    .filename_editor
+                    .update(cx, |editor, cx| editor.set_text("second.rs", cx));
+                panel.confirm_edit(cx).unwrap()
+            })
+            .await
+            .unwrap();
+        cx.executor().run_until_parked();
+
+        // The file is written to disk, shows up in the worktree and is opened.
+        assert_eq!(fs.load("/src/test/second.rs".as_ref()).await.unwrap(), "");
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v src",
+                "    v test",
+                "          first.rs",
+                "          second.rs  <== selected",
+            ]
+        );
+        ensure_single_file_is_opened(&workspace, "test/second.rs", cx);
+        workspace
+            .update(cx, |workspace, cx| {
+                assert!(workspace.active_item_as::<Editor>(cx).is_some());
+            })
+            .unwrap();
+
+        // Reusing the name of an existing file is refused with an error.
+        select_path(&panel, "src/test/", cx);
+        panel.update(cx, |panel, cx| panel.new_file(&NewFile, cx));
+        panel.update(cx, |panel, cx| {
+            panel
+                .filename_editor
+                .update(cx, |editor, cx| editor.set_text("first.rs", cx));
+            assert!(panel.confirm_edit(cx).is_none());
+        });
+        workspace
+            .update(cx, |workspace, _| {
+                assert_eq!(
+                    workspace.notification_ids(),
+                    [NotificationId::unique::<EntryAlreadyExists>()]
+                );
+            })
+            .unwrap();
+
+        // Renaming an entry to its own name isn't a collision.
+        panel.update(cx, |panel, cx| panel.cancel(&Cancel, cx));
+        select_path(&panel, "src/test/first.rs", cx);
+        panel.update(cx, |panel, cx| {
+            panel.rename(&Rename, cx);
+            panel
+                .filename_editor
+                .update(cx, |editor, cx| editor.set_text("first.rs", cx));
+            assert!(panel.confirm_edit(cx).is_none());
+            assert!(panel.edit_state.is_none());
+        });
+        workspace
+            .update(cx, |workspace, _| {
+                assert_eq!(
+                    workspace.notification_ids(),
+                    [NotificationId::unique::<EntryAlreadyExists>()]
+                );
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_dir_toggle_collapse(cx: &mut gpui::TestAppContext) {
         init_test_with_editor(cx);
@@ -3377,6 +4277,111 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_reveal_active_file(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectPanelSettings>(cx, |project_panel_settings| {
+                    project_panel_settings.auto_reveal_entries = Some(false)
+                });
+            })
+        });
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "dir_1": {
+                    "nested_dir": {
+                        "file_a.py": "# File contents",
+                    },
+                    "file_1.py": "# File contents",
+                },
+                "dir_2": {
+                    "file_1.py": "# File contents",
+                }
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v project_root", "    > dir_1", "    > dir_2"]
+        );
+
+        let worktree_id = project.update(cx, |project, cx| {
+            let worktree_id = project.worktrees().next().unwrap().read(cx).id();
+            project.set_active_path(
+                Some(ProjectPath {
+                    worktree_id,
+                    path: Path::new("dir_1/nested_dir/file_a.py").into(),
+                }),
+                cx,
+            );
+            worktree_id
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v project_root", "    > dir_1", "    > dir_2"],
+            "Without auto reveal, changing the active file should not expand anything"
+        );
+
+        panel.update(cx, |panel, cx| {
+            panel.reveal_active_file(&RevealActiveFile, cx)
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v dir_1",
+                "        v nested_dir",
+                "              file_a.py  <== selected",
+                "          file_1.py",
+                "    > dir_2",
+            ]
+        );
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                panel.selected_entry(cx).map(|(worktree, _)| worktree.id()),
+                Some(worktree_id)
+            );
+            assert_eq!(panel.scroll_handle.scroll_to_item_pending(), Some(3));
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel.collapse_all_entries(&CollapseAllEntries, cx)
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v project_root", "    > dir_1", "    > dir_2"]
+        );
+
+        // Directories collapsed with the rest stay collapsed when their parent is expanded again.
+        toggle_expand_dir(&panel, "project_root/dir_1", cx);
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v dir_1  <== selected",
+                "        > nested_dir",
+                "          file_1.py",
+                "    > dir_2",
+            ]
+        );
+    }
+
     #[gpui::test]
     async fn test_new_file_move(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -3983,6 +4988,8 @@ mod tests {
                 };
                 let selected = if details.is_selected {
                     "  <== selected"
+                } else if details.is_marked {
+                    "  <== marked"
                 } else {
                     ""
                 };
@@ -11,6 +11,17 @@ pub enum ProjectPanelDockPosition {
     Right,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectPanelSortOrder {
+    /// Sort entries alphabetically by name.
+    Name,
+    /// Sort files by extension, and then by name.
+    Type,
+    /// Sort entries by when they were last modified, most recent first.
+    ModifiedTime,
+}
+
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct ProjectPanelSettings {
     pub button: bool,
@@ -22,6 +33,8 @@ pub struct ProjectPanelSettings {
     pub indent_size: f32,
     pub auto_reveal_entries: bool,
     pub auto_fold_dirs: bool,
+    pub sort_order: ProjectPanelSortOrder,
+    pub folders_first: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
@@ -65,6 +78,14 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: false
     pub auto_fold_dirs: Option<bool>,
+    /// How to order the entries within each directory.
+    ///
+    /// Default: name
+    pub sort_order: Option<ProjectPanelSortOrder>,
+    /// Whether to list directories before files within each directory.
+    ///
+    /// Default: true
+    pub folders_first: Option<bool>,
 }
 
 impl Settings for ProjectPanelSettings {
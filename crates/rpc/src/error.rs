@@ -31,6 +31,11 @@
 /// }
 /// ```
 ///
+/// Requests that fail without reaching the server have codes too:
+/// ErrorCode::Disconnected when there is no connection or it closes before a
+/// response arrives, and ErrorCode::Timeout when no response arrives in time.
+/// This lets callers tell a transient failure apart from one the server sent.
+///
 use crate::proto;
 pub use proto::ErrorCode;
 
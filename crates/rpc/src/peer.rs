@@ -1,17 +1,21 @@
 use crate::{ErrorCode, ErrorCodeExt, ErrorExt, RpcError};
 
 use super::{
-    proto::{self, AnyTypedEnvelope, EnvelopedMessage, MessageStream, PeerId, RequestMessage},
+    proto::{
+        self, AnyTypedEnvelope, CompressionSettings, EnvelopedMessage, MessageStream, PeerId,
+        RequestMessage,
+    },
     Connection,
 };
 use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use futures::{
     channel::{mpsc, oneshot},
-    stream::BoxStream,
+    stream::{self, BoxStream, PollNext},
     FutureExt, SinkExt, Stream, StreamExt, TryFutureExt,
 };
 use parking_lot::{Mutex, RwLock};
+use prost::Message as _;
 use serde::{ser::SerializeStruct, Serialize};
 use std::{
     fmt, future,
@@ -19,7 +23,7 @@ use std::{
     marker::PhantomData,
     sync::atomic::Ordering::SeqCst,
     sync::{
-        atomic::{self, AtomicU32},
+        atomic::{self, AtomicBool, AtomicU32, AtomicU64, AtomicUsize},
         Arc,
     },
     time::Duration,
@@ -101,12 +105,32 @@ pub struct Peer {
     epoch: AtomicU32,
     pub connections: RwLock<HashMap<ConnectionId, ConnectionState>>,
     next_connection_id: AtomicU32,
+    keepalive: Mutex<KeepaliveSettings>,
+    keepalives_forbidden: AtomicBool,
+    compression: Mutex<CompressionSettings>,
+    protocol_warnings: Mutex<Vec<mpsc::UnboundedSender<ProtocolWarning>>>,
+}
+
+/// Something a connection received that it couldn't make sense of and skipped, most likely
+/// because the other side runs a different version of the protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolWarning {
+    /// A frame that couldn't be decoded into an envelope.
+    MalformedMessage { connection_id: ConnectionId },
+    /// An envelope whose payload this version doesn't know about.
+    UnknownMessage {
+        connection_id: ConnectionId,
+        message_id: u32,
+    },
 }
 
 #[derive(Clone, Serialize)]
 pub struct ConnectionState {
     #[serde(skip)]
     outgoing_tx: mpsc::UnboundedSender<proto::Message>,
+    /// Messages sent here are written before any waiting in `outgoing_tx`.
+    #[serde(skip)]
+    high_priority_outgoing_tx: mpsc::UnboundedSender<proto::Message>,
     next_message_id: Arc<AtomicU32>,
     #[allow(clippy::type_complexity)]
     #[serde(skip)]
@@ -129,21 +153,201 @@ pub struct ConnectionState {
             >,
         >,
     >,
+    #[serde(skip)]
+    counters: Arc<ConnectionCounters>,
+}
+
+impl ConnectionState {
+    fn outgoing_tx_for(&self, priority: SendPriority) -> &mpsc::UnboundedSender<proto::Message> {
+        match priority {
+            SendPriority::Normal => &self.outgoing_tx,
+            SendPriority::High => &self.high_priority_outgoing_tx,
+        }
+    }
+}
+
+/// The traffic that has gone over a connection, counting envelopes but not keepalives. Sizes
+/// are those of the encoded envelopes, before compression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub messages_sent: usize,
+    pub messages_received: usize,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub last_received_at: Option<Instant>,
+}
+
+struct ConnectionCounters {
+    messages_sent: AtomicUsize,
+    messages_received: AtomicUsize,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    /// Nanoseconds from `created_at` to when the last message was received, plus one so that
+    /// zero can mean nothing has been received yet.
+    last_received_at: AtomicU64,
+    created_at: Instant,
+}
+
+impl ConnectionCounters {
+    fn new() -> Self {
+        Self {
+            messages_sent: Default::default(),
+            messages_received: Default::default(),
+            bytes_sent: Default::default(),
+            bytes_received: Default::default(),
+            last_received_at: Default::default(),
+            created_at: Instant::now(),
+        }
+    }
+
+    fn sent(&self, envelope: &proto::Envelope) {
+        self.messages_sent.fetch_add(1, SeqCst);
+        self.bytes_sent
+            .fetch_add(envelope.encoded_len() as u64, SeqCst);
+    }
+
+    fn received(&self, envelope: &proto::Envelope, received_at: Instant) {
+        self.messages_received.fetch_add(1, SeqCst);
+        self.bytes_received
+            .fetch_add(envelope.encoded_len() as u64, SeqCst);
+        let since_created = received_at.saturating_duration_since(self.created_at);
+        self.last_received_at
+            .store(since_created.as_nanos() as u64 + 1, SeqCst);
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        let last_received_at = match self.last_received_at.load(SeqCst) {
+            0 => None,
+            nanos => Some(self.created_at + Duration::from_nanos(nanos - 1)),
+        };
+        ConnectionStats {
+            messages_sent: self.messages_sent.load(SeqCst),
+            messages_received: self.messages_received.load(SeqCst),
+            bytes_sent: self.bytes_sent.load(SeqCst),
+            bytes_received: self.bytes_received.load(SeqCst),
+            last_received_at,
+        }
+    }
+}
+
+/// Which of a connection's outgoing queues a message waits in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SendPriority {
+    #[default]
+    Normal,
+    /// For small interactive messages, like cursor movements, that shouldn't wait for bulk
+    /// ones sent before them.
+    High,
+}
+
+impl SendPriority {
+    /// The priority a message is sent with when none is given: high for the ones showing what
+    /// collaborators are doing right now, like where their cursors are and what they follow.
+    pub fn for_envelope(envelope: &proto::Envelope) -> Self {
+        use proto::envelope::Payload;
+        match &envelope.payload {
+            Some(
+                Payload::UpdateFollowers(_)
+                | Payload::UpdateParticipantLocation(_)
+                | Payload::UpdateUserPresence(_),
+            ) => Self::High,
+            // Receivers defer selections until they've seen the text they refer to, so these can
+            // overtake edits still waiting to be written.
+            Some(Payload::UpdateBuffer(update))
+                if !update.operations.is_empty()
+                    && update.operations.iter().all(|operation| {
+                        matches!(
+                            operation.variant,
+                            Some(proto::operation::Variant::UpdateSelections(_))
+                        )
+                    }) =>
+            {
+                Self::High
+            }
+            _ => Self::Normal,
+        }
+    }
 }
 
 const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(1);
 const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
 pub const RECEIVE_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// How a connection checks that the other side is still there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeepaliveSettings {
+    /// How long a connection can go without sending anything before it's pinged.
+    pub interval: Duration,
+    /// How long to wait for a message or ping from the other side before dropping the
+    /// connection as dead.
+    pub receive_timeout: Duration,
+}
+
+impl Default for KeepaliveSettings {
+    fn default() -> Self {
+        Self {
+            interval: KEEPALIVE_INTERVAL,
+            receive_timeout: RECEIVE_TIMEOUT,
+        }
+    }
+}
+
 impl Peer {
     pub fn new(epoch: u32) -> Arc<Self> {
         Arc::new(Self {
             epoch: AtomicU32::new(epoch),
             connections: Default::default(),
             next_connection_id: Default::default(),
+            keepalive: Default::default(),
+            keepalives_forbidden: Default::default(),
+            compression: Default::default(),
+            protocol_warnings: Default::default(),
         })
     }
 
+    /// Returns a stream of the protocol warnings of every connection from now on.
+    pub fn protocol_warnings(&self) -> mpsc::UnboundedReceiver<ProtocolWarning> {
+        let (tx, rx) = mpsc::unbounded();
+        self.protocol_warnings.lock().push(tx);
+        rx
+    }
+
+    fn warn(&self, warning: ProtocolWarning) {
+        self.protocol_warnings
+            .lock()
+            .retain(|tx| tx.unbounded_send(warning).is_ok());
+    }
+
+    /// Changes how connections added after this call are kept alive.
+    pub fn set_keepalive(&self, settings: KeepaliveSettings) {
+        *self.keepalive.lock() = settings;
+    }
+
+    pub fn keepalive(&self) -> KeepaliveSettings {
+        *self.keepalive.lock()
+    }
+
+    /// Changes which messages are compressed on connections added after this call.
+    pub fn set_compression(&self, settings: CompressionSettings) {
+        *self.compression.lock() = settings;
+    }
+
+    pub fn compression(&self) -> CompressionSettings {
+        *self.compression.lock()
+    }
+
+    /// Stops pinging the other side of every connection, so that idle connections look dead to
+    /// it.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn forbid_keepalives(&self) {
+        self.keepalives_forbidden.store(true, SeqCst);
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn allow_keepalives(&self) {
+        self.keepalives_forbidden.store(false, SeqCst);
+    }
+
     pub fn epoch(&self) -> u32 {
         self.epoch.load(SeqCst)
     }
@@ -172,7 +376,12 @@ impl Peer {
         #[cfg(not(any(test, feature = "test-support")))]
         const INCOMING_BUFFER_SIZE: usize = 256;
         let (mut incoming_tx, incoming_rx) = mpsc::channel(INCOMING_BUFFER_SIZE);
-        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded();
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded();
+        let (high_priority_outgoing_tx, high_priority_outgoing_rx) = mpsc::unbounded();
+        let mut outgoing_rx =
+            stream::select_with_strategy(high_priority_outgoing_rx, outgoing_rx, |_: &mut ()| {
+                PollNext::Left
+            });
 
         let connection_id = ConnectionId {
             owner_id: self.epoch.load(SeqCst),
@@ -180,14 +389,18 @@ impl Peer {
         };
         let connection_state = ConnectionState {
             outgoing_tx,
+            high_priority_outgoing_tx,
             next_message_id: Default::default(),
             response_channels: Arc::new(Mutex::new(Some(Default::default()))),
             stream_response_channels: Arc::new(Mutex::new(Some(Default::default()))),
+            counters: Arc::new(ConnectionCounters::new()),
         };
-        let mut writer = MessageStream::new(connection.tx);
+        let mut writer = MessageStream::new(connection.tx).with_compression(self.compression());
         let mut reader = MessageStream::new(connection.rx);
 
         let this = self.clone();
+        let keepalive = self.keepalive();
+        let counters = connection_state.counters.clone();
         let response_channels = connection_state.response_channels.clone();
         let stream_response_channels = connection_state.stream_response_channels.clone();
 
@@ -209,11 +422,11 @@ impl Peer {
             });
 
             // Send messages on this frequency so the connection isn't closed.
-            let keepalive_timer = create_timer(KEEPALIVE_INTERVAL).fuse();
+            let keepalive_timer = create_timer(keepalive.interval).fuse();
             futures::pin_mut!(keepalive_timer);
 
             // Disconnect if we don't receive messages at least this frequently.
-            let receive_timeout = create_timer(RECEIVE_TIMEOUT).fuse();
+            let receive_timeout = create_timer(keepalive.receive_timeout).fuse();
             futures::pin_mut!(receive_timeout);
 
             loop {
@@ -227,12 +440,15 @@ impl Peer {
                         outgoing = outgoing_rx.next().fuse() => match outgoing {
                             Some(outgoing) => {
                                 tracing::trace!(%connection_id, "outgoing rpc message: writing");
+                                if let proto::Message::Envelope(envelope) = &outgoing {
+                                    counters.sent(envelope);
+                                }
                                 futures::select_biased! {
                                     result = writer.write(outgoing).fuse() => {
                                         tracing::trace!(%connection_id, "outgoing rpc message: done writing");
                                         result.context("failed to write RPC message")?;
                                         tracing::trace!(%connection_id, "keepalive interval: resetting after sending message");
-                                        keepalive_timer.set(create_timer(KEEPALIVE_INTERVAL).fuse());
+                                        keepalive_timer.set(create_timer(keepalive.interval).fuse());
                                     }
                                     _ = create_timer(WRITE_TIMEOUT).fuse() => {
                                         tracing::trace!(%connection_id, "outgoing rpc message: writing timed out");
@@ -246,13 +462,18 @@ impl Peer {
                             },
                         },
                         _ = keepalive_timer => {
+                            if this.keepalives_forbidden.load(SeqCst) {
+                                tracing::trace!(%connection_id, "keepalive interval: pinging forbidden");
+                                keepalive_timer.set(create_timer(keepalive.interval).fuse());
+                                continue;
+                            }
                             tracing::trace!(%connection_id, "keepalive interval: pinging");
                             futures::select_biased! {
                                 result = writer.write(proto::Message::Ping).fuse() => {
                                     tracing::trace!(%connection_id, "keepalive interval: done pinging");
                                     result.context("failed to send keepalive")?;
                                     tracing::trace!(%connection_id, "keepalive interval: resetting after pinging");
-                                    keepalive_timer.set(create_timer(KEEPALIVE_INTERVAL).fuse());
+                                    keepalive_timer.set(create_timer(keepalive.interval).fuse());
                                 }
                                 _ = create_timer(WRITE_TIMEOUT).fuse() => {
                                     tracing::trace!(%connection_id, "keepalive interval: pinging timed out");
@@ -261,12 +482,21 @@ impl Peer {
                             }
                         }
                         incoming = read_message => {
-                            let incoming = incoming.context("error reading rpc message from socket")?;
+                            let incoming = match incoming {
+                                Err(error) if error.is::<proto::MalformedMessage>() => {
+                                    tracing::warn!(%connection_id, ?error, "incoming rpc message: skipping");
+                                    receive_timeout.set(create_timer(keepalive.receive_timeout).fuse());
+                                    this.warn(ProtocolWarning::MalformedMessage { connection_id });
+                                    break;
+                                }
+                                incoming => incoming.context("error reading rpc message from socket")?,
+                            };
                             tracing::trace!(%connection_id, "incoming rpc message: received");
                             tracing::trace!(%connection_id, "receive timeout: resetting");
-                            receive_timeout.set(create_timer(RECEIVE_TIMEOUT).fuse());
+                            receive_timeout.set(create_timer(keepalive.receive_timeout).fuse());
                             if let (proto::Message::Envelope(incoming), received_at) = incoming {
                                 tracing::trace!(%connection_id, "incoming rpc message: processing");
+                                counters.received(&incoming, received_at);
                                 futures::select_biased! {
                                     result = incoming_tx.send((incoming, received_at)).fuse() => match result {
                                         Ok(_) => {
@@ -300,9 +530,11 @@ impl Peer {
             .write()
             .insert(connection_id, connection_state);
 
+        let peer = Arc::downgrade(self);
         let incoming_rx = incoming_rx.filter_map(move |(incoming, received_at)| {
             let response_channels = response_channels.clone();
             let stream_response_channels = stream_response_channels.clone();
+            let peer = peer.clone();
             async move {
                 let message_id = incoming.id;
                 tracing::trace!(?incoming, "incoming message future: start");
@@ -398,6 +630,12 @@ impl Peer {
                                 message_id,
                                 "unable to construct a typed envelope"
                             );
+                            if let Some(peer) = peer.upgrade() {
+                                peer.warn(ProtocolWarning::UnknownMessage {
+                                    connection_id,
+                                    message_id,
+                                });
+                            }
                             None
                         },
                     )
@@ -421,6 +659,14 @@ impl Peer {
         self.add_connection(connection, move |duration| executor.timer(duration))
     }
 
+    /// Returns the traffic over the given connection so far, if it's still open.
+    pub fn stats(&self, connection_id: ConnectionId) -> Option<ConnectionStats> {
+        self.connections
+            .read()
+            .get(&connection_id)
+            .map(|connection| connection.counters.stats())
+    }
+
     pub fn disconnect(&self, connection_id: ConnectionId) {
         self.connections.write().remove(&connection_id);
     }
@@ -462,12 +708,64 @@ impl Peer {
             .map_ok(|envelope| envelope.payload)
     }
 
+    /// Sends a request, failing with [`ErrorCode::Timeout`] if no response arrives before
+    /// `timeout` completes. A response arriving after that is discarded.
+    pub fn request_with_timeout<T: RequestMessage>(
+        &self,
+        receiver_id: ConnectionId,
+        request: T,
+        timeout: impl Future<Output = ()>,
+    ) -> impl Future<Output = Result<T::Response>> {
+        let response_channels = self
+            .connection_state(receiver_id)
+            .ok()
+            .map(|connection| connection.response_channels);
+        let (message_id, response) = self.start_request(None, receiver_id, request);
+        async move {
+            let response = response.fuse();
+            let timeout = timeout.fuse();
+            futures::pin_mut!(response, timeout);
+            futures::select_biased! {
+                response = response => response.map(|envelope| envelope.payload),
+                _ = timeout => {
+                    if let Some((message_id, response_channels)) = message_id.zip(response_channels) {
+                        if let Some(response_channels) = response_channels.lock().as_mut() {
+                            response_channels.remove(&message_id);
+                        }
+                    }
+                    Err(RpcError::from_proto(
+                        &proto::Error {
+                            message: "request timed out".into(),
+                            code: ErrorCode::Timeout as i32,
+                            tags: Vec::new(),
+                        },
+                        T::NAME,
+                    ))
+                }
+            }
+        }
+    }
+
     pub fn request_internal<T: RequestMessage>(
         &self,
         original_sender_id: Option<ConnectionId>,
         receiver_id: ConnectionId,
         request: T,
     ) -> impl Future<Output = Result<TypedEnvelope<T::Response>>> {
+        self.start_request(original_sender_id, receiver_id, request)
+            .1
+    }
+
+    /// Sends a request, returning the id of the message it was sent in and its response.
+    fn start_request<T: RequestMessage>(
+        &self,
+        original_sender_id: Option<ConnectionId>,
+        receiver_id: ConnectionId,
+        request: T,
+    ) -> (
+        Option<u32>,
+        impl Future<Output = Result<TypedEnvelope<T::Response>>>,
+    ) {
         let (tx, rx) = oneshot::channel();
         let send = self.connection_state(receiver_id).and_then(|connection| {
             let message_id = connection.next_message_id.fetch_add(1, SeqCst);
@@ -475,22 +773,20 @@ impl Peer {
                 .response_channels
                 .lock()
                 .as_mut()
-                .ok_or_else(|| anyhow!("connection was closed"))?
+                .ok_or_else(connection_closed)?
                 .insert(message_id, tx);
+            let envelope =
+                request.into_envelope(message_id, None, original_sender_id.map(Into::into));
             connection
-                .outgoing_tx
-                .unbounded_send(proto::Message::Envelope(request.into_envelope(
-                    message_id,
-                    None,
-                    original_sender_id.map(Into::into),
-                )))
-                .map_err(|_| anyhow!("connection was closed"))?;
-            Ok(())
+                .outgoing_tx_for(SendPriority::for_envelope(&envelope))
+                .unbounded_send(proto::Message::Envelope(envelope))
+                .map_err(|_| connection_closed())?;
+            Ok(message_id)
         });
-        async move {
+        let message_id = send.as_ref().ok().copied();
+        let response = async move {
             send?;
-            let (response, received_at, _barrier) =
-                rx.await.map_err(|_| anyhow!("connection was closed"))?;
+            let (response, received_at, _barrier) = rx.await.map_err(|_| connection_closed())?;
 
             if let Some(proto::envelope::Payload::Error(error)) = &response.payload {
                 Err(RpcError::from_proto(&error, T::NAME))
@@ -504,7 +800,8 @@ impl Peer {
                     received_at,
                 })
             }
-        }
+        };
+        (message_id, response)
     }
 
     pub fn request_stream<T: RequestMessage>(
@@ -519,14 +816,14 @@ impl Peer {
             stream_response_channels
                 .lock()
                 .as_mut()
-                .ok_or_else(|| anyhow!("connection was closed"))?
+                .ok_or_else(connection_closed)?
                 .insert(message_id, tx);
             connection
                 .outgoing_tx
                 .unbounded_send(proto::Message::Envelope(
                     request.into_envelope(message_id, None, None),
                 ))
-                .map_err(|_| anyhow!("connection was closed"))?;
+                .map_err(|_| connection_closed())?;
             Ok((message_id, stream_response_channels))
         });
 
@@ -539,11 +836,7 @@ impl Peer {
                 future::ready(match response {
                     Ok(response) => {
                         if let Some(proto::envelope::Payload::Error(error)) = &response.payload {
-                            Some(Err(anyhow!(
-                                "RPC request {} failed - {}",
-                                T::NAME,
-                                error.message
-                            )))
+                            Some(Err(RpcError::from_proto(error, T::NAME)))
                         } else if let Some(proto::envelope::Payload::EndStream(_)) =
                             &response.payload
                         {
@@ -572,8 +865,27 @@ impl Peer {
         let message_id = connection
             .next_message_id
             .fetch_add(1, atomic::Ordering::SeqCst);
+        let envelope = message.into_envelope(message_id, None, None);
         connection
-            .outgoing_tx
+            .outgoing_tx_for(SendPriority::for_envelope(&envelope))
+            .unbounded_send(proto::Message::Envelope(envelope))?;
+        Ok(())
+    }
+
+    /// Sends a message, writing it ahead of any lower priority ones still waiting to be
+    /// written. Messages of the same priority are written in the order they're sent.
+    pub fn send_with_priority<T: EnvelopedMessage>(
+        &self,
+        receiver_id: ConnectionId,
+        message: T,
+        priority: SendPriority,
+    ) -> Result<()> {
+        let connection = self.connection_state(receiver_id)?;
+        let message_id = connection
+            .next_message_id
+            .fetch_add(1, atomic::Ordering::SeqCst);
+        connection
+            .outgoing_tx_for(priority)
             .unbounded_send(proto::Message::Envelope(
                 message.into_envelope(message_id, None, None),
             ))?;
@@ -590,13 +902,10 @@ impl Peer {
         let message_id = connection
             .next_message_id
             .fetch_add(1, atomic::Ordering::SeqCst);
+        let envelope = message.into_envelope(message_id, None, Some(sender_id.into()));
         connection
-            .outgoing_tx
-            .unbounded_send(proto::Message::Envelope(message.into_envelope(
-                message_id,
-                None,
-                Some(sender_id.into()),
-            )))?;
+            .outgoing_tx_for(SendPriority::for_envelope(&envelope))
+            .unbounded_send(proto::Message::Envelope(envelope))?;
         Ok(())
     }
 
@@ -689,6 +998,13 @@ impl Peer {
     }
 }
 
+/// The error requests fail with when their connection closes before they get a response.
+fn connection_closed() -> anyhow::Error {
+    ErrorCode::Disconnected
+        .message("connection was closed".into())
+        .anyhow()
+}
+
 impl Serialize for Peer {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1021,6 +1337,203 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_request_with_timeout(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let server = Peer::new(0);
+        let client = Peer::new(0);
+
+        let (client_to_server_conn, server_to_client_conn, _kill) =
+            Connection::in_memory(cx.executor());
+        let (client_to_server_conn_id, io_task1, _client_incoming) =
+            client.add_test_connection(client_to_server_conn, cx.executor());
+        let (_, io_task2, mut server_incoming) =
+            server.add_test_connection(server_to_client_conn, cx.executor());
+        executor.spawn(io_task1).detach();
+        executor.spawn(io_task2).detach();
+
+        let response = executor.spawn(client.request_with_timeout(
+            client_to_server_conn_id,
+            proto::Ping {},
+            executor.timer(Duration::from_secs(1)),
+        ));
+        let request = server_incoming
+            .next()
+            .await
+            .unwrap()
+            .into_any()
+            .downcast::<TypedEnvelope<proto::Ping>>()
+            .unwrap();
+        executor.advance_clock(Duration::from_secs(1));
+        let error = response.await.unwrap_err();
+        assert_eq!(error.error_code(), ErrorCode::Timeout);
+        assert!(client
+            .connection_state(client_to_server_conn_id)
+            .unwrap()
+            .response_channels
+            .lock()
+            .as_ref()
+            .unwrap()
+            .is_empty());
+
+        // A late response is discarded.
+        server.respond(request.receipt(), proto::Ack {}).unwrap();
+        executor.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_connection_stats(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let server = Peer::new(0);
+        let client = Peer::new(0);
+
+        let (client_to_server_conn, server_to_client_conn, _kill) =
+            Connection::in_memory(cx.executor());
+        let (client_to_server_conn_id, io_task1, _client_incoming) =
+            client.add_test_connection(client_to_server_conn, cx.executor());
+        let (server_to_client_conn_id, io_task2, mut server_incoming) =
+            server.add_test_connection(server_to_client_conn, cx.executor());
+        executor.spawn(io_task1).detach();
+        executor.spawn(io_task2).detach();
+        executor
+            .spawn({
+                let server = server.clone();
+                async move {
+                    while let Some(envelope) = server_incoming.next().await {
+                        let envelope = envelope
+                            .into_any()
+                            .downcast::<TypedEnvelope<proto::Ping>>()
+                            .unwrap();
+                        server.respond(envelope.receipt(), proto::Ack {}).unwrap();
+                    }
+                }
+            })
+            .detach();
+
+        let stats = client.stats(client_to_server_conn_id).unwrap();
+        assert_eq!(stats.messages_sent, 0);
+        assert_eq!(stats.messages_received, 0);
+        assert_eq!(stats.bytes_sent, 0);
+        assert_eq!(stats.last_received_at, None);
+
+        client
+            .request(client_to_server_conn_id, proto::Ping {})
+            .await
+            .unwrap();
+        client
+            .request(client_to_server_conn_id, proto::Ping {})
+            .await
+            .unwrap();
+        executor.run_until_parked();
+
+        let client_stats = client.stats(client_to_server_conn_id).unwrap();
+        let server_stats = server.stats(server_to_client_conn_id).unwrap();
+        assert_eq!(client_stats.messages_sent, 2);
+        assert_eq!(client_stats.messages_received, 2);
+        assert!(client_stats.bytes_sent > 0);
+        assert!(client_stats.last_received_at.is_some());
+        assert_eq!(server_stats.messages_sent, client_stats.messages_received);
+        assert_eq!(server_stats.messages_received, client_stats.messages_sent);
+        assert_eq!(server_stats.bytes_sent, client_stats.bytes_received);
+        assert_eq!(server_stats.bytes_received, client_stats.bytes_sent);
+
+        client.disconnect(client_to_server_conn_id);
+        assert_eq!(client.stats(client_to_server_conn_id), None);
+    }
+
+    #[gpui::test]
+    async fn test_compressed_messages(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let server = Peer::new(0);
+        let client = Peer::new(0);
+        client.set_compression(CompressionSettings {
+            enabled: true,
+            threshold: 64,
+        });
+
+        let (client_to_server_conn, server_to_client_conn, _kill) =
+            Connection::in_memory(cx.executor());
+        let (client_to_server_conn_id, io_task1, _client_incoming) =
+            client.add_test_connection(client_to_server_conn, cx.executor());
+        let (_, io_task2, mut server_incoming) =
+            server.add_test_connection(server_to_client_conn, cx.executor());
+        executor.spawn(io_task1).detach();
+        executor.spawn(io_task2).detach();
+
+        // Messages on either side of the threshold arrive intact.
+        for root_name in ["a".repeat(8), "abcdefg".repeat(1000)] {
+            let message = proto::UpdateWorktree {
+                root_name,
+                ..Default::default()
+            };
+            client
+                .send(client_to_server_conn_id, message.clone())
+                .unwrap();
+            let received = server_incoming
+                .next()
+                .await
+                .unwrap()
+                .into_any()
+                .downcast::<TypedEnvelope<proto::UpdateWorktree>>()
+                .unwrap();
+            assert_eq!(received.payload, message);
+        }
+    }
+
+    #[gpui::test]
+    async fn test_skipping_unreadable_messages(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let (client_conn, mut server_conn, _kill) = Connection::in_memory(executor.clone());
+
+        let client = Peer::new(0);
+        let mut warnings = client.protocol_warnings();
+        let (connection_id, io_handler, mut incoming) =
+            client.add_test_connection(client_conn, executor.clone());
+        executor.spawn(io_handler).detach();
+
+        // A frame that isn't an envelope, then an envelope without a known payload.
+        server_conn
+            .send(WebSocketMessage::Binary(vec![0, 0xff, 0xff]))
+            .await
+            .unwrap();
+        let mut writer = MessageStream::new(server_conn.tx);
+        writer
+            .write(proto::Message::Envelope(proto::Envelope {
+                id: 1,
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        writer
+            .write(proto::Message::Envelope(
+                proto::Test { id: 7 }.into_envelope(2, None, None),
+            ))
+            .await
+            .unwrap();
+
+        // The connection survives both, and later messages still arrive.
+        let message = incoming
+            .next()
+            .await
+            .unwrap()
+            .into_any()
+            .downcast::<TypedEnvelope<proto::Test>>()
+            .unwrap();
+        assert_eq!(message.payload.id, 7);
+        assert_eq!(
+            warnings.next().await,
+            Some(ProtocolWarning::MalformedMessage { connection_id })
+        );
+        assert_eq!(
+            warnings.next().await,
+            Some(ProtocolWarning::UnknownMessage {
+                connection_id,
+                message_id: 1
+            })
+        );
+        assert!(client.stats(connection_id).is_some());
+    }
+
     #[gpui::test(iterations = 50)]
     async fn test_disconnect(cx: &mut TestAppContext) {
         let executor = cx.executor();
@@ -1074,9 +1587,8 @@ mod tests {
         let _request = server_conn.rx.next().await.unwrap().unwrap();
 
         drop(server_conn);
-        assert_eq!(
-            response.await.unwrap_err().to_string(),
-            "connection was closed"
-        );
+        let error = response.await.unwrap_err();
+        assert_eq!(error.to_string(), "connection was closed");
+        assert_eq!(error.error_code(), ErrorCode::Disconnected);
     }
 }
@@ -1,7 +1,7 @@
 #![allow(non_snake_case)]
 
 use super::{entity_messages, messages, request_messages, ConnectionId, TypedEnvelope};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use async_tungstenite::tungstenite::Message as WebSocketMessage;
 use collections::HashMap;
 use futures::{SinkExt as _, StreamExt as _};
@@ -295,6 +295,7 @@ messages!(
     (UpdateInviteInfo, Foreground),
     (UpdateLanguageServer, Foreground),
     (UpdateParticipantLocation, Foreground),
+    (UpdateUserPresence, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
     (UpdateWorktree, Foreground),
@@ -502,11 +503,46 @@ entity_messages!(
 const KIB: usize = 1024;
 const MIB: usize = KIB * 1024;
 const MAX_BUFFER_LEN: usize = MIB;
+const COMPRESSION_THRESHOLD: usize = KIB;
+
+/// The first byte of every binary frame, saying whether the envelope after it is compressed.
+const UNCOMPRESSED_FRAME: u8 = 0;
+const COMPRESSED_FRAME: u8 = 1;
+
+/// The context of errors reading frames that don't hold a valid envelope. Frames after one
+/// of these can still be read.
+#[derive(Debug)]
+pub struct MalformedMessage;
+
+impl fmt::Display for MalformedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "received a malformed message")
+    }
+}
+
+/// Which envelopes a [`MessageStream`] compresses before writing them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressionSettings {
+    pub enabled: bool,
+    /// The encoded size in bytes above which envelopes are compressed. Compressing smaller
+    /// ones would cost more time than it saves.
+    pub threshold: usize,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: COMPRESSION_THRESHOLD,
+        }
+    }
+}
 
 /// A stream of protobuf messages.
 pub struct MessageStream<S> {
     stream: S,
     encoding_buffer: Vec<u8>,
+    compression: CompressionSettings,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -522,9 +558,17 @@ impl<S> MessageStream<S> {
         Self {
             stream,
             encoding_buffer: Vec::new(),
+            compression: CompressionSettings::default(),
         }
     }
 
+    /// Sets which envelopes are compressed when written. Envelopes are read whether they're
+    /// compressed or not.
+    pub fn with_compression(mut self, compression: CompressionSettings) -> Self {
+        self.compression = compression;
+        self
+    }
+
     pub fn inner_mut(&mut self) -> &mut S {
         &mut self.stream
     }
@@ -547,9 +591,23 @@ where
                 message
                     .encode(&mut self.encoding_buffer)
                     .map_err(io::Error::from)?;
-                let buffer =
-                    zstd::stream::encode_all(self.encoding_buffer.as_slice(), COMPRESSION_LEVEL)
-                        .unwrap();
+                let buffer = if self.compression.enabled
+                    && self.encoding_buffer.len() > self.compression.threshold
+                {
+                    let mut buffer = vec![COMPRESSED_FRAME];
+                    zstd::stream::copy_encode(
+                        self.encoding_buffer.as_slice(),
+                        &mut buffer,
+                        COMPRESSION_LEVEL,
+                    )
+                    .unwrap();
+                    buffer
+                } else {
+                    let mut buffer = Vec::with_capacity(self.encoding_buffer.len() + 1);
+                    buffer.push(UNCOMPRESSED_FRAME);
+                    buffer.extend_from_slice(&self.encoding_buffer);
+                    buffer
+                };
 
                 self.encoding_buffer.clear();
                 self.encoding_buffer.shrink_to(MAX_BUFFER_LEN);
@@ -580,12 +638,10 @@ where
             let received_at = Instant::now();
             match bytes? {
                 WebSocketMessage::Binary(bytes) => {
-                    zstd::stream::copy_decode(bytes.as_slice(), &mut self.encoding_buffer).unwrap();
-                    let envelope = Envelope::decode(self.encoding_buffer.as_slice())
-                        .map_err(io::Error::from)?;
-
+                    let envelope = self.decode_frame(&bytes);
                     self.encoding_buffer.clear();
                     self.encoding_buffer.shrink_to(MAX_BUFFER_LEN);
+                    let envelope = envelope.context(MalformedMessage)?;
                     return Ok((Message::Envelope(envelope), received_at));
                 }
                 WebSocketMessage::Ping(_) => return Ok((Message::Ping, received_at)),
@@ -596,6 +652,17 @@ where
         }
         Err(anyhow!("connection closed"))
     }
+
+    fn decode_frame(&mut self, frame: &[u8]) -> Result<Envelope> {
+        match frame.split_first() {
+            Some((&COMPRESSED_FRAME, compressed)) => {
+                zstd::stream::copy_decode(compressed, &mut self.encoding_buffer)?;
+                Ok(Envelope::decode(self.encoding_buffer.as_slice())?)
+            }
+            Some((&UNCOMPRESSED_FRAME, encoded)) => Ok(Envelope::decode(encoded)?),
+            _ => Err(anyhow!("unknown frame kind")),
+        }
+    }
 }
 
 impl From<Timestamp> for SystemTime {
@@ -737,6 +804,57 @@ mod tests {
         assert!(stream.encoding_buffer.capacity() <= MAX_BUFFER_LEN);
     }
 
+    #[gpui::test]
+    async fn test_compression_threshold() {
+        let small = Envelope {
+            payload: Some(envelope::Payload::UpdateWorktree(UpdateWorktree {
+                root_name: "a".repeat(10),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let large = Envelope {
+            payload: Some(envelope::Payload::UpdateWorktree(UpdateWorktree {
+                root_name: "a".repeat(10 * KIB),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let mut sink = MessageStream::new(tx.sink_map_err(|_| anyhow!("")));
+        sink.write(Message::Envelope(small.clone())).await.unwrap();
+        sink.write(Message::Envelope(large.clone())).await.unwrap();
+        let mut sink = sink.with_compression(CompressionSettings {
+            enabled: false,
+            ..Default::default()
+        });
+        sink.write(Message::Envelope(large.clone())).await.unwrap();
+        drop(sink);
+
+        let frames = rx.collect::<Vec<_>>().await;
+        let frame_kinds = frames
+            .iter()
+            .map(|frame| match frame {
+                WebSocketMessage::Binary(bytes) => bytes[0],
+                _ => panic!("expected a binary frame"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frame_kinds,
+            [UNCOMPRESSED_FRAME, COMPRESSED_FRAME, UNCOMPRESSED_FRAME]
+        );
+
+        // Either kind of frame reads back as the envelope that was written.
+        let mut stream = MessageStream::new(futures::stream::iter(frames).map(anyhow::Ok));
+        for expected in [small, large.clone(), large] {
+            match stream.read().await.unwrap().0 {
+                Message::Envelope(envelope) => assert_eq!(envelope, expected),
+                _ => panic!("expected an envelope"),
+            }
+        }
+    }
+
     #[gpui::test]
     fn test_converting_peer_id_from_and_to_u64() {
         let peer_id = PeerId {
@@ -10,7 +10,8 @@ use util::asset_str;
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
 pub use settings_store::{
-    Settings, SettingsJsonSchemaParams, SettingsLocation, SettingsSources, SettingsStore,
+    Settings, SettingsJsonSchemaParams, SettingsLayer, SettingsLocation, SettingsParseError,
+    SettingsSources, SettingsStore,
 };
 
 #[derive(RustEmbed)]
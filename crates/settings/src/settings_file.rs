@@ -4,7 +4,7 @@ use fs::Fs;
 use futures::{channel::mpsc, StreamExt};
 use gpui::{AppContext, BackgroundExecutor, BorrowAppContext};
 use std::{io::ErrorKind, path::PathBuf, sync::Arc, time::Duration};
-use util::{paths, ResultExt};
+use util::paths;
 
 pub const EMPTY_THEME_NAME: &str = "empty-theme";
 
@@ -62,26 +62,32 @@ pub fn watch_config_file(
     rx
 }
 
+/// Applies the user settings file whenever it changes, reporting the outcome to
+/// `settings_changed`.
+///
+/// When the file is invalid, the current settings are kept and the error wraps a
+/// [`SettingsParseError`](crate::SettingsParseError) with the position of the problem.
 pub fn handle_settings_file_changes(
     mut user_settings_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
+    settings_changed: impl Fn(Option<anyhow::Error>, &mut AppContext) + 'static,
 ) {
     let user_settings_content = cx
         .background_executor()
         .block(user_settings_file_rx.next())
         .unwrap();
-    cx.update_global(|store: &mut SettingsStore, cx| {
-        store
-            .set_user_settings(&user_settings_content, cx)
-            .log_err();
+    let result = cx.update_global(|store: &mut SettingsStore, cx| {
+        store.set_user_settings(&user_settings_content, cx)
     });
+    settings_changed(result.err(), cx);
     cx.spawn(move |mut cx| async move {
         while let Some(user_settings_content) = user_settings_file_rx.next().await {
-            let result = cx.update_global(|store: &mut SettingsStore, cx| {
-                store
-                    .set_user_settings(&user_settings_content, cx)
-                    .log_err();
+            let result = cx.update(|cx| {
+                let result = cx.update_global(|store: &mut SettingsStore, cx| {
+                    store.set_user_settings(&user_settings_content, cx)
+                });
                 cx.refresh();
+                settings_changed(result.err(), cx);
             });
             if result.is_err() {
                 break; // App dropped
@@ -134,3 +140,68 @@ pub fn update_settings_file<T: Settings>(
     })
     .detach_and_log_err(cx);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SettingsParseError, SettingsSources};
+    use gpui::TestAppContext;
+    use serde::Deserialize;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TurboSetting(bool);
+
+    impl Settings for TurboSetting {
+        const KEY: Option<&'static str> = Some("turbo");
+        type FileContent = Option<bool>;
+
+        fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+            sources.json_merge()
+        }
+    }
+
+    #[gpui::test]
+    fn test_invalid_settings_file_changes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let mut store = SettingsStore::default();
+            store.register_setting::<TurboSetting>(cx);
+            store
+                .set_default_settings(r#"{ "turbo": false }"#, cx)
+                .unwrap();
+            cx.set_global(store);
+        });
+
+        let (tx, rx) = mpsc::unbounded();
+        tx.unbounded_send(r#"{ "turbo": true }"#.to_string())
+            .unwrap();
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            handle_settings_file_changes(rx, cx, {
+                let errors = errors.clone();
+                move |error, _| {
+                    errors
+                        .borrow_mut()
+                        .push(error.map(|error| error.downcast::<SettingsParseError>().unwrap()))
+                }
+            })
+        });
+        assert_eq!(errors.borrow_mut().drain(..).collect::<Vec<_>>(), [None]);
+        cx.update(|cx| assert_eq!(TurboSetting::get_global(cx), &TurboSetting(true)));
+
+        // An invalid edit reports where the file is broken, and keeps the settings as they were.
+        tx.unbounded_send("{\n    \"turbo\": false,\n    \"user\" }".to_string())
+            .unwrap();
+        cx.run_until_parked();
+        let error = errors.borrow_mut().pop().unwrap().unwrap();
+        assert_eq!((error.line, error.column), (3, 12));
+        cx.update(|cx| assert_eq!(TurboSetting::get_global(cx), &TurboSetting(true)));
+
+        // Fixing the file applies it again.
+        tx.unbounded_send(r#"{ "turbo": false }"#.to_string())
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(errors.borrow_mut().pop(), Some(None));
+        cx.update(|cx| assert_eq!(TurboSetting::get_global(cx), &TurboSetting(false)));
+    }
+}
@@ -7,7 +7,7 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
-    fmt::Debug,
+    fmt::{self, Debug},
     ops::Range,
     path::Path,
     str,
@@ -144,6 +144,22 @@ pub struct SettingsLocation<'a> {
     pub path: &'a Path,
 }
 
+/// One of the layers of settings that are merged into the effective settings, listed from
+/// least to most specific.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsLayer {
+    Default,
+    Extension,
+    User,
+    /// The user settings nested under the name of the current release channel.
+    ReleaseChannel,
+    /// A `.zed/settings.json` file in a project, applying to the files under `path`.
+    Project {
+        worktree_id: usize,
+        path: Arc<Path>,
+    },
+}
+
 pub struct SettingsJsonSchemaParams<'a> {
     pub staff_mode: bool,
     pub language_names: &'a [String],
@@ -425,6 +441,9 @@ impl SettingsStore {
     }
 
     /// Sets the user settings via a JSON string.
+    ///
+    /// If the content can't be parsed, the current settings are kept and the error wraps a
+    /// [`SettingsParseError`] saying where the content is invalid.
     pub fn set_user_settings(
         &mut self,
         user_settings_content: &str,
@@ -432,8 +451,12 @@ impl SettingsStore {
     ) -> Result<()> {
         let settings: serde_json::Value = parse_json_with_comments(user_settings_content)?;
         if settings.is_object() {
-            self.raw_user_settings = settings;
-            self.recompute_values(None, cx)?;
+            let previous_settings = std::mem::replace(&mut self.raw_user_settings, settings);
+            if let Err(error) = self.recompute_values(None, cx) {
+                self.raw_user_settings = previous_settings;
+                self.recompute_values(None, cx).log_err();
+                return Err(error);
+            }
             Ok(())
         } else {
             Err(anyhow!("settings must be an object"))
@@ -449,8 +472,12 @@ impl SettingsStore {
         cx: &mut AppContext,
     ) -> Result<()> {
         if let Some(content) = settings_content {
+            let settings = parse_json_with_comments(content)?;
+            for key in self.unknown_keys(&settings) {
+                log::warn!("unknown setting {key:?} in the project settings at {path:?}");
+            }
             self.raw_local_settings
-                .insert((root_id, path.clone()), parse_json_with_comments(content)?);
+                .insert((root_id, path.clone()), settings);
         } else {
             self.raw_local_settings.remove(&(root_id, path.clone()));
         }
@@ -486,6 +513,80 @@ impl SettingsStore {
             .map(|((_, path), content)| (path.clone(), serde_json::to_string(content).unwrap()))
     }
 
+    /// Returns the most specific layer that sets the value at `key_path` for files at `location`,
+    /// or `None` if no layer sets it.
+    ///
+    /// Objects are merged key by key across the layers, while any other value, including an
+    /// array, replaces the one from the layers below it.
+    pub fn layer_for_key(
+        &self,
+        key_path: &[&str],
+        location: Option<SettingsLocation>,
+    ) -> Option<SettingsLayer> {
+        fn sets_key(settings: &serde_json::Value, key_path: &[&str]) -> bool {
+            let mut value = settings;
+            for key in key_path {
+                match value.get(key) {
+                    Some(nested) => value = nested,
+                    None => return false,
+                }
+            }
+            !value.is_null()
+        }
+
+        if let Some(SettingsLocation { worktree_id, path }) = location {
+            for ((root_id, settings_path), settings) in self.raw_local_settings.iter().rev() {
+                if *root_id == worktree_id
+                    && path.starts_with(settings_path)
+                    && sets_key(settings, key_path)
+                {
+                    return Some(SettingsLayer::Project {
+                        worktree_id,
+                        path: settings_path.clone(),
+                    });
+                }
+            }
+        }
+        if let Some(release_settings) = self
+            .raw_user_settings
+            .get(release_channel::RELEASE_CHANNEL.dev_name())
+        {
+            if sets_key(release_settings, key_path) {
+                return Some(SettingsLayer::ReleaseChannel);
+            }
+        }
+        if sets_key(&self.raw_user_settings, key_path) {
+            Some(SettingsLayer::User)
+        } else if sets_key(&self.raw_extension_settings, key_path) {
+            Some(SettingsLayer::Extension)
+        } else if sets_key(&self.raw_default_settings, key_path) {
+            Some(SettingsLayer::Default)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the top-level keys of `settings` that no registered setting reads.
+    ///
+    /// Settings without a key read from the top level, so the keys in the default settings
+    /// are known too.
+    fn unknown_keys(&self, settings: &serde_json::Value) -> Vec<String> {
+        let Some(settings) = settings.as_object() else {
+            return Vec::new();
+        };
+        settings
+            .keys()
+            .filter(|key| {
+                self.raw_default_settings.get(key.as_str()).is_none()
+                    && !self
+                        .setting_values
+                        .values()
+                        .any(|setting_value| setting_value.key() == Some(key.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn json_schema(
         &self,
         schema_params: &SettingsJsonSchemaParams,
@@ -516,6 +617,15 @@ impl SettingsStore {
                     .entry(key.to_string())
                     .or_insert_with(|| Schema::Object(SchemaObject::default()));
                 if let Schema::Object(key_schema) = key_schema {
+                    // Describe the key with the doc comment of the setting's file content.
+                    if let Some(description) = setting_schema
+                        .schema
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.description.clone())
+                    {
+                        key_schema.metadata().description.get_or_insert(description);
+                    }
                     key_schema
                 } else {
                     continue;
@@ -581,6 +691,25 @@ impl SettingsStore {
             }
         }
 
+        /// Records the value each property takes in the default settings as its default.
+        fn add_defaults(schema: &mut SchemaObject, defaults: &serde_json::Value) {
+            let (Some(object), Some(defaults)) = (schema.object.as_mut(), defaults.as_object())
+            else {
+                return;
+            };
+            for (key, property) in &mut object.properties {
+                if let (Schema::Object(property), Some(default)) = (property, defaults.get(key)) {
+                    property
+                        .metadata()
+                        .default
+                        .get_or_insert_with(|| default.clone());
+                    add_defaults(property, default);
+                }
+            }
+        }
+
+        add_defaults(&mut combined_schema.schema, &self.raw_default_settings);
+
         for release_stage in ["dev", "nightly", "stable", "preview"] {
             let schema = combined_schema.schema.clone();
             combined_schema
@@ -1020,8 +1149,34 @@ fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len:
     adjusted_text
 }
 
+/// An error in the JSON of a settings file, with the 1-based position where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SettingsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SettingsParseError {}
+
+impl From<serde_json_lenient::Error> for SettingsParseError {
+    fn from(error: serde_json_lenient::Error) -> Self {
+        Self {
+            line: error.line(),
+            column: error.column(),
+            message: error.to_string(),
+        }
+    }
+}
+
 pub fn parse_json_with_comments<T: DeserializeOwned>(content: &str) -> Result<T> {
-    Ok(serde_json_lenient::from_str(content)?)
+    serde_json_lenient::from_str(content).map_err(|error| SettingsParseError::from(error).into())
 }
 
 #[cfg(test)]
@@ -1159,6 +1314,167 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_project_settings_layers(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<TurboSetting>(cx);
+        store.register_setting::<ExcludedFilesSetting>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "turbo": false,
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    },
+                    "excluded_files": ["**/.git"]
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{ "user": { "name": "Max", "age": 31 }, "excluded_files": ["**/target"] }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_local_settings(
+                1,
+                Path::new("/root1").into(),
+                Some(r#"{ "user": { "age": 40 }, "excluded_files": ["*.log"], "unknown": true }"#),
+                cx,
+            )
+            .unwrap();
+
+        let location = Some(SettingsLocation {
+            worktree_id: 1,
+            path: Path::new("/root1/something"),
+        });
+        // Nested objects are merged key by key, with the project winning.
+        assert_eq!(
+            store.get::<UserSettings>(location),
+            &UserSettings {
+                name: "Max".to_string(),
+                age: 40,
+                staff: false,
+            }
+        );
+        assert_eq!(
+            store.layer_for_key(&["user", "age"], location),
+            Some(SettingsLayer::Project {
+                worktree_id: 1,
+                path: Path::new("/root1").into(),
+            })
+        );
+        assert_eq!(
+            store.layer_for_key(&["user", "name"], location),
+            Some(SettingsLayer::User)
+        );
+        assert_eq!(
+            store.layer_for_key(&["user", "staff"], location),
+            Some(SettingsLayer::Default)
+        );
+        assert_eq!(
+            store.layer_for_key(&["user", "age"], None),
+            Some(SettingsLayer::User)
+        );
+        assert_eq!(store.layer_for_key(&["user", "height"], location), None);
+
+        // Arrays are replaced by the more specific layer, rather than concatenated.
+        assert_eq!(
+            store.get::<ExcludedFilesSetting>(location),
+            &ExcludedFilesSetting(vec!["*.log".to_string()])
+        );
+        assert_eq!(
+            store.get::<ExcludedFilesSetting>(None),
+            &ExcludedFilesSetting(vec!["**/target".to_string()])
+        );
+        assert_eq!(
+            store.layer_for_key(&["excluded_files"], location),
+            Some(SettingsLayer::Project {
+                worktree_id: 1,
+                path: Path::new("/root1").into(),
+            })
+        );
+
+        // Unknown keys are reported, but the rest of the file still applies.
+        assert_eq!(
+            store.unknown_keys(&serde_json::json!({ "turbo": true, "unknown": true })),
+            ["unknown"]
+        );
+    }
+
+    #[gpui::test]
+    fn test_json_schema(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "turbo": false,
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        let schema = store.json_schema(
+            &SettingsJsonSchemaParams {
+                staff_mode: false,
+                language_names: &[],
+                font_names: &[],
+            },
+            cx,
+        );
+        let properties = &schema["properties"];
+        assert_eq!(properties["turbo"]["type"], "boolean");
+        assert_eq!(properties["turbo"]["default"], false);
+        assert_eq!(
+            properties["user"]["description"],
+            "The user's details, shown next to their edits."
+        );
+        assert_eq!(properties["user"]["properties"]["age"]["default"], 30);
+        assert_eq!(
+            properties["user"]["properties"]["name"]["description"],
+            "The name shown to collaborators."
+        );
+        assert_eq!(
+            properties["user"]["properties"]["name"]["default"],
+            "John Doe"
+        );
+    }
+
+    #[gpui::test]
+    fn test_invalid_user_settings(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_default_settings(r#"{ "turbo": false }"#, cx)
+            .unwrap();
+        store.set_user_settings(r#"{ "turbo": true }"#, cx).unwrap();
+
+        let error = store
+            .set_user_settings("{\n    \"turbo\": false,\n    \"user\" }", cx)
+            .unwrap_err();
+        let error = error.downcast_ref::<SettingsParseError>().unwrap();
+        assert_eq!((error.line, error.column), (3, 12));
+
+        // The settings from before the invalid edit are kept.
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(true));
+        assert_eq!(
+            store.raw_user_settings,
+            serde_json::json!({ "turbo": true })
+        );
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut AppContext) {
         let mut store = SettingsStore::default();
@@ -1345,8 +1661,10 @@ mod tests {
         staff: bool,
     }
 
+    /// The user's details, shown next to their edits.
     #[derive(Default, Clone, Serialize, Deserialize, JsonSchema)]
     struct UserSettingsJson {
+        /// The name shown to collaborators.
         name: Option<String>,
         age: Option<u32>,
         staff: Option<bool>,
@@ -1373,6 +1691,18 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ExcludedFilesSetting(Vec<String>);
+
+    impl Settings for ExcludedFilesSetting {
+        const KEY: Option<&'static str> = Some("excluded_files");
+        type FileContent = Option<Vec<String>>;
+
+        fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+            sources.json_merge()
+        }
+    }
+
     #[derive(Clone, Debug, PartialEq, Deserialize)]
     struct MultiKeySettings {
         #[serde(default)]
@@ -0,0 +1,243 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, ParentElement,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{
+    item::{ItemHandle, TabContentParams},
+    pane::{render_item_indicator, tab_details},
+    Pane,
+};
+
+const PANEL_WIDTH_REMS: f32 = 24.;
+
+pub(crate) fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|pane: &mut Pane, _| {
+        pane.set_tab_list_builder(|pane, cx| cx.new_view(|cx| TabList::new(pane, cx)));
+    })
+    .detach();
+}
+
+/// The searchable list of all of a pane's tabs, opened from its tab bar when they don't fit in it.
+pub struct TabList {
+    pub(crate) picker: View<Picker<TabListDelegate>>,
+}
+
+impl TabList {
+    fn new(pane: WeakView<Pane>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = TabListDelegate::new(cx.view().downgrade(), pane, cx);
+        Self {
+            picker: cx.new_view(|cx| Picker::uniform_list(delegate, cx)),
+        }
+    }
+}
+
+impl EventEmitter<DismissEvent> for TabList {}
+
+impl FocusableView for TabList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for TabList {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TabList")
+            .w(rems(PANEL_WIDTH_REMS))
+            .child(self.picker.clone())
+    }
+}
+
+struct Tab {
+    item_index: usize,
+    item: Box<dyn ItemHandle>,
+    detail: usize,
+    preview: bool,
+}
+
+pub struct TabListDelegate {
+    tab_list: WeakView<TabList>,
+    pane: WeakView<Pane>,
+    tabs: Vec<Tab>,
+    candidates: Vec<StringMatchCandidate>,
+    pub(crate) matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl TabListDelegate {
+    fn new(tab_list: WeakView<TabList>, pane: WeakView<Pane>, cx: &mut WindowContext) -> Self {
+        let mut tabs = Vec::new();
+        let mut selected_index = 0;
+        if let Some(pane) = pane.upgrade() {
+            let pane = pane.read(cx);
+            let items: Vec<Box<dyn ItemHandle>> =
+                pane.items().map(|item| item.boxed_clone()).collect();
+            let details = tab_details(&items, cx);
+            for ((item_index, item), detail) in items.into_iter().enumerate().zip(details) {
+                if item_index == pane.active_item_index() {
+                    selected_index = tabs.len();
+                }
+                tabs.push(Tab {
+                    item_index,
+                    preview: pane.is_active_preview_item(item.item_id()),
+                    item,
+                    detail,
+                });
+            }
+        }
+        let candidates = tabs
+            .iter()
+            .enumerate()
+            .map(|(ix, tab)| {
+                let label = tab
+                    .item
+                    .tab_description(tab.detail, cx)
+                    .unwrap_or_else(|| "untitled".into());
+                StringMatchCandidate::new(ix, label.to_string())
+            })
+            .collect();
+
+        Self {
+            tab_list,
+            pane,
+            tabs,
+            candidates,
+            matches: Vec::new(),
+            selected_index,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn tab_item(&self, ix: usize) -> Option<&dyn ItemHandle> {
+        let tab = self.tabs.get(self.matches.get(ix)?.candidate_id)?;
+        Some(tab.item.as_ref())
+    }
+}
+
+impl PickerDelegate for TabListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search tabs…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No matching tabs".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                if !query.is_empty() {
+                    delegate.selected_index = 0;
+                }
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(tab) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.tabs.get(mat.candidate_id))
+        else {
+            return;
+        };
+        let item_index = tab.item_index;
+        self.pane
+            .update(cx, |pane, cx| {
+                pane.activate_item(item_index, true, true, cx)
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.tab_list
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let tab = self.tabs.get(mat.candidate_id)?;
+        let label = if mat.positions.is_empty() {
+            let params = TabContentParams {
+                detail: Some(tab.detail),
+                selected: true,
+                preview: tab.preview,
+            };
+            tab.item.tab_content(params, cx)
+        } else {
+            HighlightedLabel::new(mat.string.clone(), mat.positions.clone()).into_any_element()
+        };
+
+        Some(
+            ListItem::new(ix)
+                .spacing(ListItemSpacing::Sparse)
+                .inset(true)
+                .selected(selected)
+                .start_slot::<AnyElement>(
+                    h_flex()
+                        .flex_shrink_0()
+                        .children(render_item_indicator(tab.item.boxed_clone(), cx))
+                        .into_any_element(),
+                )
+                .child(h_flex().w_full().child(label)),
+        )
+    }
+}
@@ -1,3 +1,4 @@
+mod tab_list;
 #[cfg(test)]
 mod tab_switcher_tests;
 
@@ -38,6 +39,7 @@ impl ModalView for TabSwitcher {}
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(TabSwitcher::register).detach();
+    tab_list::init(cx);
 }
 
 impl TabSwitcher {
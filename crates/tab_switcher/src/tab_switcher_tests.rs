@@ -1,4 +1,5 @@
 use super::*;
+use crate::tab_list::{TabList, TabListDelegate};
 use editor::Editor;
 use gpui::{TestAppContext, VisualTestContext};
 use menu::SelectPrev;
@@ -72,6 +73,70 @@ async fn test_open_with_prev_tab_selected_and_cycle_on_toggle_action(
     });
 }
 
+#[gpui::test]
+async fn test_tab_list(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "alpha.txt": "First file",
+                "beta.txt": "Second file",
+                "gamma.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+    let tab_1 = open_buffer("alpha.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("beta.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("gamma.txt", &workspace, cx).await;
+    let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+    // The tabs are listed in the tab bar's order, with the active one selected.
+    pane.update(cx, |pane, cx| pane.toggle_tab_list(cx));
+    cx.run_until_parked();
+    let tab_list = get_tab_list(&pane, cx);
+    tab_list.update(cx, |tab_list, _| {
+        let delegate = &tab_list.delegate;
+        assert_eq!(delegate.matches.len(), 3);
+        assert_eq!(delegate.selected_index(), 2);
+        for (ix, tab) in [&tab_1, &tab_2, &tab_3].into_iter().enumerate() {
+            assert_eq!(
+                delegate.tab_item(ix).map(|item| item.item_id()),
+                Some(tab.item_id())
+            );
+        }
+    });
+    cx.update(|cx| assert!(tab_list.focus_handle(cx).contains_focused(cx)));
+
+    // Searching narrows the list down, and confirming activates the tab.
+    cx.simulate_input("alp");
+    tab_list.update(cx, |tab_list, _| {
+        let delegate = &tab_list.delegate;
+        assert_eq!(delegate.matches.len(), 1);
+        assert_eq!(
+            delegate.tab_item(0).map(|item| item.item_id()),
+            Some(tab_1.item_id())
+        );
+    });
+    cx.dispatch_action(menu::Confirm);
+    cx.run_until_parked();
+    pane.update(cx, |pane, cx| {
+        assert_eq!(
+            pane.active_item().map(|item| item.item_id()),
+            Some(tab_1.item_id())
+        );
+        assert!(pane.tab_list().is_none());
+        assert!(pane.focus_handle(cx).contains_focused(cx));
+    });
+}
+
 #[gpui::test]
 async fn test_open_with_last_tab_selected(cx: &mut gpui::TestAppContext) {
     let app_state = init_test(cx);
@@ -251,6 +316,20 @@ fn open_tab_switcher(
     get_active_tab_switcher(workspace, cx)
 }
 
+#[track_caller]
+fn get_tab_list(pane: &View<Pane>, cx: &mut VisualTestContext) -> View<Picker<TabListDelegate>> {
+    pane.update(cx, |pane, cx| {
+        pane.tab_list()
+            .expect("tab list is not open")
+            .clone()
+            .downcast::<TabList>()
+            .unwrap()
+            .read(cx)
+            .picker
+            .clone()
+    })
+}
+
 #[track_caller]
 fn get_active_tab_switcher(
     workspace: &View<Workspace>,
@@ -350,9 +350,10 @@ impl PickerDelegate for TasksModalDelegate {
             TaskSourceKind::UserInput => Some(Icon::new(IconName::Terminal)),
             TaskSourceKind::AbsPath { .. } => Some(Icon::new(IconName::Settings)),
             TaskSourceKind::Worktree { .. } => Some(Icon::new(IconName::FileTree)),
-            TaskSourceKind::Language { name } => file_icons::FileIcons::get(cx)
-                .get_type_icon(&name.to_lowercase())
-                .map(|icon_path| Icon::from_path(icon_path)),
+            TaskSourceKind::Language { name } => {
+                file_icons::FileIcons::get_type_icon(&name.to_lowercase(), cx)
+                    .map(|icon_path| Icon::from_path(icon_path))
+            }
         };
 
         Some(
@@ -1035,13 +1035,7 @@ impl Terminal {
 
     ///Paste text into the terminal
     pub fn paste(&mut self, text: &str) {
-        let paste_text = if self.last_content.mode.contains(TermMode::BRACKETED_PASTE) {
-            format!("{}{}{}", "\x1b[200~", text.replace('\x1b', ""), "\x1b[201~")
-        } else {
-            text.replace("\r\n", "\r").replace('\n', "\r")
-        };
-
-        self.input(paste_text);
+        self.input(paste_text(text, &self.last_content.mode));
     }
 
     pub fn sync(&mut self, cx: &mut ModelContext<Self>) {
@@ -1658,17 +1652,29 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
     .into()
 }
 
+/// Converts pasted text into the input for the terminal. When the program running in it has
+/// enabled bracketed paste, the text is wrapped in markers, and stripped of escapes that could
+/// end the paste early, so that it can tell the text apart from typed commands.
+fn paste_text(text: &str, mode: &TermMode) -> String {
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        format!("{}{}{}", "\x1b[200~", text.replace('\x1b', ""), "\x1b[201~")
+    } else {
+        text.replace("\r\n", "\r").replace('\n', "\r")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alacritty_terminal::{
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::{cell::Cell, TermMode},
     };
     use gpui::{point, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, paste_text, rgb_for_index, IndexedCell, TerminalContent,
+        TerminalSize,
     };
 
     #[test]
@@ -1680,6 +1686,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_paste_text() {
+        let text = "ls\r\nrm -rf \x1b[201~/\n";
+
+        // Without bracketed paste, newlines are sent as returns.
+        assert_eq!(
+            paste_text(text, &TermMode::empty()),
+            "ls\rrm -rf \x1b[201~/\r"
+        );
+
+        // With it, the text is sent between markers, without any escape that could end it.
+        assert_eq!(
+            paste_text(text, &TermMode::BRACKETED_PASTE),
+            "\x1b[200~ls\r\nrm -rf [201~/\n\x1b[201~"
+        );
+    }
+
     #[test]
     fn test_mouse_to_cell_test() {
         let mut rng = thread_rng();
@@ -1,14 +1,27 @@
 use clock::ReplicaId;
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Write as _},
+    ops::Range,
+};
 
 pub struct Network<T: Clone, R: rand::Rng> {
-    inboxes: std::collections::BTreeMap<ReplicaId, Vec<Envelope<T>>>,
+    inboxes: BTreeMap<ReplicaId, Vec<Envelope<T>>>,
     all_messages: Vec<T>,
+    drop_probability: f64,
+    /// How many clock steps it takes messages to reach each peer that isn't
+    /// reached immediately.
+    latency: BTreeMap<ReplicaId, Range<usize>>,
+    /// The seed of `rng`, when the network was created from one.
+    seed: Option<u64>,
     rng: R,
 }
 
 #[derive(Clone)]
 struct Envelope<T: Clone> {
     message: T,
+    /// The number of clock steps left before the message can be received.
+    deliverable_after: usize,
 }
 
 impl<T: Clone, R: rand::Rng> Network<T, R> {
@@ -16,10 +29,17 @@ impl<T: Clone, R: rand::Rng> Network<T, R> {
         Network {
             inboxes: Default::default(),
             all_messages: Vec::new(),
+            drop_probability: 0.,
+            latency: Default::default(),
+            seed: None,
             rng,
         }
     }
 
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
     pub fn add_peer(&mut self, id: ReplicaId) {
         self.inboxes.insert(id, Vec::new());
     }
@@ -29,6 +49,35 @@ impl<T: Clone, R: rand::Rng> Network<T, R> {
             .insert(new_replica_id, self.inboxes[&old_replica_id].clone());
     }
 
+    /// Sets the probability of each copy of a broadcast message being lost
+    /// before reaching its receiver.
+    pub fn set_drop_probability(&mut self, probability: f64) {
+        self.drop_probability = probability.clamp(0., 1.);
+    }
+
+    pub fn drop_probability(&self) -> f64 {
+        self.drop_probability
+    }
+
+    /// Sets how many clock steps, drawn from `steps` for each message, it
+    /// takes messages to reach the given peer.
+    pub fn set_latency(&mut self, replica_id: ReplicaId, steps: Range<usize>) {
+        self.latency.insert(replica_id, steps);
+    }
+
+    /// Brings every buffered message `steps` clock steps closer to
+    /// being deliverable.
+    pub fn advance_clock(&mut self, steps: usize) {
+        for envelope in self.inboxes.values_mut().flatten() {
+            envelope.deliverable_after = envelope.deliverable_after.saturating_sub(steps);
+        }
+    }
+
+    /// Every message broadcast so far, including the ones that were dropped.
+    pub fn all_messages(&self) -> &[T] {
+        &self.all_messages
+    }
+
     pub fn is_idle(&self) -> bool {
         self.inboxes.values().all(|i| i.is_empty())
     }
@@ -40,11 +89,21 @@ impl<T: Clone, R: rand::Rng> Network<T, R> {
                     // Insert one or more duplicates of this message, potentially *before* the previous
                     // message sent by this peer to simulate out-of-order delivery.
                     for _ in 0..self.rng.gen_range(1..4) {
+                        // Only roll for drops when they're enabled, so that seeds keep
+                        // reproducing the same runs when they aren't.
+                        if self.drop_probability > 0. && self.rng.gen_bool(self.drop_probability) {
+                            continue;
+                        }
+                        let deliverable_after = match self.latency.get(replica) {
+                            Some(steps) if !steps.is_empty() => self.rng.gen_range(steps.clone()),
+                            _ => 0,
+                        };
                         let insertion_index = self.rng.gen_range(0..inbox.len() + 1);
                         inbox.insert(
                             insertion_index,
                             Envelope {
                                 message: message.clone(),
+                                deliverable_after,
                             },
                         );
                     }
@@ -54,16 +113,80 @@ impl<T: Clone, R: rand::Rng> Network<T, R> {
         self.all_messages.extend(messages);
     }
 
+    /// Whether any messages are buffered for the given peer, including ones
+    /// that haven't reached it yet.
     pub fn has_unreceived(&self, receiver: ReplicaId) -> bool {
         !self.inboxes[&receiver].is_empty()
     }
 
+    /// Whether any messages buffered for the given peer have reached it, so
+    /// that [`Self::receive`] can return them.
+    pub fn has_deliverable(&self, receiver: ReplicaId) -> bool {
+        self.inboxes[&receiver]
+            .iter()
+            .any(|envelope| envelope.deliverable_after == 0)
+    }
+
+    /// Receives some of the messages that have reached the given peer, in the
+    /// order they're buffered in.
     pub fn receive(&mut self, receiver: ReplicaId) -> Vec<T> {
         let inbox = self.inboxes.get_mut(&receiver).unwrap();
-        let count = self.rng.gen_range(0..inbox.len() + 1);
-        inbox
-            .drain(0..count)
-            .map(|envelope| envelope.message)
-            .collect()
+        let deliverable = inbox
+            .iter()
+            .filter(|envelope| envelope.deliverable_after == 0)
+            .count();
+        let count = self.rng.gen_range(0..deliverable + 1);
+        let mut messages = Vec::with_capacity(count);
+        inbox.retain(|envelope| {
+            if messages.len() < count && envelope.deliverable_after == 0 {
+                messages.push(envelope.message.clone());
+                false
+            } else {
+                true
+            }
+        });
+        messages
+    }
+}
+
+impl<T: Clone, R: rand::Rng + rand::SeedableRng> Network<T, R> {
+    /// Creates a network whose randomness comes from the given seed, which
+    /// [`Self::describe_state`] reports so that failing runs can be reproduced.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut network = Self::new(R::seed_from_u64(seed));
+        network.seed = Some(seed);
+        network
+    }
+}
+
+impl<T: Clone + Debug, R: rand::Rng> Network<T, R> {
+    /// Describes the seed, what's buffered for each peer and every message sent
+    /// so far. It's the same across runs with the same seed.
+    pub fn describe_state(&self) -> String {
+        let mut state = String::new();
+        match self.seed {
+            Some(seed) => writeln!(state, "seed: {seed}"),
+            None => writeln!(state, "seed: unknown"),
+        }
+        .unwrap();
+        writeln!(state, "drop probability: {}", self.drop_probability).unwrap();
+        writeln!(state, "inboxes:").unwrap();
+        for (replica_id, inbox) in &self.inboxes {
+            let delayed = inbox
+                .iter()
+                .filter(|envelope| envelope.deliverable_after > 0)
+                .count();
+            writeln!(
+                state,
+                "  {replica_id}: {} buffered, {delayed} not yet deliverable",
+                inbox.len()
+            )
+            .unwrap();
+        }
+        writeln!(state, "all messages:").unwrap();
+        for (ix, message) in self.all_messages.iter().enumerate() {
+            writeln!(state, "  {ix}: {message:?}").unwrap();
+        }
+        state
     }
 }
@@ -169,9 +169,14 @@ fn test_line_endings() {
         LineEnding::Windows
     );
 
+    assert!(!LineEnding::is_mixed("one\r\ntwo\r\n"));
+    assert!(LineEnding::is_mixed("one\r\ntwo\nthree"));
+    assert!(LineEnding::is_mixed("one\ntwo\r\n"));
+
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "one\r\ntwo\rthree".into());
     assert_eq!(buffer.text(), "one\ntwo\nthree");
     assert_eq!(buffer.line_ending(), LineEnding::Windows);
+    assert!(!buffer.has_mixed_line_endings());
     buffer.check_invariants();
 
     buffer.edit([(buffer.len()..buffer.len(), "\r\nfour")]);
@@ -740,7 +745,7 @@ fn test_random_concurrent_edits(mut rng: StdRng) {
                 network.broadcast(buffer.replica_id, ops);
                 mutation_count -= 1;
             }
-            71..=100 if network.has_unreceived(replica_id) => {
+            71..=100 if network.has_deliverable(replica_id) => {
                 let ops = network.receive(replica_id);
                 if !ops.is_empty() {
                     log::info!(
@@ -771,3 +776,92 @@ fn test_random_concurrent_edits(mut rng: StdRng) {
         buffer.check_invariants();
     }
 }
+
+#[gpui::test(iterations = 10)]
+fn test_network_drop_probability(rng: StdRng) {
+    let mut network = Network::new(rng);
+    network.add_peer(0);
+    network.add_peer(1);
+    assert_eq!(network.drop_probability(), 0.);
+
+    let mut buffer1 = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    let mut buffer2 = Buffer::new(1, BufferId::new(1).unwrap(), "abc".into());
+
+    // Every message is lost, but still recorded.
+    network.set_drop_probability(1.);
+    let op = buffer1.edit([(3..3, "def")]);
+    network.broadcast(0, vec![op]);
+    assert!(!network.has_unreceived(1));
+    assert!(network.is_idle());
+    assert_eq!(network.all_messages().len(), 1);
+
+    // Resending the dropped messages makes the replicas converge.
+    network.set_drop_probability(0.);
+    let messages = network.all_messages().to_vec();
+    network.broadcast(0, messages);
+    while network.has_deliverable(1) {
+        buffer2.apply_ops(network.receive(1)).unwrap();
+    }
+    assert_eq!(buffer2.text(), "abcdef");
+    assert_eq!(buffer1.text(), buffer2.text());
+}
+
+#[gpui::test(iterations = 10)]
+fn test_network_latency(rng: StdRng) {
+    let mut network = Network::new(rng);
+    network.add_peer(0);
+    network.add_peer(1);
+    network.add_peer(2);
+    network.set_latency(2, 3..5);
+
+    let mut buffer0 = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    let mut buffer1 = Buffer::new(1, BufferId::new(1).unwrap(), "abc".into());
+    let mut buffer2 = Buffer::new(2, BufferId::new(1).unwrap(), "abc".into());
+    let op = buffer0.edit([(3..3, "def")]);
+    network.broadcast(0, vec![op]);
+
+    // The fast peer receives the messages right away.
+    while network.has_deliverable(1) {
+        buffer1.apply_ops(network.receive(1)).unwrap();
+    }
+    assert_eq!(buffer1.text(), "abcdef");
+
+    // The slow one lags behind until enough time has passed, while the network
+    // isn't idle.
+    network.advance_clock(2);
+    assert!(network.receive(2).is_empty());
+    assert!(network.has_unreceived(2));
+    assert!(!network.has_deliverable(2));
+    assert!(!network.is_idle());
+
+    network.advance_clock(2);
+    while network.has_deliverable(2) {
+        buffer2.apply_ops(network.receive(2)).unwrap();
+    }
+    assert_eq!(buffer2.text(), "abcdef");
+    assert!(network.is_idle());
+}
+
+#[test]
+fn test_network_describe_state() {
+    let run = |seed| {
+        let mut network = Network::<_, StdRng>::from_seed(seed);
+        network.add_peer(0);
+        network.add_peer(1);
+        network.set_latency(1, 1..3);
+        let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+        for text in ["d", "e", "f"] {
+            let end = buffer.len();
+            let op = buffer.edit([(end..end, text)]);
+            network.broadcast(0, vec![op]);
+        }
+        network.advance_clock(1);
+        network.receive(1);
+        network.describe_state()
+    };
+
+    let state = run(7);
+    assert!(state.starts_with("seed: 7\n"), "{state}");
+    assert!(state.contains("  2: "), "{state}");
+    assert_eq!(state, run(7));
+}
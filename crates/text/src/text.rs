@@ -59,6 +59,9 @@ pub struct Buffer {
     subscriptions: Topic,
     edit_id_resolvers: HashMap<clock::Lamport, Vec<oneshot::Sender<()>>>,
     wait_for_version_txs: Vec<(clock::Global, oneshot::Sender<()>)>,
+    /// Whether the text the buffer was created with had both Unix and Windows line endings,
+    /// before they were normalized.
+    mixed_line_endings: bool,
 }
 
 #[repr(transparent)]
@@ -519,8 +522,12 @@ pub struct UndoOperation {
 impl Buffer {
     pub fn new(replica_id: u16, remote_id: BufferId, mut base_text: String) -> Buffer {
         let line_ending = LineEnding::detect(&base_text);
+        let mixed_line_endings = LineEnding::is_mixed(&base_text);
         LineEnding::normalize(&mut base_text);
-        Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text))
+        let mut buffer =
+            Self::new_normalized(replica_id, remote_id, line_ending, Rope::from(base_text));
+        buffer.mixed_line_endings = mixed_line_endings;
+        buffer
     }
 
     pub fn new_normalized(
@@ -577,6 +584,7 @@ impl Buffer {
             subscriptions: Default::default(),
             edit_id_resolvers: Default::default(),
             wait_for_version_txs: Default::default(),
+            mixed_line_endings: false,
         }
     }
 
@@ -797,6 +805,18 @@ impl Buffer {
 
     pub fn set_line_ending(&mut self, line_ending: LineEnding) {
         self.snapshot.line_ending = line_ending;
+        self.mixed_line_endings = false;
+    }
+
+    /// Whether the buffer was loaded from text mixing Unix and Windows line endings, and hasn't
+    /// been given a single line ending since. It's saved with [`BufferSnapshot::line_ending`].
+    pub fn has_mixed_line_endings(&self) -> bool {
+        self.mixed_line_endings
+    }
+
+    /// Records whether the text the buffer was last loaded from mixed line endings.
+    pub fn set_mixed_line_endings(&mut self, mixed_line_endings: bool) {
+        self.mixed_line_endings = mixed_line_endings;
     }
 
     pub fn apply_ops<I: IntoIterator<Item = Operation>>(&mut self, ops: I) -> Result<()> {
@@ -2773,6 +2793,30 @@ impl LineEnding {
         }
     }
 
+    /// Returns whether the text has both Unix and Windows line endings.
+    pub fn is_mixed(text: &str) -> bool {
+        let mut has_unix = false;
+        let mut has_windows = false;
+        for (ix, _) in text.match_indices('\n') {
+            if ix > 0 && text.as_bytes()[ix - 1] == b'\r' {
+                has_windows = true;
+            } else {
+                has_unix = true;
+            }
+            if has_unix && has_windows {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::Unix => "LF",
+            LineEnding::Windows => "CRLF",
+        }
+    }
+
     pub fn normalize(text: &mut String) {
         if let Cow::Owned(replaced) = LINE_SEPARATORS_REGEX.replace_all(text, "\n") {
             *text = replaced;
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use gpui::Hsla;
+
+use crate::ThemeStyles;
+
+/// The contrast ratio WCAG 2 level AA asks of body text.
+pub const WCAG_AA_CONTRAST_RATIO: f32 = 4.5;
+
+/// Returns the WCAG contrast ratio between two colors, from 1 for colors of the same luminance
+/// up to 21 for black on white. Alpha is ignored.
+pub fn contrast_ratio(a: Hsla, b: Hsla) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn relative_luminance(color: Hsla) -> f32 {
+    let rgb = color.to_rgb();
+    let linear = |channel: f32| {
+        if channel <= 0.03928 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(rgb.r) + 0.7152 * linear(rgb.g) + 0.0722 * linear(rgb.b)
+}
+
+/// Returns `foreground` with its lightness changed as little as possible for it to have at
+/// least `min_contrast` against each of `backgrounds`, keeping its hue and saturation.
+///
+/// The lightness moves away from the backgrounds', and stops at white or black when the
+/// contrast can't be reached.
+pub fn ensure_contrast(foreground: Hsla, backgrounds: &[Hsla], min_contrast: f32) -> Hsla {
+    let lowest_contrast = |color: Hsla| {
+        backgrounds
+            .iter()
+            .map(|background| contrast_ratio(color, *background))
+            .fold(f32::INFINITY, f32::min)
+    };
+    if lowest_contrast(foreground) >= min_contrast {
+        return foreground;
+    }
+
+    // Below this luminance, white contrasts with a background more than black does.
+    const MIDDLE_LUMINANCE: f32 = 0.179;
+    let background_luminance = backgrounds
+        .iter()
+        .map(|background| relative_luminance(*background))
+        .sum::<f32>()
+        / backgrounds.len().max(1) as f32;
+    let extreme = Hsla {
+        l: if background_luminance < MIDDLE_LUMINANCE {
+            1.
+        } else {
+            0.
+        },
+        ..foreground
+    };
+    if lowest_contrast(extreme) < min_contrast {
+        return extreme;
+    }
+
+    // Lightness moves luminance the same way for a given hue and saturation, so the least
+    // change can be searched for between the foreground and the extreme.
+    let (mut insufficient, mut sufficient) = (foreground.l, extreme.l);
+    for _ in 0..16 {
+        let lightness = (insufficient + sufficient) / 2.;
+        if lowest_contrast(Hsla {
+            l: lightness,
+            ..foreground
+        }) >= min_contrast
+        {
+            sufficient = lightness;
+        } else {
+            insufficient = lightness;
+        }
+    }
+    Hsla {
+        l: sufficient,
+        ..foreground
+    }
+}
+
+/// Changes the text, icon and syntax colors of a theme to have at least `min_contrast`
+/// against the backgrounds they're drawn on.
+pub(crate) fn increase_contrast(styles: &mut ThemeStyles, min_contrast: f32) {
+    let colors = &mut styles.colors;
+    let backgrounds = [
+        colors.background,
+        colors.surface_background,
+        colors.elevated_surface_background,
+        colors.editor_background,
+    ];
+    for color in [
+        &mut colors.text,
+        &mut colors.text_muted,
+        &mut colors.text_placeholder,
+        &mut colors.text_accent,
+        &mut colors.icon,
+        &mut colors.icon_muted,
+    ] {
+        *color = ensure_contrast(*color, &backgrounds, min_contrast);
+    }
+
+    let editor_background = [colors.editor_background];
+    for color in [
+        &mut colors.editor_foreground,
+        &mut colors.editor_line_number,
+        &mut colors.editor_active_line_number,
+    ] {
+        *color = ensure_contrast(*color, &editor_background, min_contrast);
+    }
+
+    let syntax = Arc::make_mut(&mut styles.syntax);
+    for (_, highlight) in &mut syntax.highlights {
+        if let Some(color) = &mut highlight.color {
+            *color = ensure_contrast(*color, &editor_background, min_contrast);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ThemeRegistry;
+
+    #[test]
+    fn test_high_contrast_variant() {
+        let registry = ThemeRegistry::default();
+        for name in ["One Dark", "One Light"] {
+            let theme = registry.get(name).unwrap();
+            let variant = registry
+                .high_contrast_variant(name, WCAG_AA_CONTRAST_RATIO)
+                .unwrap();
+            assert_eq!(registry.get(&variant.name).unwrap().id, variant.id);
+            assert_eq!(variant.appearance, theme.appearance);
+
+            let colors = variant.colors();
+            for (foreground, background) in [
+                (colors.text, colors.background),
+                (colors.text_muted, colors.surface_background),
+                (colors.text_placeholder, colors.editor_background),
+                (colors.icon_muted, colors.elevated_surface_background),
+                (colors.editor_foreground, colors.editor_background),
+                (colors.editor_line_number, colors.editor_background),
+            ] {
+                assert!(
+                    contrast_ratio(foreground, background) >= WCAG_AA_CONTRAST_RATIO,
+                    "{name}: {foreground:?} on {background:?}"
+                );
+            }
+            for (token, highlight) in &variant.syntax().highlights {
+                if let Some(color) = highlight.color {
+                    assert!(
+                        contrast_ratio(color, colors.editor_background) >= WCAG_AA_CONTRAST_RATIO,
+                        "{name}: {token}"
+                    );
+                }
+            }
+
+            // Only lightness changes, so the hues are the same.
+            let original = theme.colors();
+            for (before, after) in [
+                (original.text_accent, colors.text_accent),
+                (original.text_muted, colors.text_muted),
+            ] {
+                assert_eq!((before.h, before.s), (after.h, after.s));
+            }
+        }
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = gpui::black();
+        let white = gpui::white();
+        assert!((contrast_ratio(black, white) - 21.).abs() < 0.01);
+        assert!((contrast_ratio(white, black) - 21.).abs() < 0.01);
+        assert_eq!(contrast_ratio(white, white), 1.);
+
+        // Colors that already contrast enough are left alone.
+        assert_eq!(ensure_contrast(white, &[black], 7.), white);
+        let gray = gpui::hsla(0.6, 0.5, 0.3, 1.);
+        let adjusted = ensure_contrast(gray, &[black], 7.);
+        assert!(contrast_ratio(adjusted, black) >= 7.);
+        assert!(adjusted.l > gray.l);
+    }
+}
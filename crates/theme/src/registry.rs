@@ -1,8 +1,8 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use derive_more::{Deref, DerefMut};
 use fs::Fs;
 use futures::StreamExt;
@@ -35,6 +35,10 @@ impl Global for GlobalThemeRegistry {}
 
 struct ThemeRegistryState {
     themes: HashMap<SharedString, Arc<Theme>>,
+    /// The content of the user themes, to rebuild the themes extending one when it changes.
+    user_themes: HashMap<String, ThemeContent>,
+    /// Why user theme files failed to reload, until they reload successfully.
+    load_errors: HashMap<PathBuf, SharedString>,
 }
 
 pub struct ThemeRegistry {
@@ -64,6 +68,8 @@ impl ThemeRegistry {
         let registry = Self {
             state: RwLock::new(ThemeRegistryState {
                 themes: HashMap::default(),
+                user_themes: HashMap::default(),
+                load_errors: HashMap::default(),
             }),
             assets,
         };
@@ -91,96 +97,212 @@ impl ThemeRegistry {
         }
     }
 
-    #[allow(unused)]
-    fn insert_user_theme_families(&self, families: impl IntoIterator<Item = ThemeFamilyContent>) {
-        for family in families.into_iter() {
-            self.insert_user_themes(family.themes);
-        }
+    fn insert_user_theme_families(
+        &self,
+        families: impl IntoIterator<Item = ThemeFamilyContent>,
+    ) -> Result<()> {
+        self.insert_user_themes(families.into_iter().flat_map(|family| family.themes))
     }
 
-    pub fn insert_user_themes(&self, themes: impl IntoIterator<Item = ThemeContent>) {
-        self.insert_themes(themes.into_iter().map(|user_theme| {
-            let mut theme_colors = match user_theme.appearance {
-                AppearanceContent::Light => ThemeColors::light(),
-                AppearanceContent::Dark => ThemeColors::dark(),
-            };
-            theme_colors.refine(&user_theme.style.theme_colors_refinement());
-
-            let mut status_colors = match user_theme.appearance {
-                AppearanceContent::Light => StatusColors::light(),
-                AppearanceContent::Dark => StatusColors::dark(),
-            };
-            status_colors.refine(&user_theme.style.status_colors_refinement());
+    /// Adds the given themes to the registry, resolving each one's `extends` from the other
+    /// given themes or the ones already registered. User themes extending the given ones are
+    /// rebuilt on top of them.
+    ///
+    /// Themes whose parent is missing, or that extend each other in a cycle, are skipped and
+    /// reported in the returned error.
+    pub fn insert_user_themes(&self, themes: impl IntoIterator<Item = ThemeContent>) -> Result<()> {
+        let mut pending = {
+            let mut state = self.state.write();
+            let mut changed = HashSet::default();
+            for theme in themes {
+                changed.insert(theme.name.clone());
+                state.user_themes.insert(theme.name.clone(), theme);
+            }
+            loop {
+                let extending = state
+                    .user_themes
+                    .values()
+                    .filter(|theme| {
+                        !changed.contains(&theme.name)
+                            && theme
+                                .extends
+                                .as_ref()
+                                .map_or(false, |parent| changed.contains(parent))
+                    })
+                    .map(|theme| theme.name.clone())
+                    .collect::<Vec<_>>();
+                if extending.is_empty() {
+                    break;
+                }
+                changed.extend(extending);
+            }
+            changed
+                .into_iter()
+                .filter_map(|name| Some((name.clone(), state.user_themes.get(&name)?.clone())))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut names = pending.keys().cloned().collect::<Vec<_>>();
+        names.sort();
 
-            let mut player_colors = match user_theme.appearance {
-                AppearanceContent::Light => PlayerColors::light(),
-                AppearanceContent::Dark => PlayerColors::dark(),
-            };
-            player_colors.merge(&user_theme.style.players);
+        let mut errors = Vec::new();
+        for name in names {
+            if let Err(error) = self.resolve_user_theme(&name, &mut pending, &mut Vec::new()) {
+                errors.push(format!("{error:#}"));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(errors.join("\n")))
+        }
+    }
 
-            let mut syntax_colors = match user_theme.appearance {
-                AppearanceContent::Light => SyntaxTheme::light(),
-                AppearanceContent::Dark => SyntaxTheme::dark(),
-            };
+    /// Registers the pending theme with the given name once its parents are registered, and
+    /// returns it. `chain` holds the themes extending this one, to detect cycles.
+    fn resolve_user_theme(
+        &self,
+        name: &str,
+        pending: &mut HashMap<String, ThemeContent>,
+        chain: &mut Vec<String>,
+    ) -> Result<Arc<Theme>> {
+        if chain.iter().any(|extending| extending == name) {
+            return Err(anyhow!(
+                "themes extend each other in a cycle: {} -> {name}",
+                chain.join(" -> ")
+            ));
+        }
+        let Some(user_theme) = pending.remove(name) else {
+            return self.get(name);
+        };
 
-            let window_background_appearance = user_theme
-                .style
-                .window_background_appearance
-                .map(Into::into)
-                .unwrap_or_default();
-
-            if !user_theme.style.syntax.is_empty() {
-                syntax_colors.highlights = user_theme
-                    .style
-                    .syntax
-                    .iter()
-                    .map(|(syntax_token, highlight)| {
-                        (
-                            syntax_token.clone(),
-                            HighlightStyle {
-                                color: highlight
-                                    .color
-                                    .as_ref()
-                                    .and_then(|color| try_parse_color(color).ok()),
-                                font_style: highlight.font_style.map(Into::into),
-                                font_weight: highlight.font_weight.map(Into::into),
-                                ..Default::default()
-                            },
-                        )
-                    })
-                    .collect::<Vec<_>>();
+        let parent = match &user_theme.extends {
+            Some(parent_name) => {
+                chain.push(name.to_string());
+                let parent = self.resolve_user_theme(parent_name, pending, chain);
+                chain.pop();
+                Some(parent.with_context(|| format!("resolving the parent of theme {name:?}"))?)
             }
+            None => None,
+        };
 
-            Theme {
-                id: uuid::Uuid::new_v4().to_string(),
-                name: user_theme.name.into(),
-                appearance: match user_theme.appearance {
-                    AppearanceContent::Light => Appearance::Light,
-                    AppearanceContent::Dark => Appearance::Dark,
-                },
-                styles: ThemeStyles {
+        let theme = Arc::new(Self::user_theme(user_theme, parent.as_deref()));
+        self.state
+            .write()
+            .themes
+            .insert(theme.name.clone(), theme.clone());
+        Ok(theme)
+    }
+
+    /// Builds a theme from its content, starting from the colors of its parent if it has one,
+    /// or else from the default ones for its appearance.
+    fn user_theme(user_theme: ThemeContent, parent: Option<&Theme>) -> Theme {
+        let mut styles = match parent {
+            Some(parent) => parent.styles.clone(),
+            None => {
+                let (colors, status, player, syntax) = match user_theme.appearance {
+                    AppearanceContent::Light => (
+                        ThemeColors::light(),
+                        StatusColors::light(),
+                        PlayerColors::light(),
+                        SyntaxTheme::light(),
+                    ),
+                    AppearanceContent::Dark => (
+                        ThemeColors::dark(),
+                        StatusColors::dark(),
+                        PlayerColors::dark(),
+                        SyntaxTheme::dark(),
+                    ),
+                };
+                ThemeStyles {
                     system: SystemColors::default(),
-                    window_background_appearance,
-                    colors: theme_colors,
-                    status: status_colors,
-                    player: player_colors,
-                    syntax: Arc::new(syntax_colors),
+                    window_background_appearance: Default::default(),
+                    colors,
+                    status,
+                    player,
+                    syntax: Arc::new(syntax),
                     accents: Vec::new(),
-                },
+                }
+            }
+        };
+
+        styles
+            .colors
+            .refine(&user_theme.style.theme_colors_refinement());
+        styles
+            .status
+            .refine(&user_theme.style.status_colors_refinement());
+        styles.player.merge(&user_theme.style.players);
+
+        if let Some(window_background_appearance) = user_theme.style.window_background_appearance {
+            styles.window_background_appearance = window_background_appearance.into();
+        }
+
+        if !user_theme.style.syntax.is_empty() {
+            let highlights = user_theme
+                .style
+                .syntax
+                .iter()
+                .map(|(syntax_token, highlight)| {
+                    (
+                        syntax_token.clone(),
+                        HighlightStyle {
+                            color: highlight
+                                .color
+                                .as_ref()
+                                .and_then(|color| try_parse_color(color).ok()),
+                            font_style: highlight.font_style.map(Into::into),
+                            font_weight: highlight.font_weight.map(Into::into),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // A theme's own syntax colors replace the defaults, but only override the ones of
+            // the theme it extends.
+            let syntax = Arc::make_mut(&mut styles.syntax);
+            if parent.is_some() {
+                for (syntax_token, highlight) in highlights {
+                    match syntax
+                        .highlights
+                        .iter_mut()
+                        .find(|(token, _)| *token == syntax_token)
+                    {
+                        Some((_, existing)) => *existing = highlight,
+                        None => syntax.highlights.push((syntax_token, highlight)),
+                    }
+                }
+            } else {
+                syntax.highlights = highlights;
             }
-        }));
+        }
+
+        Theme {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: user_theme.name.into(),
+            appearance: match user_theme.appearance {
+                AppearanceContent::Light => Appearance::Light,
+                AppearanceContent::Dark => Appearance::Dark,
+            },
+            styles,
+        }
     }
 
     /// Removes the themes with the given names from the registry.
     pub fn remove_user_themes(&self, themes_to_remove: &[SharedString]) {
-        self.state
-            .write()
+        let mut state = self.state.write();
+        state
             .themes
-            .retain(|name, _| !themes_to_remove.contains(name))
+            .retain(|name, _| !themes_to_remove.contains(name));
+        state
+            .user_themes
+            .retain(|name, _| !themes_to_remove.iter().any(|removed| removed == name));
     }
 
     pub fn clear(&mut self) {
-        self.state.write().themes.clear();
+        let mut state = self.state.write();
+        state.themes.clear();
+        state.user_themes.clear();
     }
 
     pub fn list_names(&self, _staff: bool) -> Vec<SharedString> {
@@ -210,6 +332,26 @@ impl ThemeRegistry {
             .cloned()
     }
 
+    /// Registers a copy of the theme with the given name whose text, icon and syntax colors
+    /// have at least `min_contrast` against their backgrounds, and returns it. Only the
+    /// lightness of colors changes, so the copy keeps the theme's hues.
+    pub fn high_contrast_variant(&self, name: &str, min_contrast: f32) -> Result<Arc<Theme>> {
+        let theme = self.get(name)?;
+        let mut styles = theme.styles.clone();
+        crate::high_contrast::increase_contrast(&mut styles, min_contrast);
+        let variant = Arc::new(Theme {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: format!("{} (High Contrast)", theme.name).into(),
+            appearance: theme.appearance,
+            styles,
+        });
+        self.state
+            .write()
+            .themes
+            .insert(variant.name.clone(), variant.clone());
+        Ok(variant)
+    }
+
     /// Loads the themes bundled with the Zed binary and adds them to the registry.
     pub fn load_bundled_themes(&self) {
         let theme_paths = self
@@ -231,7 +373,9 @@ impl ThemeRegistry {
                 continue;
             };
 
-            self.insert_user_theme_families([theme_family]);
+            self.insert_user_theme_families([theme_family])
+                .with_context(|| format!("failed to load theme at path \"{path}\""))
+                .log_err();
         }
     }
 
@@ -242,15 +386,22 @@ impl ThemeRegistry {
             .await
             .with_context(|| format!("reading themes from {themes_path:?}"))?;
 
+        // All the families are read before any is added, so that themes can extend the ones in
+        // files that are listed after theirs.
+        let mut families = Vec::new();
         while let Some(theme_path) = theme_paths.next().await {
             let Some(theme_path) = theme_path.log_err() else {
                 continue;
             };
-
-            self.load_user_theme(&theme_path, fs.clone())
+            let Some(family) = Self::read_user_theme(&theme_path, fs.clone())
                 .await
-                .log_err();
+                .log_err()
+            else {
+                continue;
+            };
+            families.push(family);
         }
+        self.insert_user_theme_families(families).log_err();
 
         Ok(())
     }
@@ -266,9 +417,36 @@ impl ThemeRegistry {
     pub async fn load_user_theme(&self, theme_path: &Path, fs: Arc<dyn Fs>) -> Result<()> {
         let theme = Self::read_user_theme(theme_path, fs).await?;
 
-        self.insert_user_theme_families([theme]);
+        self.insert_user_theme_families([theme])
+    }
 
-        Ok(())
+    /// Reloads the user theme at the given path after its file changed.
+    ///
+    /// When the file can't be loaded, its themes stay as they were and the error is returned by
+    /// [`Self::load_error`] until the file loads again.
+    pub async fn reload_user_theme(&self, theme_path: &Path, fs: Arc<dyn Fs>) -> Result<()> {
+        let result = self
+            .load_user_theme(theme_path, fs)
+            .await
+            .with_context(|| format!("reloading theme from {theme_path:?}"));
+        let mut state = self.state.write();
+        match &result {
+            Ok(()) => {
+                state.load_errors.remove(theme_path);
+            }
+            Err(error) => {
+                state
+                    .load_errors
+                    .insert(theme_path.to_path_buf(), format!("{error:#}").into());
+            }
+        }
+        result
+    }
+
+    /// Returns why the user theme at the given path last failed to reload, unless it has
+    /// reloaded since.
+    pub fn load_error(&self, theme_path: &Path) -> Option<SharedString> {
+        self.state.read().load_errors.get(theme_path).cloned()
     }
 }
 
@@ -277,3 +455,197 @@ impl Default for ThemeRegistry {
         Self::new(Box::new(()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{rgba, TestAppContext};
+
+    fn theme_json(background: &str) -> String {
+        serde_json::json!({
+            "name": "Test",
+            "author": "Test",
+            "themes": [{
+                "name": "Test Theme",
+                "appearance": "dark",
+                "style": { "background": background },
+            }],
+        })
+        .to_string()
+    }
+
+    fn theme_content(json: serde_json::Value) -> ThemeContent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_theme_inheritance() {
+        let registry = ThemeRegistry::default();
+        registry
+            .insert_user_themes([
+                theme_content(serde_json::json!({
+                    "name": "Child",
+                    "extends": "Base",
+                    "appearance": "dark",
+                    "style": { "background": "#0000ffff" },
+                })),
+                theme_content(serde_json::json!({
+                    "name": "Base",
+                    "appearance": "dark",
+                    "style": { "background": "#ff0000ff", "border": "#00ff00ff" },
+                })),
+            ])
+            .unwrap();
+        let child = registry.get("Child").unwrap();
+        assert_eq!(child.colors().background, rgba(0x0000ffff).into());
+        assert_eq!(child.colors().border, rgba(0x00ff00ff).into());
+
+        // Themes can extend ones that were registered before.
+        registry
+            .insert_user_themes([theme_content(serde_json::json!({
+                "name": "Grandchild",
+                "extends": "Child",
+                "appearance": "dark",
+                "style": {},
+            }))])
+            .unwrap();
+        let grandchild = registry.get("Grandchild").unwrap();
+        assert_eq!(grandchild.colors().background, rgba(0x0000ffff).into());
+
+        // Changing a theme rebuilds the themes extending it.
+        registry
+            .insert_user_themes([theme_content(serde_json::json!({
+                "name": "Base",
+                "appearance": "dark",
+                "style": { "background": "#ff0000ff", "border": "#ffffffff" },
+            }))])
+            .unwrap();
+        assert_eq!(
+            registry.get("Child").unwrap().colors().border,
+            rgba(0xffffffff).into()
+        );
+        assert_eq!(
+            registry.get("Grandchild").unwrap().colors().border,
+            rgba(0xffffffff).into()
+        );
+
+        // Cycles and missing parents are errors, and those themes aren't registered.
+        let error = registry
+            .insert_user_themes([
+                theme_content(serde_json::json!({
+                    "name": "A",
+                    "extends": "B",
+                    "appearance": "dark",
+                    "style": {},
+                })),
+                theme_content(serde_json::json!({
+                    "name": "B",
+                    "extends": "A",
+                    "appearance": "dark",
+                    "style": {},
+                })),
+                theme_content(serde_json::json!({
+                    "name": "Orphan",
+                    "extends": "Missing",
+                    "appearance": "dark",
+                    "style": {},
+                })),
+            ])
+            .unwrap_err();
+        assert!(error.to_string().contains("cycle"));
+        assert!(error.to_string().contains("Missing"));
+        assert!(registry.get("A").is_err());
+        assert!(registry.get("B").is_err());
+        assert!(registry.get("Orphan").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_loading_themes_extending_later_files(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/themes",
+            serde_json::json!({
+                "a_child.json": serde_json::json!({
+                    "name": "Child Family",
+                    "author": "Test",
+                    "themes": [{
+                        "name": "Child",
+                        "extends": "Test Theme",
+                        "appearance": "dark",
+                        "style": {},
+                    }],
+                })
+                .to_string(),
+                "z_base.json": theme_json("#ff0000ff"),
+            }),
+        )
+        .await;
+
+        let registry = ThemeRegistry::default();
+        registry
+            .load_user_themes(Path::new("/themes"), fs)
+            .await
+            .unwrap();
+        assert_eq!(
+            registry.get("Child").unwrap().colors().background,
+            rgba(0xff0000ff).into()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_reloading_theme(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let theme_path = Path::new("/themes/test.json");
+        fs.insert_file(theme_path, theme_json("#ff0000ff").into_bytes())
+            .await;
+
+        let registry = ThemeRegistry::default();
+        registry
+            .load_user_theme(theme_path, fs.clone())
+            .await
+            .unwrap();
+
+        // Editing the file changes the theme.
+        fs.atomic_write(theme_path.into(), theme_json("#00ff00ff"))
+            .await
+            .unwrap();
+        registry
+            .reload_user_theme(theme_path, fs.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            registry.get("Test Theme").unwrap().colors().background,
+            rgba(0x00ff00ff).into()
+        );
+        assert_eq!(registry.load_error(theme_path), None);
+
+        // A broken edit keeps the theme as it was.
+        fs.atomic_write(theme_path.into(), "{ \"name\": ".into())
+            .await
+            .unwrap();
+        assert!(registry
+            .reload_user_theme(theme_path, fs.clone())
+            .await
+            .is_err());
+        assert_eq!(
+            registry.get("Test Theme").unwrap().colors().background,
+            rgba(0x00ff00ff).into()
+        );
+        assert!(registry.load_error(theme_path).is_some());
+
+        // Fixing it clears the error.
+        fs.atomic_write(theme_path.into(), theme_json("#0000ffff"))
+            .await
+            .unwrap();
+        registry
+            .reload_user_theme(theme_path, fs.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            registry.get("Test Theme").unwrap().colors().background,
+            rgba(0x0000ffff).into()
+        );
+        assert_eq!(registry.load_error(theme_path), None);
+    }
+}
@@ -64,6 +64,9 @@ pub struct ThemeFamilyContent {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ThemeContent {
     pub name: String,
+    /// The name of a theme to take every color this one doesn't set from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     pub appearance: AppearanceContent,
     pub style: ThemeStyleContent,
 }
@@ -18,7 +18,8 @@ use settings::{Settings, SettingsJsonSchemaParams, SettingsSources};
 use std::sync::Arc;
 use util::ResultExt as _;
 
-const MIN_FONT_SIZE: Pixels = px(6.0);
+/// The smallest font size text is rendered with, however far the font size is decreased.
+pub const MIN_FONT_SIZE: Pixels = px(6.0);
 const MIN_LINE_HEIGHT: f32 = 1.0;
 
 #[derive(
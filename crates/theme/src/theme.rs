@@ -8,6 +8,7 @@
 
 mod default_colors;
 mod default_theme;
+mod high_contrast;
 mod one_themes;
 pub mod prelude;
 mod registry;
@@ -21,6 +22,7 @@ use std::sync::Arc;
 use ::settings::{Settings, SettingsStore};
 pub use default_colors::*;
 pub use default_theme::*;
+pub use high_contrast::{contrast_ratio, ensure_contrast, WCAG_AA_CONTRAST_RATIO};
 pub use registry::*;
 pub use scale::*;
 pub use schema::*;
@@ -54,6 +54,7 @@ impl VsCodeThemeConverter {
 
         Ok(ThemeContent {
             name: self.theme_metadata.name,
+            extends: None,
             appearance,
             style: ThemeStyleContent {
                 window_background_appearance: Some(theme::WindowBackgroundContent::Opaque),
@@ -17,6 +17,11 @@ pub fn temp_tree(tree: serde_json::Value) -> TempDir {
     dir
 }
 
+/// Writes the given tree of directories and files under `path`.
+///
+/// Objects are directories, strings are files with those contents, and null is an empty
+/// directory. An object with only a `"link"` string, like `{ "link": "../target" }`, is a
+/// symlink to that path, which needn't exist.
 fn write_tree(path: &Path, tree: serde_json::Value) {
     use serde_json::Value;
     use std::fs;
@@ -26,6 +31,12 @@ fn write_tree(path: &Path, tree: serde_json::Value) {
             let mut path = PathBuf::from(path);
             path.push(name);
             match contents {
+                Value::Object(ref object)
+                    if object.len() == 1 && object.get("link").is_some_and(Value::is_string) =>
+                {
+                    let target = object["link"].as_str().unwrap();
+                    symlink(Path::new(target), &path);
+                }
                 Value::Object(_) => {
                     fs::create_dir(&path).unwrap();
 
@@ -51,10 +62,36 @@ fn write_tree(path: &Path, tree: serde_json::Value) {
     }
 }
 
+#[cfg(unix)]
+fn symlink(target: &Path, path: &Path) {
+    std::os::unix::fs::symlink(target, path).unwrap();
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, path: &Path) {
+    // Windows needs to know what kind of file a link points to, so dangling ones are assumed
+    // to point to files.
+    if path.parent().unwrap().join(target).is_dir() {
+        std::os::windows::fs::symlink_dir(target, path).unwrap();
+    } else {
+        std::os::windows::fs::symlink_file(target, path).unwrap();
+    }
+}
+
+/// Returns `rows` lines of `cols` characters each, the first made of `start_char` and each
+/// following one of the next character after it.
 pub fn sample_text(rows: usize, cols: usize, start_char: char) -> String {
+    let alphabet = (0..rows)
+        .map(|row| (start_char as u32 + row as u32) as u8 as char)
+        .collect::<Vec<_>>();
+    sample_text_with(rows, cols, &alphabet)
+}
+
+/// Returns `rows` lines of `cols` characters each, every line made of the next character
+/// of `alphabet`, starting over once it runs out.
+pub fn sample_text_with(rows: usize, cols: usize, alphabet: &[char]) -> String {
     let mut text = String::new();
-    for row in 0..rows {
-        let c: char = (start_char as u32 + row as u32) as u8 as char;
+    for (row, c) in alphabet.iter().cycle().take(rows).enumerate() {
         let mut line = c.to_string().repeat(cols);
         if row < rows - 1 {
             line.push('\n');
@@ -63,3 +100,57 @@ pub fn sample_text(rows: usize, cols: usize, start_char: char) -> String {
     }
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_text() {
+        assert_eq!(sample_text(3, 2, 'x'), "xx\nyy\nzz");
+        assert_eq!(
+            sample_text_with(5, 2, &['🏀', '中', 'é']),
+            "🏀🏀\n中中\néé\n🏀🏀\n中中"
+        );
+        assert_eq!(sample_text_with(0, 2, &['a']), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_temp_tree_symlinks() {
+        use serde_json::json;
+        use std::fs;
+
+        let dir = temp_tree(json!({
+            "a": {
+                "file.txt": "contents",
+                "link": "a file named link",
+            },
+            "b": {
+                "to-file": { "link": "../a/file.txt" },
+                "to-dir": { "link": "../a" },
+                "cycle": { "link": "cycle" },
+                "dangling": { "link": "../missing" },
+            },
+        }));
+        let root = dir.path();
+
+        assert_eq!(
+            fs::read_to_string(root.join("a/link")).unwrap(),
+            "a file named link"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("b/to-file")).unwrap(),
+            "contents"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("b/to-dir/file.txt")).unwrap(),
+            "contents"
+        );
+        for name in ["cycle", "dangling"] {
+            let path = root.join("b").join(name);
+            assert!(fs::symlink_metadata(&path).unwrap().is_symlink());
+            assert!(fs::metadata(&path).is_err());
+        }
+    }
+}
@@ -6,11 +6,13 @@ mod vim_test_context;
 use std::time::Duration;
 
 use command_palette::CommandPalette;
-use editor::DisplayPoint;
+use editor::{DisplayPoint, EditorSettings};
 use futures::StreamExt;
 use gpui::{KeyBinding, Modifiers, MouseButton, TestAppContext};
+use language::CursorShape;
 pub use neovim_backed_binding_test_context::*;
 pub use neovim_backed_test_context::*;
+use settings::SettingsStore;
 pub use vim_test_context::*;
 
 use indoc::indoc;
@@ -75,6 +77,29 @@ async fn test_toggle_through_settings(cx: &mut gpui::TestAppContext) {
     assert_eq!(cx.mode(), Mode::Normal);
 }
 
+#[gpui::test]
+async fn test_cursor_shape_setting(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+    let cursor_shape =
+        |cx: &mut VimTestContext| cx.update_editor(|editor, _| editor.cursor_shape());
+
+    // Vim's modes keep deciding the cursor's shape when the setting changes.
+    cx.update(|cx| {
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.cursor_shape = Some(CursorShape::Underscore)
+            });
+        })
+    });
+    assert_eq!(cursor_shape(&mut cx), CursorShape::Block);
+    cx.simulate_keystroke("i");
+    assert_eq!(cursor_shape(&mut cx), CursorShape::Bar);
+
+    // Without vim, the setting applies again.
+    cx.disable_vim();
+    assert_eq!(cursor_shape(&mut cx), CursorShape::Underscore);
+}
+
 #[gpui::test]
 async fn test_cancel_selection(cx: &mut gpui::TestAppContext) {
     let mut cx = VimTestContext::new(cx, true).await;
@@ -27,7 +27,7 @@ use gpui::{
     actions, impl_actions, Action, AppContext, EntityId, FocusableView, Global, KeystrokeEvent,
     Subscription, View, ViewContext, WeakView, WindowContext,
 };
-use language::{CursorShape, Point, SelectionGoal, TransactionId};
+use language::{Point, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
 use normal::normal_replace;
@@ -796,7 +796,7 @@ impl Vim {
 
     fn unhook_vim_settings(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
         if editor.mode() == EditorMode::Full {
-            editor.set_cursor_shape(CursorShape::Bar, cx);
+            editor.reset_cursor_shape(cx);
             editor.set_clip_at_line_ends(false, cx);
             editor.set_collapse_matches(false);
             editor.set_input_enabled(true);
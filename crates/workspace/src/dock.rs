@@ -1,5 +1,5 @@
 use crate::persistence::model::DockData;
-use crate::{status_bar::StatusItemView, Workspace};
+use crate::{status_bar::StatusItemView, Workspace, WorkspaceSettings};
 use crate::{DraggedDock, Event};
 use gpui::{
     deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, ClickEvent, Entity,
@@ -9,12 +9,14 @@ use gpui::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::SettingsStore;
+use settings::{Settings, SettingsStore};
 use std::sync::Arc;
 use ui::{h_flex, ContextMenu, IconButton, Tooltip};
 use ui::{prelude::*, right_click_menu};
 
 const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
+/// The width of a side dock collapsed to a rail of its panels' icons.
+pub const COLLAPSED_DOCK_WIDTH: Pixels = Pixels(36.);
 
 pub enum PanelEvent {
     ZoomIn,
@@ -146,6 +148,10 @@ pub struct Dock {
     position: DockPosition,
     panel_entries: Vec<PanelEntry>,
     is_open: bool,
+    collapsed: bool,
+    /// Whether the dock was collapsed because the window got too narrow for it, rather than by
+    /// the user, so it can expand again once there's room.
+    collapsed_to_fit: bool,
     active_panel_index: usize,
     focus_handle: FocusHandle,
     pub(crate) serialized_dock: Option<DockData>,
@@ -186,6 +192,8 @@ impl DockPosition {
 
 struct PanelEntry {
     panel: Arc<dyn PanelHandle>,
+    /// Whether the panel's tab was closed in the bottom dock's tab strip.
+    tab_closed: bool,
     _subscriptions: [Subscription; 3],
 }
 
@@ -214,6 +222,8 @@ impl Dock {
                 panel_entries: Default::default(),
                 active_panel_index: 0,
                 is_open: false,
+                collapsed: false,
+                collapsed_to_fit: false,
                 focus_handle: focus_handle.clone(),
                 _subscriptions: [focus_subscription, zoom_subscription],
                 serialized_dock: None,
@@ -300,6 +310,72 @@ impl Dock {
         self.active_panel_index
     }
 
+    /// Whether the dock shows a rail of its panels' icons instead of the active panel. Only the
+    /// left and right docks can be collapsed.
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub(crate) fn set_collapsed(&mut self, collapsed: bool, cx: &mut ViewContext<Self>) {
+        let collapsed = collapsed && self.position.axis() == Axis::Horizontal;
+        self.collapsed_to_fit = false;
+        if collapsed != self.collapsed {
+            self.collapsed = collapsed;
+            if collapsed {
+                self.zoom_out(cx);
+            }
+            cx.notify();
+        }
+    }
+
+    /// Whether the dock was collapsed on purpose, rather than to fit in the window, so that it
+    /// stays collapsed when the workspace is restored.
+    pub(crate) fn is_collapsed_by_user(&self) -> bool {
+        self.collapsed && !self.collapsed_to_fit
+    }
+
+    /// Collapses the open dock when it doesn't `fit` in the window, and expands it again once it
+    /// does if it was collapsed that way.
+    pub(crate) fn collapse_to_fit(&mut self, fits: bool, cx: &mut ViewContext<Self>) {
+        if !fits && self.is_open && !self.collapsed {
+            self.set_collapsed(true, cx);
+            self.collapsed_to_fit = self.collapsed;
+        } else if fits && self.collapsed_to_fit {
+            self.set_collapsed(false, cx);
+        }
+    }
+
+    /// The narrowest the panels of a side dock are shown, whatever size they ask for.
+    pub fn min_width(&self, cx: &AppContext) -> Pixels {
+        px(WorkspaceSettings::get_global(cx).dock_min_width)
+    }
+
+    /// Keeps the size of a side dock's panels within the widths the settings allow.
+    fn clamp_size(&self, size: Pixels, cx: &AppContext) -> Pixels {
+        match self.position.axis() {
+            Axis::Horizontal => {
+                let size = size.max(self.min_width(cx));
+                match WorkspaceSettings::get_global(cx).dock_max_width {
+                    Some(max_width) => size.min(px(max_width).max(self.min_width(cx))),
+                    None => size,
+                }
+            }
+            Axis::Vertical => size,
+        }
+    }
+
+    /// Expands the dock from its icon rail, showing and focusing the panel at `panel_ix`.
+    pub fn expand_panel(&mut self, panel_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.panel_entries.get(panel_ix) else {
+            return;
+        };
+        let focus_handle = entry.panel.focus_handle(cx);
+        self.set_collapsed(false, cx);
+        self.set_open(true, cx);
+        self.activate_panel(panel_ix, cx);
+        cx.focus(&focus_handle);
+    }
+
     pub(crate) fn set_open(&mut self, open: bool, cx: &mut ViewContext<Self>) {
         if open != self.is_open {
             self.is_open = open;
@@ -415,6 +491,7 @@ impl Dock {
                         .position(|entry| entry.panel.panel_id() == Entity::entity_id(&panel))
                     {
                         this.set_open(true, cx);
+                        this.set_collapsed(false, cx);
                         this.activate_panel(ix, cx);
                         cx.focus_view(&panel);
                     }
@@ -434,6 +511,7 @@ impl Dock {
 
         self.panel_entries.push(PanelEntry {
             panel: Arc::new(panel.clone()),
+            tab_closed: false,
             _subscriptions: subscriptions,
         });
         if let Some(serialized) = self.serialized_dock.clone() {
@@ -441,8 +519,9 @@ impl Dock {
                 self.activate_panel(self.panel_entries.len() - 1, cx);
                 if serialized.visible {
                     self.set_open(true, cx);
+                    self.set_collapsed(serialized.collapsed, cx);
                 }
-                if serialized.zoom {
+                if serialized.zoom && !self.collapsed {
                     if let Some(panel) = self.active_panel() {
                         panel.set_zoomed(true, cx)
                     };
@@ -478,6 +557,9 @@ impl Dock {
     }
 
     pub fn activate_panel(&mut self, panel_ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.panel_entries.get_mut(panel_ix) {
+            entry.tab_closed = false;
+        }
         if panel_ix != self.active_panel_index {
             if let Some(active_panel) = self.panel_entries.get(self.active_panel_index) {
                 active_panel.panel.set_active(false, cx);
@@ -492,6 +574,43 @@ impl Dock {
         }
     }
 
+    /// The panels shown in the bottom dock's tab strip, which only appears once there's more than
+    /// one of them.
+    pub fn tab_panels(&self) -> Vec<Arc<dyn PanelHandle>> {
+        if self.position != DockPosition::Bottom {
+            return Vec::new();
+        }
+        self.panel_entries
+            .iter()
+            .filter(|entry| !entry.tab_closed)
+            .map(|entry| entry.panel.clone())
+            .collect()
+    }
+
+    /// Removes the panel at `panel_ix` from the tab strip, switching to the nearest remaining tab.
+    /// Closing the last tab closes the dock, and brings all the tabs back for when it reopens.
+    pub fn close_panel_tab(&mut self, panel_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.panel_entries.get_mut(panel_ix) else {
+            return;
+        };
+        entry.tab_closed = true;
+        if panel_ix == self.active_panel_index {
+            let next_tab = (panel_ix + 1..self.panel_entries.len())
+                .chain((0..panel_ix).rev())
+                .find(|ix| !self.panel_entries[*ix].tab_closed);
+            match next_tab {
+                Some(ix) => self.activate_panel(ix, cx),
+                None => {
+                    for entry in &mut self.panel_entries {
+                        entry.tab_closed = false;
+                    }
+                    self.set_open(false, cx);
+                }
+            }
+        }
+        cx.notify();
+    }
+
     pub fn visible_panel(&self) -> Option<&Arc<dyn PanelHandle>> {
         let entry = self.visible_entry()?;
         Some(&entry.panel)
@@ -502,7 +621,7 @@ impl Dock {
     }
 
     fn visible_entry(&self) -> Option<&PanelEntry> {
-        if self.is_open {
+        if self.is_open && !self.collapsed {
             self.panel_entries.get(self.active_panel_index)
         } else {
             None
@@ -522,22 +641,22 @@ impl Dock {
         self.panel_entries
             .iter()
             .find(|entry| entry.panel.panel_id() == panel.panel_id())
-            .map(|entry| entry.panel.size(cx))
+            .map(|entry| self.clamp_size(entry.panel.size(cx), cx))
     }
 
     pub fn active_panel_size(&self, cx: &WindowContext) -> Option<Pixels> {
-        if self.is_open {
+        if self.is_open && !self.collapsed {
             self.panel_entries
                 .get(self.active_panel_index)
-                .map(|entry| entry.panel.size(cx))
+                .map(|entry| self.clamp_size(entry.panel.size(cx), cx))
         } else {
             None
         }
     }
 
     pub fn resize_active_panel(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        let size = size.map(|size| self.clamp_size(size.max(RESIZE_HANDLE_SIZE), cx).round());
         if let Some(entry) = self.panel_entries.get_mut(self.active_panel_index) {
-            let size = size.map(|size| size.max(RESIZE_HANDLE_SIZE).round());
             entry.panel.set_size(size, cx);
             cx.notify();
         }
@@ -551,6 +670,89 @@ impl Dock {
         }
     }
 
+    pub fn toggle_collapsed_action(&self) -> Option<Box<dyn Action>> {
+        match self.position {
+            DockPosition::Left => Some(crate::ToggleLeftDockCollapsed.boxed_clone()),
+            DockPosition::Bottom => None,
+            DockPosition::Right => Some(crate::ToggleRightDockCollapsed.boxed_clone()),
+        }
+    }
+
+    fn render_tab_strip(&self, cx: &ViewContext<Self>) -> Option<impl IntoElement> {
+        let tabs = self
+            .panel_entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.tab_closed)
+            .filter_map(|(ix, entry)| {
+                let label = entry.panel.icon_tooltip(cx)?;
+                let is_active = ix == self.active_panel_index;
+                Some(
+                    h_flex()
+                        .id(ix)
+                        .gap_0p5()
+                        .child(
+                            Button::new(entry.panel.persistent_name(), label)
+                                .label_size(LabelSize::Small)
+                                .selected(is_active)
+                                .on_click(cx.listener(move |dock, _, cx| {
+                                    dock.activate_panel(ix, cx);
+                                    if let Some(entry) = dock.panel_entries.get(ix) {
+                                        entry.panel.focus_handle(cx).focus(cx);
+                                    }
+                                })),
+                        )
+                        .child(
+                            IconButton::new(("close-dock-tab", ix), IconName::Close)
+                                .icon_size(IconSize::XSmall)
+                                .on_click(
+                                    cx.listener(move |dock, _, cx| dock.close_panel_tab(ix, cx)),
+                                )
+                                .tooltip(|cx| Tooltip::text("Close Tab", cx)),
+                        ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        (tabs.len() > 1).then(|| {
+            h_flex()
+                .flex_none()
+                .w_full()
+                .px_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().tab_bar_background)
+                .children(tabs)
+        })
+    }
+
+    fn render_icon_rail(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let buttons = self
+            .panel_entries
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, entry)| {
+                let icon = entry.panel.icon(cx)?;
+                let tooltip = entry.panel.icon_tooltip(cx)?;
+                Some(
+                    IconButton::new(entry.panel.persistent_name(), icon)
+                        .icon_size(IconSize::Small)
+                        .selected(ix == self.active_panel_index)
+                        .on_click(cx.listener(move |dock, _, cx| dock.expand_panel(ix, cx)))
+                        .tooltip(move |cx| Tooltip::text(tooltip, cx)),
+                )
+            });
+
+        v_flex()
+            .w(COLLAPSED_DOCK_WIDTH)
+            .h_full()
+            .py_1()
+            .gap_1()
+            .items_center()
+            .children(buttons)
+    }
+
     fn dispatch_context() -> KeyContext {
         let mut dispatch_context = KeyContext::new_with_defaults();
         dispatch_context.add("Dock");
@@ -563,7 +765,7 @@ impl Render for Dock {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let dispatch_context = Self::dispatch_context();
         if let Some(entry) = self.visible_entry() {
-            let size = entry.panel.size(cx);
+            let size = self.clamp_size(entry.panel.size(cx), cx);
 
             let position = self.position;
             let create_resize_handle = || {
@@ -631,7 +833,10 @@ impl Render for Dock {
                     div()
                         .map(|this| match self.position().axis() {
                             Axis::Horizontal => this.min_w(size).h_full(),
-                            Axis::Vertical => this.min_h(size).w_full(),
+                            Axis::Vertical => this.min_h(size).w_full().flex().flex_col(),
+                        })
+                        .when(self.position() == DockPosition::Bottom, |this| {
+                            this.children(self.render_tab_strip(cx))
                         })
                         .child(
                             entry
@@ -641,6 +846,19 @@ impl Render for Dock {
                         ),
                 )
                 .when(self.resizeable, |this| this.child(create_resize_handle()))
+        } else if self.is_open && self.collapsed && !self.panel_entries.is_empty() {
+            div()
+                .key_context(dispatch_context)
+                .track_focus(&self.focus_handle)
+                .flex()
+                .h_full()
+                .bg(cx.theme().colors().panel_background)
+                .border_color(cx.theme().colors().border)
+                .map(|this| match self.position() {
+                    DockPosition::Left => this.border_r(),
+                    DockPosition::Right | DockPosition::Bottom => this.border_l(),
+                })
+                .child(self.render_icon_rail(cx))
         } else {
             div()
                 .key_context(dispatch_context)
@@ -679,6 +897,12 @@ impl Render for PanelButtons {
                 let icon_tooltip = entry.panel.icon_tooltip(cx)?;
                 let name = entry.panel.persistent_name();
                 let panel = entry.panel.clone();
+                let toggle_collapsed_action = dock.toggle_collapsed_action();
+                let collapse_label = if is_open && dock.is_collapsed() {
+                    format!("Expand {} Dock", dock.position.to_label())
+                } else {
+                    format!("Collapse {} Dock", dock.position.to_label())
+                };
 
                 let is_active_button = i == active_index && is_open;
                 let (action, tooltip) = if is_active_button {
@@ -718,6 +942,11 @@ impl Render for PanelButtons {
                                         )
                                     }
                                 }
+                                if let Some(action) = toggle_collapsed_action.as_ref() {
+                                    menu = menu
+                                        .separator()
+                                        .action(collapse_label.clone(), action.boxed_clone());
+                                }
                                 menu
                             })
                         })
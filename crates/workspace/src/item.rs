@@ -31,7 +31,7 @@ use std::{
     time::Duration,
 };
 use theme::Theme;
-use ui::Element as _;
+use ui::{Element as _, Icon};
 
 pub const LEADER_UPDATE_THROTTLE: Duration = Duration::from_millis(200);
 
@@ -39,6 +39,7 @@ pub const LEADER_UPDATE_THROTTLE: Duration = Duration::from_millis(200);
 pub struct ItemSettings {
     pub git_status: bool,
     pub close_position: ClosePosition,
+    pub file_icons: bool,
 }
 
 #[derive(Deserialize)]
@@ -75,6 +76,10 @@ pub struct ItemSettingsContent {
     ///
     /// Default: right
     close_position: Option<ClosePosition>,
+    /// Whether to show the file icon of the item's file on its tab.
+    ///
+    /// Default: false
+    file_icons: Option<bool>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -141,6 +146,10 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn tab_content(&self, _params: TabContentParams, _cx: &WindowContext) -> AnyElement {
         gpui::Empty.into_any()
     }
+    /// The icon shown on the item's tab when `tabs.file_icons` is enabled.
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        None
+    }
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
 
     fn deactivated(&mut self, _: &mut ViewContext<Self>) {}
@@ -261,6 +270,10 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn pixel_position_of_cursor(&self, _: &AppContext) -> Option<Point<Pixels>> {
         None
     }
+
+    /// Called when the item is added to a pane, or that pane's font size changes, with the
+    /// amount by which the pane's font size differs from the configured one.
+    fn set_font_size_adjustment(&mut self, _adjustment: Pixels, _cx: &mut ViewContext<Self>) {}
 }
 
 pub trait ItemHandle: 'static + Send {
@@ -273,6 +286,7 @@ pub trait ItemHandle: 'static + Send {
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString>;
     fn tab_description(&self, detail: usize, cx: &AppContext) -> Option<SharedString>;
     fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon>;
     fn telemetry_event_text(&self, cx: &WindowContext) -> Option<&'static str>;
     fn dragged_tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement;
     fn project_path(&self, cx: &AppContext) -> Option<ProjectPath>;
@@ -330,6 +344,7 @@ pub trait ItemHandle: 'static + Send {
     fn serialized_item_kind(&self) -> Option<&'static str>;
     fn show_toolbar(&self, cx: &AppContext) -> bool;
     fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>>;
+    fn set_font_size_adjustment(&self, adjustment: Pixels, cx: &mut WindowContext);
 }
 
 pub trait WeakItemHandle: Send + Sync {
@@ -379,6 +394,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).tab_content(params, cx)
     }
 
+    fn tab_icon(&self, cx: &WindowContext) -> Option<Icon> {
+        self.read(cx).tab_icon(cx)
+    }
+
     fn dragged_tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
         self.read(cx).tab_content(
             TabContentParams {
@@ -702,6 +721,10 @@ impl<T: Item> ItemHandle for View<T> {
     fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>> {
         self.read(cx).pixel_position_of_cursor(cx)
     }
+
+    fn set_font_size_adjustment(&self, adjustment: Pixels, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.set_font_size_adjustment(adjustment, cx))
+    }
 }
 
 impl From<Box<dyn ItemHandle>> for AnyView {
@@ -866,8 +889,8 @@ pub mod test {
     use crate::{ItemId, ItemNavHistory, Pane, Workspace, WorkspaceId};
     use gpui::{
         AnyElement, AppContext, Context as _, EntityId, EventEmitter, FocusableView,
-        InteractiveElement, IntoElement, Model, Render, SharedString, Task, View, ViewContext,
-        VisualContext, WeakView,
+        InteractiveElement, IntoElement, Model, Pixels, Render, SharedString, Task, View,
+        ViewContext, VisualContext, WeakView,
     };
     use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
     use std::{any::Any, cell::Cell, path::Path};
@@ -891,6 +914,7 @@ pub mod test {
         pub nav_history: Option<ItemNavHistory>,
         pub tab_descriptions: Option<Vec<&'static str>>,
         pub tab_detail: Cell<Option<usize>>,
+        pub font_size_adjustment: Pixels,
         focus_handle: gpui::FocusHandle,
     }
 
@@ -952,6 +976,7 @@ pub mod test {
                 nav_history: None,
                 tab_descriptions: None,
                 tab_detail: Default::default(),
+                font_size_adjustment: Pixels::ZERO,
                 workspace_id: Default::default(),
                 focus_handle: cx.focus_handle(),
             }
@@ -1074,6 +1099,10 @@ pub mod test {
             self.push_to_nav_history(cx);
         }
 
+        fn set_font_size_adjustment(&mut self, adjustment: Pixels, _: &mut ViewContext<Self>) {
+            self.font_size_adjustment = adjustment;
+        }
+
         fn clone_on_split(
             &self,
             _workspace_id: WorkspaceId,
@@ -1095,6 +1124,7 @@ pub mod test {
                 nav_history: None,
                 tab_descriptions: None,
                 tab_detail: Default::default(),
+                font_size_adjustment: self.font_size_adjustment,
                 workspace_id: self.workspace_id,
                 focus_handle: cx.focus_handle(),
             }))
@@ -159,6 +159,14 @@ impl Workspace {
         );
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn notification_ids(&self) -> Vec<NotificationId> {
+        self.notifications
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
     pub fn dismiss_notification(&mut self, id: &NotificationId, cx: &mut ViewContext<Self>) {
         self.dismiss_notification_internal(id, cx)
     }
@@ -4,7 +4,7 @@ use crate::{
         WeakItemHandle,
     },
     toolbar::Toolbar,
-    workspace_settings::{AutosaveSetting, TabBarSettings, WorkspaceSettings},
+    workspace_settings::{AutosaveSetting, ShowTabBar, TabBarSettings, WorkspaceSettings},
     NewCenterTerminal, NewFile, NewSearch, OpenInTerminal, OpenTerminal, OpenVisible,
     SplitDirection, ToggleZoom, Workspace,
 };
@@ -13,10 +13,11 @@ use collections::{HashMap, HashSet, VecDeque};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
     actions, anchored, deferred, impl_actions, prelude::*, Action, AnchorCorner, AnyElement,
-    AppContext, AsyncWindowContext, ClickEvent, DismissEvent, Div, DragMoveEvent, EntityId,
-    EventEmitter, ExternalPaths, FocusHandle, FocusableView, KeyContext, Model, MouseButton,
-    MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render, ScrollHandle,
-    Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView, WindowContext,
+    AnyView, AppContext, AsyncWindowContext, ClickEvent, DismissEvent, Div, DragMoveEvent,
+    EntityId, EventEmitter, ExternalPaths, FocusHandle, FocusableView, KeyContext, ManagedView,
+    Model, MouseButton, MouseDownEvent, NavigationDirection, Pixels, Point, PromptLevel, Render,
+    ScrollHandle, Subscription, Task, View, ViewContext, VisualContext, WeakFocusHandle, WeakView,
+    WindowContext,
 };
 use parking_lot::Mutex;
 use project::{Project, ProjectEntryId, ProjectPath};
@@ -125,6 +126,10 @@ actions!(
         SplitRight,
         SplitDown,
         TogglePreviewTab,
+        TogglePinTab,
+        IncreaseFontSize,
+        DecreaseFontSize,
+        ResetFontSize,
     ]
 );
 
@@ -148,6 +153,7 @@ pub enum Event {
     Focus,
     ZoomIn,
     ZoomOut,
+    ChangeFontSize,
 }
 
 impl fmt::Debug for Event {
@@ -174,6 +180,7 @@ impl fmt::Debug for Event {
             Event::Focus => f.write_str("Focus"),
             Event::ZoomIn => f.write_str("ZoomIn"),
             Event::ZoomOut => f.write_str("ZoomOut"),
+            Event::ChangeFontSize => f.write_str("ChangeFontSize"),
         }
     }
 }
@@ -190,11 +197,15 @@ pub struct Pane {
     was_focused: bool,
     active_item_index: usize,
     preview_item_id: Option<EntityId>,
+    /// The number of tabs at the start of the tab bar that are pinned.
+    pinned_tab_count: usize,
     last_focus_handle_by_item: HashMap<EntityId, WeakFocusHandle>,
     nav_history: NavHistory,
     toolbar: View<Toolbar>,
     pub new_item_menu: Option<View<ContextMenu>>,
     split_item_menu: Option<View<ContextMenu>>,
+    tab_list_menu: Option<(AnyView, FocusHandle)>,
+    tab_list_builder: Option<Rc<dyn Fn(&mut ViewContext<Pane>) -> (AnyView, FocusHandle)>>,
     //     tab_context_menu: View<ContextMenu>,
     pub(crate) workspace: WeakView<Workspace>,
     project: Model<Project>,
@@ -209,6 +220,12 @@ pub struct Pane {
     /// Is None if navigation buttons are permanently turned off (and should not react to setting changes).
     /// Otherwise, when `display_nav_history_buttons` is Some, it determines whether nav buttons should be displayed.
     display_nav_history_buttons: Option<bool>,
+    tab_bar_visible: bool,
+    /// Whether the `tab_bar.show` setting applies to this pane, which it only does for the panes
+    /// in the center of the workspace, so that dock panes keep their tab bar buttons.
+    follow_tab_bar_setting: bool,
+    /// How much larger than the configured buffer font size the items in this pane are rendered.
+    font_size_adjustment: Pixels,
     double_click_dispatch_action: Box<dyn Action>,
 }
 
@@ -292,6 +309,7 @@ impl Pane {
             zoomed: false,
             active_item_index: 0,
             preview_item_id: None,
+            pinned_tab_count: 0,
             last_focus_handle_by_item: Default::default(),
             nav_history: NavHistory(Arc::new(Mutex::new(NavHistoryState {
                 mode: NavigationMode::Normal,
@@ -305,6 +323,8 @@ impl Pane {
             toolbar: cx.new_view(|_| Toolbar::new()),
             new_item_menu: None,
             split_item_menu: None,
+            tab_list_menu: None,
+            tab_list_builder: None,
             tab_bar_scroll_handle: ScrollHandle::new(),
             drag_split_direction: None,
             workspace,
@@ -383,6 +403,9 @@ impl Pane {
             display_nav_history_buttons: Some(
                 TabBarSettings::get_global(cx).show_nav_history_buttons,
             ),
+            tab_bar_visible: true,
+            follow_tab_bar_setting: false,
+            font_size_adjustment: Pixels::ZERO,
             _subscriptions: subscriptions,
             double_click_dispatch_action,
         }
@@ -440,6 +463,10 @@ impl Pane {
             .as_ref()
             .or(self.split_item_menu.as_ref())
             .map_or(false, |menu| menu.focus_handle(cx).is_focused(cx))
+            || self
+                .tab_list_menu
+                .as_ref()
+                .map_or(false, |(_, focus_handle)| focus_handle.contains_focused(cx))
     }
 
     fn focus_out(&mut self, cx: &mut ViewContext<Self>) {
@@ -725,10 +752,17 @@ impl Pane {
                 if existing_item_is_active && destination_index.is_none() {
                     insertion_index = existing_item_index;
                 } else {
+                    let was_pinned = self.is_tab_pinned(existing_item_index);
                     self.items.remove(existing_item_index);
                     if existing_item_index < self.active_item_index {
                         self.active_item_index -= 1;
                     }
+                    // Pinned tabs stay among the pinned ones, and others stay after them.
+                    insertion_index = if was_pinned {
+                        insertion_index.min(self.pinned_tab_count - 1)
+                    } else {
+                        insertion_index.max(self.pinned_tab_count)
+                    };
                     insertion_index = insertion_index.min(self.items.len());
 
                     self.items.insert(insertion_index, item.clone());
@@ -745,6 +779,7 @@ impl Pane {
 
             self.activate_item(insertion_index, activate_pane, focus_item, cx);
         } else {
+            insertion_index = insertion_index.max(self.pinned_tab_count);
             self.items.insert(insertion_index, item.clone());
 
             if insertion_index <= self.active_item_index
@@ -757,6 +792,7 @@ impl Pane {
             cx.notify();
         }
 
+        item.set_font_size_adjustment(self.font_size_adjustment, cx);
         cx.emit(Event::AddItem { item });
     }
 
@@ -804,6 +840,10 @@ impl Pane {
             .position(|i| i.item_id() == item.item_id())
     }
 
+    pub fn index_for_item_id(&self, item_id: EntityId) -> Option<usize> {
+        self.items.iter().position(|i| i.item_id() == item_id)
+    }
+
     pub fn item_for_index(&self, ix: usize) -> Option<&dyn ItemHandle> {
         self.items.get(ix).map(|i| i.as_ref())
     }
@@ -916,10 +956,11 @@ impl Pane {
         }
 
         let active_item_id = self.items[self.active_item_index].item_id();
+        let pinned_item_ids = self.pinned_item_ids();
         Some(self.close_items(
             cx,
             action.save_intent.unwrap_or(SaveIntent::Close),
-            move |item_id| item_id != active_item_id,
+            move |item_id| item_id != active_item_id && !pinned_item_ids.contains(&item_id),
         ))
     }
 
@@ -929,7 +970,7 @@ impl Pane {
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<Result<()>>> {
         let item_ids: Vec<_> = self
-            .items()
+            .unpinned_items()
             .filter(|item| !item.is_dirty(cx))
             .map(|item| item.item_id())
             .collect();
@@ -958,6 +999,7 @@ impl Pane {
         let item_ids: Vec<_> = self
             .items()
             .take_while(|item| item.item_id() != item_id)
+            .skip(self.pinned_tab_count)
             .map(|item| item.item_id())
             .collect();
         self.close_items(cx, SaveIntent::Close, move |item_id| {
@@ -983,7 +1025,7 @@ impl Pane {
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
         let item_ids: Vec<_> = self
-            .items()
+            .unpinned_items()
             .rev()
             .take_while(|item| item.item_id() != item_id)
             .map(|item| item.item_id())
@@ -1002,11 +1044,72 @@ impl Pane {
             return None;
         }
 
-        Some(
-            self.close_items(cx, action.save_intent.unwrap_or(SaveIntent::Close), |_| {
-                true
-            }),
-        )
+        let pinned_item_ids = self.pinned_item_ids();
+        Some(self.close_items(
+            cx,
+            action.save_intent.unwrap_or(SaveIntent::Close),
+            move |item_id| !pinned_item_ids.contains(&item_id),
+        ))
+    }
+
+    pub fn pinned_tab_count(&self) -> usize {
+        self.pinned_tab_count
+    }
+
+    pub fn is_tab_pinned(&self, ix: usize) -> bool {
+        ix < self.pinned_tab_count
+    }
+
+    fn pinned_item_ids(&self) -> Vec<EntityId> {
+        self.items[..self.pinned_tab_count]
+            .iter()
+            .map(|item| item.item_id())
+            .collect()
+    }
+
+    fn unpinned_items(&self) -> impl DoubleEndedIterator<Item = &Box<dyn ItemHandle>> {
+        self.items[self.pinned_tab_count..].iter()
+    }
+
+    /// Pins the first `count` tabs, as when restoring a pane.
+    pub fn set_pinned_tab_count(&mut self, count: usize, cx: &mut ViewContext<Self>) {
+        self.pinned_tab_count = count.min(self.items.len());
+        cx.notify();
+    }
+
+    /// Pins the active tab, moving it after the other pinned ones, or unpins it, moving it
+    /// before the unpinned ones. Pinned tabs aren't closed along with others.
+    pub fn toggle_pin_tab(&mut self, _: &TogglePinTab, cx: &mut ViewContext<Self>) {
+        if self.items.is_empty() {
+            return;
+        }
+        let ix = self.active_item_index;
+        if self.is_tab_pinned(ix) {
+            self.pinned_tab_count -= 1;
+            self.move_item_to(ix, self.pinned_tab_count);
+        } else {
+            let item_id = self.items[ix].item_id();
+            if self.is_active_preview_item(item_id) {
+                self.set_preview_item_id(None, cx);
+            }
+            self.move_item_to(ix, self.pinned_tab_count);
+            self.pinned_tab_count += 1;
+        }
+        self.tab_bar_scroll_handle
+            .scroll_to_item(self.active_item_index);
+        cx.notify();
+    }
+
+    fn move_item_to(&mut self, from: usize, to: usize) {
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        if self.active_item_index == from {
+            self.active_item_index = to;
+        } else if from < self.active_item_index && to >= self.active_item_index {
+            self.active_item_index -= 1;
+        } else if from > self.active_item_index && to <= self.active_item_index {
+            self.active_item_index += 1;
+        }
     }
 
     pub(super) fn file_names_for_prompt(
@@ -1086,6 +1189,7 @@ impl Pane {
                 match answer.await {
                     Ok(0) => save_intent = SaveIntent::SaveAll,
                     Ok(1) => save_intent = SaveIntent::Skip,
+                    Ok(2) => return Ok(()),
                     _ => {}
                 }
             }
@@ -1186,6 +1290,9 @@ impl Pane {
         }
 
         let item = self.items.remove(item_index);
+        if self.is_tab_pinned(item_index) {
+            self.pinned_tab_count -= 1;
+        }
 
         cx.emit(Event::RemoveItem {
             item_id: item.item_id(),
@@ -1447,7 +1554,16 @@ impl Pane {
             },
             cx,
         );
-        let close_side = &ItemSettings::get_global(cx).close_position;
+        let item_settings = ItemSettings::get_global(cx);
+        let close_side = &item_settings.close_position;
+        let is_pinned = self.is_tab_pinned(ix);
+        let icon = if item_settings.file_icons || is_pinned {
+            item.tab_icon(cx)
+        } else {
+            None
+        };
+        // Pinned tabs show only their icon, when they have one.
+        let label = (!is_pinned || icon.is_none()).then_some(label);
         let indicator = render_item_indicator(item.boxed_clone(), cx);
         let item_id = item.item_id();
         let is_first_item = ix == 0;
@@ -1523,18 +1639,21 @@ impl Pane {
                 tab.tooltip(move |cx| Tooltip::text(text.clone(), cx))
             })
             .start_slot::<Indicator>(indicator)
-            .end_slot(
-                IconButton::new("close tab", IconName::Close)
-                    .shape(IconButtonShape::Square)
-                    .icon_color(Color::Muted)
-                    .size(ButtonSize::None)
-                    .icon_size(IconSize::XSmall)
-                    .on_click(cx.listener(move |pane, _, cx| {
-                        pane.close_item_by_id(item_id, SaveIntent::Close, cx)
-                            .detach_and_log_err(cx);
-                    })),
-            )
-            .child(label);
+            .when(!is_pinned, |tab| {
+                tab.end_slot(
+                    IconButton::new("close tab", IconName::Close)
+                        .shape(IconButtonShape::Square)
+                        .icon_color(Color::Muted)
+                        .size(ButtonSize::None)
+                        .icon_size(IconSize::XSmall)
+                        .on_click(cx.listener(move |pane, _, cx| {
+                            pane.close_item_by_id(item_id, SaveIntent::Close, cx)
+                                .detach_and_log_err(cx);
+                        })),
+                )
+            })
+            .children(icon.map(|icon| icon.size(IconSize::Small).color(Color::Muted)))
+            .children(label);
 
         let single_entry_to_resolve = {
             let item_entries = self.items[ix].project_entry_ids(cx);
@@ -1563,8 +1682,11 @@ impl Pane {
                             "Close Others",
                             Some(Box::new(CloseInactiveItems { save_intent: None })),
                             cx.handler_for(&pane, move |pane, cx| {
-                                pane.close_items(cx, SaveIntent::Close, |id| id != item_id)
-                                    .detach_and_log_err(cx);
+                                let pinned_item_ids = pane.pinned_item_ids();
+                                pane.close_items(cx, SaveIntent::Close, |id| {
+                                    id != item_id && !pinned_item_ids.contains(&id)
+                                })
+                                .detach_and_log_err(cx);
                             }),
                         )
                         .separator()
@@ -1604,6 +1726,17 @@ impl Pane {
                                     task.detach_and_log_err(cx)
                                 }
                             }),
+                        )
+                        .separator()
+                        .entry(
+                            if is_pinned { "Unpin Tab" } else { "Pin Tab" },
+                            Some(Box::new(TogglePinTab)),
+                            cx.handler_for(&pane, move |pane, cx| {
+                                if let Some(ix) = pane.index_for_item_id(item_id) {
+                                    pane.activate_item(ix, true, true, cx);
+                                    pane.toggle_pin_tab(&TogglePinTab, cx);
+                                }
+                            }),
                         );
 
                     if let Some(entry) = single_entry_to_resolve {
@@ -1701,6 +1834,11 @@ impl Pane {
                     )
                 },
             )
+            .when(
+                self.tab_list_builder.is_some()
+                    && (self.tabs_overflow() || self.tab_list_menu.is_some()),
+                |tab_bar| tab_bar.end_child(self.render_tab_list_button(cx)),
+            )
             .when(self.has_focus(cx), |tab_bar| {
                 tab_bar.end_child({
                     let render_tab_buttons = self.render_tab_bar_buttons.clone();
@@ -1749,15 +1887,78 @@ impl Pane {
             )
     }
 
-    pub fn render_menu_overlay(menu: &View<ContextMenu>) -> Div {
-        div().absolute().bottom_0().right_0().size_0().child(
-            deferred(
-                anchored()
-                    .anchor(AnchorCorner::TopRight)
-                    .child(menu.clone()),
+    /// Whether the tabs don't all fit in the tab bar, as of when it was last laid out.
+    fn tabs_overflow(&self) -> bool {
+        let bar = self.tab_bar_scroll_handle.bounds();
+        let first_tab = self.tab_bar_scroll_handle.bounds_for_item(0);
+        let last_tab = self
+            .tab_bar_scroll_handle
+            .bounds_for_item(self.items.len().saturating_sub(1));
+        match (first_tab, last_tab) {
+            (Some(first_tab), Some(last_tab)) => {
+                first_tab.left() < bar.left() || last_tab.right() > bar.right()
+            }
+            _ => false,
+        }
+    }
+
+    fn render_tab_list_button(&self, cx: &mut ViewContext<'_, Pane>) -> impl IntoElement {
+        h_flex()
+            .child(
+                IconButton::new("tab_list", IconName::ChevronDown)
+                    .icon_size(IconSize::Small)
+                    .icon_color(Color::Muted)
+                    .on_click(cx.listener(|pane, _, cx| pane.toggle_tab_list(cx)))
+                    .tooltip(|cx| Tooltip::text("All Tabs", cx)),
             )
-            .with_priority(1),
-        )
+            .when_some(self.tab_list_menu.as_ref(), |el, (tab_list_menu, _)| {
+                el.child(Self::render_menu_overlay(tab_list_menu))
+            })
+    }
+
+    /// Lets the tab bar offer a list of all the pane's tabs when they don't fit in it, built
+    /// with `build` when it's opened.
+    pub fn set_tab_list_builder<M: ManagedView>(
+        &mut self,
+        build: impl 'static + Fn(WeakView<Pane>, &mut WindowContext) -> View<M>,
+    ) {
+        self.tab_list_builder = Some(Rc::new(move |cx| {
+            let tab_list = build(cx.view().downgrade(), cx);
+            cx.subscribe(&tab_list, |pane, _, _: &DismissEvent, cx| {
+                pane.focus(cx);
+                pane.tab_list_menu = None;
+            })
+            .detach();
+            let focus_handle = tab_list.focus_handle(cx);
+            (tab_list.into(), focus_handle)
+        }));
+    }
+
+    /// Opens the list of all the pane's tabs, or closes it if it's open.
+    pub fn toggle_tab_list(&mut self, cx: &mut ViewContext<Self>) {
+        if self.tab_list_menu.take().is_some() {
+            self.focus(cx);
+        } else if let Some(build) = self.tab_list_builder.clone() {
+            let (tab_list, focus_handle) = build(cx);
+            cx.focus(&focus_handle);
+            self.tab_list_menu = Some((tab_list, focus_handle));
+        }
+        cx.notify();
+    }
+
+    /// The list of all the pane's tabs, while it's open.
+    pub fn tab_list(&self) -> Option<&AnyView> {
+        self.tab_list_menu.as_ref().map(|(tab_list, _)| tab_list)
+    }
+
+    pub fn render_menu_overlay(menu: &(impl Into<AnyView> + Clone)) -> Div {
+        let menu: AnyView = menu.clone().into();
+        div()
+            .absolute()
+            .bottom_0()
+            .right_0()
+            .size_0()
+            .child(deferred(anchored().anchor(AnchorCorner::TopRight).child(menu)).with_priority(1))
     }
 
     pub fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
@@ -1954,6 +2155,61 @@ impl Pane {
     pub fn display_nav_history_buttons(&mut self, display: Option<bool>) {
         self.display_nav_history_buttons = display;
     }
+
+    pub fn is_tab_bar_visible(&self) -> bool {
+        self.tab_bar_visible
+    }
+
+    /// Whether the tab bar is drawn, taking the `tab_bar.show` setting into account for center
+    /// panes.
+    pub fn should_display_tab_bar(&self, cx: &AppContext) -> bool {
+        if !self.tab_bar_visible || self.active_item().is_none() {
+            return false;
+        }
+        if !self.follow_tab_bar_setting {
+            return true;
+        }
+        match TabBarSettings::get_global(cx).show {
+            ShowTabBar::Always => true,
+            ShowTabBar::Never => false,
+            ShowTabBar::Auto => self.items.len() > 1,
+        }
+    }
+
+    pub fn set_follow_tab_bar_setting(&mut self, follow: bool, cx: &mut ViewContext<Self>) {
+        if self.follow_tab_bar_setting != follow {
+            self.follow_tab_bar_setting = follow;
+            cx.notify();
+        }
+    }
+
+    pub fn set_tab_bar_visible(&mut self, visible: bool, cx: &mut ViewContext<Self>) {
+        if self.tab_bar_visible != visible {
+            self.tab_bar_visible = visible;
+            cx.notify();
+        }
+    }
+
+    pub fn font_size_adjustment(&self) -> Pixels {
+        self.font_size_adjustment
+    }
+
+    /// Renders the items in this pane with a font size that differs from the global one by `adjustment`.
+    ///
+    /// The adjustment is clamped so that the font size doesn't drop below [`theme::MIN_FONT_SIZE`].
+    pub fn set_font_size_adjustment(&mut self, adjustment: Pixels, cx: &mut ViewContext<Self>) {
+        let adjustment = adjustment
+            .max(theme::MIN_FONT_SIZE - ThemeSettings::get_global(cx).buffer_font_size(cx));
+        if self.font_size_adjustment == adjustment {
+            return;
+        }
+        self.font_size_adjustment = adjustment;
+        for item in &self.items {
+            item.set_font_size_adjustment(adjustment, cx);
+        }
+        cx.emit(Event::ChangeFontSize);
+        cx.notify();
+    }
 }
 
 impl FocusableView for Pane {
@@ -1985,6 +2241,15 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &GoBack, cx| pane.navigate_backward(cx)))
             .on_action(cx.listener(|pane, _: &GoForward, cx| pane.navigate_forward(cx)))
             .on_action(cx.listener(Pane::toggle_zoom))
+            .on_action(cx.listener(|pane, _: &IncreaseFontSize, cx| {
+                pane.set_font_size_adjustment(pane.font_size_adjustment + px(1.), cx)
+            }))
+            .on_action(cx.listener(|pane, _: &DecreaseFontSize, cx| {
+                pane.set_font_size_adjustment(pane.font_size_adjustment - px(1.), cx)
+            }))
+            .on_action(cx.listener(|pane, _: &ResetFontSize, cx| {
+                pane.set_font_size_adjustment(Pixels::ZERO, cx)
+            }))
             .on_action(cx.listener(|pane: &mut Pane, action: &ActivateItem, cx| {
                 pane.activate_item(action.0, true, true, cx);
             }))
@@ -1997,6 +2262,7 @@ impl Render for Pane {
             .on_action(cx.listener(|pane: &mut Pane, _: &ActivateNextItem, cx| {
                 pane.activate_next_item(true, cx);
             }))
+            .on_action(cx.listener(Pane::toggle_pin_tab))
             .when(PreviewTabsSettings::get_global(cx).enabled, |this| {
                 this.on_action(cx.listener(|pane: &mut Pane, _: &TogglePreviewTab, cx| {
                     if let Some(active_item_id) = pane.active_item().map(|i| i.item_id()) {
@@ -2068,7 +2334,7 @@ impl Render for Pane {
                     }
                 }),
             )
-            .when(self.active_item().is_some(), |pane| {
+            .when(self.should_display_tab_bar(cx), |pane| {
                 pane.child(self.render_tab_bar(cx))
             })
             .child({
@@ -2181,6 +2447,17 @@ impl ItemNavHistory {
     pub fn pop_forward(&mut self, cx: &mut WindowContext) -> Option<NavigationEntry> {
         self.history.pop(NavigationMode::GoingForward, cx)
     }
+
+    /// Returns whether pushing now would land on top of an entry from this same item whose data
+    /// satisfies `predicate`, so that callers can avoid recording the same location twice.
+    pub fn is_last_entry<D: 'static>(&self, predicate: impl FnOnce(&D) -> bool) -> bool {
+        self.history.is_last_entry(&self.item, predicate)
+    }
+
+    /// Discards the locations that going forward would return to, as pushing a new one does.
+    pub fn clear_forward_stack(&mut self, cx: &mut WindowContext) {
+        self.history.clear_forward_stack(cx);
+    }
 }
 
 impl NavHistory {
@@ -2241,6 +2518,28 @@ impl NavHistory {
         entry
     }
 
+    fn is_last_entry<D: 'static>(
+        &self,
+        item: &Arc<dyn WeakItemHandle>,
+        predicate: impl FnOnce(&D) -> bool,
+    ) -> bool {
+        let state = self.0.lock();
+        if !matches!(
+            state.mode,
+            NavigationMode::Normal | NavigationMode::ReopeningClosedItem
+        ) {
+            return false;
+        }
+        state.backward_stack.back().map_or(false, |entry| {
+            entry.item.id() == item.id()
+                && entry
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.downcast_ref::<D>())
+                    .map_or(false, predicate)
+        })
+    }
+
     pub fn push<D: 'static + Send + Any>(
         &mut self,
         data: Option<D>,
@@ -2300,6 +2599,14 @@ impl NavHistory {
         state.did_update(cx);
     }
 
+    fn clear_forward_stack(&mut self, cx: &mut WindowContext) {
+        let state = &mut *self.0.lock();
+        if !state.forward_stack.is_empty() {
+            state.forward_stack.clear();
+            state.did_update(cx);
+        }
+    }
+
     pub fn remove_item(&mut self, item_id: EntityId) {
         let mut state = self.0.lock();
         state.paths_by_item.remove(&item_id);
@@ -2840,9 +3147,159 @@ mod tests {
         cx.executor().run_until_parked();
         cx.simulate_prompt_answer(2);
         save.await.unwrap();
+        assert_item_labels(&pane, ["A^", "B^", "C*^"], cx);
+
+        let save = pane
+            .update(cx, |pane, cx| {
+                pane.close_all_items(&CloseAllItems { save_intent: None }, cx)
+            })
+            .unwrap();
+
+        cx.executor().run_until_parked();
+        cx.simulate_prompt_answer(1);
+        save.await.unwrap();
         assert_item_labels(&pane, [], cx);
     }
 
+    #[gpui::test]
+    async fn test_pinned_tabs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        // Pinned tabs move to the left, in the order they were pinned.
+        set_labeled_items(&pane, ["A", "B", "C*", "D", "E"], cx);
+        pane.update(cx, |pane, cx| pane.toggle_pin_tab(&TogglePinTab, cx));
+        assert_item_labels(&pane, ["C*", "A", "B", "D", "E"], cx);
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(4, false, false, cx);
+            pane.toggle_pin_tab(&TogglePinTab, cx);
+        });
+        assert_item_labels(&pane, ["C", "E*", "A", "B", "D"], cx);
+        pane.update(cx, |pane, _| assert_eq!(pane.pinned_tab_count(), 2));
+
+        // Closing the other tabs leaves the pinned ones open.
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(3, false, false, cx);
+            pane.close_inactive_items(&CloseInactiveItems { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["C", "E", "B*"], cx);
+
+        // New tabs open after the pinned ones.
+        pane.update(cx, |pane, cx| pane.activate_item(0, false, false, cx));
+        add_labeled_item(&pane, "F", false, cx);
+        assert_item_labels(&pane, ["C", "E", "F*", "B"], cx);
+
+        pane.update(cx, |pane, cx| {
+            pane.close_all_items(&CloseAllItems { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        pane.update(cx, |pane, cx| pane.activate_item(0, false, false, cx));
+        assert_item_labels(&pane, ["C*", "E"], cx);
+
+        // Unpinned tabs move after the pinned ones.
+        pane.update(cx, |pane, cx| pane.toggle_pin_tab(&TogglePinTab, cx));
+        assert_item_labels(&pane, ["E", "C*"], cx);
+        pane.update(cx, |pane, _| assert_eq!(pane.pinned_tab_count(), 1));
+
+        // Pinned tabs can still be closed themselves.
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(0, false, false, cx);
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["C*"], cx);
+        pane.update(cx, |pane, _| assert_eq!(pane.pinned_tab_count(), 0));
+    }
+
+    #[gpui::test]
+    async fn test_auto_hide_tab_bar(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<TabBarSettings>(cx, |settings| {
+                settings.show = Some(ShowTabBar::Auto);
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        add_labeled_item(&pane, "A", false, cx);
+        cx.update(|cx| cx.refresh());
+        assert!(!pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+        assert!(cx.debug_bounds("TAB-0").is_none());
+
+        // A second tab brings the tab bar back.
+        add_labeled_item(&pane, "B", false, cx);
+        cx.update(|cx| cx.refresh());
+        assert!(pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+        assert!(cx.debug_bounds("TAB-0").is_some());
+        assert!(cx.debug_bounds("TAB-1").is_some());
+
+        pane.update(cx, |pane, cx| {
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        cx.update(|cx| cx.refresh());
+        assert!(cx.debug_bounds("TAB-0").is_none());
+    }
+
+    #[gpui::test]
+    async fn test_tab_bar_setting_ignores_dock_panes(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<TabBarSettings>(cx, |settings| {
+                settings.show = Some(ShowTabBar::Never);
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let center_pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        // A pane like the terminal panel's, which keeps its new-terminal and split buttons in
+        // the tab bar.
+        let dock_pane = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| {
+                Pane::new(
+                    workspace.weak_handle(),
+                    project.clone(),
+                    Default::default(),
+                    None,
+                    crate::NewTerminal.boxed_clone(),
+                    cx,
+                )
+            })
+        });
+
+        add_labeled_item(&center_pane, "A", false, cx);
+        add_labeled_item(&dock_pane, "B", false, cx);
+        assert!(!center_pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+        assert!(dock_pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<TabBarSettings>(cx, |settings| {
+                settings.show = Some(ShowTabBar::Auto);
+            });
+        });
+        assert!(!center_pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+        assert!(dock_pane.read_with(cx, |pane, cx| pane.should_display_tab_bar(cx)));
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
@@ -13,8 +13,6 @@ use std::sync::Arc;
 use ui::prelude::*;
 
 pub const HANDLE_HITBOX_SIZE: f32 = 4.0;
-const HORIZONTAL_MIN_SIZE: f32 = 80.;
-const VERTICAL_MIN_SIZE: f32 = 100.;
 
 /// One or many panes, arranged in a horizontal or vertical axis due to a split.
 /// Panes have all their tabs and capabilities preserved, and can be split again or resized.
@@ -114,6 +112,96 @@ impl PaneGroup {
         )
     }
 
+    /// Returns the pane next to `pane` in `direction` by the shape of the tree, without needing
+    /// the panes to have been laid out. Of the panes along the neighbouring edge, it picks the one
+    /// facing the middle of `pane`. When `wrap` is set, going past the outermost pane comes back
+    /// in from the opposite side.
+    pub fn pane_in_direction(
+        &self,
+        pane: &View<Pane>,
+        direction: SplitDirection,
+        wrap: bool,
+    ) -> Option<&View<Pane>> {
+        let mut path = Vec::new();
+        if !self.root.path_to_pane(pane, &mut path) {
+            return None;
+        }
+
+        let axis = direction.axis();
+        let (depth, next_ix) = path
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, (group, _))| group.axis == axis)
+            .find_map(|(depth, (group, ix))| {
+                let next_ix = if direction.increasing() {
+                    Some(ix + 1).filter(|next_ix| *next_ix < group.members.len())
+                } else {
+                    ix.checked_sub(1)
+                };
+                Some((depth, next_ix?))
+            })
+            .or_else(|| {
+                let depth = path.iter().position(|(group, _)| group.axis == axis)?;
+                let next_ix = if direction.increasing() {
+                    0
+                } else {
+                    path[depth].0.members.len() - 1
+                };
+                wrap.then_some((depth, next_ix))
+            })?;
+
+        // Where the middle of `pane` falls across the group the neighbour was found in.
+        let (mut start, mut end) = (0., 1.);
+        for (group, ix) in &path[depth + 1..] {
+            if group.axis != axis {
+                let (member_start, member_end) = group.member_span(*ix);
+                let length = end - start;
+                (start, end) = (start + member_start * length, start + member_end * length);
+            }
+        }
+        let mut position = (start + end) / 2.;
+
+        let mut member = &path[depth].0.members[next_ix];
+        loop {
+            match member {
+                Member::Pane(found) => return (found != pane).then_some(found),
+                Member::Axis(group) => {
+                    let ix = if group.axis == axis {
+                        if direction.increasing() {
+                            0
+                        } else {
+                            group.members.len() - 1
+                        }
+                    } else {
+                        let ix = group.member_at(position);
+                        let (member_start, member_end) = group.member_span(ix);
+                        position = (position - member_start) / (member_end - member_start);
+                        ix
+                    };
+                    member = &group.members[ix];
+                }
+            }
+        }
+    }
+
+    /// Returns the pane beside `pane` in the group that would take its space if it was removed,
+    /// preferring the one before it.
+    pub fn neighbor_of(&self, pane: &View<Pane>) -> Option<&View<Pane>> {
+        let mut path = Vec::new();
+        if !self.root.path_to_pane(pane, &mut path) {
+            return None;
+        }
+        let (group, _) = path.last()?;
+        let directions = match group.axis {
+            Axis::Horizontal => [SplitDirection::Left, SplitDirection::Right],
+            Axis::Vertical => [SplitDirection::Up, SplitDirection::Down],
+        };
+        directions
+            .into_iter()
+            .find_map(|direction| self.pane_in_direction(pane, direction, false))
+    }
+
     pub(crate) fn panes(&self) -> Vec<&View<Pane>> {
         let mut panes = Vec::new();
         self.root.collect_panes(&mut panes);
@@ -303,6 +391,28 @@ impl Member {
         }
     }
 
+    /// Collects the groups leading down to `pane`, with the index of the member each one
+    /// continues into, returning whether `pane` was found.
+    fn path_to_pane<'a>(
+        &'a self,
+        pane: &View<Pane>,
+        path: &mut Vec<(&'a PaneAxis, usize)>,
+    ) -> bool {
+        match self {
+            Member::Pane(found) => found == pane,
+            Member::Axis(axis) => {
+                for (ix, member) in axis.members.iter().enumerate() {
+                    path.push((axis, ix));
+                    if member.path_to_pane(pane, path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+        }
+    }
+
     fn collect_panes<'a>(&'a self, panes: &mut Vec<&'a View<Pane>>) {
         match self {
             Member::Axis(axis) => {
@@ -349,6 +459,21 @@ impl PaneAxis {
         }
     }
 
+    /// The fraction of the group's length where the member at `ix` starts and ends.
+    fn member_span(&self, ix: usize) -> (f32, f32) {
+        let flexes = self.flexes.lock();
+        let total = flexes.iter().sum::<f32>();
+        let start = flexes[..ix].iter().sum::<f32>() / total;
+        (start, start + flexes[ix] / total)
+    }
+
+    /// The index of the member covering `position`, a fraction of the group's length.
+    fn member_at(&self, position: f32) -> usize {
+        (0..self.members.len())
+            .find(|ix| position < self.member_span(*ix).1)
+            .unwrap_or(self.members.len() - 1)
+    }
+
     fn split(
         &mut self,
         old_pane: &View<Pane>,
@@ -612,7 +737,7 @@ mod element {
 
     use crate::WorkspaceSettings;
 
-    use super::{HANDLE_HITBOX_SIZE, HORIZONTAL_MIN_SIZE, VERTICAL_MIN_SIZE};
+    use super::HANDLE_HITBOX_SIZE;
 
     const DIVIDER_SIZE: f32 = 1.0;
 
@@ -677,9 +802,10 @@ mod element {
             workspace: WeakView<Workspace>,
             cx: &mut WindowContext,
         ) {
+            let settings = WorkspaceSettings::get_global(cx);
             let min_size = match axis {
-                Axis::Horizontal => px(HORIZONTAL_MIN_SIZE),
-                Axis::Vertical => px(VERTICAL_MIN_SIZE),
+                Axis::Horizontal => px(settings.minimum_pane_width),
+                Axis::Vertical => px(settings.minimum_pane_height),
             };
             let mut flexes = flexes.lock();
             debug_assert!(flex_values_in_bounds(flexes.as_slice()));
@@ -309,6 +309,18 @@ define_connection! {
         ALTER TABLE workspaces DROP COLUMN remote_project_id;
         ALTER TABLE workspaces ADD COLUMN dev_server_project_id INTEGER;
     ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN font_size_adjustment REAL;
+    ),
+    sql!(
+        ALTER TABLE panes ADD COLUMN pinned_count INTEGER;
+    ),
+    // Add collapsed docks
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN left_dock_collapsed INTEGER; //bool
+        ALTER TABLE workspaces ADD COLUMN right_dock_collapsed INTEGER; //bool
+        ALTER TABLE workspaces ADD COLUMN bottom_dock_collapsed INTEGER; //bool
+    ),
     ];
 }
 
@@ -359,12 +371,15 @@ impl WorkspaceDb {
                     left_dock_visible,
                     left_dock_active_panel,
                     left_dock_zoom,
+                    left_dock_collapsed,
                     right_dock_visible,
                     right_dock_active_panel,
                     right_dock_zoom,
+                    right_dock_collapsed,
                     bottom_dock_visible,
                     bottom_dock_active_panel,
-                    bottom_dock_zoom
+                    bottom_dock_zoom,
+                    bottom_dock_collapsed
                 FROM workspaces
                 WHERE local_paths = ?
             })
@@ -432,27 +447,33 @@ impl WorkspaceDb {
                                 left_dock_visible,
                                 left_dock_active_panel,
                                 left_dock_zoom,
+                                left_dock_collapsed,
                                 right_dock_visible,
                                 right_dock_active_panel,
                                 right_dock_zoom,
+                                right_dock_collapsed,
                                 bottom_dock_visible,
                                 bottom_dock_active_panel,
                                 bottom_dock_zoom,
+                                bottom_dock_collapsed,
                                 timestamp
                             )
-                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, CURRENT_TIMESTAMP)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, CURRENT_TIMESTAMP)
                             ON CONFLICT DO
                             UPDATE SET
                                 local_paths = ?2,
                                 left_dock_visible = ?3,
                                 left_dock_active_panel = ?4,
                                 left_dock_zoom = ?5,
-                                right_dock_visible = ?6,
-                                right_dock_active_panel = ?7,
-                                right_dock_zoom = ?8,
-                                bottom_dock_visible = ?9,
-                                bottom_dock_active_panel = ?10,
-                                bottom_dock_zoom = ?11,
+                                left_dock_collapsed = ?6,
+                                right_dock_visible = ?7,
+                                right_dock_active_panel = ?8,
+                                right_dock_zoom = ?9,
+                                right_dock_collapsed = ?10,
+                                bottom_dock_visible = ?11,
+                                bottom_dock_active_panel = ?12,
+                                bottom_dock_zoom = ?13,
+                                bottom_dock_collapsed = ?14,
                                 timestamp = CURRENT_TIMESTAMP
                         ))?((workspace.id, &local_paths, workspace.docks))
                         .context("Updating workspace")?;
@@ -483,27 +504,33 @@ impl WorkspaceDb {
                                 left_dock_visible,
                                 left_dock_active_panel,
                                 left_dock_zoom,
+                                left_dock_collapsed,
                                 right_dock_visible,
                                 right_dock_active_panel,
                                 right_dock_zoom,
+                                right_dock_collapsed,
                                 bottom_dock_visible,
                                 bottom_dock_active_panel,
                                 bottom_dock_zoom,
+                                bottom_dock_collapsed,
                                 timestamp
                             )
-                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, CURRENT_TIMESTAMP)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, CURRENT_TIMESTAMP)
                             ON CONFLICT DO
                             UPDATE SET
                                 dev_server_project_id = ?2,
                                 left_dock_visible = ?3,
                                 left_dock_active_panel = ?4,
                                 left_dock_zoom = ?5,
-                                right_dock_visible = ?6,
-                                right_dock_active_panel = ?7,
-                                right_dock_zoom = ?8,
-                                bottom_dock_visible = ?9,
-                                bottom_dock_active_panel = ?10,
-                                bottom_dock_zoom = ?11,
+                                left_dock_collapsed = ?6,
+                                right_dock_visible = ?7,
+                                right_dock_active_panel = ?8,
+                                right_dock_zoom = ?9,
+                                right_dock_collapsed = ?10,
+                                bottom_dock_visible = ?11,
+                                bottom_dock_active_panel = ?12,
+                                bottom_dock_zoom = ?13,
+                                bottom_dock_collapsed = ?14,
                                 timestamp = CURRENT_TIMESTAMP
                         ))?((
                             workspace.id,
@@ -648,6 +675,8 @@ impl WorkspaceDb {
                 SerializedPaneGroup::Pane(SerializedPane {
                     active: true,
                     children: vec![],
+                    font_size_adjustment: 0.,
+                    pinned_count: 0,
                 })
             }))
     }
@@ -664,9 +693,11 @@ impl WorkspaceDb {
             Option<PaneId>,
             Option<bool>,
             Option<String>,
+            Option<f32>,
+            Option<usize>,
         );
         self.select_bound::<GroupKey, GroupOrPane>(sql!(
-            SELECT group_id, axis, pane_id, active, flexes
+            SELECT group_id, axis, pane_id, active, flexes, font_size_adjustment, pinned_count
                 FROM (SELECT
                         group_id,
                         axis,
@@ -675,7 +706,9 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         workspace_id,
-                        flexes
+                        flexes,
+                        NULL as font_size_adjustment,
+                        NULL as pinned_count
                       FROM pane_groups
                     UNION
                       SELECT
@@ -686,33 +719,38 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         panes.workspace_id as workspace_id,
-                        NULL
+                        NULL,
+                        panes.font_size_adjustment as font_size_adjustment,
+                        panes.pinned_count as pinned_count
                       FROM center_panes
                       JOIN panes ON center_panes.pane_id = panes.pane_id)
                 WHERE parent_group_id IS ? AND workspace_id = ?
                 ORDER BY position
         ))?((group_id, workspace_id))?
         .into_iter()
-        .map(|(group_id, axis, pane_id, active, flexes)| {
-            if let Some((group_id, axis)) = group_id.zip(axis) {
-                let flexes = flexes
-                    .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
-                    .transpose()?;
-
-                Ok(SerializedPaneGroup::Group {
-                    axis,
-                    children: self.get_pane_group(workspace_id, Some(group_id))?,
-                    flexes,
-                })
-            } else if let Some((pane_id, active)) = pane_id.zip(active) {
-                Ok(SerializedPaneGroup::Pane(SerializedPane::new(
-                    self.get_items(pane_id)?,
-                    active,
-                )))
-            } else {
-                bail!("Pane Group Child was neither a pane group or a pane");
-            }
-        })
+        .map(
+            |(group_id, axis, pane_id, active, flexes, font_size, pinned)| {
+                if let Some((group_id, axis)) = group_id.zip(axis) {
+                    let flexes = flexes
+                        .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
+                        .transpose()?;
+
+                    Ok(SerializedPaneGroup::Group {
+                        axis,
+                        children: self.get_pane_group(workspace_id, Some(group_id))?,
+                        flexes,
+                    })
+                } else if let Some((pane_id, active)) = pane_id.zip(active) {
+                    Ok(SerializedPaneGroup::Pane(
+                        SerializedPane::new(self.get_items(pane_id)?, active)
+                            .with_font_size_adjustment(font_size.unwrap_or_default())
+                            .with_pinned_count(pinned.unwrap_or_default()),
+                    ))
+                } else {
+                    bail!("Pane Group Child was neither a pane group or a pane");
+                }
+            },
+        )
         // Filter out panes and pane groups which don't have any children or items
         .filter(|pane_group| match pane_group {
             Ok(SerializedPaneGroup::Group { children, .. }) => !children.is_empty(),
@@ -779,10 +817,15 @@ impl WorkspaceDb {
         parent: Option<(GroupId, usize)>,
     ) -> Result<PaneId> {
         let pane_id = conn.select_row_bound::<_, i64>(sql!(
-            INSERT INTO panes(workspace_id, active)
-            VALUES (?, ?)
+            INSERT INTO panes(workspace_id, active, font_size_adjustment, pinned_count)
+            VALUES (?, ?, ?, ?)
             RETURNING pane_id
-        ))?((workspace_id, pane.active))?
+        ))?((
+            workspace_id,
+            pane.active,
+            pane.font_size_adjustment,
+            pane.pinned_count,
+        ))?
         .ok_or_else(|| anyhow!("Could not retrieve inserted pane_id"))?;
 
         let (parent_id, order) = unzip_option(parent);
@@ -861,6 +904,7 @@ impl WorkspaceDb {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::persistence::model::DockData;
     use db::open_test_db;
     use gpui;
 
@@ -1051,7 +1095,15 @@ mod tests {
             center_group,
             bounds: Default::default(),
             display: Default::default(),
-            docks: Default::default(),
+            docks: DockStructure {
+                left: DockData {
+                    visible: true,
+                    active_panel: Some("ProjectPanel".into()),
+                    zoom: false,
+                    collapsed: true,
+                },
+                ..Default::default()
+            },
             fullscreen: false,
             centered_layout: false,
         };
@@ -1223,6 +1275,65 @@ mod tests {
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
 
+    #[gpui::test]
+    async fn test_pane_font_size_adjustment() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_pane_font_size_adjustment").await);
+
+        let center_pane = group(
+            Axis::Horizontal,
+            vec![
+                SerializedPaneGroup::Pane(
+                    SerializedPane::new(
+                        vec![SerializedItem::new("Terminal", 1, true, false)],
+                        true,
+                    )
+                    .with_font_size_adjustment(4.),
+                ),
+                SerializedPaneGroup::Pane(SerializedPane::new(
+                    vec![SerializedItem::new("Terminal", 2, true, false)],
+                    false,
+                )),
+            ],
+        );
+
+        let workspace = default_workspace(&["/tmp"], &center_pane);
+
+        db.save_workspace(workspace.clone()).await;
+
+        let new_workspace = db.workspace_for_roots(&["/tmp"]).unwrap();
+
+        assert_eq!(workspace.center_group, new_workspace.center_group);
+    }
+
+    #[gpui::test]
+    async fn test_pane_pinned_count() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_pane_pinned_count").await);
+
+        let center_pane = SerializedPaneGroup::Pane(
+            SerializedPane::new(
+                vec![
+                    SerializedItem::new("Terminal", 1, false, false),
+                    SerializedItem::new("Terminal", 2, true, false),
+                    SerializedItem::new("Terminal", 3, false, false),
+                ],
+                true,
+            )
+            .with_pinned_count(2),
+        );
+
+        let workspace = default_workspace(&["/tmp"], &center_pane);
+
+        db.save_workspace(workspace.clone()).await;
+
+        let new_workspace = db.workspace_for_roots(&["/tmp"]).unwrap();
+
+        assert_eq!(workspace.center_group, new_workspace.center_group);
+    }
+
     #[gpui::test]
     async fn test_cleanup_panes() {
         env_logger::try_init().ok();
@@ -1293,4 +1404,54 @@ mod tests {
 
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
+
+    #[gpui::test]
+    async fn test_pane_group_layout_round_trip() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_pane_group_layout_round_trip").await);
+
+        //  -----------------------------
+        //  | 1   | 2                   |
+        //  |     |- - - - - - - - - - -|
+        //  |     | 3                   |
+        //  -----------------------------
+        let pane = |item_id, active| {
+            SerializedPaneGroup::Pane(SerializedPane::new(
+                vec![SerializedItem::new("Terminal", item_id, true, false)],
+                active,
+            ))
+        };
+        let center_group = SerializedPaneGroup::Group {
+            axis: SerializedAxis(Axis::Horizontal),
+            flexes: Some(vec![0.25, 1.75]),
+            children: vec![
+                pane(1, false),
+                SerializedPaneGroup::Group {
+                    axis: SerializedAxis(Axis::Vertical),
+                    flexes: Some(vec![1.5, 0.5]),
+                    children: vec![pane(2, true), pane(3, false)],
+                },
+            ],
+        };
+        let workspace = default_workspace(&["/tmp"], &center_group);
+        db.save_workspace(workspace.clone()).await;
+        assert_eq!(
+            db.workspace_for_roots(&["/tmp"]).unwrap().center_group,
+            center_group
+        );
+
+        // A workspace with a single pane, with or without items, isn't wrapped in a group.
+        for center_group in [
+            pane(4, true),
+            SerializedPaneGroup::Pane(SerializedPane::new(Vec::new(), true)),
+        ] {
+            let workspace = default_workspace(&["/tmp"], &center_group);
+            db.save_workspace(workspace.clone()).await;
+            assert_eq!(
+                db.workspace_for_roots(&["/tmp"]).unwrap().center_group,
+                center_group
+            );
+        }
+    }
 }
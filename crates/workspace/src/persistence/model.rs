@@ -7,7 +7,7 @@ use db::sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
 };
-use gpui::{AsyncWindowContext, Bounds, DevicePixels, Model, Task, View, WeakView};
+use gpui::{px, AsyncWindowContext, Bounds, DevicePixels, Model, Task, View, WeakView};
 use project::Project;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -153,6 +153,7 @@ pub struct DockData {
     pub(crate) visible: bool,
     pub(crate) active_panel: Option<String>,
     pub(crate) zoom: bool,
+    pub(crate) collapsed: bool,
 }
 
 impl Column for DockData {
@@ -160,11 +161,13 @@ impl Column for DockData {
         let (visible, next_index) = Option::<bool>::column(statement, start_index)?;
         let (active_panel, next_index) = Option::<String>::column(statement, next_index)?;
         let (zoom, next_index) = Option::<bool>::column(statement, next_index)?;
+        let (collapsed, next_index) = Option::<bool>::column(statement, next_index)?;
         Ok((
             DockData {
                 visible: visible.unwrap_or(false),
                 active_panel,
                 zoom: zoom.unwrap_or(false),
+                collapsed: collapsed.unwrap_or(false),
             },
             next_index,
         ))
@@ -175,7 +178,8 @@ impl Bind for DockData {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
         let next_index = statement.bind(&self.visible, start_index)?;
         let next_index = statement.bind(&self.active_panel, next_index)?;
-        statement.bind(&self.zoom, next_index)
+        let next_index = statement.bind(&self.zoom, next_index)?;
+        statement.bind(&self.collapsed, next_index)
     }
 }
 
@@ -195,6 +199,8 @@ impl Default for SerializedPaneGroup {
         Self::Pane(SerializedPane {
             children: vec![SerializedItem::default()],
             active: false,
+            font_size_adjustment: 0.,
+            pinned_count: 0,
         })
     }
 }
@@ -247,6 +253,10 @@ impl SerializedPaneGroup {
                     .update(cx, |workspace, cx| workspace.add_pane(cx).downgrade())
                     .log_err()?;
                 let active = serialized_pane.active;
+                pane.update(cx, |pane, cx| {
+                    pane.set_font_size_adjustment(px(serialized_pane.font_size_adjustment), cx)
+                })
+                .log_err()?;
                 let new_items = serialized_pane
                     .deserialize_to(project, &pane, workspace_id, workspace.clone(), cx)
                     .await
@@ -267,15 +277,33 @@ impl SerializedPaneGroup {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct SerializedPane {
     pub(crate) active: bool,
     pub(crate) children: Vec<SerializedItem>,
+    pub(crate) font_size_adjustment: f32,
+    /// The number of pinned items at the start of `children`.
+    pub(crate) pinned_count: usize,
 }
 
 impl SerializedPane {
     pub fn new(children: Vec<SerializedItem>, active: bool) -> Self {
-        SerializedPane { children, active }
+        SerializedPane {
+            children,
+            active,
+            font_size_adjustment: 0.,
+            pinned_count: 0,
+        }
+    }
+
+    pub fn with_font_size_adjustment(mut self, font_size_adjustment: f32) -> Self {
+        self.font_size_adjustment = font_size_adjustment;
+        self
+    }
+
+    pub fn with_pinned_count(mut self, pinned_count: usize) -> Self {
+        self.pinned_count = pinned_count;
+        self
     }
 
     pub async fn deserialize_to(
@@ -310,7 +338,12 @@ impl SerializedPane {
         }
 
         let mut items = Vec::new();
-        for item_handle in futures::future::join_all(item_tasks).await {
+        let mut pinned_count = 0;
+        for (index, item_handle) in futures::future::join_all(item_tasks)
+            .await
+            .into_iter()
+            .enumerate()
+        {
             let item_handle = item_handle.log_err();
             items.push(item_handle.clone());
 
@@ -318,8 +351,12 @@ impl SerializedPane {
                 pane.update(cx, |pane, cx| {
                     pane.add_item(item_handle.clone(), true, true, None, cx);
                 })?;
+                if index < self.pinned_count {
+                    pinned_count += 1;
+                }
             }
         }
+        pane.update(cx, |pane, cx| pane.set_pinned_tab_count(pinned_count, cx))?;
 
         if let Some(active_item_index) = active_item_index {
             pane.update(cx, |pane, cx| {
@@ -15,6 +15,16 @@ pub trait StatusItemView: Render {
     );
 }
 
+/// The end of the status bar where an item is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusItemLocation {
+    /// Shown after the items that were added to the left before it.
+    Left,
+    /// Shown before the items that were added to the right before it, so that the first item
+    /// added is at the far right.
+    Right,
+}
+
 trait StatusItemViewHandle: Send {
     fn to_any(&self) -> AnyView;
     fn set_active_pane_item(
@@ -75,17 +85,42 @@ impl StatusBar {
         this
     }
 
-    pub fn add_left_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
-    where
+    /// Adds an item at the given end of the status bar. Each item is a view, so it re-renders
+    /// on its own when it changes, and it's told whenever the active pane item changes.
+    pub fn add_item<T>(
+        &mut self,
+        item: View<T>,
+        location: StatusItemLocation,
+        cx: &mut ViewContext<Self>,
+    ) where
         T: 'static + StatusItemView,
     {
         let active_pane_item = self.active_pane.read(cx).active_item();
         item.set_active_pane_item(active_pane_item.as_deref(), cx);
 
-        self.left_items.push(Box::new(item));
+        match location {
+            StatusItemLocation::Left => self.left_items.push(Box::new(item)),
+            StatusItemLocation::Right => self.right_items.push(Box::new(item)),
+        }
         cx.notify();
     }
 
+    pub fn add_left_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
+    where
+        T: 'static + StatusItemView,
+    {
+        self.add_item(item, StatusItemLocation::Left, cx);
+    }
+
+    /// Returns the items at the given end of the status bar, in the order they were added.
+    pub fn items(&self, location: StatusItemLocation) -> impl '_ + Iterator<Item = AnyView> {
+        match location {
+            StatusItemLocation::Left => self.left_items.iter(),
+            StatusItemLocation::Right => self.right_items.iter(),
+        }
+        .map(|item| item.to_any())
+    }
+
     pub fn item_of_type<T: StatusItemView>(&self) -> Option<View<T>> {
         self.left_items
             .iter()
@@ -143,11 +178,7 @@ impl StatusBar {
     where
         T: 'static + StatusItemView,
     {
-        let active_pane_item = self.active_pane.read(cx).active_item();
-        item.set_active_pane_item(active_pane_item.as_deref(), cx);
-
-        self.right_items.push(Box::new(item));
-        cx.notify();
+        self.add_item(item, StatusItemLocation::Right, cx);
     }
 
     pub fn set_active_pane(&mut self, active_pane: &View<Pane>, cx: &mut ViewContext<Self>) {
@@ -26,10 +26,10 @@ use futures::{
     Future, FutureExt, StreamExt,
 };
 use gpui::{
-    actions, canvas, impl_actions, point, relative, size, Action, AnyElement, AnyView, AnyWeakView,
-    AppContext, AsyncAppContext, AsyncWindowContext, Bounds, DevicePixels, DragMoveEvent,
-    ElementId, Entity as _, EntityId, EventEmitter, FocusHandle, FocusableView, Global,
-    GlobalElementId, KeyContext, Keystroke, LayoutId, ManagedView, Model, ModelContext,
+    actions, canvas, impl_actions, point, px, relative, size, Action, AnyElement, AnyView,
+    AnyWeakView, AppContext, AsyncAppContext, AsyncWindowContext, Bounds, DevicePixels,
+    DragMoveEvent, ElementId, Entity as _, EntityId, EventEmitter, FocusHandle, FocusableView,
+    Global, GlobalElementId, KeyContext, Keystroke, LayoutId, ManagedView, Model, ModelContext,
     PathPromptOptions, Point, PromptLevel, Render, Size, Subscription, Task, View, WeakView,
     WindowHandle, WindowOptions,
 };
@@ -60,7 +60,7 @@ use sqlez::{
     statement::Statement,
 };
 use status_bar::StatusBar;
-pub use status_bar::StatusItemView;
+pub use status_bar::{StatusItemLocation, StatusItemView};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -86,7 +86,7 @@ use ui::{
 use util::{maybe, ResultExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehaviour, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, RestoreOnStartupBehaviour, ShowTabBar, TabBarSettings, WorkspaceSettings,
 };
 
 use crate::notifications::NotificationId;
@@ -135,7 +135,11 @@ actions!(
         ToggleLeftDock,
         ToggleRightDock,
         ToggleBottomDock,
+        ToggleLeftDockCollapsed,
+        ToggleRightDockCollapsed,
+        CloseActivePane,
         ToggleCenteredLayout,
+        ToggleZenMode,
         CloseAllDocks,
     ]
 );
@@ -445,6 +449,40 @@ impl AppState {
 
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Arc<Self> {
+        TestAppStateBuilder::default().build(cx)
+    }
+}
+
+/// Builds an [`AppState`] for tests, with fakes for the services that aren't overridden.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Default)]
+pub struct TestAppStateBuilder {
+    fs: Option<Arc<dyn fs::Fs>>,
+    languages: Option<Arc<LanguageRegistry>>,
+    themes: Option<theme::LoadThemes>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl TestAppStateBuilder {
+    /// Uses the given file system instead of an empty [`fs::FakeFs`].
+    pub fn with_fs(mut self, fs: Arc<dyn fs::Fs>) -> Self {
+        self.fs = Some(fs);
+        self
+    }
+
+    /// Uses the given language registry instead of an empty one.
+    pub fn with_languages(mut self, languages: Arc<LanguageRegistry>) -> Self {
+        self.languages = Some(languages);
+        self
+    }
+
+    /// Loads the given themes instead of just the base ones.
+    pub fn with_themes(mut self, themes: theme::LoadThemes) -> Self {
+        self.themes = Some(themes);
+        self
+    }
+
+    pub fn build(self, cx: &mut AppContext) -> Arc<AppState> {
         use node_runtime::FakeNodeRuntime;
         use settings::SettingsStore;
         use ui::Context as _;
@@ -454,19 +492,23 @@ impl AppState {
             cx.set_global(settings_store);
         }
 
-        let fs = fs::FakeFs::new(cx.background_executor().clone());
-        let languages = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+        let fs = self
+            .fs
+            .unwrap_or_else(|| fs::FakeFs::new(cx.background_executor().clone()) as _);
+        let languages = self
+            .languages
+            .unwrap_or_else(|| Arc::new(LanguageRegistry::test(cx.background_executor().clone())));
         let clock = Arc::new(clock::FakeSystemClock::default());
         let http_client = util::http::FakeHttpClient::with_404_response();
         let client = Client::new(clock, http_client.clone(), cx);
         let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx));
         let workspace_store = cx.new_model(|cx| WorkspaceStore::new(client.clone(), cx));
 
-        theme::init(theme::LoadThemes::JustBase, cx);
+        theme::init(self.themes.unwrap_or(theme::LoadThemes::JustBase), cx);
         client::init(&client, cx);
         crate::init_settings(cx);
 
-        Arc::new(Self {
+        Arc::new(AppState {
             client,
             fs,
             languages,
@@ -589,12 +631,23 @@ pub struct Workspace {
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    /// The layout to restore when leaving zen mode, or `None` when zen mode is off.
+    zen_mode: Option<WorkspaceLayout>,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
 }
 
 impl EventEmitter<Event> for Workspace {}
 
+/// The parts of the workspace's layout that zen mode hides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorkspaceLayout {
+    pub left_dock_open: bool,
+    pub bottom_dock_open: bool,
+    pub right_dock_open: bool,
+    pub tab_bars_visible: bool,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ViewId {
     pub creator: PeerId,
@@ -700,14 +753,16 @@ impl Workspace {
         let pane_history_timestamp = Arc::new(AtomicUsize::new(0));
 
         let center_pane = cx.new_view(|cx| {
-            Pane::new(
+            let mut pane = Pane::new(
                 weak_handle.clone(),
                 project.clone(),
                 pane_history_timestamp.clone(),
                 None,
                 NewFile.boxed_clone(),
                 cx,
-            )
+            );
+            pane.set_follow_tab_bar_setting(true, cx);
+            pane
         });
         cx.subscribe(&center_pane, Self::handle_pane_event).detach();
 
@@ -775,6 +830,7 @@ impl Workspace {
         let subscriptions = vec![
             cx.observe_window_activation(Self::on_window_activation_changed),
             cx.observe_window_bounds(move |this, cx| {
+                this.collapse_docks_to_fit(cx);
                 if this.bounds_save_task_queued.is_some() {
                     return;
                 }
@@ -823,6 +879,7 @@ impl Workspace {
                 ThemeSettings::reload_current_theme(cx);
             }),
             cx.observe(&left_dock, |this, _, cx| {
+                this.collapse_docks_to_fit(cx);
                 this.serialize_workspace(cx);
                 cx.notify();
             }),
@@ -831,6 +888,7 @@ impl Workspace {
                 cx.notify();
             }),
             cx.observe(&right_dock, |this, _, cx| {
+                this.collapse_docks_to_fit(cx);
                 this.serialize_workspace(cx);
                 cx.notify();
             }),
@@ -880,6 +938,7 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            zen_mode: None,
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
         }
@@ -1503,6 +1562,7 @@ impl Workspace {
                 match answer.await.log_err() {
                     Some(0) => save_intent = SaveIntent::SaveAll,
                     Some(1) => save_intent = SaveIntent::Skip,
+                    Some(2) => return Ok(false),
                     _ => {}
                 }
             }
@@ -1907,6 +1967,56 @@ impl Workspace {
         self.serialize_workspace(cx);
     }
 
+    /// Collapses the dock on the given side to a rail of its panels' icons, or expands it back to
+    /// the active panel. A closed dock is opened collapsed.
+    pub fn toggle_dock_collapsed(&mut self, dock_side: DockPosition, cx: &mut ViewContext<Self>) {
+        let dock = match dock_side {
+            DockPosition::Left => &self.left_dock,
+            DockPosition::Right => &self.right_dock,
+            DockPosition::Bottom => return,
+        };
+        let mut focus_center = false;
+        dock.update(cx, |dock, cx| {
+            let collapse = !dock.is_open() || !dock.is_collapsed();
+            if collapse {
+                focus_center = dock.focus_handle(cx).contains_focused(cx);
+                dock.set_open(true, cx);
+                dock.set_collapsed(true, cx);
+            } else {
+                let ix = dock.active_panel_index();
+                dock.expand_panel(ix, cx);
+            }
+        });
+
+        if focus_center {
+            self.active_pane.update(cx, |pane, cx| pane.focus(cx))
+        }
+
+        cx.notify();
+        self.serialize_workspace(cx);
+    }
+
+    /// Collapses the side docks that would leave the center narrower than the minimum pane width
+    /// in the current window, and expands the ones that were collapsed that way once they fit.
+    fn collapse_docks_to_fit(&mut self, cx: &mut ViewContext<Self>) {
+        let minimum_pane_width = px(WorkspaceSettings::get_global(cx).minimum_pane_width);
+        let available_width = cx.viewport_size().width - minimum_pane_width;
+        let mut required_width = px(0.);
+        for dock in [&self.left_dock, &self.right_dock] {
+            dock.update(cx, |dock, cx| {
+                let min_width = dock.min_width(cx);
+                dock.collapse_to_fit(required_width + min_width <= available_width, cx);
+                if dock.is_open() {
+                    required_width += if dock.is_collapsed() {
+                        dock::COLLAPSED_DOCK_WIDTH
+                    } else {
+                        min_width
+                    };
+                }
+            });
+        }
+    }
+
     pub fn close_all_docks(&mut self, cx: &mut ViewContext<Self>) {
         let docks = [&self.left_dock, &self.bottom_dock, &self.right_dock];
 
@@ -1953,6 +2063,7 @@ impl Workspace {
                     if let Some(panel) = panel.as_ref() {
                         if should_focus(&**panel, cx) {
                             dock.set_open(true, cx);
+                            dock.set_collapsed(false, cx);
                             panel.focus_handle(cx).focus(cx);
                         } else {
                             focus_center = true;
@@ -1986,6 +2097,7 @@ impl Workspace {
                 dock.update(cx, |dock, cx| {
                     dock.activate_panel(panel_index, cx);
                     dock.set_open(true, cx);
+                    dock.set_collapsed(false, cx);
                 });
             }
         }
@@ -2043,15 +2155,20 @@ impl Workspace {
 
     fn add_pane(&mut self, cx: &mut ViewContext<Self>) -> View<Pane> {
         let pane = cx.new_view(|cx| {
-            Pane::new(
+            let mut pane = Pane::new(
                 self.weak_handle(),
                 self.project.clone(),
                 self.pane_history_timestamp.clone(),
                 None,
                 NewFile.boxed_clone(),
                 cx,
-            )
+            );
+            pane.set_follow_tab_bar_setting(true, cx);
+            pane
         });
+        if self.zen_mode.is_some() {
+            pane.update(cx, |pane, cx| pane.set_tab_bar_visible(false, cx));
+        }
         cx.subscribe(&pane, Self::handle_pane_event).detach();
         self.panes.push(pane.clone());
         cx.focus_view(&pane);
@@ -2400,6 +2517,12 @@ impl Workspace {
                         SplitDirection::Left => try_dock(&self.left_dock),
                         SplitDirection::Right => try_dock(&self.right_dock),
                     }
+                    .or_else(|| {
+                        let wrap = WorkspaceSettings::get_global(cx).wrap_pane_navigation;
+                        self.center
+                            .pane_in_direction(&self.active_pane, direction, wrap)
+                            .map(|pane| Target::Pane(pane.clone()))
+                    })
                 }
             }
 
@@ -2448,7 +2571,11 @@ impl Workspace {
         cx: &WindowContext,
     ) -> Option<View<Pane>> {
         let Some(bounding_box) = self.center.bounding_box_for_pane(&self.active_pane) else {
-            return None;
+            // The panes haven't been laid out yet, so go by the shape of the pane tree.
+            return self
+                .center
+                .pane_in_direction(&self.active_pane, direction, false)
+                .cloned();
         };
         let cursor = self.active_pane.read(cx).pixel_position_of_cursor(cx);
         let center = match cursor {
@@ -2572,6 +2699,7 @@ impl Workspace {
                 }
                 cx.notify();
             }
+            pane::Event::ChangeFontSize => {}
         }
 
         self.serialize_workspace(cx);
@@ -2689,8 +2817,9 @@ impl Workspace {
     }
 
     fn remove_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
+        let neighbor = self.center.neighbor_of(&pane).cloned();
         if self.center.remove(&pane).unwrap() {
-            self.force_remove_pane(&pane, cx);
+            self.force_remove_pane(&pane, neighbor.as_ref(), cx);
             self.unfollow(&pane, cx);
             self.last_leaders_by_pane.remove(&pane.downgrade());
             for removed_item in pane.read(cx).items() {
@@ -2703,6 +2832,29 @@ impl Workspace {
         }
     }
 
+    /// Closes the active pane along with its items, asking to save the dirty ones, and moves
+    /// focus to the pane beside it. The last pane in the center is never closed.
+    pub fn close_active_pane(&mut self, _: &CloseActivePane, cx: &mut ViewContext<Self>) {
+        if self.center.panes().len() < 2 {
+            return;
+        }
+        let pane = self.active_pane.clone();
+        let close_items = pane.update(cx, |pane, cx| {
+            pane.close_items(cx, SaveIntent::Close, |_| true)
+        });
+        cx.spawn(|this, mut cx| async move {
+            close_items.await?;
+            this.update(&mut cx, |this, cx| {
+                // A pane removes itself once its last item closes, but an empty one has to be
+                // removed here.
+                if pane.read(cx).items_len() == 0 && this.panes.contains(&pane) {
+                    this.remove_pane(pane, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn panes(&self) -> &[View<Pane>] {
         &self.panes
     }
@@ -2711,6 +2863,14 @@ impl Workspace {
         &self.active_pane
     }
 
+    /// The center pane that has focus. Unlike the active pane, there's none while focus is in a
+    /// dock or anywhere else outside the center.
+    pub fn focused_pane(&self, cx: &WindowContext) -> Option<&View<Pane>> {
+        self.panes
+            .iter()
+            .find(|pane| pane.focus_handle(cx).contains_focused(cx))
+    }
+
     pub fn adjacent_pane(&mut self, cx: &mut ViewContext<Self>) -> View<Pane> {
         self.find_pane_in_direction(SplitDirection::Right, cx)
             .or_else(|| self.find_pane_in_direction(SplitDirection::Left, cx))
@@ -3509,16 +3669,21 @@ impl Workspace {
                 }
             }
             Member::Pane(pane) => {
-                self.force_remove_pane(&pane, cx);
+                self.force_remove_pane(&pane, None, cx);
             }
         }
     }
 
-    fn force_remove_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Workspace>) {
+    fn force_remove_pane(
+        &mut self,
+        pane: &View<Pane>,
+        focus_on: Option<&View<Pane>>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
         self.panes.retain(|p| p != pane);
-        self.panes
-            .last()
-            .unwrap()
+        focus_on
+            .filter(|focus_on| self.panes.contains(focus_on))
+            .unwrap_or_else(|| self.panes.last().unwrap())
             .update(cx, |pane, cx| pane.focus(cx));
         if self.last_active_center_pane == Some(pane.downgrade()) {
             self.last_active_center_pane = None;
@@ -3543,7 +3708,7 @@ impl Workspace {
 
     fn serialize_workspace_internal(&self, cx: &mut WindowContext) -> Task<()> {
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
-            let (items, active) = {
+            let (items, active, font_size_adjustment, pinned_count) = {
                 let pane = pane_handle.read(cx);
                 let active_item_id = pane.active_item().map(|item| item.item_id());
                 (
@@ -3558,10 +3723,17 @@ impl Workspace {
                         })
                         .collect::<Vec<_>>(),
                     pane.has_focus(cx),
+                    pane.font_size_adjustment(),
+                    pane.items()
+                        .take(pane.pinned_tab_count())
+                        .filter(|item_handle| item_handle.serialized_item_kind().is_some())
+                        .count(),
                 )
             };
 
             SerializedPane::new(items, active)
+                .with_font_size_adjustment(font_size_adjustment.0)
+                .with_pinned_count(pinned_count)
         }
 
         fn build_serialized_pane_group(
@@ -3592,7 +3764,9 @@ impl Workspace {
             let left_dock = this.left_dock.read(cx);
             let left_visible = left_dock.is_open();
             let left_active_panel = left_dock
-                .visible_panel()
+                .is_open()
+                .then(|| left_dock.active_panel())
+                .flatten()
                 .map(|panel| panel.persistent_name().to_string());
             let left_dock_zoom = left_dock
                 .visible_panel()
@@ -3602,7 +3776,9 @@ impl Workspace {
             let right_dock = this.right_dock.read(cx);
             let right_visible = right_dock.is_open();
             let right_active_panel = right_dock
-                .visible_panel()
+                .is_open()
+                .then(|| right_dock.active_panel())
+                .flatten()
                 .map(|panel| panel.persistent_name().to_string());
             let right_dock_zoom = right_dock
                 .visible_panel()
@@ -3612,7 +3788,9 @@ impl Workspace {
             let bottom_dock = this.bottom_dock.read(cx);
             let bottom_visible = bottom_dock.is_open();
             let bottom_active_panel = bottom_dock
-                .visible_panel()
+                .is_open()
+                .then(|| bottom_dock.active_panel())
+                .flatten()
                 .map(|panel| panel.persistent_name().to_string());
             let bottom_dock_zoom = bottom_dock
                 .visible_panel()
@@ -3624,16 +3802,19 @@ impl Workspace {
                     visible: left_visible,
                     active_panel: left_active_panel,
                     zoom: left_dock_zoom,
+                    collapsed: left_dock.is_collapsed_by_user(),
                 },
                 right: DockData {
                     visible: right_visible,
                     active_panel: right_active_panel,
                     zoom: right_dock_zoom,
+                    collapsed: right_dock.is_collapsed_by_user(),
                 },
                 bottom: DockData {
                     visible: bottom_visible,
                     active_panel: bottom_active_panel,
                     zoom: bottom_dock_zoom,
+                    collapsed: bottom_dock.is_collapsed_by_user(),
                 },
             }
         }
@@ -3826,6 +4007,16 @@ impl Workspace {
                     workspace.toggle_dock(DockPosition::Bottom, cx);
                 }),
             )
+            .on_action(cx.listener(
+                |workspace: &mut Workspace, _: &ToggleLeftDockCollapsed, cx| {
+                    workspace.toggle_dock_collapsed(DockPosition::Left, cx);
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace: &mut Workspace, _: &ToggleRightDockCollapsed, cx| {
+                    workspace.toggle_dock_collapsed(DockPosition::Right, cx);
+                },
+            ))
             .on_action(
                 cx.listener(|workspace: &mut Workspace, _: &CloseAllDocks, cx| {
                     workspace.close_all_docks(cx);
@@ -3833,6 +4024,7 @@ impl Workspace {
             )
             .on_action(cx.listener(Workspace::open))
             .on_action(cx.listener(Workspace::close_window))
+            .on_action(cx.listener(Workspace::close_active_pane))
             .on_action(cx.listener(Workspace::activate_pane_at_index))
             .on_action(
                 cx.listener(|workspace: &mut Workspace, _: &ReopenClosedItem, cx| {
@@ -3840,6 +4032,7 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(Workspace::toggle_zen_mode))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -3917,6 +4110,74 @@ impl Workspace {
         cx.notify();
     }
 
+    pub fn is_zen_mode(&self) -> bool {
+        self.zen_mode.is_some()
+    }
+
+    /// Returns which parts of the workspace's layout are currently shown.
+    pub fn layout(&self, cx: &AppContext) -> WorkspaceLayout {
+        WorkspaceLayout {
+            left_dock_open: self.left_dock.read(cx).is_open(),
+            bottom_dock_open: self.bottom_dock.read(cx).is_open(),
+            right_dock_open: self.right_dock.read(cx).is_open(),
+            tab_bars_visible: self
+                .panes
+                .iter()
+                .all(|pane| pane.read(cx).is_tab_bar_visible()),
+        }
+    }
+
+    fn set_layout(&mut self, layout: WorkspaceLayout, cx: &mut ViewContext<Self>) {
+        for (dock, open) in [
+            (&self.left_dock, layout.left_dock_open),
+            (&self.bottom_dock, layout.bottom_dock_open),
+            (&self.right_dock, layout.right_dock_open),
+        ] {
+            dock.update(cx, |dock, cx| dock.set_open(open, cx));
+        }
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| {
+                pane.set_tab_bar_visible(layout.tab_bars_visible, cx)
+            });
+        }
+    }
+
+    /// Hides the docks, tab bars and status bar to leave only the centered editor on screen,
+    /// or brings back the layout from before zen mode was entered.
+    pub fn toggle_zen_mode(&mut self, _: &ToggleZenMode, cx: &mut ViewContext<Self>) {
+        if let Some(layout) = self.zen_mode.take() {
+            self.set_layout(layout, cx);
+        } else {
+            let layout = self.layout(cx);
+            self.zen_mode = Some(layout);
+            self.set_layout(
+                WorkspaceLayout {
+                    left_dock_open: false,
+                    bottom_dock_open: false,
+                    right_dock_open: false,
+                    tab_bars_visible: false,
+                },
+                cx,
+            );
+            self.dismiss_zoomed_items_to_reveal(None, cx);
+            self.active_pane.update(cx, |pane, cx| pane.focus(cx));
+        }
+        cx.notify();
+    }
+
+    fn zen_mode_max_width(&self, cx: &WindowContext) -> Option<Pixels> {
+        self.zen_mode.as_ref()?;
+        let columns = WorkspaceSettings::get_global(cx).zen_mode.max_line_width?;
+        let theme_settings = ThemeSettings::get_global(cx);
+        let font_id = cx.text_system().resolve_font(&theme_settings.buffer_font);
+        let em_width = cx
+            .text_system()
+            .advance(font_id, theme_settings.buffer_font_size(cx), 'm')
+            .log_err()?
+            .width;
+        Some(em_width * columns as f32)
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -4067,7 +4328,9 @@ impl Render for Workspace {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let mut context = KeyContext::new_with_defaults();
         context.add("Workspace");
+        let zen_mode_max_width = self.zen_mode_max_width(cx);
         let centered_layout = self.centered_layout
+            && self.zen_mode.is_none()
             && self.center.panes().len() == 1
             && self.active_item(cx).is_some();
         let render_padding = |size| {
@@ -4186,15 +4449,32 @@ impl Render for Workspace {
                                             .when_some(paddings.0, |this, p| {
                                                 this.child(p.border_r_1())
                                             })
-                                            .child(self.center.render(
-                                                &self.project,
-                                                &self.follower_states,
-                                                self.active_call(),
-                                                &self.active_pane,
-                                                self.zoomed.as_ref(),
-                                                &self.app_state,
-                                                cx,
-                                            ))
+                                            .map(|this| {
+                                                let center = self.center.render(
+                                                    &self.project,
+                                                    &self.follower_states,
+                                                    self.active_call(),
+                                                    &self.active_pane,
+                                                    self.zoomed.as_ref(),
+                                                    &self.app_state,
+                                                    cx,
+                                                );
+                                                if self.zen_mode.is_some() {
+                                                    this.justify_center().child(
+                                                        div()
+                                                            .size_full()
+                                                            .when_some(
+                                                                zen_mode_max_width,
+                                                                |this, max_width| {
+                                                                    this.max_w(max_width)
+                                                                },
+                                                            )
+                                                            .child(center),
+                                                    )
+                                                } else {
+                                                    this.child(center)
+                                                }
+                                            })
                                             .when_some(paddings.1, |this, p| {
                                                 this.child(p.border_l_1())
                                             }),
@@ -4238,7 +4518,9 @@ impl Render for Workspace {
                     .child(self.modal_layer.clone())
                     .children(self.render_notifications(cx)),
             )
-            .child(self.status_bar.clone())
+            .when(self.zen_mode.is_none(), |this| {
+                this.child(self.status_bar.clone())
+            })
             .children(if self.project.read(cx).is_disconnected() {
                 Some(DisconnectedOverlay)
             } else {
@@ -5178,12 +5460,28 @@ mod tests {
     use fs::FakeFs;
     use gpui::{
         px, BorrowAppContext, DismissEvent, Empty, EventEmitter, FocusHandle, FocusableView,
-        Render, TestAppContext, VisualTestContext,
+        Modifiers, MouseButton, Render, TestAppContext, VisualTestContext,
     };
     use project::{Project, ProjectEntryId};
     use serde_json::json;
     use settings::SettingsStore;
 
+    #[gpui::test]
+    async fn test_app_state_builder(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "" })).await;
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+
+        let app_state = cx.update(|cx| {
+            TestAppStateBuilder::default()
+                .with_fs(fs.clone())
+                .with_languages(languages.clone())
+                .build(cx)
+        });
+        assert!(app_state.fs.is_file(Path::new("/root/a.txt")).await);
+        assert!(Arc::ptr_eq(&app_state.languages, &languages));
+    }
+
     #[gpui::test]
     async fn test_tab_disambiguation(cx: &mut TestAppContext) {
         init_test(cx);
@@ -5343,26 +5641,43 @@ mod tests {
         let task = workspace.update(cx, |w, cx| w.prepare_to_close(false, cx));
         assert!(task.await.unwrap());
 
-        // When there are dirty untitled items, prompt to save each one. If the user
-        // cancels any prompt, then abort.
+        // When there are several dirty items, prompt to save all of them at once. If the
+        // user cancels the prompt, then abort.
         let item2 = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
         let item3 = cx.new_view(|cx| {
             TestItem::new(cx)
                 .with_dirty(true)
                 .with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
         });
+        let item4 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_project_items(&[TestProjectItem::new(2, "2.txt", cx)])
+        });
         workspace.update(cx, |w, cx| {
             w.add_item_to_active_pane(Box::new(item2.clone()), None, cx);
             w.add_item_to_active_pane(Box::new(item3.clone()), None, cx);
+            w.add_item_to_active_pane(Box::new(item4.clone()), None, cx);
         });
         let task = workspace.update(cx, |w, cx| w.prepare_to_close(false, cx));
         cx.executor().run_until_parked();
-        cx.simulate_prompt_answer(2); // cancel save all
-        cx.executor().run_until_parked();
+        assert!(cx.has_pending_prompt());
         cx.simulate_prompt_answer(2); // cancel save all
         cx.executor().run_until_parked();
         assert!(!cx.has_pending_prompt());
         assert!(!task.await.unwrap());
+
+        // Discarding all of the changes closes the window without any further prompts.
+        let task = workspace.update(cx, |w, cx| w.prepare_to_close(false, cx));
+        cx.executor().run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1); // discard all
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+        for item in [&item3, &item4] {
+            item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+        }
     }
 
     #[gpui::test]
@@ -5416,9 +5731,20 @@ mod tests {
         });
         cx.executor().run_until_parked();
 
+        // There's a single prompt for all of the dirty items. Cancelling it keeps them open.
         assert!(cx.has_pending_prompt());
-        // Ignore "Save all" prompt
         cx.simulate_prompt_answer(2);
+        close_items.await.unwrap();
+        assert!(!cx.has_pending_prompt());
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.items_len(), 4);
+            assert_eq!(pane.active_item().unwrap().item_id(), item2.item_id());
+        });
+
+        // Closing the items one at a time prompts for each of them.
+        let close_item = pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item1.item_id(), SaveIntent::Close, cx)
+        });
         cx.executor().run_until_parked();
         // There's a prompt to save item 1.
         pane.update(cx, |pane, _| {
@@ -5427,28 +5753,44 @@ mod tests {
         });
         // Confirm saving item 1.
         cx.simulate_prompt_answer(0);
-        cx.executor().run_until_parked();
+        close_item.await.unwrap();
 
-        // Item 1 is saved. There's a prompt to save item 3.
+        // Item 1 is saved.
         pane.update(cx, |pane, cx| {
             assert_eq!(item1.read(cx).save_count, 1);
             assert_eq!(item1.read(cx).save_as_count, 0);
             assert_eq!(item1.read(cx).reload_count, 0);
             assert_eq!(pane.items_len(), 3);
+        });
+
+        // There's a prompt to save item 3.
+        let close_item = pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item3.item_id(), SaveIntent::Close, cx)
+        });
+        cx.executor().run_until_parked();
+        pane.update(cx, |pane, _| {
             assert_eq!(pane.active_item().unwrap().item_id(), item3.item_id());
         });
         assert!(cx.has_pending_prompt());
 
         // Cancel saving item 3.
         cx.simulate_prompt_answer(1);
-        cx.executor().run_until_parked();
+        close_item.await.unwrap();
 
-        // Item 3 is reloaded. There's a prompt to save item 4.
+        // Item 3 is reloaded.
         pane.update(cx, |pane, cx| {
             assert_eq!(item3.read(cx).save_count, 0);
             assert_eq!(item3.read(cx).save_as_count, 0);
             assert_eq!(item3.read(cx).reload_count, 1);
             assert_eq!(pane.items_len(), 2);
+        });
+
+        // There's a prompt to save item 4.
+        let close_item = pane.update(cx, |pane, cx| {
+            pane.close_item_by_id(item4.item_id(), SaveIntent::Close, cx)
+        });
+        cx.executor().run_until_parked();
+        pane.update(cx, |pane, _| {
             assert_eq!(pane.active_item().unwrap().item_id(), item4.item_id());
         });
         assert!(cx.has_pending_prompt());
@@ -5459,7 +5801,7 @@ mod tests {
 
         // There's a prompt for a path for item 4.
         cx.simulate_new_path_selection(|_| Some(Default::default()));
-        close_items.await.unwrap();
+        close_item.await.unwrap();
 
         // The requested items are closed.
         pane.update(cx, |pane, cx| {
@@ -5523,8 +5865,8 @@ mod tests {
         let left_pane = workspace.update(cx, |workspace, cx| {
             let left_pane = workspace.active_pane().clone();
             workspace.add_item_to_active_pane(Box::new(item_2_3.clone()), None, cx);
-            for item in single_entry_items {
-                workspace.add_item_to_active_pane(Box::new(item), None, cx);
+            for item in &single_entry_items {
+                workspace.add_item_to_active_pane(Box::new(item.clone()), None, cx);
             }
             left_pane.update(cx, |pane, cx| {
                 pane.activate_item(2, true, true, cx);
@@ -5543,42 +5885,31 @@ mod tests {
 
         cx.focus_view(&left_pane);
 
-        // When closing all of the items in the left pane, we should be prompted twice:
-        // once for project entry 0, and once for project entry 2. Project entries 1,
-        // 3, and 4 are all still open in the other paten. After those two
-        // prompts, the task should complete.
+        // When closing all of the items in the left pane and choosing to save them all,
+        // only project entries 0 and 2 should be saved. Project entries 1, 3, and 4 are
+        // all still open in the other pane.
 
         let close = left_pane.update(cx, |pane, cx| {
             pane.close_all_items(&CloseAllItems::default(), cx).unwrap()
         });
         cx.executor().run_until_parked();
 
-        // Discard "Save all" prompt
-        cx.simulate_prompt_answer(2);
-
-        cx.executor().run_until_parked();
-        left_pane.update(cx, |pane, cx| {
-            assert_eq!(
-                pane.active_item().unwrap().project_entry_ids(cx).as_slice(),
-                &[ProjectEntryId::from_proto(0)]
-            );
-        });
-        cx.simulate_prompt_answer(0);
-
-        cx.executor().run_until_parked();
-        left_pane.update(cx, |pane, cx| {
-            assert_eq!(
-                pane.active_item().unwrap().project_entry_ids(cx).as_slice(),
-                &[ProjectEntryId::from_proto(2)]
-            );
-        });
+        // Confirm "Save all" prompt
         cx.simulate_prompt_answer(0);
 
         cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
         close.await.unwrap();
         left_pane.update(cx, |pane, _| {
             assert_eq!(pane.items_len(), 0);
         });
+        for (ix, item) in single_entry_items.iter().enumerate() {
+            let expected_save_count = if ix == 0 || ix == 2 { 1 } else { 0 };
+            item.update(cx, |item, _| {
+                assert_eq!(item.save_count, expected_save_count)
+            });
+        }
+        item_2_3.update(cx, |item, _| assert_eq!(item.save_count, 0));
     }
 
     #[gpui::test]
@@ -5755,6 +6086,253 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_pane_in_direction(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        // Lay the panes out as 1 beside a column of 2 over 3, with 2 taking a quarter of it.
+        let pane_1 = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let (pane_2, pane_3) = workspace.update(cx, |workspace, cx| {
+            let pane_2 = workspace.split_pane(pane_1.clone(), SplitDirection::Right, cx);
+            let pane_3 = workspace.split_pane(pane_2.clone(), SplitDirection::Down, cx);
+            (pane_2, pane_3)
+        });
+        workspace.update(cx, |workspace, _| {
+            let Member::Axis(axis) = &workspace.center.root else {
+                panic!("expected a split");
+            };
+            let Member::Axis(column) = &axis.members[1] else {
+                panic!("expected a nested split");
+            };
+            *column.flexes.lock() = vec![0.5, 1.5];
+        });
+
+        workspace.update(cx, |workspace, _| {
+            let center = &workspace.center;
+            let pane_in_direction = |pane: &View<Pane>, direction, wrap| {
+                center.pane_in_direction(pane, direction, wrap).cloned()
+            };
+
+            // Moving across the column lands on the pane facing the middle of the origin.
+            assert_eq!(
+                pane_in_direction(&pane_1, SplitDirection::Right, false),
+                Some(pane_3.clone())
+            );
+            assert_eq!(
+                pane_in_direction(&pane_2, SplitDirection::Left, false),
+                Some(pane_1.clone())
+            );
+            assert_eq!(
+                pane_in_direction(&pane_3, SplitDirection::Up, false),
+                Some(pane_2.clone())
+            );
+            assert_eq!(
+                pane_in_direction(&pane_2, SplitDirection::Down, false),
+                Some(pane_3.clone())
+            );
+
+            // Moving past the edge only finds a pane when wrapping.
+            assert_eq!(
+                pane_in_direction(&pane_3, SplitDirection::Down, false),
+                None
+            );
+            assert_eq!(
+                pane_in_direction(&pane_3, SplitDirection::Down, true),
+                Some(pane_2.clone())
+            );
+            assert_eq!(
+                pane_in_direction(&pane_1, SplitDirection::Left, false),
+                None
+            );
+            assert_eq!(
+                pane_in_direction(&pane_1, SplitDirection::Left, true),
+                Some(pane_3.clone())
+            );
+            assert_eq!(pane_in_direction(&pane_1, SplitDirection::Up, true), None);
+        });
+
+        // When enabled, moving focus past the workspace's edge wraps around.
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                settings.wrap_pane_navigation = Some(true);
+            })
+        });
+        workspace.update(cx, |workspace, cx| {
+            cx.focus_view(&pane_1);
+            workspace.activate_pane_in_direction(SplitDirection::Left, cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.focused_pane(cx), Some(&pane_3));
+            assert_eq!(workspace.active_pane(), &pane_3);
+        });
+
+        // There's no focused pane while a dock has focus.
+        workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel, cx);
+            workspace.toggle_panel_focus::<TestPanel>(cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.focused_pane(cx), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_close_active_pane(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let pane_1 = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        pane_1.update(cx, |pane, cx| {
+            pane.add_item(Box::new(cx.new_view(TestItem::new)), true, true, None, cx)
+        });
+        let (pane_2, pane_3) = workspace.update(cx, |workspace, cx| {
+            let pane_2 = workspace.split_pane(pane_1.clone(), SplitDirection::Right, cx);
+            let pane_3 = workspace.split_pane(pane_2.clone(), SplitDirection::Down, cx);
+            (pane_2, pane_3)
+        });
+        cx.focus_view(&pane_3);
+
+        // Closing a pane unwraps the group it leaves with a single pane, and focuses the pane
+        // that takes its place.
+        workspace.update(cx, |workspace, cx| {
+            workspace.close_active_pane(&CloseActivePane, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes(), [pane_1.clone(), pane_2.clone()]);
+            assert_eq!(workspace.focused_pane(cx), Some(&pane_2));
+            let Member::Axis(axis) = &workspace.center.root else {
+                panic!("expected a split");
+            };
+            assert!(axis
+                .members
+                .iter()
+                .all(|member| matches!(member, Member::Pane(_))));
+        });
+
+        // Its items are closed along with it.
+        cx.focus_view(&pane_1);
+        workspace.update(cx, |workspace, cx| {
+            workspace.close_active_pane(&CloseActivePane, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes(), [pane_2.clone()]);
+            assert_eq!(workspace.focused_pane(cx), Some(&pane_2));
+        });
+
+        // The last pane stays open.
+        workspace.update(cx, |workspace, cx| {
+            workspace.close_active_pane(&CloseActivePane, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.panes(), [pane_2.clone()]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_zooming_a_split_pane(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let pane_1 = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let pane_2 = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(cx.new_view(TestItem::new)), None, cx);
+            workspace.split_pane(pane_1.clone(), SplitDirection::Right, cx)
+        });
+        pane_2.update(cx, |pane, cx| {
+            pane.add_item(Box::new(cx.new_view(TestItem::new)), true, true, None, cx)
+        });
+        let flexes = workspace.update(cx, |workspace, _| {
+            let Member::Axis(axis) = &workspace.center.root else {
+                panic!("expected a split");
+            };
+            *axis.flexes.lock() = vec![0.5, 1.5];
+            axis.flexes.clone()
+        });
+
+        pane_2.update(cx, |pane, cx| pane.toggle_zoom(&ToggleZoom, cx));
+        workspace.update(cx, |workspace, cx| {
+            assert!(pane_2.read(cx).is_zoomed());
+            assert_eq!(workspace.zoomed, Some(pane_2.downgrade().into()));
+        });
+
+        // Zooming out restores the split as it was.
+        pane_2.update(cx, |pane, cx| pane.toggle_zoom(&ToggleZoom, cx));
+        workspace.update(cx, |workspace, cx| {
+            assert!(!pane_2.read(cx).is_zoomed());
+            assert_eq!(workspace.zoomed, None);
+        });
+        assert_eq!(*flexes.lock(), vec![0.5, 1.5]);
+
+        // Focusing another pane zooms the zoomed one out.
+        pane_2.update(cx, |pane, cx| pane.toggle_zoom(&ToggleZoom, cx));
+        pane_1.update(cx, |pane, cx| pane.focus(cx));
+        workspace.update(cx, |workspace, cx| {
+            assert!(!pane_2.read(cx).is_zoomed());
+            assert_eq!(workspace.zoomed, None);
+        });
+        assert_eq!(*flexes.lock(), vec![0.5, 1.5]);
+    }
+
+    #[gpui::test]
+    async fn test_resizing_panes_below_the_minimum_size(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                settings.minimum_pane_width = Some(0.);
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let pane_1 = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let pane_2 = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(cx.new_view(TestItem::new)), None, cx);
+            workspace.split_pane(pane_1.clone(), SplitDirection::Right, cx)
+        });
+        cx.run_until_parked();
+        let (bounds_1, bounds_2, flexes) = workspace.update(cx, |workspace, _| {
+            let Member::Axis(axis) = &workspace.center.root else {
+                panic!("expected a split");
+            };
+            (
+                workspace.center.bounding_box_for_pane(&pane_1).unwrap(),
+                workspace.center.bounding_box_for_pane(&pane_2).unwrap(),
+                axis.flexes.clone(),
+            )
+        });
+
+        // Dragging the divider all the way to the left stops at the floor for the setting.
+        let divider = point(bounds_1.right(), bounds_1.center().y);
+        let left_edge = point(bounds_1.left(), divider.y);
+        cx.simulate_mouse_down(divider, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(left_edge, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(left_edge, MouseButton::Left, Modifiers::none());
+
+        let container_width = bounds_1.size.width + bounds_2.size.width;
+        let flexes = flexes.lock().clone();
+        let pane_1_width = container_width * (flexes[0] / flexes.len() as f32);
+        assert!(
+            (pane_1_width - px(workspace_settings::MIN_PANE_SIZE)).abs() < px(1.),
+            "pane was resized to {pane_1_width:?}"
+        );
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -5926,6 +6504,426 @@ mod tests {
         }
     }
 
+    struct TestStatusItem;
+
+    impl StatusItemView for TestStatusItem {
+        fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+    }
+
+    impl Render for TestStatusItem {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    #[gpui::test]
+    async fn test_status_bar_items(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let items = (0..4)
+            .map(|_| cx.new_view(|_| TestStatusItem))
+            .collect::<Vec<_>>();
+        let status_bar = workspace.update(cx, |workspace, _| workspace.status_bar().clone());
+        status_bar.update(cx, |status_bar, cx| {
+            status_bar.add_item(items[0].clone(), StatusItemLocation::Left, cx);
+            status_bar.add_item(items[1].clone(), StatusItemLocation::Right, cx);
+            status_bar.add_left_item(items[2].clone(), cx);
+            status_bar.add_right_item(items[3].clone(), cx);
+        });
+
+        // Each end lists its items in the order they were added, whichever method added them.
+        status_bar.read_with(cx, |status_bar, _| {
+            let ids = |location| {
+                status_bar
+                    .items(location)
+                    .map(|item| item.entity_id())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                ids(StatusItemLocation::Left),
+                [items[0].entity_id(), items[2].entity_id()]
+            );
+            assert_eq!(
+                ids(StatusItemLocation::Right),
+                [items[1].entity_id(), items[3].entity_id()]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_collapsing_dock_to_icon_rail(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (panel_1, panel_2) = workspace.update(cx, |workspace, cx| {
+            let panel_1 = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            let panel_2 = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(panel_1.clone(), cx);
+            workspace.add_panel(panel_2.clone(), cx);
+            (panel_1, panel_2)
+        });
+        panel_1.update(cx, |panel, cx| panel.set_size(Some(px(250.)), cx));
+
+        // Collapsing a closed dock opens it as a rail, hiding the active panel.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock_collapsed(DockPosition::Left, cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            let dock = workspace.left_dock().read(cx);
+            assert!(dock.is_open());
+            assert!(dock.is_collapsed());
+            assert!(dock.visible_panel().is_none());
+            assert_eq!(dock.active_panel_size(cx), None);
+        });
+
+        // Clicking a panel's icon expands the dock to that panel, at the size it had before.
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .left_dock()
+                .update(cx, |dock, cx| dock.expand_panel(1, cx));
+        });
+        workspace.update(cx, |workspace, cx| {
+            let dock = workspace.left_dock().read(cx);
+            assert!(!dock.is_collapsed());
+            assert_eq!(
+                dock.visible_panel().unwrap().panel_id(),
+                panel_2.entity_id()
+            );
+            assert!(panel_2.read(cx).focus_handle(cx).contains_focused(cx));
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .left_dock()
+                .update(cx, |dock, cx| dock.activate_panel(0, cx));
+            workspace.toggle_dock_collapsed(DockPosition::Left, cx);
+            workspace.toggle_dock_collapsed(DockPosition::Left, cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            let dock = workspace.left_dock().read(cx);
+            assert!(!dock.is_collapsed());
+            assert_eq!(dock.active_panel_size(cx), Some(px(250.)));
+        });
+
+        // Focusing a panel in a collapsed dock expands it.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock_collapsed(DockPosition::Left, cx);
+            workspace.focus_panel::<TestPanel>(cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.left_dock().read(cx).is_collapsed());
+        });
+
+        // The bottom dock can't be collapsed, nor is it opened by trying.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock_collapsed(DockPosition::Bottom, cx);
+            let bottom_dock = workspace.bottom_dock().read(cx);
+            assert!(!bottom_dock.is_collapsed());
+            assert!(!bottom_dock.is_open());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_dock_width_bounds(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let (left_panel, right_panel) = workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            let right_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(left_panel.clone(), cx);
+            workspace.add_panel(right_panel.clone(), cx);
+            workspace.toggle_dock(DockPosition::Left, cx);
+            workspace.toggle_dock(DockPosition::Right, cx);
+            (left_panel, right_panel)
+        });
+
+        // Side docks are resized within their width bounds, which only have a minimum by default.
+        workspace.update(cx, |workspace, cx| {
+            let left_dock = workspace.left_dock();
+            let min_width = left_dock.read(cx).min_width(cx);
+            left_dock.update(cx, |dock, cx| dock.resize_active_panel(Some(px(1337.)), cx));
+            assert_eq!(left_dock.read(cx).active_panel_size(cx), Some(px(1337.)));
+            left_dock.update(cx, |dock, cx| dock.resize_active_panel(Some(px(10.)), cx));
+            assert_eq!(left_dock.read(cx).active_panel_size(cx), Some(min_width));
+            left_panel.update(cx, |panel, cx| panel.set_size(Some(px(900.)), cx));
+            right_panel.update(cx, |panel, cx| panel.set_size(Some(px(200.)), cx));
+        });
+
+        // Panels asking for a size outside the configured bounds are shown within them.
+        cx.update(|cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.dock_min_width = Some(150.);
+                    settings.dock_max_width = Some(400.);
+                });
+            });
+        });
+        workspace.update(cx, |workspace, cx| {
+            let left_dock = workspace.left_dock().read(cx);
+            assert_eq!(left_dock.active_panel_size(cx), Some(px(400.)));
+            let right_dock = workspace.right_dock().read(cx);
+            assert_eq!(right_dock.active_panel_size(cx), Some(px(200.)));
+        });
+        cx.update(|cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.dock_min_width = None;
+                    settings.dock_max_width = None;
+                });
+            });
+        });
+
+        // Shrinking the window collapses the docks that would squeeze the center, and so does
+        // opening one that doesn't fit.
+        let (minimum_pane_width, min_width) = cx.update(|cx| {
+            let settings = WorkspaceSettings::get_global(cx);
+            (px(settings.minimum_pane_width), px(settings.dock_min_width))
+        });
+        let height = px(600.);
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_dock(DockPosition::Right, cx);
+        });
+        cx.simulate_resize(size(min_width * 2. + minimum_pane_width - px(1.), height));
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.left_dock().read(cx).is_collapsed());
+            assert!(!workspace.right_dock().read(cx).is_collapsed());
+            workspace.toggle_dock(DockPosition::Right, cx);
+        });
+        workspace.update(cx, |workspace, cx| {
+            assert!(!workspace.left_dock().read(cx).is_collapsed());
+            assert!(workspace.right_dock().read(cx).is_collapsed());
+        });
+        cx.simulate_resize(size(minimum_pane_width, height));
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.left_dock().read(cx).is_collapsed());
+            assert!(workspace.right_dock().read(cx).is_collapsed());
+        });
+
+        // Growing it again expands them, but not the ones the user collapsed.
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .left_dock()
+                .update(cx, |dock, cx| dock.set_collapsed(true, cx));
+        });
+        cx.simulate_resize(size(px(1600.), height));
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.left_dock().read(cx).is_collapsed());
+            assert!(!workspace.right_dock().read(cx).is_collapsed());
+            assert_eq!(
+                workspace.right_dock().read(cx).active_panel_size(cx),
+                Some(px(200.))
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_bottom_dock_tabs(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let panels = workspace.update(cx, |workspace, cx| {
+            let panels = (0..3)
+                .map(|_| cx.new_view(|cx| TestPanel::new(DockPosition::Bottom, cx)))
+                .collect::<Vec<_>>();
+            for panel in &panels {
+                workspace.add_panel(panel.clone(), cx);
+            }
+            workspace.bottom_dock().update(cx, |dock, cx| {
+                dock.set_open(true, cx);
+                dock.activate_panel(1, cx);
+            });
+            panels
+        });
+        let panel_ids = |dock: &View<Dock>, cx: &mut VisualTestContext| {
+            dock.update(cx, |dock, _| {
+                dock.tab_panels()
+                    .iter()
+                    .map(|panel| panel.panel_id())
+                    .collect::<Vec<_>>()
+            })
+        };
+        let dock = workspace.update(cx, |workspace, _| workspace.bottom_dock().clone());
+        assert_eq!(
+            panel_ids(&dock, cx),
+            panels
+                .iter()
+                .map(|panel| panel.entity_id())
+                .collect::<Vec<_>>()
+        );
+
+        // Closing the active tab switches to the next one.
+        dock.update(cx, |dock, cx| dock.close_panel_tab(1, cx));
+        assert_eq!(
+            panel_ids(&dock, cx),
+            [panels[0].entity_id(), panels[2].entity_id()]
+        );
+        dock.update(cx, |dock, _| {
+            assert_eq!(dock.active_panel_index(), 2);
+        });
+
+        // Or the previous one, when it was the last.
+        dock.update(cx, |dock, cx| dock.close_panel_tab(2, cx));
+        dock.update(cx, |dock, _| {
+            assert!(dock.is_open());
+            assert_eq!(dock.active_panel_index(), 0);
+        });
+
+        // Activating a panel brings its tab back.
+        dock.update(cx, |dock, cx| dock.activate_panel(2, cx));
+        assert_eq!(
+            panel_ids(&dock, cx),
+            [panels[0].entity_id(), panels[2].entity_id()]
+        );
+
+        // Closing the last tab closes the dock, which shows every tab when reopened.
+        dock.update(cx, |dock, cx| {
+            dock.close_panel_tab(0, cx);
+            dock.close_panel_tab(2, cx);
+            assert!(!dock.is_open());
+        });
+        assert_eq!(panel_ids(&dock, cx).len(), 3);
+
+        // Only the bottom dock has tabs.
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.left_dock().read(cx).tab_panels().is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_zen_mode(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let pane = workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(left_panel, cx);
+            let right_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(right_panel, cx);
+            workspace
+                .left_dock()
+                .update(cx, |left_dock, cx| left_dock.set_open(true, cx));
+
+            let pane = workspace.active_pane().clone();
+            pane.update(cx, |pane, cx| {
+                let item = cx.new_view(|cx| TestItem::new(cx));
+                pane.add_item(Box::new(item), true, true, None, cx);
+            });
+            pane
+        });
+        let initial_layout = workspace.update(cx, |workspace, cx| workspace.layout(cx));
+        assert_eq!(
+            initial_layout,
+            WorkspaceLayout {
+                left_dock_open: true,
+                bottom_dock_open: false,
+                right_dock_open: false,
+                tab_bars_visible: true,
+            }
+        );
+
+        // Entering zen mode hides the docks and the tab bar.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(workspace.is_zen_mode());
+            assert_eq!(
+                workspace.layout(cx),
+                WorkspaceLayout {
+                    left_dock_open: false,
+                    bottom_dock_open: false,
+                    right_dock_open: false,
+                    tab_bars_visible: false,
+                }
+            );
+        });
+
+        // Panes split off while in zen mode don't show their tab bar either.
+        let split_pane = workspace.update(cx, |workspace, cx| {
+            workspace.split_pane(pane.clone(), SplitDirection::Right, cx)
+        });
+        split_pane.update(cx, |split_pane, _| {
+            assert!(!split_pane.is_tab_bar_visible());
+        });
+
+        // Leaving zen mode restores the layout from before it was entered.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(!workspace.is_zen_mode());
+            assert_eq!(workspace.layout(cx), initial_layout);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_pane_font_size(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let left_item = cx.new_view(|cx| TestItem::new(cx));
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(left_item.clone()), None, cx);
+            workspace.active_pane().clone()
+        });
+        let right_pane = workspace
+            .update(cx, |workspace, cx| {
+                workspace.split_and_clone(left_pane.clone(), SplitDirection::Right, cx)
+            })
+            .unwrap();
+        let right_item = right_pane.update(cx, |pane, _| {
+            pane.active_item()
+                .and_then(|item| item.downcast::<TestItem>())
+                .unwrap()
+        });
+
+        // Zooming in only affects the items of the focused pane.
+        left_pane.update(cx, |_, cx| cx.focus_self());
+        cx.dispatch_action(pane::IncreaseFontSize);
+        cx.dispatch_action(pane::IncreaseFontSize);
+        left_pane.update(cx, |pane, _| {
+            assert_eq!(pane.font_size_adjustment(), px(2.));
+        });
+        right_pane.update(cx, |pane, _| {
+            assert_eq!(pane.font_size_adjustment(), Pixels::ZERO);
+        });
+        left_item.update(cx, |item, _| assert_eq!(item.font_size_adjustment, px(2.)));
+        right_item.update(cx, |item, _| {
+            assert_eq!(item.font_size_adjustment, Pixels::ZERO)
+        });
+
+        // Items opened in the pane pick up its font size.
+        let new_item = cx.new_view(|cx| TestItem::new(cx));
+        left_pane.update(cx, |pane, cx| {
+            pane.add_item(Box::new(new_item.clone()), true, true, None, cx)
+        });
+        new_item.update(cx, |item, _| assert_eq!(item.font_size_adjustment, px(2.)));
+
+        cx.dispatch_action(pane::ResetFontSize);
+        left_item.update(cx, |item, _| {
+            assert_eq!(item.font_size_adjustment, Pixels::ZERO)
+        });
+        new_item.update(cx, |item, _| {
+            assert_eq!(item.font_size_adjustment, Pixels::ZERO)
+        });
+    }
+
     #[gpui::test]
     async fn test_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
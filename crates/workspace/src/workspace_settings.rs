@@ -4,15 +4,25 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
+/// The smallest that `minimum_pane_width` and `minimum_pane_height` can be set to, so that a pane
+/// can't be dragged too small to show its tab bar or to grab its divider again.
+pub const MIN_PANE_SIZE: f32 = 40.;
+
 #[derive(Deserialize)]
 pub struct WorkspaceSettings {
     pub active_pane_magnification: f32,
     pub centered_layout: CenteredLayoutSettings,
+    pub zen_mode: ZenModeSettings,
     pub confirm_quit: bool,
     pub show_call_status_icon: bool,
     pub autosave: AutosaveSetting,
     pub restore_on_startup: RestoreOnStartupBehaviour,
     pub drop_target_size: f32,
+    pub minimum_pane_width: f32,
+    pub minimum_pane_height: f32,
+    pub dock_min_width: f32,
+    pub dock_max_width: Option<f32>,
+    pub wrap_pane_navigation: bool,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -35,6 +45,8 @@ pub struct WorkspaceSettingsContent {
     pub active_pane_magnification: Option<f32>,
     // Centered layout related settings.
     pub centered_layout: Option<CenteredLayoutSettings>,
+    // Zen mode related settings.
+    pub zen_mode: Option<ZenModeSettings>,
     /// Whether or not to prompt the user to confirm before closing the application.
     ///
     /// Default: false
@@ -56,15 +68,56 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: `0.2` (20% of the smaller dimension of the workspace)
     pub drop_target_size: Option<f32>,
+    /// The smallest width, in pixels, that a pane can be resized to by dragging the divider
+    /// between it and its neighbours. Values below 40 are treated as 40.
+    ///
+    /// Default: `80`
+    pub minimum_pane_width: Option<f32>,
+    /// The smallest height, in pixels, that a pane can be resized to by dragging the divider
+    /// between it and its neighbours. Values below 40 are treated as 40.
+    ///
+    /// Default: `100`
+    pub minimum_pane_height: Option<f32>,
+    /// The narrowest, in pixels, that the panels of the left and right docks are shown.
+    ///
+    /// Default: `120`
+    pub dock_min_width: Option<f32>,
+    /// The widest, in pixels, that the panels of the left and right docks are shown, or null to
+    /// let them be as wide as they're resized to.
+    ///
+    /// Default: `null`
+    pub dock_max_width: Option<f32>,
+    /// Whether moving focus to the pane in a direction past the outermost pane, with no dock
+    /// open on that side, wraps around to the pane on the opposite side.
+    ///
+    /// Default: false
+    pub wrap_pane_navigation: Option<bool>,
 }
 
 #[derive(Deserialize)]
 pub struct TabBarSettings {
+    pub show: ShowTabBar,
     pub show_nav_history_buttons: bool,
 }
 
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowTabBar {
+    /// Always show the tab bar.
+    #[default]
+    Always,
+    /// Never show the tab bar.
+    Never,
+    /// Only show the tab bar when the pane has more than one tab.
+    Auto,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct TabBarSettingsContent {
+    /// When to show the tab bar of each editor pane. Dock panes always show theirs.
+    ///
+    /// Default: always
+    pub show: Option<ShowTabBar>,
     /// Whether or not to show the navigation history buttons in the tab bar.
     ///
     /// Default: true
@@ -99,13 +152,26 @@ pub struct CenteredLayoutSettings {
     pub right_padding: Option<f32>,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ZenModeSettings {
+    /// The maximum width of the editor in zen mode, in columns of the buffer font.
+    /// When unset, the editor takes up the whole width of the window.
+    ///
+    /// Default: 100
+    pub max_line_width: Option<u32>,
+}
+
 impl Settings for WorkspaceSettings {
     const KEY: Option<&'static str> = None;
 
     type FileContent = WorkspaceSettingsContent;
 
     fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
-        sources.json_merge()
+        let mut settings: Self = sources.json_merge()?;
+        settings.minimum_pane_width = settings.minimum_pane_width.max(MIN_PANE_SIZE);
+        settings.minimum_pane_height = settings.minimum_pane_height.max(MIN_PANE_SIZE);
+        Ok(settings)
     }
 }
 
@@ -99,6 +99,8 @@ pub struct LocalWorktree {
     scan_requests_tx: channel::Sender<ScanRequest>,
     path_prefixes_to_scan_tx: channel::Sender<Arc<Path>>,
     is_scanning: (watch::Sender<bool>, watch::Receiver<bool>),
+    initial_scan_completed: bool,
+    scan_event_txs: Vec<mpsc::UnboundedSender<ScanEvent>>,
     _background_scanner_tasks: Vec<Task<()>>,
     share: Option<ShareState>,
     diagnostics: HashMap<
@@ -319,6 +321,16 @@ pub enum Event {
     UpdatedGitRepositories(UpdatedGitRepositoriesSet),
 }
 
+/// The progress of a local worktree's scans, as streamed by [`LocalWorktree::scan_events`].
+#[derive(Clone, Debug)]
+pub enum ScanEvent {
+    /// Entries that were found or updated, leaving out the ones that are ignored.
+    Entries(Vec<Entry>),
+    /// The initial scan finished, so every entry it found has been sent. It isn't sent again when
+    /// changed paths are rescanned, whose entries only arrive as more [`ScanEvent::Entries`].
+    Complete,
+}
+
 impl EventEmitter<Event> for Worktree {}
 
 impl Worktree {
@@ -401,6 +413,7 @@ impl Worktree {
                             cx,
                         );
                         this.is_scanning = watch::channel_with(true);
+                        this.initial_scan_completed = false;
                     }
                 }
             })
@@ -457,6 +470,8 @@ impl Worktree {
             Worktree::Local(LocalWorktree {
                 snapshot,
                 is_scanning: watch::channel_with(true),
+                initial_scan_completed: false,
+                scan_event_txs: Vec::new(),
                 share: None,
                 scan_requests_tx,
                 path_prefixes_to_scan_tx,
@@ -714,6 +729,10 @@ fn start_background_scan_tasks(
                     } => {
                         *this.is_scanning.0.borrow_mut() = scanning;
                         this.set_snapshot(snapshot, changes, cx);
+                        if !scanning && !this.initial_scan_completed {
+                            this.initial_scan_completed = true;
+                            this.send_scan_event(ScanEvent::Complete);
+                        }
                         drop(barrier);
                     }
                 }
@@ -931,6 +950,19 @@ impl LocalWorktree {
                 .ok();
         }
 
+        if !self.scan_event_txs.is_empty() {
+            let entries = entry_changes
+                .iter()
+                .filter(|(_, _, change)| *change != PathChange::Removed)
+                .filter_map(|(_, entry_id, _)| self.snapshot.entry_for_id(*entry_id))
+                .filter(|entry| !entry.is_ignored)
+                .cloned()
+                .collect::<Vec<_>>();
+            if !entries.is_empty() {
+                self.send_scan_event(ScanEvent::Entries(entries));
+            }
+        }
+
         if !entry_changes.is_empty() {
             cx.emit(Event::UpdatedEntries(entry_changes));
         }
@@ -1034,6 +1066,26 @@ impl LocalWorktree {
         changes.into()
     }
 
+    /// Streams the entries of the worktree as scans find them, starting with the ones that were
+    /// already found, so that they can be shown before a scan of a large worktree finishes.
+    pub fn scan_events(&mut self) -> mpsc::UnboundedReceiver<ScanEvent> {
+        let (tx, rx) = mpsc::unbounded();
+        let entries = self.snapshot.entries(false).cloned().collect::<Vec<_>>();
+        if !entries.is_empty() {
+            tx.unbounded_send(ScanEvent::Entries(entries)).ok();
+        }
+        if self.initial_scan_completed {
+            tx.unbounded_send(ScanEvent::Complete).ok();
+        }
+        self.scan_event_txs.push(tx);
+        rx
+    }
+
+    fn send_scan_event(&mut self, event: ScanEvent) {
+        self.scan_event_txs
+            .retain(|tx| tx.unbounded_send(event.clone()).is_ok());
+    }
+
     pub fn scan_complete(&self) -> impl Future<Output = ()> {
         let mut is_scanning_rx = self.is_scanning.1.clone();
         async move {
@@ -1,11 +1,12 @@
 use crate::{
-    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, Snapshot, Worktree,
-    WorktreeModelHandle,
+    worktree_settings::WorktreeSettings, Entry, EntryKind, Event, PathChange, ScanEvent, Snapshot,
+    Worktree, WorktreeModelHandle,
 };
 use anyhow::Result;
 use client::Client;
 use clock::FakeSystemClock;
 use fs::{FakeFs, Fs, RealFs, RemoveOptions};
+use futures::StreamExt as _;
 use git::{repository::GitFileStatus, GITIGNORE};
 use gpui::{BorrowAppContext, ModelContext, Task, TestAppContext};
 use parking_lot::Mutex;
@@ -1279,6 +1280,86 @@ async fn test_fs_events_in_dot_git_worktree(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_streaming_scan_events(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".gitignore": "target\n",
+            "a": { "one.rs": "", "two.rs": "" },
+            "b": { "c": { "three.rs": "" } },
+            "target": { "debug": { "app": "" } },
+        }),
+    )
+    .await;
+
+    // Hold the scan up in one directory, so that it's still going when the others are found.
+    fs.pause_dir_reads("/root/b/c");
+    let tree = Worktree::local(
+        build_client(cx),
+        "/root".as_ref(),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    let mut events = tree.update(cx, |tree, _| tree.as_local_mut().unwrap().scan_events());
+
+    // Entries arrive in batches while the scan is still in progress.
+    let mut paths = Vec::new();
+    while !paths.contains(&Arc::from(Path::new("a/one.rs"))) {
+        let ScanEvent::Entries(entries) = events.next().await.unwrap() else {
+            panic!("scan completed while paused");
+        };
+        paths.extend(entries.into_iter().map(|entry| entry.path));
+    }
+    assert!(!paths.contains(&Arc::from(Path::new("b/c/three.rs"))));
+    tree.read_with(cx, |tree, _| {
+        assert!(*tree.as_local().unwrap().is_scanning.1.borrow());
+    });
+
+    // Every entry arrives before the event saying that the scan is complete.
+    fs.resume_dir_reads("/root/b/c");
+    while let ScanEvent::Entries(entries) = events.next().await.unwrap() {
+        paths.extend(entries.into_iter().map(|entry| entry.path));
+    }
+    paths.sort();
+    paths.dedup();
+    assert_eq!(
+        paths,
+        [
+            "",
+            ".gitignore",
+            "a",
+            "a/one.rs",
+            "a/two.rs",
+            "b",
+            "b/c",
+            "b/c/three.rs",
+        ]
+        .map(|path| Arc::from(Path::new(path)))
+    );
+    tree.read_with(cx, |tree, _| assert!(tree.completed_scan_id() > 0));
+
+    // Later changes are streamed too, without completing the scan again.
+    fs.create_file("/root/a/four.rs".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let mut paths = Vec::new();
+    while let Ok(Some(event)) = events.try_next() {
+        let ScanEvent::Entries(entries) = event else {
+            panic!("scan completed again after a rescan");
+        };
+        paths.extend(entries.into_iter().map(|entry| entry.path));
+    }
+    assert!(paths.contains(&Arc::from(Path::new("a/four.rs"))));
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_create_directory_during_initial_scan(cx: &mut TestAppContext) {
     init_test(cx);
@@ -46,7 +46,10 @@ use util::{
 };
 use uuid::Uuid;
 use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
-use workspace::{AppState, WorkspaceSettings, WorkspaceStore};
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    AppState, Workspace, WorkspaceSettings, WorkspaceStore,
+};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
     handle_keymap_file_changes, initialize_workspace, open_paths_with_positions, IsOnlyInstance,
@@ -143,7 +146,7 @@ fn init_headless(dev_server_token: DevServerToken) {
             fs.clone(),
             paths::SETTINGS.clone(),
         );
-        handle_settings_file_changes(user_settings_file_rx, cx);
+        handle_settings_file_changes(user_settings_file_rx, cx, handle_settings_changed);
 
         reliability::init(client.http_client(), installation_id, cx);
 
@@ -242,7 +245,7 @@ fn init_ui(args: Args) {
         load_embedded_fonts(cx);
 
         settings::init(cx);
-        handle_settings_file_changes(user_settings_file_rx, cx);
+        handle_settings_file_changes(user_settings_file_rx, cx, handle_settings_changed);
         handle_keymap_file_changes(user_keymap_file_rx, cx);
 
         client::init_settings(cx);
@@ -297,6 +300,7 @@ fn init_ui(args: Args) {
 
         load_user_themes_in_background(fs.clone(), cx);
         watch_themes(fs.clone(), cx);
+        load_user_icon_themes_in_background(fs.clone(), cx);
 
         watch_file_types(fs.clone(), cx);
 
@@ -361,6 +365,8 @@ fn init_ui(args: Args) {
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
+        bookmarks::init(cx);
+        editing_stats::init(Arc::new(clock::RealSystemClock), cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
         tasks_ui::init(cx);
@@ -845,13 +851,16 @@ fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
                     if let Some(theme_registry) =
                         cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
                     {
-                        if let Some(()) = theme_registry
-                            .load_user_theme(&path, fs.clone())
-                            .await
-                            .log_err()
-                        {
-                            cx.update(|cx| ThemeSettings::reload_current_theme(cx))
-                                .log_err();
+                        match theme_registry.reload_user_theme(&path, fs.clone()).await {
+                            Ok(()) => {
+                                cx.update(|cx| ThemeSettings::reload_current_theme(cx))
+                                    .log_err();
+                            }
+                            Err(error) => {
+                                log::error!("{error:#}");
+                                cx.update(|cx| show_theme_load_error(&path, &theme_registry, cx))
+                                    .log_err();
+                            }
                         }
                     }
                 }
@@ -861,6 +870,78 @@ fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     .detach()
 }
 
+/// Tells every open workspace why the user settings file couldn't be applied, and takes that
+/// back once it applies again.
+fn handle_settings_changed(error: Option<anyhow::Error>, cx: &mut AppContext) {
+    struct SettingsParseError;
+
+    let id = NotificationId::unique::<SettingsParseError>();
+    let message = error.map(|error| {
+        log::error!("failed to apply the user settings: {error:#}");
+        format!("Invalid user settings file\n{error:#}")
+    });
+    for window in cx.windows() {
+        let Some(workspace) = window.downcast::<Workspace>() else {
+            continue;
+        };
+        workspace
+            .update(cx, |workspace, cx| match &message {
+                Some(message) => workspace.show_notification(id.clone(), cx, |cx| {
+                    cx.new_view(|_| MessageNotification::new(message.clone()))
+                }),
+                None => workspace.dismiss_notification(&id, cx),
+            })
+            .log_err();
+    }
+}
+
+/// Tells every open workspace why the user theme at `path` failed to reload.
+fn show_theme_load_error(path: &Path, theme_registry: &ThemeRegistry, cx: &mut AppContext) {
+    struct ThemeLoadError;
+
+    let Some(error) = theme_registry.load_error(path) else {
+        return;
+    };
+    for window in cx.windows() {
+        let Some(workspace) = window.downcast::<Workspace>() else {
+            continue;
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_notification(NotificationId::unique::<ThemeLoadError>(), cx, |cx| {
+                    cx.new_view(|_| MessageNotification::new(error.clone()))
+                });
+            })
+            .log_err();
+    }
+}
+
+/// Spawns a background task to load the user icon themes from the icon themes directory, and
+/// to reload them when they change.
+fn load_user_icon_themes_in_background(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
+    use std::time::Duration;
+    cx.spawn(|mut cx| async move {
+        let icon_themes_dir = paths::ICON_THEMES_DIR.as_path();
+        if fs.is_dir(icon_themes_dir).await {
+            file_icons::FileIcons::load_user_icon_themes(icon_themes_dir, fs.clone(), &mut cx)
+                .await
+                .log_err();
+        }
+
+        let mut events = fs.watch(icon_themes_dir, Duration::from_millis(100)).await;
+        while let Some(paths) = events.next().await {
+            for path in paths {
+                if fs.metadata(&path).await.ok().flatten().is_some() {
+                    file_icons::FileIcons::load_user_icon_theme(&path, fs.clone(), &mut cx)
+                        .await
+                        .log_err();
+                }
+            }
+        }
+    })
+    .detach()
+}
+
 #[cfg(debug_assertions)]
 fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     use std::time::Duration;
@@ -879,8 +960,12 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
         let mut events = fs.watch(path.as_path(), Duration::from_millis(100)).await;
         while (events.next().await).is_some() {
             cx.update(|cx| {
-                cx.update_global(|file_types, _| {
-                    *file_types = file_icons::FileIcons::new(Assets);
+                cx.update_global(|file_types: &mut file_icons::FileIcons, _| {
+                    file_types.insert_icon_theme(
+                        file_icons::DEFAULT_ICON_THEME,
+                        file_icons::IconTheme::load(&Assets, file_icons::FILE_TYPES_ASSET)
+                            .unwrap_or_default(),
+                    );
                 });
             })
             .ok();